@@ -1,3 +1,4 @@
+use penumbra_crypto::FlowEncryptionCommittee;
 use penumbra_proto::{core::chain::v1alpha1 as pb, core::stake::v1alpha1 as pb_stake, Protobuf};
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,10 @@ pub struct AppState {
     pub validators: Vec<pb_stake::Validator>,
     /// The initial token allocations.
     pub allocations: Vec<Allocation>,
+    /// The committee that batch swap flows are encrypted to.
+    ///
+    /// Genesis files must configure a committee, since swaps are rejected without one.
+    pub flow_encryption_committee: Option<FlowEncryptionCommittee>,
 }
 
 impl From<AppState> for pb::GenesisAppState {
@@ -22,6 +27,7 @@ impl From<AppState> for pb::GenesisAppState {
             validators: a.validators.into_iter().map(Into::into).collect(),
             allocations: a.allocations.into_iter().map(Into::into).collect(),
             chain_params: Some(a.chain_params.into()),
+            flow_encryption_committee: a.flow_encryption_committee.map(Into::into),
         }
     }
 }
@@ -30,6 +36,20 @@ impl TryFrom<pb::GenesisAppState> for AppState {
     type Error = anyhow::Error;
 
     fn try_from(msg: pb::GenesisAppState) -> Result<Self, Self::Error> {
+        let committee: FlowEncryptionCommittee = msg
+            .flow_encryption_committee
+            .ok_or_else(|| anyhow::anyhow!("genesis must configure a flow encryption committee"))?
+            .try_into()?;
+        if committee.threshold == 0
+            || committee.threshold as usize > committee.public_key_shares.len()
+        {
+            return Err(anyhow::anyhow!(
+                "flow encryption committee threshold {} is invalid for {} members",
+                committee.threshold,
+                committee.public_key_shares.len()
+            ));
+        }
+
         Ok(AppState {
             chain_params: msg.chain_params.unwrap().try_into()?,
             validators: msg
@@ -43,6 +63,8 @@ impl TryFrom<pb::GenesisAppState> for AppState {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
+
+            flow_encryption_committee: Some(committee),
         })
     }
 }
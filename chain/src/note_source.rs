@@ -9,6 +9,7 @@ pub enum NoteSource {
     Genesis,
     FundingStreamReward { epoch_index: u64 },
     ProposalDepositRefund { proposal_id: u64 },
+    DaoOutput { proposal_id: u64 },
 }
 
 const CODE_INDEX: usize = 23;
@@ -34,6 +35,12 @@ impl NoteSource {
                 bytes[24..].copy_from_slice(&proposal_id.to_le_bytes());
                 bytes
             }
+            Self::DaoOutput { proposal_id } => {
+                let mut bytes = [0u8; 32];
+                bytes[CODE_INDEX] = 4;
+                bytes[24..].copy_from_slice(&proposal_id.to_le_bytes());
+                bytes
+            }
         }
     }
 }
@@ -57,6 +64,12 @@ impl TryFrom<[u8; 32]> for NoteSource {
                     );
                     Ok(Self::ProposalDepositRefund { proposal_id })
                 }
+                (4, proposal_id_bytes) => {
+                    let proposal_id = u64::from_le_bytes(
+                        proposal_id_bytes.try_into().expect("slice is of length 8"),
+                    );
+                    Ok(Self::DaoOutput { proposal_id })
+                }
                 (code, data) => Err(anyhow!(
                     "unknown note source with code {} and data {:?}",
                     code,
@@ -108,6 +121,9 @@ impl std::fmt::Debug for NoteSource {
                 "NoteSource::ProposalDepositRefund({})",
                 proposal_id
             )),
+            NoteSource::DaoOutput { proposal_id } => {
+                f.write_fmt(format_args!("NoteSource::DaoOutput({})", proposal_id))
+            }
         }
     }
 }
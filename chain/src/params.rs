@@ -57,6 +57,16 @@ pub struct ChainParameters {
     pub signed_blocks_window_len: u64,
     /// The maximum number of blocks in the window each validator can miss signing without slashing.
     pub missed_blocks_maximum: u64,
+    /// The maximum increase in a validator's total commission, in basis points, that a single
+    /// validator definition update may announce.
+    pub max_commission_change_bps: u64,
+    /// The number of epochs after the announcement of new funding streams before they take effect.
+    pub commission_change_delay_epochs: u64,
+    /// The minimum number of epochs a validator jailed for downtime must wait before being re-enabled.
+    pub minimum_jail_epochs: u64,
+    /// The maximum share of the consensus power any single validator may hold, in basis points,
+    /// or 0 for no limit.
+    pub max_validator_power_share_bps: u64,
 
     /// Whether IBC (forming connections, processing IBC packets) is enabled.
     pub ibc_enabled: bool,
@@ -95,6 +105,10 @@ impl TryFrom<pb::ChainParameters> for ChainParameters {
             base_reward_rate: msg.base_reward_rate,
             missed_blocks_maximum: msg.missed_blocks_maximum,
             signed_blocks_window_len: msg.signed_blocks_window_len,
+            max_commission_change_bps: msg.max_commission_change_bps,
+            commission_change_delay_epochs: msg.commission_change_delay_epochs,
+            minimum_jail_epochs: msg.minimum_jail_epochs,
+            max_validator_power_share_bps: msg.max_validator_power_share_bps,
             ibc_enabled: msg.ibc_enabled,
             inbound_ics20_transfers_enabled: msg.inbound_ics20_transfers_enabled,
             outbound_ics20_transfers_enabled: msg.outbound_ics20_transfers_enabled,
@@ -130,6 +144,10 @@ impl From<ChainParameters> for pb::ChainParameters {
             active_validator_limit: params.active_validator_limit,
             signed_blocks_window_len: params.signed_blocks_window_len,
             missed_blocks_maximum: params.missed_blocks_maximum,
+            max_commission_change_bps: params.max_commission_change_bps,
+            commission_change_delay_epochs: params.commission_change_delay_epochs,
+            minimum_jail_epochs: params.minimum_jail_epochs,
+            max_validator_power_share_bps: params.max_validator_power_share_bps,
             slashing_penalty_downtime_bps: params.slashing_penalty_downtime_bps,
             slashing_penalty_misbehavior_bps: params.slashing_penalty_misbehavior_bps,
             base_reward_rate: params.base_reward_rate,
@@ -157,6 +175,14 @@ impl Default for ChainParameters {
             // copied from cosmos hub
             signed_blocks_window_len: 10000,
             missed_blocks_maximum: 9500,
+            // 100 basis points = 1% per announcement
+            max_commission_change_bps: 100,
+            // the earliest epoch whose rates are not yet fixed
+            commission_change_delay_epochs: 2,
+            // doubled for each repeat offense
+            minimum_jail_epochs: 1,
+            // no limit
+            max_validator_power_share_bps: 0,
             // 1000 basis points = 10%
             slashing_penalty_misbehavior_bps: 1000,
             // 1 basis point = 0.01%
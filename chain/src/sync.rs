@@ -6,7 +6,11 @@ use penumbra_proto::{core::chain::v1alpha1 as pb, Protobuf};
 use penumbra_tct::builder::{block, epoch};
 use serde::{Deserialize, Serialize};
 
-use crate::{params::FmdParameters, quarantined::Quarantined, NoteSource};
+use crate::{
+    params::{ChainParameters, FmdParameters},
+    quarantined::Quarantined,
+    NoteSource,
+};
 
 /// A note payload annotated with the source of the note.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -41,6 +45,8 @@ pub struct CompactBlock {
     pub fmd_parameters: Option<FmdParameters>,
     // If the block indicated a proposal was being started.
     pub proposal_started: bool,
+    // Latest chain parameters. `None` if unchanged.
+    pub chain_parameters: Option<ChainParameters>,
     // **IMPORTANT NOTE FOR FUTURE HUMANS**: if you want to add new fields to the `CompactBlock`,
     // you must update `CompactBlock::requires_scanning` to check for the emptiness of those fields,
     // because the client will skip processing any compact block that is marked as not requiring
@@ -59,6 +65,7 @@ impl Default for CompactBlock {
             slashed: Vec::new(),
             fmd_parameters: None,
             proposal_started: false,
+            chain_parameters: None,
         }
     }
 }
@@ -72,6 +79,7 @@ impl CompactBlock {
             || !self.slashed.is_empty() // need to process slashing
             || self.fmd_parameters.is_some() // need to save latest FMD parameters
             || self.proposal_started // need to process proposal start
+            || self.chain_parameters.is_some() // need to save latest chain parameters
     }
 }
 
@@ -126,6 +134,7 @@ impl From<CompactBlock> for pb::CompactBlock {
             slashed: cb.slashed.into_iter().map(Into::into).collect(),
             fmd_parameters: cb.fmd_parameters.map(Into::into),
             proposal_started: cb.proposal_started,
+            chain_parameters: cb.chain_parameters.map(Into::into),
         }
     }
 }
@@ -167,6 +176,7 @@ impl TryFrom<pb::CompactBlock> for CompactBlock {
                 .collect::<Result<Vec<_>>>()?,
             fmd_parameters: value.fmd_parameters.map(TryInto::try_into).transpose()?,
             proposal_started: value.proposal_started,
+            chain_parameters: value.chain_parameters.map(TryInto::try_into).transpose()?,
         })
     }
 }
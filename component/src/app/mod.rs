@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use jmt::Version;
 use penumbra_chain::params::FmdParameters;
 use penumbra_chain::{genesis, View as _};
+use penumbra_crypto::BatchDecryptionShares;
 use penumbra_storage::{AppHash, State, StateExt, Storage};
 use penumbra_transaction::Transaction;
 use tendermint::abci::{self, types::ValidatorUpdate};
@@ -67,8 +68,10 @@ impl App {
     pub async fn commit(&mut self, storage: Storage) -> Result<(AppHash, Version)> {
         // We want to store the latest NCT in a sidecar part of the storage,
         // rather than the Penumbra state, because the serialization format for
-        // the NCT should not be consensus-critical.  We need to grab a copy of
-        // the entire NCT, so we can use it to re-instantiate the ShieldedPool.
+        // the NCT should not be consensus-critical.  The NCT is stored
+        // incrementally, so this only writes the changes made in this block.
+        // We also grab a copy of the entire NCT, so we can use it to
+        // re-instantiate the ShieldedPool.
         let nct = self.shielded_pool.note_commitment_tree();
         storage.put_nct(nct).await?;
         // Commit the pending writes, clearing the state.
@@ -90,6 +93,18 @@ impl App {
         Ok((app_hash, version))
     }
 
+    /// Checks decryption shares submitted by a member of the flow encryption
+    /// committee, which are delivered alongside transactions.
+    pub async fn check_decryption_shares(&self, shares: &BatchDecryptionShares) -> Result<()> {
+        self.dex.check_decryption_shares(shares).await
+    }
+
+    /// Records decryption shares submitted by a member of the flow encryption
+    /// committee, which must already have been checked.
+    pub async fn deliver_decryption_shares(&mut self, shares: BatchDecryptionShares) {
+        self.dex.deliver_decryption_shares(shares).await
+    }
+
     // TODO: should this just be returned by `commit`? both are called during every `EndBlock`
     pub fn tendermint_validator_updates(&self) -> Vec<ValidatorUpdate> {
         self.staking.tendermint_validator_updates()
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::shielded_pool::View as _;
 use crate::{Component, Context};
@@ -7,11 +7,13 @@ use ark_ff::Zero;
 use async_trait::async_trait;
 use decaf377::Fr;
 use penumbra_chain::{genesis, View as _};
-use penumbra_crypto::dex::lp::Reserves;
 use penumbra_crypto::{
-    asset,
-    dex::{BatchSwapOutputData, TradingPair},
-    MockFlowCiphertext, SwapFlow, Value, STAKING_TOKEN_ASSET_ID,
+    asset::Amount,
+    dex::{
+        lp::{position, LpNft, Reserves},
+        BatchSwapOutputData, LimitPrices, LimitTiers, TradingPair,
+    },
+    Balance, BatchDecryptionShares, FlowEncryptionCommittee, SwapFlow,
 };
 use penumbra_storage::{State, StateExt};
 use penumbra_transaction::action::swap_claim::ClaimedSwap;
@@ -19,13 +21,22 @@ use penumbra_transaction::{action::swap_claim::List as SwapClaimBodyList, Action
 use tendermint::abci;
 use tracing::instrument;
 
-use super::state_key;
-use super::StubCpmm;
+use super::{
+    state_key, EncryptedBatch, EncryptedBatchList, Liquidity, PositionList, Router,
+    TradingPairList, MAX_BATCH_SWAPS,
+};
+
+/// The number of blocks after which a batch that has not received enough
+/// decryption shares is abandoned, and its swaps are refunded.
+pub const FLOW_DECRYPTION_TIMEOUT: u64 = 10;
 
 pub struct Dex {
     state: State,
-    // Represents swaps taking place in the current block.
-    swaps: BTreeMap<TradingPair, SwapFlow>,
+    // Represents swaps taking place in the current block, by trading pair and
+    // limit prices, along with the number of swaps in each.
+    swaps: BTreeMap<(TradingPair, LimitPrices), (SwapFlow, usize)>,
+    // The number of swaps on each trading pair in the current block.
+    swap_counts: BTreeMap<TradingPair, usize>,
     // Represents swaps that have been claimed in the current block.
     claims: Vec<ClaimedSwap>,
 }
@@ -36,49 +47,179 @@ impl Dex {
         Self {
             state,
             swaps: Default::default(),
+            swap_counts: Default::default(),
             claims: Default::default(),
         }
     }
-}
 
-#[async_trait]
-impl Component for Dex {
-    #[instrument(name = "dex", skip(self, _app_state))]
-    async fn init_chain(&mut self, _app_state: &genesis::AppState) {
-        // Hardcode some AMMs
-        let gm = asset::REGISTRY.parse_unit("gm");
-        let gn = asset::REGISTRY.parse_unit("gn");
-        let penumbra = asset::REGISTRY.parse_unit("penumbra");
+    /// Checks that a committee member's decryption shares are valid for a
+    /// batch awaiting decryption.
+    #[instrument(name = "dex", skip(self, shares), fields(height = shares.height))]
+    pub async fn check_decryption_shares(&self, shares: &BatchDecryptionShares) -> Result<()> {
+        let committee = self
+            .state
+            .flow_encryption_committee()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("no flow encryption committee is configured"))?;
+        let batch = self
+            .state
+            .pending_batches()
+            .await?
+            .into_iter()
+            .find(|batch| batch.height == shares.height)
+            .ok_or_else(|| {
+                anyhow::anyhow!("no batch at height {} awaits decryption", shares.height)
+            })?;
+        if batch.is_decryptable(&committee) {
+            return Err(anyhow::anyhow!(
+                "batch at height {} already has enough decryption shares",
+                shares.height
+            ));
+        }
+        batch.check_shares(shares, &committee)
+    }
 
-        self.state
-            .set_stub_cpmm_reserves(
-                &TradingPair::canonical_order_for((gm.id(), gn.id())).unwrap(),
-                Reserves {
-                    r1: (10000 * 10u64.pow(gm.exponent().into())).into(),
-                    r2: (10000 * 10u64.pow(gn.exponent().into())).into(),
-                },
-            )
-            .await;
+    /// Records a committee member's decryption shares for a batch awaiting
+    /// decryption, which must already have been checked.
+    #[instrument(name = "dex", skip(self, shares), fields(height = shares.height))]
+    pub async fn deliver_decryption_shares(&mut self, shares: BatchDecryptionShares) {
+        let mut batches = self.state.pending_batches().await.unwrap();
+        let batch = batches
+            .iter_mut()
+            .find(|batch| batch.height == shares.height)
+            .expect("shares were checked against a pending batch");
+        tracing::debug!(
+            participant_index = shares.participant_index,
+            "recording decryption shares"
+        );
+        batch.shares.push(shares);
+        self.state.put_pending_batches(batches).await;
+    }
 
-        self.state
-            .set_stub_cpmm_reserves(
-                &TradingPair::canonical_order_for((gm.id(), penumbra.id())).unwrap(),
-                Reserves {
-                    r1: (10000 * 10u64.pow(gm.exponent().into())).into(),
-                    r2: (10000 * 10u64.pow(penumbra.exponent().into())).into(),
+    /// Executes a batch of swaps on a trading pair, given the decrypted flow
+    /// of the swaps with each limit prices, or refunds them if the flows could
+    /// not be decrypted.  The swaps in the `refunded_tiers` were never
+    /// decrypted, and are refunded either way.
+    ///
+    /// Swaps whose limit prices the clearing prices would violate are excluded
+    /// from the batch before it is executed, so that their inputs can be
+    /// refunded in full.  Excluding swaps changes the clearing prices, so the
+    /// swaps with the highest violated limits are excluded one step at a time,
+    /// until the clearing prices meet the limits of every remaining swap.
+    ///
+    /// Execution happens entirely against the `router`, so this can also be
+    /// used to simulate a batch without touching the state.
+    pub fn execute_batch(
+        router: &mut Router,
+        height: u64,
+        trading_pair: TradingPair,
+        flows: Result<Vec<(LimitPrices, (u64, u64))>>,
+        refunded_tiers: LimitTiers,
+    ) -> BatchSwapOutputData {
+        let failed = |(delta_1, delta_2), limit_thresholds| BatchSwapOutputData {
+            height,
+            trading_pair,
+            delta_1,
+            delta_2,
+            lambda_1: 0,
+            lambda_2: 0,
+            success: false,
+            limit_thresholds,
+            refunded_tiers,
+        };
+
+        let flows = match flows {
+            Ok(flows) => flows,
+            Err(e) => {
+                tracing::warn!(
+                    ?e,
+                    ?trading_pair,
+                    height,
+                    "could not decrypt batch swap flow"
+                );
+                return failed((0, 0), LimitPrices::MAX);
+            }
+        };
+
+        let mut limit_thresholds = LimitPrices::MAX;
+        loop {
+            let included = flows
+                .iter()
+                .filter(|(limit_prices, _)| limit_prices.satisfied_by(&limit_thresholds));
+            let (delta_1, delta_2) = match included.clone().try_fold(
+                (0u64, 0u64),
+                |(delta_1, delta_2), (_, (flow_1, flow_2))| {
+                    Some((delta_1.checked_add(*flow_1)?, delta_2.checked_add(*flow_2)?))
                 },
-            )
-            .await;
+            ) {
+                Some(deltas) => deltas,
+                None => {
+                    tracing::warn!(?trading_pair, height, "batch swap flow overflowed");
+                    return failed((0, 0), limit_thresholds);
+                }
+            };
 
-        self.state
-            .set_stub_cpmm_reserves(
-                &TradingPair::canonical_order_for((gn.id(), penumbra.id())).unwrap(),
-                Reserves {
-                    r1: (10000 * 10u64.pow(gn.exponent().into())).into(),
-                    r2: (10000 * 10u64.pow(penumbra.exponent().into())).into(),
-                },
-            )
-            .await;
+            tracing::debug!(?delta_1, ?delta_2, ?limit_thresholds, ?trading_pair);
+            let mut scratch = router.clone();
+            let (lambda_1, lambda_2) = match scratch.execute_batch(trading_pair, (delta_1, delta_2))
+            {
+                Some(lambdas) => lambdas,
+                // If there is not enough liquidity to fill the entire batch,
+                // the swap fails and the inputs are refunded.
+                None => return failed((delta_1, delta_2), limit_thresholds),
+            };
+
+            // Find the highest limits the clearing prices violate.  A violated
+            // limit is never zero, so zero means no limit was violated.
+            let clearing_prices =
+                LimitPrices::clearing_prices((delta_1, delta_2), (lambda_1, lambda_2));
+            let mut violated = LimitPrices::default();
+            for (limit_prices, _) in included {
+                if limit_prices.min_price_1 > clearing_prices.min_price_1 {
+                    violated.min_price_1 = violated.min_price_1.max(limit_prices.min_price_1);
+                }
+                if limit_prices.min_price_2 > clearing_prices.min_price_2 {
+                    violated.min_price_2 = violated.min_price_2.max(limit_prices.min_price_2);
+                }
+            }
+
+            if violated == LimitPrices::default() {
+                tracing::debug!(?lambda_1, ?lambda_2, "executed batch against positions");
+                *router = scratch;
+                return BatchSwapOutputData {
+                    height,
+                    trading_pair,
+                    delta_1,
+                    delta_2,
+                    lambda_1,
+                    lambda_2,
+                    success: true,
+                    limit_thresholds,
+                    refunded_tiers,
+                };
+            }
+
+            // Exclude the swaps with the highest violated limits, and try again
+            // without them.
+            if violated.min_price_1 > 0 {
+                limit_thresholds.min_price_1 = violated.min_price_1 - 1;
+            }
+            if violated.min_price_2 > 0 {
+                limit_thresholds.min_price_2 = violated.min_price_2 - 1;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Component for Dex {
+    #[instrument(name = "dex", skip(self, app_state))]
+    async fn init_chain(&mut self, app_state: &genesis::AppState) {
+        if let Some(committee) = &app_state.flow_encryption_committee {
+            self.state
+                .put_flow_encryption_committee(committee.clone())
+                .await;
+        }
     }
 
     #[instrument(name = "dex", skip(self, _ctx, _begin_block))]
@@ -86,43 +227,80 @@ impl Component for Dex {
 
     #[instrument(name = "dex", skip(_ctx, tx))]
     fn check_tx_stateless(_ctx: Context, tx: &Transaction) -> Result<()> {
-        // It's important to reject all LP actions for now, to prevent
-        // inflation / minting bugs until we implement all required checks
-        // (e.g., minting tokens by withdrawing reserves we don't check)
+        // Position nonces must be unique, including within a single transaction.
+        let mut nonces = BTreeSet::new();
+
         for action in tx.transaction_body.actions.iter() {
             match action {
-                Action::PositionOpen { .. }
-                | Action::PositionClose { .. }
-                | Action::PositionWithdraw { .. }
-                | Action::PositionRewardClaim { .. } => {
-                    return Err(anyhow::anyhow!("lp actions not supported yet"));
+                Action::PositionOpen(position_open) => {
+                    let position = &position_open.position;
+                    if position.pair.asset_1() >= position.pair.asset_2() {
+                        return Err(anyhow::anyhow!(
+                            "position trading pair is not in canonical order"
+                        ));
+                    }
+                    if !position.phi.is_valid() {
+                        return Err(anyhow::anyhow!("position trading function is invalid"));
+                    }
+                    let reserves = &position_open.initial_reserves;
+                    if reserves.r1 == Amount::zero() && reserves.r2 == Amount::zero() {
+                        return Err(anyhow::anyhow!("position must have non-zero reserves"));
+                    }
+                    if !nonces.insert(position.nonce) {
+                        return Err(anyhow::anyhow!(
+                            "transaction opens multiple positions with the same nonce"
+                        ));
+                    }
+                }
+                Action::PositionClose(_)
+                | Action::PositionWithdraw(_)
+                | Action::PositionRewardClaim(_) => {
+                    // These actions are checked against the position's
+                    // on-chain state in `check_tx_stateful`.
                 }
                 Action::Swap(swap) => {
-                    // Check swap proof
+                    // Swaps are decrypted together with the swaps with the
+                    // same limit prices, so they must be one of the coarse
+                    // limit tiers.
+                    if !swap.body.limit_prices.is_tier() {
+                        return Err(anyhow::anyhow!(
+                            "swap limit prices must be either no limit, or a power-of-two limit on one direction"
+                        ));
+                    }
+
+                    // Check that the amounts are well-formed encryptions to
+                    // the swap's flow encryption key, which is checked against
+                    // the committee's key in `check_tx_stateful`.
+                    swap.body
+                        .enc_delta_1
+                        .verify_encryption_proof(
+                            &swap.body.enc_delta_1_proof,
+                            &swap.body.flow_encryption_key,
+                        )
+                        .context("an encrypted swap amount is malformed")?;
+                    swap.body
+                        .enc_delta_2
+                        .verify_encryption_proof(
+                            &swap.body.enc_delta_2_proof,
+                            &swap.body.flow_encryption_key,
+                        )
+                        .context("an encrypted swap amount is malformed")?;
+
+                    // Check swap proof, which shows that the encrypted amounts
+                    // are the amounts the swap's balance commitment pays for.
                     swap.proof
                         .verify(
-                            // TODO: no value commitments until flow encryption is available
-                            // so we pass placeholder values here, the proof doesn't check these right now
-                            // and will fail when checking is re-enabled.
-                            Value {
-                                amount: 0u64.into(),
-                                asset_id: *STAKING_TOKEN_ASSET_ID,
-                            }
-                            .commit(Fr::zero()),
-                            Value {
-                                amount: 0u64.into(),
-                                asset_id: *STAKING_TOKEN_ASSET_ID,
-                            }
-                            .commit(Fr::zero()),
+                            swap.body.delta_1_commitment,
+                            swap.body.delta_2_commitment,
                             swap.body.fee_commitment,
                             swap.body.swap_nft.note_commitment,
                             swap.body.swap_nft.ephemeral_key,
+                            swap.body.limit_prices,
+                            &swap.body.flow_encryption_key,
+                            &swap.body.enc_delta_1,
+                            &swap.body.enc_delta_2,
                         )
                         .context("A swap proof did not verify")?;
-
-                    // TODO: are any other checks necessary?
-
-                    return Ok(());
                 }
                 Action::SwapClaim(swap_claim) => {
                     let fee = swap_claim.body.fee.clone();
@@ -145,8 +323,6 @@ impl Component for Dex {
                         .context("a swap claim proof did not verify")?;
 
                     // TODO: any other stateless checks?
-
-                    return Ok(());
                 }
                 _ => {}
             }
@@ -157,21 +333,130 @@ impl Component for Dex {
 
     #[instrument(name = "dex", skip(self, _ctx, tx))]
     async fn check_tx_stateful(&self, _ctx: Context, tx: &Transaction) -> Result<()> {
-        // It's important to reject all LP actions for now, to prevent
-        // inflation / minting bugs until we implement all required checks
-        // (e.g., minting tokens by withdrawing reserves we don't check)
+        // The number of swaps on each trading pair in this transaction.
+        let mut swap_counts = BTreeMap::<TradingPair, usize>::new();
+
         for action in tx.transaction_body.actions.iter() {
             match action {
-                Action::PositionOpen { .. }
-                | Action::PositionClose { .. }
-                | Action::PositionWithdraw { .. }
-                | Action::PositionRewardClaim { .. } => {
-                    return Err(anyhow::anyhow!("lp actions not supported yet"));
+                Action::PositionOpen(position_open) => {
+                    let position = &position_open.position;
+                    if self.state.position_nonce_used(&position.nonce).await? {
+                        return Err(anyhow::anyhow!(
+                            "a position with the same nonce has already been opened"
+                        ));
+                    }
+                    if self.state.position_by_id(&position.id()).await?.is_some() {
+                        return Err(anyhow::anyhow!("position {} already exists", position.id()));
+                    }
+                }
+                Action::PositionClose(position_close) => {
+                    let metadata = self
+                        .state
+                        .position_by_id(&position_close.position_id)
+                        .await?
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("position {} not found", position_close.position_id)
+                        })?;
+                    if metadata.state != position::State::Opened {
+                        return Err(anyhow::anyhow!(
+                            "position {} is {}, not opened",
+                            position_close.position_id,
+                            metadata.state
+                        ));
+                    }
+                }
+                Action::PositionWithdraw(position_withdraw) => {
+                    let metadata = self
+                        .state
+                        .position_by_id(&position_withdraw.position_id)
+                        .await?
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("position {} not found", position_withdraw.position_id)
+                        })?;
+                    if metadata.state != position::State::Closed {
+                        return Err(anyhow::anyhow!(
+                            "position {} is {}, not closed",
+                            position_withdraw.position_id,
+                            metadata.state
+                        ));
+                    }
+
+                    // The reserves commitment is transparent, so we can check
+                    // that it commits to exactly the position's final reserves.
+                    let expected_commitment = metadata
+                        .reserves
+                        .balance(&metadata.position.pair)
+                        .commit(Fr::zero());
+                    if position_withdraw.reserves_commitment != expected_commitment {
+                        return Err(anyhow::anyhow!(
+                            "reserves commitment does not match the reserves of position {}",
+                            position_withdraw.position_id
+                        ));
+                    }
+                }
+                Action::PositionRewardClaim(position_reward_claim) => {
+                    let metadata = self
+                        .state
+                        .position_by_id(&position_reward_claim.position_id)
+                        .await?
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "position {} not found",
+                                position_reward_claim.position_id
+                            )
+                        })?;
+                    if metadata.state != position::State::Withdrawn {
+                        return Err(anyhow::anyhow!(
+                            "position {} is {}, not withdrawn",
+                            position_reward_claim.position_id,
+                            metadata.state
+                        ));
+                    }
+
+                    // There are no retroactive rewards yet, so the only valid
+                    // claim is for an empty balance.
+                    if position_reward_claim.rewards_commitment
+                        != Balance::zero().commit(Fr::zero())
+                    {
+                        return Err(anyhow::anyhow!(
+                            "rewards commitment does not match the rewards of position {}",
+                            position_reward_claim.position_id
+                        ));
+                    }
                 }
-                Action::Swap(_swap) => {
-                    // TODO: are any other checks necessary?
+                Action::Swap(swap) => {
+                    // Swap flows are encrypted to the flow encryption committee,
+                    // so there must be one to decrypt them.
+                    let committee =
+                        self.state
+                            .flow_encryption_committee()
+                            .await?
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                            "swaps are disabled: no flow encryption committee is configured"
+                        )
+                            })?;
+                    if swap.body.flow_encryption_key != committee.encryption_key {
+                        return Err(anyhow::anyhow!(
+                            "swap amounts are not encrypted to the flow encryption committee"
+                        ));
+                    }
 
-                    return Ok(());
+                    // The sum of a block's flows on a trading pair has to fit
+                    // in the decryption table, which bounds the number of
+                    // swaps on each trading pair in a block.
+                    let trading_pair = swap.body.trading_pair;
+                    let count = swap_counts.entry(trading_pair).or_default();
+                    *count += 1;
+                    if self.swap_counts.get(&trading_pair).copied().unwrap_or(0) + *count
+                        > MAX_BATCH_SWAPS
+                    {
+                        return Err(anyhow::anyhow!(
+                            "the batch on trading pair {:?} is full: at most {} swaps per block",
+                            trading_pair,
+                            MAX_BATCH_SWAPS
+                        ));
+                    }
                 }
                 Action::SwapClaim(swap_claim) => {
                     // 1. Validate the epoch duration passed in the swap claim matches
@@ -201,8 +486,6 @@ impl Component for Dex {
                             "provided output data does not match chain output data"
                         ));
                     }
-
-                    return Ok(());
                 }
                 _ => {}
             }
@@ -214,26 +497,69 @@ impl Component for Dex {
     async fn execute_tx(&mut self, _ctx: Context, tx: &Transaction) {
         for action in tx.transaction_body.actions.iter() {
             match action {
-                Action::PositionOpen { .. }
-                | Action::PositionClose { .. }
-                | Action::PositionWithdraw { .. }
-                | Action::PositionRewardClaim { .. } => {}
+                Action::PositionOpen(position_open) => {
+                    let position = position_open.position.clone();
+                    let id = position.id();
+                    tracing::debug!(?id, "opening position");
+
+                    self.state.put_position_nonce(&position.nonce).await;
+                    self.state
+                        .put_position(position::Metadata {
+                            position,
+                            state: position::State::Opened,
+                            reserves: position_open.initial_reserves,
+                        })
+                        .await
+                        .unwrap();
+                    self.state
+                        .register_lpnft(id, position::State::Opened)
+                        .await
+                        .unwrap();
+                }
+                Action::PositionClose(position_close) => {
+                    let id = position_close.position_id;
+                    tracing::debug!(?id, "closing position");
+
+                    self.state
+                        .update_position_state(&id, position::State::Closed)
+                        .await
+                        .unwrap();
+                }
+                Action::PositionWithdraw(position_withdraw) => {
+                    let id = position_withdraw.position_id;
+                    tracing::debug!(?id, "withdrawing position");
+
+                    self.state
+                        .update_position_state(&id, position::State::Withdrawn)
+                        .await
+                        .unwrap();
+                }
+                Action::PositionRewardClaim(position_reward_claim) => {
+                    let id = position_reward_claim.position_id;
+                    tracing::debug!(?id, "claiming position rewards");
+
+                    self.state
+                        .update_position_state(&id, position::State::Claimed)
+                        .await
+                        .unwrap();
+                }
                 Action::Swap(swap) => {
                     // All swaps will be tallied for the block so the
                     // BatchSwapOutputData for the trading pair/block height can
-                    // be set during `end_block`.
-                    let mut swap_flows = self
+                    // be set during `end_block`.  Swaps are tallied separately
+                    // by their limit prices, so that they can be excluded from
+                    // the batch if their limits are not met.
+                    let (swap_flows, count) = self
                         .swaps
-                        .get_mut(&swap.body.trading_pair)
-                        .cloned()
-                        .unwrap_or_default();
-
-                    // Add the amount of each asset being swapped to the batch swap flow.
-                    swap_flows.0 += MockFlowCiphertext::new(swap.body.delta_1_i.into());
-                    swap_flows.1 += MockFlowCiphertext::new(swap.body.delta_2_i.into());
-
-                    // Set the batch swap flow for the trading pair.
-                    self.swaps.insert(swap.body.trading_pair, swap_flows);
+                        .entry((swap.body.trading_pair, swap.body.limit_prices))
+                        .or_default();
+
+                    // Add the encrypted amount of each asset being swapped to
+                    // the batch swap flow.
+                    swap_flows.0 += swap.body.enc_delta_1;
+                    swap_flows.1 += swap.body.enc_delta_2;
+                    *count += 1;
+                    *self.swap_counts.entry(swap.body.trading_pair).or_default() += 1;
                 }
                 Action::SwapClaim(swap_claim) => {
                     // Each swap claim gets their portion of the swap based on their contribution.
@@ -247,39 +573,70 @@ impl Component for Dex {
 
     #[instrument(name = "dex", skip(self, _ctx, end_block))]
     async fn end_block(&mut self, _ctx: Context, end_block: &abci::request::EndBlock) {
-        // For each batch swap during the block, calculate clearing prices and set in the JMT.
-        // TODO: since there are no liquidity providers right now, we'll consider all
-        // batch swaps to fail
-        for (trading_pair, swap_flows) in self.swaps.iter() {
-            let (delta_1, delta_2) = (swap_flows.0.mock_decrypt(), swap_flows.1.mock_decrypt());
-
-            tracing::debug!(?delta_1, ?delta_2, ?trading_pair);
-            let (lambda_1, lambda_2, success) =
-                match self.state.stub_cpmm_reserves(trading_pair).await.unwrap() {
-                    Some(reserves) => {
-                        tracing::debug!(?reserves, "stub cpmm is present");
-                        let mut amm = StubCpmm { reserves };
-                        let (lambda_1, lambda_2) = amm.trade_netted((delta_1, delta_2));
-                        tracing::debug!(?lambda_1, ?lambda_2, new_reserves = ?amm.reserves);
-                        self.state
-                            .set_stub_cpmm_reserves(trading_pair, amm.reserves)
-                            .await;
-                        (lambda_1, lambda_2, true)
-                    }
-                    None => (0, 0, false),
-                };
+        let height: u64 = end_block.height.try_into().unwrap();
+
+        // Batches are decrypted once enough committee members have contributed
+        // decryption shares, which happens in the blocks after the swaps were
+        // included. Batches that time out are executed as failed batches.
+        let mut ready = Vec::new();
+        let mut pending = Vec::new();
+        let mut batches_changed = !self.swaps.is_empty();
+        if let Some(committee) = self.state.flow_encryption_committee().await.unwrap() {
+            for batch in self.state.pending_batches().await.unwrap() {
+                if batch.is_decryptable(&committee)
+                    || height >= batch.height + FLOW_DECRYPTION_TIMEOUT
+                {
+                    ready.push(batch);
+                } else {
+                    pending.push(batch);
+                }
+            }
+
+            batches_changed |= !ready.is_empty();
 
-            let output_data = BatchSwapOutputData {
-                height: end_block.height.try_into().unwrap(),
-                trading_pair: *trading_pair,
-                delta_1,
-                delta_2,
-                lambda_1,
-                lambda_2,
-                success,
+            // Batch swaps are routed across the liquidity of every trading pair, so
+            // load all of it up front (if there are any swaps to execute).
+            let mut router = if ready.is_empty() {
+                Router::default()
+            } else {
+                self.state.router().await.unwrap()
             };
-            tracing::debug!(?output_data);
-            self.state.set_output_data(output_data).await;
+
+            // For each batch swap, calculate clearing prices and set in the JMT,
+            // at the height the swaps were included in.
+            for batch in ready {
+                for trading_pair in batch.trading_pairs() {
+                    let flows = if batch.is_decryptable(&committee) {
+                        batch.decrypt(&trading_pair, &committee).await
+                    } else {
+                        Err(anyhow::anyhow!("timed out awaiting decryption shares"))
+                    };
+                    let output_data = Self::execute_batch(
+                        &mut router,
+                        batch.height,
+                        trading_pair,
+                        flows,
+                        batch.refunded_tiers(&trading_pair),
+                    );
+                    tracing::debug!(?output_data);
+                    self.state.set_output_data(output_data).await;
+                }
+            }
+
+            // Write back the positions that were traded against, on any trading pair.
+            for liquidity in router.into_touched() {
+                self.state.put_liquidity(liquidity).await.unwrap();
+            }
+        }
+
+        // This block's swaps await decryption by the committee, except for
+        // those in limit tiers too small to decrypt on their own.
+        self.swap_counts.clear();
+        if !self.swaps.is_empty() {
+            pending.push(EncryptedBatch::new(height, std::mem::take(&mut self.swaps)));
+        }
+        if batches_changed {
+            self.state.put_pending_batches(pending).await;
         }
 
         // Tell the shielded pool component to include the claimed output notes in the NCT.
@@ -316,15 +673,325 @@ pub trait View: StateExt {
         .await;
     }
 
-    async fn stub_cpmm_reserves(&self, trading_pair: &TradingPair) -> Result<Option<Reserves>> {
-        self.get_domain(state_key::stub_cpmm_reserves(trading_pair).into())
+    /// Returns the committee that batch swap flows are encrypted to, if any.
+    async fn flow_encryption_committee(&self) -> Result<Option<FlowEncryptionCommittee>> {
+        self.get_domain(state_key::flow_encryption_committee().into())
             .await
     }
 
-    async fn set_stub_cpmm_reserves(&self, trading_pair: &TradingPair, reserves: Reserves) {
-        self.put_domain(state_key::stub_cpmm_reserves(trading_pair).into(), reserves)
-            .await
+    async fn put_flow_encryption_committee(&self, committee: FlowEncryptionCommittee) {
+        self.put_domain(state_key::flow_encryption_committee().into(), committee)
+            .await;
+    }
+
+    /// Returns the encrypted batches awaiting decryption, oldest first.
+    async fn pending_batches(&self) -> Result<Vec<EncryptedBatch>> {
+        Ok(self
+            .get_domain::<EncryptedBatchList, _>(state_key::pending_batches().into())
+            .await?
+            .unwrap_or_default()
+            .0)
+    }
+
+    async fn put_pending_batches(&self, batches: Vec<EncryptedBatch>) {
+        self.put_domain(
+            state_key::pending_batches().into(),
+            EncryptedBatchList(batches),
+        )
+        .await;
+    }
+
+    async fn position_by_id(&self, id: &position::Id) -> Result<Option<position::Metadata>> {
+        self.get_domain(state_key::position_by_id(id).into()).await
+    }
+
+    /// Returns the IDs of all open positions on the given trading pair.
+    async fn positions_by_trading_pair(
+        &self,
+        trading_pair: &TradingPair,
+    ) -> Result<Vec<position::Id>> {
+        Ok(self
+            .get_domain::<PositionList, _>(
+                state_key::positions_by_trading_pair(trading_pair).into(),
+            )
+            .await?
+            .unwrap_or_default()
+            .0)
+    }
+
+    /// Returns the trading pairs that have open positions.
+    async fn trading_pairs_with_positions(&self) -> Result<Vec<TradingPair>> {
+        Ok(self
+            .get_domain::<TradingPairList, _>(state_key::trading_pairs_with_positions().into())
+            .await?
+            .unwrap_or_default()
+            .0)
+    }
+
+    /// Adds or removes a trading pair from the index of trading pairs with
+    /// open positions.
+    async fn put_trading_pair_indexed(
+        &self,
+        trading_pair: TradingPair,
+        indexed: bool,
+    ) -> Result<()> {
+        let mut index = self.trading_pairs_with_positions().await?;
+        if index.contains(&trading_pair) == indexed {
+            return Ok(());
+        }
+        if indexed {
+            index.push(trading_pair);
+        } else {
+            index.retain(|indexed_pair| indexed_pair != &trading_pair);
+        }
+        self.put_domain(
+            state_key::trading_pairs_with_positions().into(),
+            TradingPairList(index),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Writes the metadata of a position, keeping the index of open positions
+    /// by trading pair in sync with its state.
+    async fn put_position(&self, metadata: position::Metadata) -> Result<()> {
+        let id = metadata.position.id();
+        let trading_pair = metadata.position.pair;
+
+        let mut index = self.positions_by_trading_pair(&trading_pair).await?;
+        let is_open = metadata.state == position::State::Opened;
+        let is_indexed = index.contains(&id);
+        if is_open != is_indexed {
+            if is_open {
+                index.push(id);
+            } else {
+                index.retain(|indexed_id| indexed_id != &id);
+            }
+            self.put_trading_pair_indexed(trading_pair, !index.is_empty())
+                .await?;
+            self.put_domain(
+                state_key::positions_by_trading_pair(&trading_pair).into(),
+                PositionList(index),
+            )
+            .await;
+        }
+
+        self.put_domain(state_key::position_by_id(&id).into(), metadata)
+            .await;
+        Ok(())
+    }
+
+    /// Moves a position to a new state, registering the LPNFT denom for the
+    /// new state.
+    ///
+    /// Withdrawing a position releases its reserves, so a position in the
+    /// [`Withdrawn`](position::State::Withdrawn) state is left empty.
+    async fn update_position_state(&self, id: &position::Id, state: position::State) -> Result<()> {
+        let mut metadata = self
+            .position_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("position {} not found", id))?;
+
+        metadata.state = state;
+        if state == position::State::Withdrawn {
+            metadata.reserves = Reserves {
+                r1: Amount::zero(),
+                r2: Amount::zero(),
+            };
+        }
+
+        self.put_position(metadata).await?;
+        self.register_lpnft(*id, state).await
+    }
+
+    /// Registers the denom of the LPNFT recording a position in the given state.
+    async fn register_lpnft(&self, id: position::Id, state: position::State) -> Result<()> {
+        self.register_denom(&LpNft::new(id, state).denom()).await
+    }
+
+    /// Returns whether a position with the given nonce has ever been opened.
+    async fn position_nonce_used(&self, nonce: &[u8; 32]) -> Result<bool> {
+        Ok(self
+            .get_proto::<bool>(state_key::position_nonce(nonce).into())
+            .await?
+            .unwrap_or(false))
+    }
+
+    /// Records that a position with the given nonce has been opened.
+    async fn put_position_nonce(&self, nonce: &[u8; 32]) {
+        self.put_proto(state_key::position_nonce(nonce).into(), true)
+            .await;
+    }
+
+    /// Loads the liquidity available on the given trading pair.
+    async fn liquidity(&self, trading_pair: &TradingPair) -> Result<Liquidity> {
+        let mut positions = Vec::new();
+        for id in self.positions_by_trading_pair(trading_pair).await? {
+            positions.push(
+                self.position_by_id(&id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("indexed position {} not found", id))?,
+            );
+        }
+        Ok(Liquidity::new(*trading_pair, positions))
+    }
+
+    /// Loads a [`Router`] over the liquidity of every trading pair with open
+    /// positions.
+    async fn router(&self) -> Result<Router> {
+        let mut liquidity = Vec::new();
+        for trading_pair in self.trading_pairs_with_positions().await? {
+            liquidity.push(self.liquidity(&trading_pair).await?);
+        }
+        Ok(Router::new(liquidity))
+    }
+
+    /// Simulates executing a batch swap with inputs `(delta_1, delta_2)` on
+    /// `trading_pair` against the current liquidity, as if it were executed at
+    /// the end of the current block, without modifying the state.
+    async fn simulate_batch(
+        &self,
+        trading_pair: TradingPair,
+        (delta_1, delta_2): (u64, u64),
+    ) -> Result<BatchSwapOutputData> {
+        let height = self.get_block_height().await?;
+        let mut router = self.router().await?;
+        Ok(Dex::execute_batch(
+            &mut router,
+            height,
+            trading_pair,
+            Ok(vec![(LimitPrices::default(), (delta_1, delta_2))]),
+            LimitTiers::default(),
+        ))
+    }
+
+    /// Writes back the positions of a [`Liquidity`] after trades have been
+    /// executed against it.
+    async fn put_liquidity(&self, liquidity: Liquidity) -> Result<()> {
+        for metadata in liquidity.positions {
+            self.put_position(metadata).await?;
+        }
+        Ok(())
     }
 }
 
 impl<T: StateExt + Send + Sync> View for T {}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_crypto::{
+        asset,
+        dex::{
+            lp::{position::Position, TradingFunction},
+            swap::PRICE_SCALE,
+        },
+    };
+
+    use super::*;
+
+    fn trading_pair() -> TradingPair {
+        let gm = asset::REGISTRY.parse_unit("gm");
+        let gn = asset::REGISTRY.parse_unit("gn");
+        TradingPair::canonical_order_for((gm.id(), gn.id())).unwrap()
+    }
+
+    /// Returns a position selling up to `reserves` of asset 2 for asset 1, at
+    /// `price` units of asset 2 per unit of asset 1.
+    fn position(pair: TradingPair, price: u64, reserves: u64, nonce: u8) -> position::Metadata {
+        position::Metadata {
+            position: Position {
+                pair,
+                phi: TradingFunction {
+                    fee: 0,
+                    k: 0u64.into(),
+                    p: price.into(),
+                    q: 1u64.into(),
+                },
+                nonce: [nonce; 32],
+            },
+            state: position::State::Opened,
+            reserves: Reserves {
+                r1: 0u64.into(),
+                r2: reserves.into(),
+            },
+        }
+    }
+
+    fn limit(min_price_2: u64) -> LimitPrices {
+        LimitPrices {
+            min_price_1: 0,
+            min_price_2,
+        }
+    }
+
+    #[test]
+    fn excluded_swaps_are_refunded_without_minting_value() {
+        let pair = trading_pair();
+        let mut router = Router::new([Liquidity::new(
+            pair,
+            [position(pair, 3, 150, 1), position(pair, 1, 1000, 2)],
+        )]);
+
+        // The input of asset 1 and the limit prices of each swap.
+        let swaps = [
+            (50, limit(0)),
+            (50, limit(1 << 21)),
+            (10, limit(1 << 20)),
+            (20, limit(1 << 20)),
+        ];
+        let mut flows = BTreeMap::<LimitPrices, (u64, u64)>::new();
+        for (delta_1_i, limit_prices) in swaps {
+            flows.entry(limit_prices).or_default().0 += delta_1_i;
+        }
+
+        // All 130 units would clear at a price of about 1.77, below the
+        // highest limit of about 2.1, but without the swap with that limit,
+        // 50 units fill at 3 and 30 units at 1.
+        let output_data = Dex::execute_batch(
+            &mut router,
+            1,
+            pair,
+            Ok(flows.into_iter().collect()),
+            LimitTiers::default(),
+        );
+        assert!(output_data.success);
+        assert_eq!((output_data.delta_1, output_data.lambda_2), (80, 180));
+
+        let mut refunded_1 = 0;
+        let mut claimed_2 = 0;
+        for (delta_1_i, limit_prices) in swaps {
+            let (lambda_1_i, lambda_2_i) =
+                output_data.pro_rata_outputs((delta_1_i, 0), limit_prices);
+            if lambda_1_i > 0 {
+                // The swap was excluded from the batch, so it is refunded in full.
+                assert_eq!((lambda_1_i, lambda_2_i), (delta_1_i, 0));
+                assert_eq!(limit_prices, limit(1 << 21));
+            } else {
+                // The swap was filled, at a price that meets its limit.
+                assert!(
+                    lambda_2_i as u128 * PRICE_SCALE as u128
+                        >= delta_1_i as u128 * limit_prices.min_price_2 as u128
+                );
+            }
+            refunded_1 += lambda_1_i;
+            claimed_2 += lambda_2_i;
+        }
+
+        // Every unit of input is either refunded or paid into the positions,
+        // and the claims pay out no more than the positions paid out.
+        let (reserves_1, reserves_2) = router.into_touched().fold((0, 0), |totals, liquidity| {
+            liquidity
+                .positions
+                .iter()
+                .fold(totals, |(r1, r2), metadata| {
+                    (
+                        r1 + u64::from(metadata.reserves.r1),
+                        r2 + u64::from(metadata.reserves.r2),
+                    )
+                })
+        });
+        assert_eq!(refunded_1 + reserves_1, 130);
+        assert_eq!(reserves_2, 1150 - output_data.lambda_2);
+        assert!(claimed_2 <= output_data.lambda_2);
+    }
+}
@@ -0,0 +1,489 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::anyhow;
+use penumbra_crypto::{
+    dex::{LimitPrices, LimitTiers, TradingPair},
+    BatchDecryptionShares, FlowEncryptionCommittee, SwapFlow,
+};
+use penumbra_eddy::{DecryptionTable, MockDecryptionTable};
+use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+use tokio::sync::OnceCell;
+
+/// The bitsize of the discrete logarithms in the decryption table.
+///
+/// Each 16-bit limb of a flow is decrypted separately, so a table of `2^20`
+/// entries can decrypt the sum of up to [`MAX_BATCH_SWAPS`] swaps on a
+/// trading pair in a single block.
+const DECRYPTION_TABLE_BITS: usize = 20;
+
+/// The maximum number of swaps on a trading pair in a single block.
+///
+/// Encryption proofs show that every limb of a swap's flow is a 16-bit value,
+/// so the sum of this many flows always fits in the decryption table.
+pub const MAX_BATCH_SWAPS: usize = 1 << (DECRYPTION_TABLE_BITS - 16);
+
+/// The minimum number of swaps in a limit tier for their flow to be decrypted
+/// separately from the other swaps on their trading pair.
+///
+/// Limit prices are public, so a tier with fewer swaps than this would reveal
+/// too much about their amounts, and is refunded without being decrypted.
+pub const MIN_TIER_SWAPS: usize = 4;
+
+/// The swap flows of a block, encrypted to the flow encryption committee and
+/// awaiting enough decryption shares to be executed.
+#[derive(Clone, Debug, Default)]
+pub struct EncryptedBatch {
+    /// The height of the block the swaps were included in.
+    pub height: u64,
+    /// The aggregate swap flow on each trading pair, of the swaps in each
+    /// limit tier.
+    ///
+    /// Swaps are aggregated by their limit prices, so that the swaps whose
+    /// limits the clearing prices would violate can be excluded from the batch.
+    pub flows: BTreeMap<(TradingPair, LimitPrices), SwapFlow>,
+    /// The limit tiers on each trading pair whose swaps are refunded without
+    /// being decrypted.
+    pub refunds: BTreeMap<TradingPair, LimitTiers>,
+    /// The decryption shares contributed by committee members so far.
+    pub shares: Vec<BatchDecryptionShares>,
+}
+
+impl EncryptedBatch {
+    /// Creates the batch of a block's swaps, given the aggregate flow and the
+    /// number of swaps in each limit tier on each trading pair.
+    ///
+    /// Only a trading pair's aggregate flow is ever decrypted on its own: when
+    /// a trading pair has swaps in more than one tier, each tier with fewer
+    /// than [`MIN_TIER_SWAPS`] swaps is refunded without being decrypted,
+    /// starting with the tiers that have a limit.
+    pub fn new(
+        height: u64,
+        swaps: BTreeMap<(TradingPair, LimitPrices), (SwapFlow, usize)>,
+    ) -> Self {
+        let mut tiers = BTreeMap::<TradingPair, Vec<(LimitPrices, SwapFlow, usize)>>::new();
+        for ((trading_pair, limit_prices), (flow, count)) in swaps {
+            tiers
+                .entry(trading_pair)
+                .or_default()
+                .push((limit_prices, flow, count));
+        }
+
+        let mut flows = BTreeMap::new();
+        let mut refunds = BTreeMap::new();
+        for (trading_pair, mut tiers) in tiers {
+            let mut refunded = LimitTiers::default();
+            // A trading pair's only tier is its aggregate flow, which is
+            // always decrypted.  Otherwise, refund the small tiers with a
+            // limit, and then the tier without a limit if it is small and any
+            // other tier is left.
+            for unlimited in [false, true] {
+                if tiers.len() > 1 {
+                    tiers.retain(|(limit_prices, _, count)| {
+                        let refund = *count < MIN_TIER_SWAPS
+                            && (*limit_prices == LimitPrices::default()) == unlimited;
+                        if refund {
+                            refunded.insert(limit_prices);
+                        }
+                        !refund
+                    });
+                }
+            }
+
+            if refunded != LimitTiers::default() {
+                refunds.insert(trading_pair, refunded);
+            }
+            for (limit_prices, flow, _) in tiers {
+                flows.insert((trading_pair, limit_prices), flow);
+            }
+        }
+
+        EncryptedBatch {
+            height,
+            flows,
+            refunds,
+            shares: Vec::new(),
+        }
+    }
+
+    /// Checks that `shares` are valid decryption shares for every flow of this
+    /// batch, from a committee member that has not yet contributed.
+    pub fn check_shares(
+        &self,
+        shares: &BatchDecryptionShares,
+        committee: &FlowEncryptionCommittee,
+    ) -> anyhow::Result<()> {
+        if self
+            .shares
+            .iter()
+            .any(|existing| existing.participant_index == shares.participant_index)
+        {
+            return Err(anyhow!(
+                "committee member {} already contributed shares for height {}",
+                shares.participant_index,
+                self.height
+            ));
+        }
+        if shares.flows.len() != self.flows.len() {
+            return Err(anyhow!(
+                "expected shares for {} flows, got {}",
+                self.flows.len(),
+                shares.flows.len()
+            ));
+        }
+        for (trading_pair, limit_prices, delta_1, delta_2) in shares.flows.iter() {
+            let flow = self
+                .flows
+                .get(&(*trading_pair, *limit_prices))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "no flow on trading pair {:?} with limit prices {:?}",
+                        trading_pair,
+                        limit_prices
+                    )
+                })?;
+            for (ciphertext, share) in [(&flow.0, delta_1), (&flow.1, delta_2)] {
+                if share.participant_index() != shares.participant_index {
+                    return Err(anyhow!("decryption share has mismatched participant index"));
+                }
+                ciphertext.verify_decryption_share(share, committee)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether enough shares have been contributed to decrypt the batch.
+    ///
+    /// A batch whose swaps were all refunded has nothing to decrypt.
+    pub fn is_decryptable(&self, committee: &FlowEncryptionCommittee) -> bool {
+        self.flows.is_empty() || self.shares.len() >= committee.threshold as usize
+    }
+
+    /// Returns the trading pairs with swaps in this batch.
+    pub fn trading_pairs(&self) -> BTreeSet<TradingPair> {
+        self.flows
+            .keys()
+            .map(|(trading_pair, _)| *trading_pair)
+            .chain(self.refunds.keys().copied())
+            .collect()
+    }
+
+    /// Returns the limit tiers on the given trading pair whose swaps are
+    /// refunded without being decrypted.
+    pub fn refunded_tiers(&self, trading_pair: &TradingPair) -> LimitTiers {
+        self.refunds.get(trading_pair).copied().unwrap_or_default()
+    }
+
+    /// Decrypts the flows on the given trading pair into the amounts of each
+    /// asset being swapped, by the limit prices of the swaps.
+    ///
+    /// The swaps in refunded tiers have no flow in the batch, so they are
+    /// never decrypted.
+    pub async fn decrypt(
+        &self,
+        trading_pair: &TradingPair,
+        committee: &FlowEncryptionCommittee,
+    ) -> anyhow::Result<Vec<(LimitPrices, (u64, u64))>> {
+        let table = decryption_table().await;
+
+        let mut flows = Vec::new();
+        for ((_, limit_prices), flow) in self
+            .flows
+            .iter()
+            .filter(|((pair, _), _)| pair == trading_pair)
+        {
+            let mut shares_1 = Vec::new();
+            let mut shares_2 = Vec::new();
+            for shares in self.shares.iter().take(committee.threshold as usize) {
+                let (_, _, delta_1, delta_2) = shares
+                    .flows
+                    .iter()
+                    .find(|(pair, limits, _, _)| pair == trading_pair && limits == limit_prices)
+                    .ok_or_else(|| anyhow!("missing decryption share"))?;
+                shares_1.push(flow.0.verify_decryption_share(delta_1, committee)?);
+                shares_2.push(flow.1.verify_decryption_share(delta_2, committee)?);
+            }
+
+            flows.push((
+                *limit_prices,
+                (
+                    flow.0.decrypt(shares_1, committee, table).await?,
+                    flow.1.decrypt(shares_2, committee, table).await?,
+                ),
+            ));
+        }
+        Ok(flows)
+    }
+}
+
+/// Builds the decryption table shared by every batch, if it hasn't been built
+/// yet.
+///
+/// Building the table takes a while, so this should be done when the node
+/// starts, rather than while executing the first batch.
+pub async fn init_decryption_table() {
+    decryption_table().await;
+}
+
+/// The decryption table shared by every batch.
+async fn decryption_table() -> &'static dyn DecryptionTable {
+    static TABLE: OnceCell<MockDecryptionTable> = OnceCell::const_new();
+    TABLE
+        .get_or_init(|| async {
+            tracing::info!(bits = DECRYPTION_TABLE_BITS, "building decryption table");
+            let table = MockDecryptionTable::default();
+            table
+                .initialize(DECRYPTION_TABLE_BITS)
+                .await
+                .expect("can initialize in-memory decryption table");
+            table
+        })
+        .await
+}
+
+impl Protobuf<pb::EncryptedBatch> for EncryptedBatch {}
+
+impl TryFrom<pb::EncryptedBatch> for EncryptedBatch {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::EncryptedBatch) -> Result<Self, Self::Error> {
+        Ok(EncryptedBatch {
+            height: msg.height,
+            flows: msg
+                .flows
+                .into_iter()
+                .map(|flow| {
+                    Ok((
+                        (
+                            flow.trading_pair
+                                .ok_or_else(|| anyhow!("missing trading_pair"))?
+                                .try_into()?,
+                            flow.limit_prices
+                                .ok_or_else(|| anyhow!("missing limit_prices"))?
+                                .try_into()?,
+                        ),
+                        flow.flow
+                            .ok_or_else(|| anyhow!("missing flow"))?
+                            .try_into()?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            refunds: msg
+                .refunds
+                .into_iter()
+                .map(|refund| {
+                    Ok((
+                        refund
+                            .trading_pair
+                            .ok_or_else(|| anyhow!("missing trading_pair"))?
+                            .try_into()?,
+                        refund
+                            .tiers
+                            .ok_or_else(|| anyhow!("missing tiers"))?
+                            .try_into()?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            shares: msg
+                .shares
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+impl From<EncryptedBatch> for pb::EncryptedBatch {
+    fn from(batch: EncryptedBatch) -> Self {
+        pb::EncryptedBatch {
+            height: batch.height,
+            flows: batch
+                .flows
+                .into_iter()
+                .map(
+                    |((trading_pair, limit_prices), flow)| pb::encrypted_batch::Flow {
+                        trading_pair: Some(trading_pair.into()),
+                        limit_prices: Some(limit_prices.into()),
+                        flow: Some(flow.into()),
+                    },
+                )
+                .collect(),
+            shares: batch.shares.into_iter().map(Into::into).collect(),
+            refunds: batch
+                .refunds
+                .into_iter()
+                .map(|(trading_pair, tiers)| pb::encrypted_batch::Refund {
+                    trading_pair: Some(trading_pair.into()),
+                    tiers: Some(tiers.into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A list of encrypted batches.
+///
+/// This is a newtype wrapper for a Vec that allows us to define a proto type.
+#[derive(Clone, Debug, Default)]
+pub struct EncryptedBatchList(pub Vec<EncryptedBatch>);
+
+impl Protobuf<pb::EncryptedBatchList> for EncryptedBatchList {}
+
+impl TryFrom<pb::EncryptedBatchList> for EncryptedBatchList {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::EncryptedBatchList) -> Result<Self, Self::Error> {
+        Ok(EncryptedBatchList(
+            msg.batches
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ))
+    }
+}
+
+impl From<EncryptedBatchList> for pb::EncryptedBatchList {
+    fn from(list: EncryptedBatchList) -> Self {
+        pb::EncryptedBatchList {
+            batches: list.0.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_crypto::{asset, FlowCiphertext};
+    use rand_core::OsRng;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn full_batch_decrypts() {
+        let (encryption_key, key_shares) = penumbra_eddy::dkg::deal(2, 3, OsRng).unwrap();
+        let committee = FlowEncryptionCommittee {
+            encryption_key,
+            public_key_shares: key_shares.iter().map(|s| s.public_key_share()).collect(),
+            threshold: 2,
+        };
+        let trading_pair = TradingPair::new(
+            asset::REGISTRY.parse_denom("nala").unwrap().id(),
+            asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
+        )
+        .unwrap();
+
+        // Every limb but the top one of each swap's flow is at its maximum.
+        let amount = 0xffff_ffff_ffffu64;
+        let mut flow = SwapFlow::default();
+        for _ in 0..MAX_BATCH_SWAPS {
+            let (delta_1, _, _) = FlowCiphertext::encrypt(amount, &encryption_key, OsRng).unwrap();
+            let (delta_2, _, _) = FlowCiphertext::encrypt(0, &encryption_key, OsRng).unwrap();
+            flow.0 += delta_1;
+            flow.1 += delta_2;
+        }
+
+        let batch = EncryptedBatch {
+            height: 1,
+            shares: key_shares[..2]
+                .iter()
+                .map(|key_share| BatchDecryptionShares {
+                    height: 1,
+                    participant_index: key_share.participant_index(),
+                    flows: vec![(
+                        trading_pair,
+                        LimitPrices::default(),
+                        flow.0.decryption_share(key_share, OsRng),
+                        flow.1.decryption_share(key_share, OsRng),
+                    )],
+                })
+                .collect(),
+            flows: [((trading_pair, LimitPrices::default()), flow)]
+                .into_iter()
+                .collect(),
+            refunds: Default::default(),
+        };
+
+        assert!(batch.is_decryptable(&committee));
+        assert_eq!(
+            batch.decrypt(&trading_pair, &committee).await.unwrap(),
+            vec![(LimitPrices::default(), (MAX_BATCH_SWAPS as u64 * amount, 0))]
+        );
+    }
+
+    #[tokio::test]
+    async fn lone_limit_swap_is_never_decrypted() {
+        let (encryption_key, key_shares) = penumbra_eddy::dkg::deal(2, 3, OsRng).unwrap();
+        let committee = FlowEncryptionCommittee {
+            encryption_key,
+            public_key_shares: key_shares.iter().map(|s| s.public_key_share()).collect(),
+            threshold: 2,
+        };
+        let trading_pair = TradingPair::new(
+            asset::REGISTRY.parse_denom("nala").unwrap().id(),
+            asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
+        )
+        .unwrap();
+        let encrypt = |amounts: &[u64]| {
+            let mut flow = SwapFlow::default();
+            for amount in amounts {
+                let (delta_1, _, _) =
+                    FlowCiphertext::encrypt(*amount, &encryption_key, OsRng).unwrap();
+                let (delta_2, _, _) = FlowCiphertext::encrypt(0, &encryption_key, OsRng).unwrap();
+                flow.0 += delta_1;
+                flow.1 += delta_2;
+            }
+            (flow, amounts.len())
+        };
+
+        // Enough swaps without a limit to be decrypted together, and a single
+        // swap with a limit no other swap uses.
+        let unlimited = LimitPrices::default();
+        let lone = LimitPrices {
+            min_price_1: 0,
+            min_price_2: 1 << 20,
+        };
+        let mut batch = EncryptedBatch::new(
+            1,
+            [
+                ((trading_pair, unlimited), encrypt(&[10; MIN_TIER_SWAPS])),
+                ((trading_pair, lone), encrypt(&[7])),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        // The lone swap's flow is not in the batch, so committee members never
+        // produce decryption shares for it, and it is refunded instead.
+        assert_eq!(batch.flows.len(), 1);
+        assert!(!batch.flows.contains_key(&(trading_pair, lone)));
+        assert!(batch.refunded_tiers(&trading_pair).contains(&lone));
+        assert!(!batch.refunded_tiers(&trading_pair).contains(&unlimited));
+
+        for key_share in &key_shares[..2] {
+            batch.shares.push(BatchDecryptionShares {
+                height: 1,
+                participant_index: key_share.participant_index(),
+                flows: batch
+                    .flows
+                    .iter()
+                    .map(|((trading_pair, limit_prices), flow)| {
+                        (
+                            *trading_pair,
+                            *limit_prices,
+                            flow.0.decryption_share(key_share, OsRng),
+                            flow.1.decryption_share(key_share, OsRng),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+        assert_eq!(
+            batch.decrypt(&trading_pair, &committee).await.unwrap(),
+            vec![(unlimited, (10 * MIN_TIER_SWAPS as u64, 0))]
+        );
+
+        // A swap alone on its trading pair is the pair's aggregate flow, so it
+        // is decrypted, as it would be without limits.
+        let batch = EncryptedBatch::new(1, [((trading_pair, lone), encrypt(&[7]))].into());
+        assert!(batch.flows.contains_key(&(trading_pair, lone)));
+        assert!(batch.refunds.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+use std::cmp::Ordering;
+
+use penumbra_crypto::{
+    asset::Amount,
+    dex::{
+        lp::{
+            position::{self, Metadata},
+            Reserves, TradingFunction,
+        },
+        TradingPair,
+    },
+};
+
+/// The liquidity available on a single trading pair: the open positions that
+/// swaps on that pair can be executed against.
+///
+/// Execution happens entirely in memory, so that the same code can be used to
+/// execute batch swaps in [`Dex::end_block`](super::Dex) and to reason about
+/// hypothetical trades without touching the state.  Callers are responsible for
+/// writing the updated positions back to the state.
+#[derive(Debug, Clone)]
+pub struct Liquidity {
+    pub trading_pair: TradingPair,
+    pub positions: Vec<Metadata>,
+}
+
+impl Liquidity {
+    /// Collects the liquidity for `trading_pair` out of the given positions,
+    /// discarding any positions that cannot be traded against.
+    pub fn new(trading_pair: TradingPair, positions: impl IntoIterator<Item = Metadata>) -> Self {
+        let positions = positions
+            .into_iter()
+            .filter(|metadata| {
+                metadata.position.pair == trading_pair
+                    && metadata.state == position::State::Opened
+                    && metadata.position.phi.is_valid()
+            })
+            .collect();
+
+        Self {
+            trading_pair,
+            positions,
+        }
+    }
+
+    /// Executes a batch swap with inputs `(delta_1, delta_2)` against the
+    /// positions, returning the outputs `(lambda_1, lambda_2)`.
+    ///
+    /// The input of asset 1 is filled first, followed by the input of asset 2,
+    /// so that the cross flow is netted against the reserves the first leg
+    /// added to the positions.
+    ///
+    /// Execution is all-or-nothing: if either input cannot be filled in full,
+    /// this returns `None` and leaves the positions unchanged.
+    pub fn execute_batch(&mut self, (delta_1, delta_2): (u64, u64)) -> Option<(u64, u64)> {
+        let mut scratch = self.clone();
+        let lambda_2 = scratch.fill_1_to_2(delta_1)?;
+        let lambda_1 = scratch.fill_2_to_1(delta_2)?;
+        *self = scratch;
+        Some((lambda_1, lambda_2))
+    }
+
+    /// Fills an input `delta_1` of asset 1 against the positions, best price
+    /// first, returning the total output of asset 2.
+    ///
+    /// Returns `None` and leaves the positions unchanged if there is not enough
+    /// liquidity to fill the entire input.
+    pub fn fill_1_to_2(&mut self, delta_1: u64) -> Option<u64> {
+        self.fill(delta_1, TradingFunction::cmp_price_1_to_2, |m, delta| {
+            m.position.phi.fill_1_to_2(delta, &m.reserves)
+        })
+    }
+
+    /// Fills an input `delta_2` of asset 2 against the positions, best price
+    /// first, returning the total output of asset 1.
+    ///
+    /// Returns `None` and leaves the positions unchanged if there is not enough
+    /// liquidity to fill the entire input.
+    pub fn fill_2_to_1(&mut self, delta_2: u64) -> Option<u64> {
+        self.fill(delta_2, TradingFunction::cmp_price_2_to_1, |m, delta| {
+            m.position.phi.fill_2_to_1(delta, &m.reserves)
+        })
+    }
+
+    /// The depth of the liquidity available for trading asset 1 into asset 2:
+    /// the total reserves of asset 2 offered at each effective price (in units
+    /// of asset 2 per unit of asset 1), best price first.
+    pub fn depth_1_to_2(&self) -> Vec<(f64, Amount)> {
+        self.depth(|phi| phi.effective_price_1_to_2(), |r| r.r2)
+    }
+
+    /// The depth of the liquidity available for trading asset 2 into asset 1:
+    /// the total reserves of asset 1 offered at each effective price (in units
+    /// of asset 1 per unit of asset 2), best price first.
+    pub fn depth_2_to_1(&self) -> Vec<(f64, Amount)> {
+        self.depth(|phi| phi.effective_price_2_to_1(), |r| r.r1)
+    }
+
+    fn depth(
+        &self,
+        price: impl Fn(&TradingFunction) -> f64,
+        reserves: impl Fn(&Reserves) -> Amount,
+    ) -> Vec<(f64, Amount)> {
+        let mut levels = self
+            .positions
+            .iter()
+            .map(|m| (price(&m.position.phi), reserves(&m.reserves)))
+            .filter(|(_, reserves)| *reserves != Amount::zero())
+            .collect::<Vec<_>>();
+        levels.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        levels.dedup_by(|(price, reserves), (level_price, level_reserves)| {
+            if price == level_price {
+                *level_reserves = *level_reserves + *reserves;
+                true
+            } else {
+                false
+            }
+        });
+        levels
+    }
+
+    fn fill(
+        &mut self,
+        delta: u64,
+        cmp_price: impl Fn(&TradingFunction, &TradingFunction) -> Ordering,
+        fill_one: impl Fn(&Metadata, Amount) -> (Amount, Reserves, Amount),
+    ) -> Option<u64> {
+        if delta == 0 {
+            return Some(0);
+        }
+
+        // Visit positions from the best to the worst price for the trader,
+        // breaking ties by position ID so that execution is deterministic.
+        let mut order = (0..self.positions.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.positions[a], &self.positions[b]);
+            match cmp_price(&b.position.phi, &a.position.phi) {
+                Ordering::Equal => a.position.id().cmp(&b.position.id()),
+                ordering => ordering,
+            }
+        });
+
+        let mut positions = self.positions.clone();
+        let mut remaining = delta;
+        let mut output = 0u64;
+        for i in order {
+            if remaining == 0 {
+                break;
+            }
+            let (unfilled, new_reserves, lambda) = fill_one(&positions[i], remaining.into());
+            positions[i].reserves = new_reserves;
+            remaining = unfilled.into();
+            output = output.checked_add(lambda.into())?;
+        }
+
+        if remaining > 0 {
+            tracing::debug!(?delta, ?remaining, "insufficient liquidity to fill input");
+            return None;
+        }
+
+        self.positions = positions;
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_crypto::{asset, dex::lp::position::Position};
+
+    use super::*;
+
+    fn position(pair: TradingPair, p: u64, q: u64, r1: u64, r2: u64, nonce: u8) -> Metadata {
+        Metadata {
+            position: Position {
+                pair,
+                phi: TradingFunction {
+                    fee: 0,
+                    k: 0u64.into(),
+                    p: p.into(),
+                    q: q.into(),
+                },
+                nonce: [nonce; 32],
+            },
+            state: position::State::Opened,
+            reserves: Reserves {
+                r1: r1.into(),
+                r2: r2.into(),
+            },
+        }
+    }
+
+    fn pair() -> TradingPair {
+        let gm = asset::REGISTRY.parse_unit("gm");
+        let gn = asset::REGISTRY.parse_unit("gn");
+        TradingPair::canonical_order_for((gm.id(), gn.id())).unwrap()
+    }
+
+    #[test]
+    fn fills_best_price_first() {
+        let pair = pair();
+        let mut liquidity = Liquidity::new(
+            pair,
+            [
+                position(pair, 1, 1, 0, 100, 1),
+                position(pair, 2, 1, 0, 100, 2),
+            ],
+        );
+
+        // The second position pays 2 units of asset 2 per unit of asset 1, so
+        // the first 50 units of input are filled there.
+        assert_eq!(liquidity.fill_1_to_2(60), Some(110));
+        assert_eq!(liquidity.positions[1].reserves.r2, 0u64.into());
+        assert_eq!(liquidity.positions[0].reserves.r2, 90u64.into());
+    }
+
+    #[test]
+    fn batch_is_all_or_nothing() {
+        let pair = pair();
+        let mut liquidity = Liquidity::new(pair, [position(pair, 1, 1, 0, 100, 1)]);
+
+        assert_eq!(liquidity.execute_batch((101, 0)), None);
+        assert_eq!(liquidity.positions[0].reserves.r2, 100u64.into());
+
+        // Filling the input of asset 1 provides the reserves to fill the input
+        // of asset 2.
+        assert_eq!(liquidity.execute_batch((100, 50)), Some((50, 100)));
+        assert_eq!(liquidity.positions[0].reserves.r1, 50u64.into());
+        assert_eq!(liquidity.positions[0].reserves.r2, 50u64.into());
+    }
+
+    #[test]
+    fn depth_aggregates_price_levels() {
+        let pair = pair();
+        let liquidity = Liquidity::new(
+            pair,
+            [
+                position(pair, 1, 1, 10, 100, 1),
+                position(pair, 2, 1, 0, 30, 2),
+                position(pair, 1, 1, 20, 50, 3),
+                position(pair, 1, 2, 0, 0, 4),
+            ],
+        );
+
+        assert_eq!(
+            liquidity.depth_1_to_2(),
+            vec![(2.0, 30u64.into()), (1.0, 150u64.into())]
+        );
+        // Positions without reserves of the output asset are omitted.
+        assert_eq!(liquidity.depth_2_to_1(), vec![(1.0, 30u64.into())]);
+    }
+
+    #[test]
+    fn ignores_closed_positions() {
+        let pair = pair();
+        let mut closed = position(pair, 1, 1, 0, 100, 1);
+        closed.state = position::State::Closed;
+        let liquidity = Liquidity::new(pair, [closed]);
+
+        assert!(liquidity.positions.is_empty());
+    }
+}
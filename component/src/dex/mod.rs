@@ -1,9 +1,18 @@
 mod component;
+mod encrypted_batch;
+mod execution;
 pub mod metrics;
+mod position_list;
+mod router;
 pub mod state_key;
-mod stub_cpmm;
+mod trading_pair_list;
 
-use stub_cpmm::StubCpmm;
+use encrypted_batch::EncryptedBatchList;
+use position_list::PositionList;
+use trading_pair_list::TradingPairList;
 
 pub use self::metrics::register_metrics;
-pub use component::{Dex, View};
+pub use component::{Dex, View, FLOW_DECRYPTION_TIMEOUT};
+pub use encrypted_batch::{init_decryption_table, EncryptedBatch, MAX_BATCH_SWAPS, MIN_TIER_SWAPS};
+pub use execution::Liquidity;
+pub use router::Router;
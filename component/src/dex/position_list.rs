@@ -0,0 +1,33 @@
+use penumbra_crypto::dex::lp::position;
+use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+use serde::{Deserialize, Serialize};
+
+/// A list of position IDs.
+///
+/// This is a newtype wrapper for a Vec that allows us to define a proto type.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "pb::PositionIdList", into = "pb::PositionIdList")]
+pub struct PositionList(pub Vec<position::Id>);
+
+impl Protobuf<pb::PositionIdList> for PositionList {}
+
+impl TryFrom<pb::PositionIdList> for PositionList {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::PositionIdList) -> Result<Self, Self::Error> {
+        Ok(PositionList(
+            msg.position_ids
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ))
+    }
+}
+
+impl From<PositionList> for pb::PositionIdList {
+    fn from(list: PositionList) -> Self {
+        pb::PositionIdList {
+            position_ids: list.0.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use penumbra_crypto::{asset, dex::TradingPair};
+
+use super::Liquidity;
+
+/// The maximum number of hops in a route, i.e., the number of trading pairs a
+/// single flow can be filled through.
+const MAX_HOPS: usize = 3;
+
+/// Routes swaps across the liquidity of all trading pairs.
+///
+/// A flow on a trading pair with no liquidity of its own (or with liquidity
+/// at a worse price) can be filled along a route through intermediate assets,
+/// e.g., from `gn` to the staking token and from the staking token to `gm`.
+/// The input and output of a routed flow are still accounted to the trading
+/// pair it was submitted on, so that the resulting clearing data can be
+/// consumed by `SwapClaim`s on that pair, exactly as for a direct fill.
+///
+/// Like [`Liquidity`], the router executes entirely in memory.  Callers are
+/// responsible for writing the liquidity it touched back to the state.
+#[derive(Debug, Clone, Default)]
+pub struct Router {
+    liquidity: BTreeMap<TradingPair, Liquidity>,
+    touched: BTreeSet<TradingPair>,
+}
+
+impl Router {
+    /// Creates a router over the given liquidity.
+    pub fn new(liquidity: impl IntoIterator<Item = Liquidity>) -> Self {
+        Self {
+            liquidity: liquidity
+                .into_iter()
+                .map(|liquidity| (liquidity.trading_pair, liquidity))
+                .collect(),
+            touched: Default::default(),
+        }
+    }
+
+    /// Executes a batch swap with inputs `(delta_1, delta_2)` on
+    /// `trading_pair`, returning the outputs `(lambda_1, lambda_2)`.
+    ///
+    /// As with [`Liquidity::execute_batch`], the input of asset 1 is filled
+    /// first, followed by the input of asset 2, and execution is
+    /// all-or-nothing: if either input cannot be filled in full, this returns
+    /// `None` and leaves the liquidity unchanged.
+    pub fn execute_batch(
+        &mut self,
+        trading_pair: TradingPair,
+        (delta_1, delta_2): (u64, u64),
+    ) -> Option<(u64, u64)> {
+        let mut scratch = self.clone();
+        let lambda_2 = scratch.fill(trading_pair.asset_1(), trading_pair.asset_2(), delta_1)?;
+        let lambda_1 = scratch.fill(trading_pair.asset_2(), trading_pair.asset_1(), delta_2)?;
+        *self = scratch;
+        Some((lambda_1, lambda_2))
+    }
+
+    /// Fills an input `delta` of the `input` asset into the `output` asset,
+    /// along whichever route gives the largest output.
+    ///
+    /// The entire input is filled along a single route.  Routes are compared
+    /// shortest first, so that a direct fill is preferred over a routed one
+    /// with the same output.
+    ///
+    /// Returns `None` and leaves the liquidity unchanged if no route can fill
+    /// the entire input.
+    pub fn fill(&mut self, input: asset::Id, output: asset::Id, delta: u64) -> Option<u64> {
+        if delta == 0 {
+            return Some(0);
+        }
+
+        let mut best: Option<(u64, Vec<Liquidity>)> = None;
+        for route in self.routes(input, output) {
+            if let Some((lambda, updated)) = self.simulate(&route, delta) {
+                // A fill that consumes the input without producing any output
+                // is no better than failing the swap and refunding the input.
+                if lambda > 0 && best.as_ref().map_or(true, |(best, _)| lambda > *best) {
+                    best = Some((lambda, updated));
+                }
+            }
+        }
+
+        let (lambda, updated) = best?;
+        for liquidity in updated {
+            self.touched.insert(liquidity.trading_pair);
+            self.liquidity.insert(liquidity.trading_pair, liquidity);
+        }
+        Some(lambda)
+    }
+
+    /// Consumes the router, returning the liquidity of every trading pair
+    /// whose positions were traded against.
+    pub fn into_touched(mut self) -> impl Iterator<Item = Liquidity> {
+        let touched = std::mem::take(&mut self.touched);
+        touched
+            .into_iter()
+            .filter_map(move |trading_pair| self.liquidity.remove(&trading_pair))
+    }
+
+    /// Enumerates the routes from `input` to `output`, as sequences of assets
+    /// with at most [`MAX_HOPS`] hops, ordered from shortest to longest.
+    fn routes(&self, input: asset::Id, output: asset::Id) -> Vec<Vec<asset::Id>> {
+        let mut neighbors = BTreeMap::<asset::Id, BTreeSet<asset::Id>>::new();
+        for (trading_pair, liquidity) in self.liquidity.iter() {
+            if liquidity.positions.is_empty() {
+                continue;
+            }
+            let (asset_1, asset_2) = (trading_pair.asset_1(), trading_pair.asset_2());
+            neighbors.entry(asset_1).or_default().insert(asset_2);
+            neighbors.entry(asset_2).or_default().insert(asset_1);
+        }
+
+        let mut routes = Vec::new();
+        let mut paths = vec![vec![input]];
+        for _ in 0..MAX_HOPS {
+            let mut next_paths = Vec::new();
+            for path in paths {
+                let last = *path.last().expect("paths are non-empty");
+                for &next in neighbors.get(&last).into_iter().flatten() {
+                    // Only consider simple paths, so that no trading pair is
+                    // traded against twice along the same route.
+                    if path.contains(&next) {
+                        continue;
+                    }
+                    let mut next_path = path.clone();
+                    next_path.push(next);
+                    if next == output {
+                        routes.push(next_path);
+                    } else {
+                        next_paths.push(next_path);
+                    }
+                }
+            }
+            paths = next_paths;
+        }
+
+        routes
+    }
+
+    /// Fills `delta` along `route` without modifying the router, returning
+    /// the output and the updated liquidity of each trading pair on the route.
+    fn simulate(&self, route: &[asset::Id], delta: u64) -> Option<(u64, Vec<Liquidity>)> {
+        let mut amount = delta;
+        let mut updated = Vec::new();
+        for hop in route.windows(2) {
+            let (from, to) = (hop[0], hop[1]);
+            let trading_pair = TradingPair::canonical_order_for((from, to)).ok()?;
+            let mut liquidity = self.liquidity.get(&trading_pair)?.clone();
+            amount = if trading_pair.asset_1() == from {
+                liquidity.fill_1_to_2(amount)?
+            } else {
+                liquidity.fill_2_to_1(amount)?
+            };
+            updated.push(liquidity);
+        }
+        Some((amount, updated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_crypto::dex::lp::{
+        position::{self, Metadata, Position},
+        Reserves, TradingFunction,
+    };
+
+    use super::*;
+
+    fn asset(unit: &str) -> asset::Id {
+        asset::REGISTRY.parse_unit(unit).id()
+    }
+
+    /// Returns a position selling up to `reserves` of `output` for `input`, at
+    /// `price` units of `output` per unit of `input`.
+    fn liquidity(input: &str, output: &str, price: u64, reserves: u64, nonce: u8) -> Liquidity {
+        let (input, output) = (asset(input), asset(output));
+        let pair = TradingPair::canonical_order_for((input, output)).unwrap();
+        let ((p, q), reserves) = if pair.asset_1() == input {
+            ((price, 1), (0, reserves))
+        } else {
+            ((1, price), (reserves, 0))
+        };
+        Liquidity::new(
+            pair,
+            [Metadata {
+                position: Position {
+                    pair,
+                    phi: TradingFunction {
+                        fee: 0,
+                        k: 0u64.into(),
+                        p: p.into(),
+                        q: q.into(),
+                    },
+                    nonce: [nonce; 32],
+                },
+                state: position::State::Opened,
+                reserves: Reserves {
+                    r1: reserves.0.into(),
+                    r2: reserves.1.into(),
+                },
+            }],
+        )
+    }
+
+    #[test]
+    fn fills_through_intermediate_asset() {
+        let mut router = Router::new([
+            liquidity("gn", "upenumbra", 2, 1000, 1),
+            liquidity("upenumbra", "gm", 3, 1000, 2),
+        ]);
+
+        assert_eq!(router.fill(asset("gn"), asset("gm"), 10), Some(60));
+        assert_eq!(router.into_touched().count(), 2);
+    }
+
+    #[test]
+    fn prefers_the_best_route() {
+        let mut router = Router::new([
+            liquidity("gn", "gm", 5, 1000, 1),
+            liquidity("gn", "upenumbra", 2, 1000, 2),
+            liquidity("upenumbra", "gm", 3, 1000, 3),
+        ]);
+
+        assert_eq!(router.fill(asset("gn"), asset("gm"), 10), Some(60));
+
+        // The route through the staking token has been traded against, but
+        // the direct position has not.
+        let direct = TradingPair::canonical_order_for((asset("gn"), asset("gm"))).unwrap();
+        assert!(router
+            .into_touched()
+            .all(|liquidity| liquidity.trading_pair != direct));
+    }
+
+    #[test]
+    fn routed_batch_is_all_or_nothing() {
+        let mut router = Router::new([
+            liquidity("gn", "upenumbra", 1, 100, 1),
+            liquidity("upenumbra", "gm", 1, 50, 2),
+        ]);
+        let pair = TradingPair::canonical_order_for((asset("gn"), asset("gm"))).unwrap();
+        let deltas = if pair.asset_1() == asset("gn") {
+            (100, 0)
+        } else {
+            (0, 100)
+        };
+
+        assert_eq!(router.execute_batch(pair, deltas), None);
+        assert_eq!(router.into_touched().count(), 0);
+    }
+}
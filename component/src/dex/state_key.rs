@@ -1,18 +1,38 @@
-use penumbra_crypto::dex::TradingPair;
+use penumbra_crypto::dex::{lp::position, TradingPair};
 
-pub fn stub_cpmm_reserves(trading_pair: &TradingPair) -> String {
+pub fn output_data(height: u64, trading_pair: TradingPair) -> String {
     format!(
-        "dex/stub_cpmm_reserves/{}/{}",
+        "dex/output/{}/{}/{}",
+        height,
         &trading_pair.asset_1(),
         &trading_pair.asset_2()
     )
 }
 
-pub fn output_data(height: u64, trading_pair: TradingPair) -> String {
+pub fn position_by_id(id: &position::Id) -> String {
+    format!("dex/position/{}", id)
+}
+
+pub fn trading_pairs_with_positions() -> &'static str {
+    "dex/trading_pairs"
+}
+
+pub fn positions_by_trading_pair(trading_pair: &TradingPair) -> String {
     format!(
-        "dex/output/{}/{}/{}",
-        height,
+        "dex/positions/{}/{}",
         &trading_pair.asset_1(),
         &trading_pair.asset_2()
     )
 }
+
+pub fn position_nonce(nonce: &[u8; 32]) -> String {
+    format!("dex/position_nonce/{}", hex::encode(nonce))
+}
+
+pub fn flow_encryption_committee() -> &'static str {
+    "dex/flow_encryption_committee"
+}
+
+pub fn pending_batches() -> &'static str {
+    "dex/pending_batches"
+}
@@ -0,0 +1,33 @@
+use penumbra_crypto::dex::TradingPair;
+use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+use serde::{Deserialize, Serialize};
+
+/// A list of trading pairs.
+///
+/// This is a newtype wrapper for a Vec that allows us to define a proto type.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "pb::TradingPairList", into = "pb::TradingPairList")]
+pub struct TradingPairList(pub Vec<TradingPair>);
+
+impl Protobuf<pb::TradingPairList> for TradingPairList {}
+
+impl TryFrom<pb::TradingPairList> for TradingPairList {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::TradingPairList) -> Result<Self, Self::Error> {
+        Ok(TradingPairList(
+            msg.trading_pairs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ))
+    }
+}
+
+impl From<TradingPairList> for pb::TradingPairList {
+    fn from(list: TradingPairList) -> Self {
+        pb::TradingPairList {
+            trading_pairs: list.0.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -2,12 +2,17 @@ use anyhow::{Context as _, Result};
 
 use super::proposal::{self, chain_params};
 use penumbra_transaction::action::{
-    ProposalSubmit, ProposalWithdraw, ProposalWithdrawBody, ValidatorVote, ValidatorVoteBody,
+    DelegatorVote, DelegatorVoteBody, ProposalSubmit, ProposalWithdraw, ProposalWithdrawBody,
+    ValidatorVote, ValidatorVoteBody,
 };
 
 pub mod stateless {
+    use std::collections::BTreeMap;
+
+    use penumbra_crypto::asset::{self, Amount};
     use penumbra_proto::Protobuf;
-    use penumbra_transaction::action::Proposal;
+    use penumbra_tct as tct;
+    use penumbra_transaction::{action::Proposal, plan::ActionPlan, AuthHash};
 
     use super::*;
 
@@ -50,11 +55,41 @@ pub mod stateless {
                 }
             }
             DaoSpend {
-                schedule_transactions: _,
-                cancel_transactions: _,
+                schedule_transactions,
+                cancel_transactions: _, // checked statefully against the existing schedule
             } => {
-                // TODO: check that scheduled transactions are valid without any witness or auth data
-                anyhow::bail!("DAO spend proposals are not yet supported")
+                // The DAO holds no keys, so it can only execute transactions which need no witness
+                // or auth data: those consisting only of outputs, paid out of the DAO treasury
+                for (_, transaction) in schedule_transactions {
+                    if transaction.actions.is_empty() {
+                        anyhow::bail!(
+                            "scheduled DAO transactions must contain at least one output"
+                        );
+                    }
+                    if !transaction
+                        .actions
+                        .iter()
+                        .all(|action| matches!(action, ActionPlan::Output(_)))
+                    {
+                        anyhow::bail!("scheduled DAO transactions may only contain outputs");
+                    }
+                    if transaction.fee.0.amount != Amount::zero() {
+                        anyhow::bail!("scheduled DAO transactions must not pay a fee");
+                    }
+                    // The DAO treasury can never hold more than `u64::MAX` of any asset, so a
+                    // transaction whose outputs sum past it could never be executed
+                    let mut totals = BTreeMap::<asset::Id, u64>::new();
+                    for output in transaction.output_plans() {
+                        let total = totals.entry(output.value.asset_id).or_default();
+                        *total = total
+                            .checked_add(output.value.amount.inner)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "scheduled DAO transaction outputs overflow the total amount"
+                                )
+                            })?;
+                    }
+                }
             }
         }
 
@@ -79,16 +114,57 @@ pub mod stateless {
 
         Ok(())
     }
+
+    pub fn delegator_vote(
+        auth_hash: &AuthHash,
+        anchor: tct::Root,
+        DelegatorVote {
+            body:
+                DelegatorVoteBody {
+                    proposal: _, // Checked in stateful verification
+                    start_position,
+                    vote: _, // All votes are valid, so we don't need to do anything with this
+                    value,
+                    nullifier,
+                    rk,
+                },
+            auth_sig,
+            proof,
+        }: &DelegatorVote,
+    ) -> Result<()> {
+        // Check the signature using the randomized spend key, over the whole transaction:
+        rk.verify(auth_hash.as_ref(), auth_sig)
+            .context("delegator vote auth signature failed to verify")?;
+
+        if value.amount == 0u64.into() {
+            anyhow::bail!("delegator vote must be cast with a nonzero amount");
+        }
+
+        proof
+            .verify(anchor, *start_position, *value, *nullifier, *rk)
+            .context("a delegator vote proof did not verify")?;
+
+        // This is stateless verification, so we still need to check that the proposal being voted
+        // on exists, that it started at the claimed position, that the value is a delegation token,
+        // and that this note hasn't voted on it already.
+
+        Ok(())
+    }
 }
 
 pub mod stateful {
     use super::super::View as _;
     use super::*;
+    use crate::shielded_pool::View as _;
     use crate::stake::View as _;
     use penumbra_chain::View as _;
-    use penumbra_crypto::{GovernanceKey, IdentityKey, STAKING_TOKEN_DENOM};
+    use penumbra_crypto::{
+        DelegationToken, GovernanceKey, IdentityKey, Nullifier, Value, STAKING_TOKEN_DENOM,
+    };
     use penumbra_storage::State;
+    use penumbra_tct as tct;
     use penumbra_transaction::{action::ProposalPayload, AuthHash};
+    use std::collections::BTreeMap;
 
     pub async fn proposal_submit(
         state: &State,
@@ -126,6 +202,16 @@ pub mod stateful {
                     // TODO: should this return a more descriptive error?
                     return Err(anyhow::anyhow!("invalid chain parameters"));
                 }
+
+                let new_chain_params =
+                    chain_params::resolve_parameters(new_parameters, &old_parameters)?;
+                if !chain_params::unbondings_preserved(state, &new_chain_params, &old_parameters)
+                    .await?
+                {
+                    anyhow::bail!(
+                        "cannot shrink unbonding epochs below the unbondings currently in flight"
+                    );
+                }
             }
             ProposalPayload::DaoSpend {
                 schedule_transactions,
@@ -137,7 +223,34 @@ pub mod stateful {
                 for (scheduled_height, _) in cancel_transactions.iter() {
                     height_in_future_of_voting_end(state, *scheduled_height).await?;
                 }
-                // TODO: check that all transactions to cancel exist already and match auth hash
+
+                // Each cancellation removes one matching transaction, so there must be at least as
+                // many matching transactions scheduled as there are cancellations of them
+                let mut cancellations = BTreeMap::<(u64, [u8; 64]), usize>::new();
+                for (scheduled_height, auth_hash) in cancel_transactions.iter() {
+                    let mut key = [0u8; 64];
+                    key.copy_from_slice(auth_hash.as_ref());
+                    *cancellations.entry((*scheduled_height, key)).or_default() += 1;
+                }
+                for ((scheduled_height, auth_hash), count) in cancellations {
+                    let scheduled = state
+                        .dao_transactions(scheduled_height)
+                        .await?
+                        .iter()
+                        .filter(|(_, transaction)| {
+                            transaction.plan_auth_hash().as_ref() == auth_hash.as_slice()
+                        })
+                        .count();
+                    if scheduled < count {
+                        anyhow::bail!(
+                            "cannot cancel {} DAO transactions with auth hash {} at height {}: only {} are scheduled",
+                            count,
+                            hex::encode(auth_hash),
+                            scheduled_height,
+                            scheduled
+                        );
+                    }
+                }
             }
         }
 
@@ -232,6 +345,90 @@ pub mod stateful {
         Ok(())
     }
 
+    pub async fn delegator_vote(
+        state: &State,
+        DelegatorVote {
+            body:
+                DelegatorVoteBody {
+                    proposal,
+                    start_position,
+                    vote: _, // All votes are valid, so we don't need to do anything with this
+                    value,
+                    nullifier,
+                    rk: _, // We already checked this in stateless verification
+                },
+            auth_sig: _, // We already checked this in stateless verification
+            proof: _,    // We already checked this in stateless verification
+        }: &DelegatorVote,
+    ) -> Result<()> {
+        proposal_voteable(state, *proposal).await?;
+        start_position_matches_proposal(state, *proposal, *start_position).await?;
+        nullifier_has_not_voted(state, *proposal, *nullifier).await?;
+        delegation_validator(state, value).await?;
+        Ok(())
+    }
+
+    async fn start_position_matches_proposal(
+        state: &State,
+        proposal_id: u64,
+        start_position: tct::Position,
+    ) -> Result<()> {
+        let proposal_start_position = state
+            .proposal_voting_start_position(proposal_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("proposal {} does not exist", proposal_id))?;
+
+        if start_position != proposal_start_position {
+            anyhow::bail!(
+                "delegator vote start position {} does not match proposal {} start position {}",
+                u64::from(start_position),
+                proposal_id,
+                u64::from(proposal_start_position),
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn nullifier_has_not_voted(
+        state: &State,
+        proposal_id: u64,
+        nullifier: Nullifier,
+    ) -> Result<()> {
+        state
+            .check_nullifier_unvoted(proposal_id, nullifier)
+            .await?;
+
+        // The proof only shows the note existed before the proposal started, not that it was still
+        // unspent then, so we additionally require that the note has not been spent at all. This
+        // means delegators must vote before moving their staked notes, but it rules out voting with
+        // a note that was spent before the proposal started.
+        state
+            .check_nullifier_unspent(nullifier)
+            .await
+            .context("staked note used to vote has already been spent")?;
+
+        Ok(())
+    }
+
+    /// Determine the validator to whom the voted value is delegated, failing if it is not a
+    /// delegation token for a known validator.
+    pub async fn delegation_validator(state: &State, value: &Value) -> Result<IdentityKey> {
+        let denom = state
+            .denom_by_asset(&value.asset_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("unknown asset {} in delegator vote", value.asset_id))?;
+        let identity_key = DelegationToken::try_from(denom)
+            .context("delegator votes must be cast with delegation tokens")?
+            .validator();
+
+        if state.validator(&identity_key).await?.is_none() {
+            anyhow::bail!("validator {} does not exist", identity_key);
+        }
+
+        Ok(identity_key)
+    }
+
     async fn proposal_voteable(state: &State, proposal_id: u64) -> Result<()> {
         if let Some(proposal_state) = state.proposal_state(proposal_id).await? {
             use proposal::State::*;
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use penumbra_chain::genesis;
@@ -8,7 +10,7 @@ use tracing::instrument;
 
 use crate::{Component, Context};
 
-use super::{check, execute};
+use super::{check, execute, View as _};
 
 pub struct Governance {
     state: State,
@@ -26,10 +28,24 @@ impl Component for Governance {
     async fn init_chain(&mut self, _app_state: &genesis::AppState) {}
 
     #[instrument(name = "governance", skip(self, _ctx, _begin_block))]
-    async fn begin_block(&mut self, _ctx: Context, _begin_block: &abci::request::BeginBlock) {}
+    async fn begin_block(&mut self, _ctx: Context, _begin_block: &abci::request::BeginBlock) {
+        // A block can only begin while the chain is halted if the operator has chosen to resume
+        // from the halt, so record that the chain has resumed
+        if let Some(proposal_id) = self
+            .state
+            .halted_by()
+            .await
+            .expect("can check whether the chain is halted")
+        {
+            tracing::info!(proposal = %proposal_id, "resuming from emergency chain halt");
+            self.state.resume_from_halt(proposal_id).await;
+        }
+    }
 
     #[instrument(name = "governance", skip(_ctx, tx))]
     fn check_tx_stateless(_ctx: Context, tx: &Transaction) -> Result<()> {
+        let auth_hash = tx.transaction_body().auth_hash();
+
         for proposal_submit in tx.proposal_submits() {
             check::stateless::proposal_submit(proposal_submit)?;
         }
@@ -39,10 +55,18 @@ impl Component for Governance {
         for validator_vote in tx.validator_votes() {
             check::stateless::validator_vote(validator_vote)?;
         }
-        // TODO: fill in when delegator votes happen
-        // for delegator_vote in tx.delegator_votes() {
-        //     check::stateless::delegator_vote(delegator_vote)?;
-        // }
+
+        let mut voted_nullifiers = BTreeSet::new();
+        for delegator_vote in tx.delegator_votes() {
+            check::stateless::delegator_vote(&auth_hash, tx.anchor, delegator_vote)?;
+
+            // Check the same staked note doesn't vote twice in this transaction.
+            if !voted_nullifiers
+                .insert((delegator_vote.body.proposal, delegator_vote.body.nullifier))
+            {
+                return Err(anyhow::anyhow!("Double vote"));
+            }
+        }
 
         Ok(())
     }
@@ -60,10 +84,9 @@ impl Component for Governance {
         for validator_vote in tx.validator_votes() {
             check::stateful::validator_vote(&self.state, validator_vote).await?;
         }
-        // TODO: fill in when delegator votes happen
-        // for delegator_vote in tx.delegator_votes() {
-        //     check::stateful::delegator_vote(&self.state, delegator_vote).await?;
-        // }
+        for delegator_vote in tx.delegator_votes() {
+            check::stateful::delegator_vote(&self.state, delegator_vote).await?;
+        }
 
         Ok(())
     }
@@ -79,10 +102,9 @@ impl Component for Governance {
         for validator_vote in tx.validator_votes() {
             execute::validator_vote(&self.state, validator_vote).await;
         }
-        // TODO: fill in when delegator votes happen
-        // for delegator_vote in tx.delegator_votes() {
-        //     execute::delegator_vote(&self.state, delegator_vote).await;
-        // }
+        for delegator_vote in tx.delegator_votes() {
+            execute::delegator_vote(&self.state, delegator_vote).await;
+        }
     }
 
     #[instrument(name = "governance", skip(self, _ctx, _end_block))]
@@ -1,14 +1,17 @@
 use crate::governance::proposal::Outcome;
 
 use super::{
+    check,
     proposal::{self, chain_params},
     tally, View as _,
 };
+use crate::shielded_pool::View as _;
 use penumbra_chain::View as _;
+use penumbra_crypto::{Value, STAKING_TOKEN_ASSET_ID};
 use penumbra_storage::State;
 use penumbra_transaction::action::{
-    ProposalPayload, ProposalSubmit, ProposalWithdraw, ProposalWithdrawBody, ValidatorVote,
-    ValidatorVoteBody,
+    DelegatorVote, DelegatorVoteBody, ProposalPayload, ProposalSubmit, ProposalWithdraw,
+    ProposalWithdrawBody, ValidatorVote, ValidatorVoteBody,
 };
 use tracing::instrument;
 
@@ -63,6 +66,17 @@ pub async fn proposal_submit(
         .await;
     state.put_proposal_voting_end(proposal_id, voting_end).await;
 
+    // Record the position in the NCT as of the end of the previous block, so that delegators can
+    // vote with any staked note that existed before the proposal was submitted
+    let start_position = state
+        .nct_position()
+        .await
+        .expect("can get NCT position")
+        .expect("NCT position is set after genesis");
+    state
+        .put_proposal_voting_start_position(proposal_id, start_position)
+        .await;
+
     tracing::debug!(proposal = %proposal_id, "created proposal");
 }
 
@@ -108,8 +122,44 @@ pub async fn validator_vote(
     tracing::debug!(proposal = %proposal, "cast validator vote");
 }
 
-// TODO: fill in when delegator votes happen
-// pub async fn delegator_vote(state: &State, delegator_vote: &DelegatorVote) {}
+#[instrument(skip(state))]
+pub async fn delegator_vote(
+    state: &State,
+    DelegatorVote {
+        auth_sig: _,
+        proof: _,
+        body:
+            DelegatorVoteBody {
+                proposal,
+                start_position: _, // This is only used for checks so that the proof can be verified
+                vote,
+                value,
+                nullifier,
+                rk: _, // This is only used for checks so that stateless verification can be done on the signature
+            },
+    }: &DelegatorVote,
+) {
+    let identity_key = check::stateful::delegation_validator(state, value)
+        .await
+        .expect("delegator vote was checked to be a delegation token");
+    let height = state
+        .get_block_height()
+        .await
+        .expect("can get block height");
+
+    state
+        .cast_delegator_vote(
+            *proposal,
+            identity_key,
+            *vote,
+            value.amount.into(),
+            *nullifier,
+            height,
+        )
+        .await;
+
+    tracing::debug!(proposal = %proposal, "cast delegator vote");
+}
 
 #[instrument(skip(state))]
 pub async fn enact_all_passed_proposals(state: &State) {
@@ -132,7 +182,6 @@ pub async fn enact_all_passed_proposals(state: &State) {
         .await
         .expect("can get unfinished proposals")
     {
-        // TODO: tally delegator votes
         if let Some(outcome) = parameters
             .tally(state, circumstance, proposal_id)
             .await
@@ -149,7 +198,19 @@ pub async fn enact_all_passed_proposals(state: &State) {
                     .await
                     .expect("can add proposal refund");
             } else {
-                tracing::debug!(proposal = %proposal_id, "burning proposal deposit for vetoed proposal");
+                tracing::debug!(proposal = %proposal_id, "forfeiting proposal deposit for vetoed proposal to the DAO");
+                let deposit_amount = state
+                    .proposal_deposit_amount(proposal_id)
+                    .await
+                    .expect("can get proposal deposit amount")
+                    .expect("deposit amount must exist for proposal");
+                state
+                    .dao_deposit(Value {
+                        amount: deposit_amount,
+                        asset_id: *STAKING_TOKEN_ASSET_ID,
+                    })
+                    .await
+                    .expect("can deposit forfeited proposal deposit to the DAO");
             }
 
             // If the proposal passes, enact it now
@@ -187,33 +248,20 @@ async fn enact_proposal(state: &State, proposal_id: u64) {
             // Nothing to do for signaling proposals
         }
         ProposalPayload::Emergency { halt_chain } => {
-            let height = state
-                .get_block_height()
-                .await
-                .expect("can get block height");
-
-            // If the proposal calls to halt the chain...
+            // If the proposal calls to halt the chain, record the halt: the consensus worker will
+            // refuse to begin any further blocks until operators resume from this proposal
             if halt_chain {
-                // Check to see if the operator has set the environment variable indicating they
-                // wish to resume from this particular chain halt, i.e. the chain has already halted
-                // and they are bringing it back up again
-                if std::env::var("PD_RESUME_FROM_EMERGENCY_HALT_PROPOSAL")
-                    .ok()
-                    .and_then(|v| v.parse::<u64>().ok()) // value of var must be number
-                    .filter(|&resume_from| resume_from == proposal_id) // number must be this proposal's id (to prevent an always-on resume functionality)
-                    .is_some()
-                {
-                    // If so, just print an information message, and don't halt the chain
-                    tracing::info!(proposal = %proposal_id, %height, "resuming from emergency chain halt");
-                } else {
-                    // If not, print an informational message and immediately exit the process
-                    tracing::error!(proposal = %proposal_id, %height, "emergency proposal passed, calling for immediate chain halt");
-                    std::process::exit(0);
-                }
+                let height = state
+                    .get_block_height()
+                    .await
+                    .expect("can get block height");
+
+                tracing::error!(proposal = %proposal_id, %height, "emergency proposal passed, halting the chain after this block");
+                state.put_halt_proposal(proposal_id).await;
             }
         }
         ProposalPayload::ParameterChange {
-            effective_height: _,
+            effective_height,
             new_parameters,
         } => {
             let height = state
@@ -221,45 +269,114 @@ async fn enact_proposal(state: &State, proposal_id: u64) {
                 .await
                 .expect("can get block height");
 
-            // Since other proposals may have changed the chain parameters in the meantime,
-            // and parameter validation must ensure consistency across all parameters, we
-            // need to perform a final validation step prior to applying the new parameters.
-            let old_parameters = state
-                .get_chain_params()
-                .await
-                .expect("can get chain parameters");
-
-            if !chain_params::is_valid_stateless(&new_parameters)
-                || !chain_params::is_valid_stateful(&new_parameters, &old_parameters)
-            {
-                // The parameters are invalid, so we cannot apply them.
+            if !chain_params::is_valid_stateless(&new_parameters) {
+                // The parameters are invalid, so we cannot schedule them.
                 tracing::info!(proposal = %proposal_id, %height, "chain param proposal passed, however the new parameters are invalid");
-                // TODO: should there be a more descriptive error message here?
                 return;
             }
 
-            // Apply the new (valid) parameter changes immediately:
-            let new_params = chain_params::resolve_parameters(&new_parameters, &old_parameters)
-                .expect("can resolve validated parameters");
-
-            state.put_chain_params(new_params).await;
+            // Schedule the new parameters to be applied at their effective height, or in this
+            // block if that height has already been reached. Stateful validation is deferred until
+            // then, since other proposals may change the chain parameters in the meantime.
+            let effective_height = effective_height.max(height);
+            tracing::debug!(proposal = %proposal_id, %effective_height, "scheduling chain parameter change");
+            state
+                .schedule_parameter_change(effective_height, proposal_id)
+                .await
+                .expect("can schedule parameter change");
         }
         ProposalPayload::DaoSpend {
-            schedule_transactions: _,
-            cancel_transactions: _,
+            schedule_transactions,
+            cancel_transactions,
         } => {
-            // TODO: schedule transaction cancellations by removing the first matching one from the
-            // front of the schedule for their effective block
-            // TODO: schedule new transactions by appending them to the end of the schedule for their
+            // Cancel transactions first, by removing the first matching one from the schedule for
+            // their effective block
+            for (scheduled_height, auth_hash) in cancel_transactions {
+                if !state
+                    .cancel_dao_transaction(scheduled_height, auth_hash)
+                    .await
+                    .expect("can cancel DAO transaction")
+                {
+                    tracing::info!(proposal = %proposal_id, %scheduled_height, ?auth_hash, "DAO transaction to cancel is no longer scheduled");
+                }
+            }
+
+            // Then schedule new transactions by appending them to the end of the schedule for their
             // effective block
-            // TODO: don't forget to fill in the part in the shielded pool where the transactions
-            // actually get included in a block
-            todo!("implement daospend execution")
+            for (effective_height, transaction) in schedule_transactions {
+                tracing::debug!(proposal = %proposal_id, %effective_height, "scheduling DAO transaction");
+                state
+                    .schedule_dao_transaction(effective_height, proposal_id, transaction)
+                    .await
+                    .expect("can schedule DAO transaction");
+            }
         }
     }
 }
 
-pub async fn enact_pending_parameter_changes(_state: &State) {
-    // TODO: read the new parameters for this block, if any, and change the chain params to reflect
-    // them. Parameters should be stored in the state as a map from name to value string.
+#[instrument(skip(state))]
+pub async fn enact_pending_parameter_changes(state: &State) {
+    let height = state
+        .get_block_height()
+        .await
+        .expect("can get block height");
+
+    let scheduled = state
+        .scheduled_parameter_changes(height)
+        .await
+        .expect("can get scheduled parameter changes");
+
+    if scheduled.is_empty() {
+        return;
+    }
+
+    // Apply each proposal's changes in order of proposal id, on top of those applied before it.
+    // Each proposal's changes are applied entirely or not at all, and the resulting parameters are
+    // written to the state in one go.
+    let mut parameters = state
+        .get_chain_params()
+        .await
+        .expect("can get chain parameters");
+    let mut changed = false;
+    for proposal_id in scheduled {
+        let new_parameters = match state
+            .proposal_payload(proposal_id)
+            .await
+            .expect("can get proposal payload")
+            .expect("proposal payload is present")
+        {
+            ProposalPayload::ParameterChange { new_parameters, .. } => new_parameters,
+            _ => panic!("scheduled parameter change proposal has a different payload"),
+        };
+
+        // Since other proposals may have changed the chain parameters in the meantime, and
+        // parameter validation must ensure consistency across all parameters, we need to perform
+        // a final validation step prior to applying the new parameters.
+        if !chain_params::is_valid_stateless(&new_parameters)
+            || !chain_params::is_valid_stateful(&new_parameters, &parameters)
+        {
+            tracing::info!(proposal = %proposal_id, %height, "scheduled chain parameter change is invalid, not applying it");
+            continue;
+        }
+
+        let resolved = chain_params::resolve_parameters(&new_parameters, &parameters)
+            .expect("can resolve validated parameters");
+
+        if !chain_params::unbondings_preserved(state, &resolved, &parameters)
+            .await
+            .expect("can check unbondings in flight")
+        {
+            tracing::info!(proposal = %proposal_id, %height, "scheduled chain parameter change would shrink unbonding epochs below unbondings in flight, not applying it");
+            continue;
+        }
+
+        tracing::info!(proposal = %proposal_id, %height, "applying chain parameter change");
+        parameters = resolved;
+        changed = true;
+    }
+
+    if changed {
+        state.put_chain_params(parameters).await;
+        state.put_chain_params_changed(height).await;
+    }
 }
@@ -2,10 +2,13 @@ use core::fmt;
 use std::{collections::BTreeMap, str::FromStr};
 
 use anyhow::{Context as _, Result};
-use penumbra_chain::params::ChainParameters;
+use penumbra_chain::{params::ChainParameters, View as _};
 use penumbra_proto::{core::governance::v1alpha1 as pb, Protobuf};
+use penumbra_storage::State;
 use serde::{Deserialize, Serialize};
 
+use crate::shielded_pool::View as _;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(
     try_from = "pb::MutableChainParameter",
@@ -19,6 +22,10 @@ pub enum MutableParam {
     SlashingPenaltyDowntimeBps,
     SignedBlocksWindowLen,
     MissedBlocksMaximum,
+    MaxCommissionChangeBps,
+    CommissionChangeDelayEpochs,
+    MinimumJailEpochs,
+    MaxValidatorPowerShareBps,
 }
 
 impl Protobuf<pb::MutableChainParameter> for MutableParam {}
@@ -42,7 +49,7 @@ impl From<MutableParam> for pb::MutableChainParameter {
 
 impl MutableParam {
     // TODO: would be nicer as a macro but after a bit of fiddling i couldn't get it right
-    pub const fn iter() -> [MutableParam; 7] {
+    pub const fn iter() -> [MutableParam; 11] {
         [
             MutableParam::UnbondingEpochs,
             MutableParam::ActiveValidatorLimit,
@@ -51,6 +58,10 @@ impl MutableParam {
             MutableParam::SlashingPenaltyDowntimeBps,
             MutableParam::SignedBlocksWindowLen,
             MutableParam::MissedBlocksMaximum,
+            MutableParam::MaxCommissionChangeBps,
+            MutableParam::CommissionChangeDelayEpochs,
+            MutableParam::MinimumJailEpochs,
+            MutableParam::MaxValidatorPowerShareBps,
         ]
     }
 
@@ -65,6 +76,10 @@ impl MutableParam {
             MutableParam::SlashingPenaltyDowntimeBps => "Slashing penalty specified in basis points applied to validator reward rates as punishment for downtime. Must be at least 1.",
             MutableParam::SignedBlocksWindowLen => "Number of blocks to use as the window for detecting validator downtime. Must be at least 2 and greater than or equal to missed_blocks_maximum.",
             MutableParam::MissedBlocksMaximum => "The maximum number of blocks a validator may miss in the signed_blocks_window_len before being slashed for downtime. Must be at least 1 and less than or equal to signed_blocks_window_len.",
+            MutableParam::MaxCommissionChangeBps => "The maximum increase in a validator's total commission, in basis points, that a single validator definition update may announce. Must be at most 10000.",
+            MutableParam::CommissionChangeDelayEpochs => "The number of epochs after the announcement of new funding streams before they take effect. Must be at least 2.",
+            MutableParam::MinimumJailEpochs => "The minimum number of epochs a validator jailed for downtime must wait before being re-enabled, doubled for each repeat offense. Must be at least 1.",
+            MutableParam::MaxValidatorPowerShareBps => "The maximum share of the consensus power any single validator may hold, in basis points, or 0 for no limit. Must be at most 10000.",
         }
     }
 }
@@ -83,6 +98,12 @@ impl std::str::FromStr for MutableParam {
             "slashing_penalty_downtime_bps" => Result::Ok(MutableParam::SlashingPenaltyDowntimeBps),
             "signed_blocks_window_len" => Result::Ok(MutableParam::SignedBlocksWindowLen),
             "missed_blocks_maximum" => Result::Ok(MutableParam::MissedBlocksMaximum),
+            "max_commission_change_bps" => Result::Ok(MutableParam::MaxCommissionChangeBps),
+            "commission_change_delay_epochs" => {
+                Result::Ok(MutableParam::CommissionChangeDelayEpochs)
+            }
+            "minimum_jail_epochs" => Result::Ok(MutableParam::MinimumJailEpochs),
+            "max_validator_power_share_bps" => Result::Ok(MutableParam::MaxValidatorPowerShareBps),
             _ => Err(anyhow::anyhow!("mutable parameter not found")),
         }
     }
@@ -100,6 +121,14 @@ impl fmt::Display for MutableParam {
             MutableParam::SlashingPenaltyDowntimeBps => write!(f, "slashing_penalty_downtime_bps"),
             MutableParam::SignedBlocksWindowLen => write!(f, "signed_blocks_window_len"),
             MutableParam::MissedBlocksMaximum => write!(f, "missed_blocks_maximum"),
+            MutableParam::MaxCommissionChangeBps => write!(f, "max_commission_change_bps"),
+            MutableParam::CommissionChangeDelayEpochs => {
+                write!(f, "commission_change_delay_epochs")
+            }
+            MutableParam::MinimumJailEpochs => write!(f, "minimum_jail_epochs"),
+            MutableParam::MaxValidatorPowerShareBps => {
+                write!(f, "max_validator_power_share_bps")
+            }
         }
     }
 }
@@ -161,12 +190,45 @@ pub fn is_valid_stateful(
 }
 
 /// Determines if the newly proposed parameter set represents a valid change from the previous parameters.
-fn param_changes_valid(_new_params: &ChainParameters, _old_params: &ChainParameters) -> bool {
-    // There are currently no checks to perform here, but it's feasible that we'll want to add some
-    // in the future.
+fn param_changes_valid(new_params: &ChainParameters, old_params: &ChainParameters) -> bool {
+    // Parameters which are not mutable must not change.
+    if new_params.chain_id != old_params.chain_id
+        || new_params.epoch_duration != old_params.epoch_duration
+    {
+        return false;
+    }
+
     true
 }
 
+/// Determines if the newly proposed parameter set represents a valid change given the unbondings
+/// currently in flight.
+///
+/// Shrinking `unbonding_epochs` is only permitted when nothing is scheduled to finish unbonding in
+/// the epochs that would fall outside the new unbonding window, so that no unbonding already in
+/// flight completes later than one begun after the change.
+pub async fn unbondings_preserved(
+    state: &State,
+    new_params: &ChainParameters,
+    old_params: &ChainParameters,
+) -> Result<bool> {
+    if new_params.unbonding_epochs >= old_params.unbonding_epochs {
+        return Ok(true);
+    }
+
+    let current_epoch = state.get_current_epoch().await?.index;
+    let new_end = current_epoch + new_params.unbonding_epochs;
+    let old_end = current_epoch + old_params.unbonding_epochs;
+
+    for epoch in (new_end + 1)..=old_end {
+        if !state.scheduled_to_apply(epoch).await?.is_empty() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
 /// Determines if the parameter values are consistent with one another.
 fn params_are_consistent(params: &ChainParameters) -> bool {
     // Check that the signed blocks window length is greater than or equal to the missed blocks maximum.
@@ -212,6 +274,21 @@ pub fn resolve_parameters(
             MutableParam::MissedBlocksMaximum => {
                 new_chain_params.missed_blocks_maximum = value.parse().context("invalid value")?
             }
+            MutableParam::MaxCommissionChangeBps => {
+                new_chain_params.max_commission_change_bps =
+                    value.parse().context("invalid value")?
+            }
+            MutableParam::CommissionChangeDelayEpochs => {
+                new_chain_params.commission_change_delay_epochs =
+                    value.parse().context("invalid value")?
+            }
+            MutableParam::MinimumJailEpochs => {
+                new_chain_params.minimum_jail_epochs = value.parse().context("invalid value")?
+            }
+            MutableParam::MaxValidatorPowerShareBps => {
+                new_chain_params.max_validator_power_share_bps =
+                    value.parse().context("invalid value")?
+            }
         }
     }
 
@@ -286,5 +363,43 @@ fn param_value_is_valid(param: &MutableParam, value: &str) -> bool {
             // Missed blocks maximum must be at least 1.
             value >= 1
         }
+        MutableParam::MaxCommissionChangeBps => {
+            let value = match value.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => return false,
+            };
+
+            // Max commission change cannot exceed 100%.
+            value <= 10_000
+        }
+        MutableParam::CommissionChangeDelayEpochs => {
+            let value = match value.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => return false,
+            };
+
+            // Commission change delay must be at least 2, since the rates for the next epoch are
+            // already fixed when new funding streams are announced.
+            value >= 2
+        }
+        MutableParam::MinimumJailEpochs => {
+            let value = match value.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => return false,
+            };
+
+            // Minimum jail duration must be at least 1, so that a jailed validator
+            // cannot be re-enabled in the same epoch.
+            value >= 1
+        }
+        MutableParam::MaxValidatorPowerShareBps => {
+            let value = match value.parse::<u64>() {
+                Ok(value) => value,
+                Err(_) => return false,
+            };
+
+            // A validator's share of the consensus power cannot exceed 100%.
+            value <= 10_000
+        }
     }
 }
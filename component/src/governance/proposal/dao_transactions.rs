@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use penumbra_proto::{core::transaction::v1alpha1 as pb, Protobuf};
+use penumbra_transaction::plan::TransactionPlan;
+
+/// A protobuf-represented sequence of transactions scheduled for execution by the DAO, each
+/// annotated with the id of the proposal which scheduled it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DaoTransactions {
+    pub transactions: Vec<(u64, TransactionPlan)>,
+}
+
+impl Protobuf<pb::DaoTransactions> for DaoTransactions {}
+
+impl From<DaoTransactions> for pb::DaoTransactions {
+    fn from(list: DaoTransactions) -> Self {
+        pb::DaoTransactions {
+            transactions: list
+                .transactions
+                .into_iter()
+                .map(
+                    |(proposal_id, transaction)| pb::dao_transactions::ScheduledTransaction {
+                        proposal_id,
+                        transaction: Some(transaction.into()),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::DaoTransactions> for DaoTransactions {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb::DaoTransactions) -> Result<Self, Self::Error> {
+        Ok(DaoTransactions {
+            transactions: msg
+                .transactions
+                .into_iter()
+                .map(|scheduled| {
+                    Ok((
+                        scheduled.proposal_id,
+                        scheduled
+                            .transaction
+                            .ok_or_else(|| anyhow::anyhow!("missing scheduled DAO transaction"))?
+                            .try_into()?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
@@ -1,6 +1,8 @@
 pub mod chain_params;
+mod dao_transactions;
 mod list;
 mod state;
 
+pub use dao_transactions::DaoTransactions;
 pub use list::ProposalList;
 pub use state::{Outcome, State, Withdrawn};
@@ -1,4 +1,5 @@
-use penumbra_crypto::IdentityKey;
+use penumbra_crypto::{asset, IdentityKey, Nullifier};
+use penumbra_transaction::action::Vote;
 
 pub fn latest_proposal_id() -> &'static str {
     "governance/latest_proposal_id"
@@ -32,6 +33,10 @@ pub fn proposal_voting_start(proposal_id: u64) -> String {
     format!("governance/proposal/{}/voting_start", proposal_id)
 }
 
+pub fn proposal_voting_start_position(proposal_id: u64) -> String {
+    format!("governance/proposal/{}/voting_start_position", proposal_id)
+}
+
 pub fn proposal_voting_end(proposal_id: u64) -> String {
     format!("governance/proposal/{}/voting_end", proposal_id)
 }
@@ -44,6 +49,30 @@ pub fn proposal_refunds(block_height: u64) -> String {
     format!("governance/proposal_refunds/{}", block_height)
 }
 
+pub fn scheduled_parameter_changes(block_height: u64) -> String {
+    format!("governance/scheduled_parameter_changes/{}", block_height)
+}
+
+pub fn chain_params_changed(block_height: u64) -> String {
+    format!("governance/chain_params_changed/{}", block_height)
+}
+
+pub fn dao_balance(asset_id: &asset::Id) -> String {
+    format!("governance/dao/balance/{}", asset_id)
+}
+
+pub fn dao_transactions(block_height: u64) -> String {
+    format!("governance/dao/transactions/{}", block_height)
+}
+
+pub fn halt_proposal() -> &'static str {
+    "governance/halt_proposal"
+}
+
+pub fn resumed_halt_proposal() -> &'static str {
+    "governance/resumed_halt_proposal"
+}
+
 pub fn proposal_withdrawal_key(proposal_id: u64) -> String {
     format!("governance/proposal/{}/withdraw_key", proposal_id)
 }
@@ -58,3 +87,24 @@ pub fn validator_vote(proposal_id: u64, identity_key: IdentityKey) -> String {
         proposal_id, identity_key
     )
 }
+
+pub fn delegator_voting_validators(proposal_id: u64) -> String {
+    format!(
+        "governance/proposal/{}/delegator_voting_validators",
+        proposal_id
+    )
+}
+
+pub fn delegator_vote_amount(proposal_id: u64, identity_key: IdentityKey, vote: Vote) -> String {
+    format!(
+        "governance/proposal/{}/delegator_votes/{}/{}",
+        proposal_id, identity_key, vote
+    )
+}
+
+pub fn voted_nullifier_lookup(proposal_id: u64, nullifier: Nullifier) -> String {
+    format!(
+        "governance/proposal/{}/voted_nullifiers/{}",
+        proposal_id, nullifier
+    )
+}
@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use num_rational::Ratio;
 use penumbra_chain::params::ChainParameters;
+use penumbra_crypto::IdentityKey;
 use penumbra_transaction::action::Vote;
 
 use super::{proposal::Withdrawn, View as _};
@@ -192,6 +195,27 @@ impl Parameters {
         // Initialize a tally for this proposal
         let mut tally = Tally::new(circumstance, ending_block, withdrawn, emergency);
 
+        // Tally delegator votes first, keeping track of how much of each validator's power has
+        // been voted directly by its delegators
+        let base_rate = state.current_base_rate().await?;
+        let mut delegated_power = BTreeMap::<IdentityKey, u64>::new();
+        for identity_key in state.delegator_voting_validators(proposal_id).await? {
+            let rate = state
+                .current_validator_rate(&identity_key)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing rate data for validator"))?;
+            for vote in [Vote::Yes, Vote::No, Vote::Abstain, Vote::NoWithVeto] {
+                let amount = state
+                    .delegator_vote_amount(proposal_id, identity_key, vote)
+                    .await?;
+                let power = rate.voting_power(amount, &base_rate);
+                tally.add(vote, power);
+                *delegated_power.entry(identity_key).or_default() += power;
+            }
+        }
+
+        // Then tally validator votes, with the power of each validator reduced by the power its
+        // delegators voted themselves, so that delegator votes override their validator's vote
         for identity_key in state.voting_validators(proposal_id).await? {
             let vote = state
                 .validator_vote(proposal_id, identity_key)
@@ -201,7 +225,11 @@ impl Parameters {
                 .validator_power(&identity_key)
                 .await?
                 .expect("validator has a power");
-            tally.add(vote, power);
+            let overridden = delegated_power
+                .get(&identity_key)
+                .copied()
+                .unwrap_or_default();
+            tally.add(vote, power.saturating_sub(overridden));
         }
 
         Ok(tally.evaluate(self))
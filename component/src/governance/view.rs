@@ -3,12 +3,17 @@ use std::collections::BTreeSet;
 use anyhow::Result;
 use async_trait::async_trait;
 use penumbra_crypto::{
-    asset::Amount,
+    asset::{self, Amount},
     rdsa::{SpendAuth, VerificationKey},
-    Address, IdentityKey, Value, STAKING_TOKEN_ASSET_ID,
+    Address, IdentityKey, Nullifier, Value, STAKING_TOKEN_ASSET_ID,
 };
 use penumbra_storage::StateExt;
-use penumbra_transaction::action::{Proposal, ProposalPayload, Vote};
+use penumbra_tct as tct;
+use penumbra_transaction::{
+    action::{Proposal, ProposalPayload, Vote},
+    plan::TransactionPlan,
+    AuthHash,
+};
 
 use crate::stake::{self, validator, View as _};
 
@@ -95,8 +100,8 @@ pub trait View: StateExt {
     }
 
     /// Get the proposal deposit amount for a proposal.
-    async fn proposal_deposit_amount(&self, proposal_id: u64) -> Result<Option<u64>> {
-        self.get_proto(state_key::proposal_deposit_amount(proposal_id).into())
+    async fn proposal_deposit_amount(&self, proposal_id: u64) -> Result<Option<Amount>> {
+        self.get_domain(state_key::proposal_deposit_amount(proposal_id).into())
             .await
     }
 
@@ -289,6 +294,124 @@ pub trait View: StateExt {
         .await
     }
 
+    /// Get the position in the note commitment tree at which voting started for a proposal.
+    async fn proposal_voting_start_position(
+        &self,
+        proposal_id: u64,
+    ) -> Result<Option<tct::Position>> {
+        Ok(self
+            .get_proto::<u64>(state_key::proposal_voting_start_position(proposal_id).into())
+            .await?
+            .map(Into::into))
+    }
+
+    /// Set the position in the note commitment tree at which voting started for a proposal.
+    async fn put_proposal_voting_start_position(
+        &self,
+        proposal_id: u64,
+        start_position: tct::Position,
+    ) {
+        self.put_proto(
+            state_key::proposal_voting_start_position(proposal_id).into(),
+            u64::from(start_position),
+        )
+        .await
+    }
+
+    /// Get the list of validators whose delegators voted on a proposal.
+    async fn delegator_voting_validators(&self, proposal_id: u64) -> Result<Vec<IdentityKey>> {
+        Ok(self
+            .get_domain::<stake::validator::List, _>(
+                state_key::delegator_voting_validators(proposal_id).into(),
+            )
+            .await?
+            .unwrap_or_default()
+            .0)
+    }
+
+    /// Get the total amount of a validator's delegation tokens which were voted a particular way
+    /// on a proposal.
+    async fn delegator_vote_amount(
+        &self,
+        proposal_id: u64,
+        identity_key: IdentityKey,
+        vote: Vote,
+    ) -> Result<u64> {
+        Ok(self
+            .get_proto::<u64>(
+                state_key::delegator_vote_amount(proposal_id, identity_key, vote).into(),
+            )
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Check whether a staked note, identified by its nullifier, has already voted on a proposal.
+    async fn check_nullifier_unvoted(&self, proposal_id: u64, nullifier: Nullifier) -> Result<()> {
+        if let Some(height) = self
+            .get_proto::<u64>(state_key::voted_nullifier_lookup(proposal_id, nullifier).into())
+            .await?
+        {
+            anyhow::bail!(
+                "nullifier {} already voted on proposal {} at height {}",
+                nullifier,
+                proposal_id,
+                height
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Record a delegator vote for a proposal.
+    ///
+    /// Delegator votes are aggregated per validator, so only the total amount of each validator's
+    /// delegation tokens voting each way is recorded, along with the nullifier of the staked note
+    /// used to vote, so it cannot vote twice.
+    async fn cast_delegator_vote(
+        &self,
+        proposal_id: u64,
+        identity_key: IdentityKey,
+        vote: Vote,
+        amount: u64,
+        nullifier: Nullifier,
+        height: u64,
+    ) {
+        // Record the nullifier, so the same staked note can't vote again
+        self.put_proto(
+            state_key::voted_nullifier_lookup(proposal_id, nullifier).into(),
+            height,
+        )
+        .await;
+
+        // Add the amount to the running total for this validator and vote
+        let total = self
+            .delegator_vote_amount(proposal_id, identity_key, vote)
+            .await
+            .expect("can fetch delegator vote amount");
+        self.put_proto(
+            state_key::delegator_vote_amount(proposal_id, identity_key, vote).into(),
+            total + amount,
+        )
+        .await;
+
+        // Record the fact that this validator's delegators have voted on this proposal
+        let mut voting_validators = self
+            .get_domain::<stake::validator::List, _>(
+                state_key::delegator_voting_validators(proposal_id).into(),
+            )
+            .await
+            .expect("can fetch delegator voting validators")
+            .unwrap_or_default();
+        if !voting_validators.0.contains(&identity_key) {
+            voting_validators.0.push(identity_key);
+            self.put_domain(
+                state_key::delegator_voting_validators(proposal_id).into(),
+                voting_validators,
+            )
+            .await;
+        }
+    }
+
     /// Get the proposal voting end block for a given proposal.
     async fn proposal_voting_end(&self, proposal_id: u64) -> Result<Option<u64>> {
         Ok(self
@@ -305,6 +428,163 @@ pub trait View: StateExt {
         .await
     }
 
+    /// Schedule the parameter changes of a passed proposal to be enacted at the given height.
+    async fn schedule_parameter_change(&self, block_height: u64, proposal_id: u64) -> Result<()> {
+        let mut scheduled = self
+            .get_domain::<proposal::ProposalList, _>(
+                state_key::scheduled_parameter_changes(block_height).into(),
+            )
+            .await?
+            .unwrap_or_default();
+        scheduled.proposals.insert(proposal_id);
+        self.put_domain(
+            state_key::scheduled_parameter_changes(block_height).into(),
+            scheduled,
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Get the ids of the parameter change proposals scheduled to be enacted at the given height.
+    async fn scheduled_parameter_changes(&self, block_height: u64) -> Result<BTreeSet<u64>> {
+        Ok(self
+            .get_domain::<proposal::ProposalList, _>(
+                state_key::scheduled_parameter_changes(block_height).into(),
+            )
+            .await?
+            .unwrap_or_default()
+            .proposals)
+    }
+
+    /// Mark the chain parameters as having changed in the given block.
+    async fn put_chain_params_changed(&self, block_height: u64) {
+        self.put_proto(state_key::chain_params_changed(block_height).into(), true)
+            .await
+    }
+
+    /// Check whether the chain parameters changed in the given block.
+    ///
+    /// This is meant to be called from within the shielded pool component, which will include the
+    /// new parameters in the compact block.
+    async fn chain_params_changed(&self, block_height: u64) -> Result<bool> {
+        Ok(self
+            .get_proto::<bool>(state_key::chain_params_changed(block_height).into())
+            .await?
+            .unwrap_or(false))
+    }
+
+    /// Get the id of the emergency proposal which halted the chain, if the chain is halted.
+    async fn halted_by(&self) -> Result<Option<u64>> {
+        let halt_proposal = self
+            .get_proto::<u64>(state_key::halt_proposal().into())
+            .await?;
+        let resumed_halt_proposal = self
+            .get_proto::<u64>(state_key::resumed_halt_proposal().into())
+            .await?;
+
+        Ok(halt_proposal.filter(|&proposal_id| Some(proposal_id) != resumed_halt_proposal))
+    }
+
+    /// Halt the chain after the current block, on account of the given emergency proposal.
+    async fn put_halt_proposal(&self, proposal_id: u64) {
+        self.put_proto(state_key::halt_proposal().into(), proposal_id)
+            .await
+    }
+
+    /// Resume the chain from the halt caused by the given emergency proposal.
+    async fn resume_from_halt(&self, proposal_id: u64) {
+        self.put_proto(state_key::resumed_halt_proposal().into(), proposal_id)
+            .await
+    }
+
+    /// Get the balance of the DAO treasury in the given asset.
+    async fn dao_balance(&self, asset_id: &asset::Id) -> Result<Amount> {
+        Ok(self
+            .get_domain(state_key::dao_balance(asset_id).into())
+            .await?
+            .unwrap_or_else(Amount::zero))
+    }
+
+    /// Deposit a value into the DAO treasury.
+    async fn dao_deposit(&self, value: Value) -> Result<()> {
+        let balance = self.dao_balance(&value.asset_id).await?;
+        self.put_domain(
+            state_key::dao_balance(&value.asset_id).into(),
+            balance + value.amount,
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Withdraw a value from the DAO treasury, failing if the treasury holds too little of it.
+    async fn dao_withdraw(&self, value: Value) -> Result<()> {
+        let balance = self.dao_balance(&value.asset_id).await?;
+        if balance < value.amount {
+            anyhow::bail!(
+                "DAO balance {} of asset {} is insufficient to withdraw {}",
+                balance,
+                value.asset_id,
+                value.amount
+            );
+        }
+        self.put_domain(
+            state_key::dao_balance(&value.asset_id).into(),
+            balance - value.amount,
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Get the DAO transactions scheduled for execution at the given height, in order, along with
+    /// the ids of the proposals which scheduled them.
+    async fn dao_transactions(&self, block_height: u64) -> Result<Vec<(u64, TransactionPlan)>> {
+        Ok(self
+            .get_domain::<proposal::DaoTransactions, _>(
+                state_key::dao_transactions(block_height).into(),
+            )
+            .await?
+            .unwrap_or_default()
+            .transactions)
+    }
+
+    /// Schedule a DAO transaction for execution at the given height, after any transactions
+    /// already scheduled for that height.
+    async fn schedule_dao_transaction(
+        &self,
+        block_height: u64,
+        proposal_id: u64,
+        transaction: TransactionPlan,
+    ) -> Result<()> {
+        let mut transactions = self.dao_transactions(block_height).await?;
+        transactions.push((proposal_id, transaction));
+        self.put_domain(
+            state_key::dao_transactions(block_height).into(),
+            proposal::DaoTransactions { transactions },
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Cancel the first DAO transaction scheduled for execution at the given height whose auth hash
+    /// matches the given one, returning whether any transaction was cancelled.
+    async fn cancel_dao_transaction(&self, block_height: u64, auth_hash: AuthHash) -> Result<bool> {
+        let mut transactions = self.dao_transactions(block_height).await?;
+        let index = match transactions
+            .iter()
+            .position(|(_, transaction)| transaction.plan_auth_hash() == auth_hash)
+        {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        transactions.remove(index);
+        self.put_domain(
+            state_key::dao_transactions(block_height).into(),
+            proposal::DaoTransactions { transactions },
+        )
+        .await;
+        Ok(true)
+    }
+
     /// Get the total voting power across all validators.
     async fn total_voting_power(&self) -> Result<u64> {
         let mut total = 0;
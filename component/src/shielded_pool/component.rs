@@ -1,10 +1,6 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{
-    governance::View as _,
-    stake::{validator, View as _},
-    Component, Context,
-};
+use crate::{governance::View as _, stake::View as _, Component, Context};
 use anyhow::{anyhow, Context as _, Result};
 use ark_ff::PrimeField;
 use async_trait::async_trait;
@@ -23,7 +19,7 @@ use penumbra_crypto::{
 use penumbra_storage::{State, StateExt};
 use penumbra_tct as tct;
 use penumbra_transaction::{
-    action::{swap_claim::List as SwapClaimBodyList, Undelegate},
+    action::{swap_claim::List as SwapClaimBodyList, Redelegate, Undelegate},
     Action, Transaction,
 };
 use tendermint::abci;
@@ -273,7 +269,8 @@ impl Component for ShieldedPool {
             .unwrap();
         }
 
-        // TODO: execute any scheduled DAO spend transactions for this block
+        // Execute any scheduled DAO spend transactions for this block
+        self.execute_dao_transactions().await;
 
         // Include all output notes from DEX swaps for this block
         self.output_dex_swaps().await;
@@ -291,6 +288,21 @@ impl Component for ShieldedPool {
         // Refund any proposals from this block which are pending refund
         self.process_proposal_refunds().await;
 
+        // Include the latest chain parameters if they changed in this block
+        if self
+            .state
+            .chain_params_changed(height)
+            .await
+            .expect("can check whether chain parameters changed")
+        {
+            self.compact_block.chain_parameters = Some(
+                self.state
+                    .get_chain_params()
+                    .await
+                    .expect("can get chain parameters"),
+            );
+        }
+
         // Close the block in the NCT
         self.finish_nct_block().await;
 
@@ -481,6 +493,10 @@ impl ShieldedPool {
         self.state
             .set_nct_block_anchor(height, compact_block.block_root)
             .await;
+        // Write the position of the next commitment to be inserted:
+        if let Some(position) = self.note_commitment_tree.position() {
+            self.state.set_nct_position(position).await;
+        }
         // Write the current epoch anchor, if on an epoch boundary:
         if let Some(epoch_root) = compact_block.epoch_root {
             let epoch_duration = self.epoch_duration().await;
@@ -564,48 +580,34 @@ impl ShieldedPool {
 
     /// Returns the epoch and identity key for quarantining a transaction, if it should be
     /// quarantined, otherwise `None`.
+    ///
+    /// Redelegations are quarantined under their source validator, so that the
+    /// redelegated stake remains slashable for that validator's misbehavior
+    /// until its unbonding period has elapsed.
     async fn should_quarantine(&self, transaction: &Transaction) -> Option<(u64, IdentityKey)> {
         let validator_identity =
             transaction
                 .transaction_body
                 .actions
                 .iter()
-                .find_map(|action| {
-                    if let Action::Undelegate(Undelegate {
+                .find_map(|action| match action {
+                    Action::Undelegate(Undelegate {
                         validator_identity, ..
-                    }) = action
-                    {
-                        Some(validator_identity)
-                    } else {
-                        None
-                    }
+                    }) => Some(validator_identity),
+                    Action::Redelegate(Redelegate {
+                        source_validator, ..
+                    }) => Some(source_validator),
+                    _ => None,
                 })?;
 
-        let validator_bonding_state = self
+        // The staking component records quarantined redelegations under the
+        // same epoch, so that it can reverse them if they are rolled back.
+        let should_quarantine = self
             .state
-            .validator_bonding_state(validator_identity)
+            .quarantine_end_epoch(validator_identity)
             .await
-            .expect("validator lookup in state succeeds")
-            .expect("validator is present in state");
-
-        let should_quarantine = match validator_bonding_state {
-            validator::BondingState::Unbonded => None,
-            validator::BondingState::Unbonding { unbonding_epoch } => {
-                Some((unbonding_epoch, *validator_identity))
-            }
-            validator::BondingState::Bonded => {
-                let unbonding_epochs = self
-                    .state
-                    .get_chain_params()
-                    .await
-                    .expect("can get chain params")
-                    .unbonding_epochs;
-                Some((
-                    self.epoch().await.index + unbonding_epochs,
-                    *validator_identity,
-                ))
-            }
-        };
+            .expect("validator is present in state")
+            .map(|unbonding_epoch| (unbonding_epoch, *validator_identity));
 
         tracing::debug!(?should_quarantine, "should quarantine");
 
@@ -688,6 +690,59 @@ impl ShieldedPool {
         }
     }
 
+    #[instrument(skip(self))]
+    async fn execute_dao_transactions(&mut self) {
+        let block_height = self.height().await;
+
+        for (proposal_id, transaction) in self
+            .state
+            .dao_transactions(block_height)
+            .await
+            .expect("DAO transactions can be fetched")
+        {
+            // Check that the DAO can pay for every output before paying for any of them, so that
+            // each transaction is executed either entirely or not at all
+            let mut totals = BTreeMap::<asset::Id, u64>::new();
+            let mut sufficient = true;
+            for output in transaction.output_plans() {
+                let total = totals.entry(output.value.asset_id).or_default();
+                match total.checked_add(output.value.amount.inner) {
+                    Some(sum) => *total = sum,
+                    // No balance can cover a total which overflows
+                    None => sufficient = false,
+                }
+            }
+            for (asset_id, total) in totals.iter() {
+                let balance = self
+                    .state
+                    .dao_balance(asset_id)
+                    .await
+                    .expect("DAO balance can be fetched");
+                if balance.inner < *total {
+                    sufficient = false;
+                }
+            }
+            if !sufficient {
+                tracing::info!(proposal = %proposal_id, "DAO balance is insufficient to execute scheduled transaction, skipping it");
+                continue;
+            }
+
+            for output in transaction.output_plans() {
+                self.state
+                    .dao_withdraw(output.value)
+                    .await
+                    .expect("DAO balance is sufficient");
+                self.mint_note(
+                    output.value,
+                    &output.dest_address,
+                    NoteSource::DaoOutput { proposal_id },
+                )
+                .await
+                .expect("can mint DAO output");
+            }
+        }
+    }
+
     #[instrument(skip(self))]
     async fn output_dex_swaps(&mut self) {
         let block_height = self.height().await;
@@ -865,6 +920,11 @@ pub trait View: StateExt {
         .await;
     }
 
+    async fn nct_anchor(&self, height: u64) -> Result<Option<tct::Root>> {
+        self.get_domain(state_key::anchor_by_height(height).into())
+            .await
+    }
+
     async fn set_nct_block_anchor(&self, height: u64, nct_block_anchor: tct::builder::block::Root) {
         tracing::debug!(?height, ?nct_block_anchor, "writing block anchor");
 
@@ -903,6 +963,22 @@ pub trait View: StateExt {
         .await;
     }
 
+    async fn set_nct_position(&self, position: tct::Position) {
+        self.put_proto(state_key::nct_position().into(), u64::from(position))
+            .await;
+    }
+
+    /// Gets the position in the NCT at which the next note commitment will be inserted, as of the
+    /// end of the previous block.
+    ///
+    /// Every note commitment in the tree as of the end of the previous block has a lesser position.
+    async fn nct_position(&self) -> Result<Option<tct::Position>> {
+        Ok(self
+            .get_proto::<u64>(state_key::nct_position().into())
+            .await?
+            .map(Into::into))
+    }
+
     /// Checks whether a claimed NCT anchor is a previous valid state root.
     async fn check_claimed_anchor(&self, anchor: tct::Root) -> Result<()> {
         if let Some(anchor_height) = self
@@ -996,7 +1072,8 @@ pub trait View: StateExt {
     }
 
     // Unschedule the unquarantining of all notes and nullifiers for the given validator, in any
-    // epoch which could possibly still be unbonding
+    // epoch which could possibly still be unbonding: quarantined things are scheduled to be
+    // applied at most `unbonding_epochs` after the epoch in which they were quarantined
     async fn unschedule_all_slashed(&self) -> Result<Vec<IdentityKey>> {
         let height = self.get_block_height().await?;
         let epoch_duration = self.get_epoch_duration().await?;
@@ -1008,7 +1085,7 @@ pub trait View: StateExt {
             .await?
             .unwrap_or_default();
 
-        for epoch in this_epoch.index.saturating_sub(unbonding_epochs)..=this_epoch.index {
+        for epoch in this_epoch.index..=this_epoch.index + unbonding_epochs {
             let mut updated_scheduled = self.scheduled_to_apply(epoch).await?;
             for &identity_key in &slashed.validators {
                 let unbonding = updated_scheduled.unschedule_validator(identity_key);
@@ -1058,3 +1135,206 @@ pub trait View: StateExt {
 }
 
 impl<T: StateExt> View for T {}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_chain::params::ChainParameters;
+    use penumbra_crypto::{
+        rdsa::{SigningKey, SpendAuth},
+        DelegationToken, GovernanceKey,
+    };
+    use penumbra_storage::Storage;
+    use penumbra_transaction::TransactionBody;
+    use rand_core::OsRng;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::stake::{
+        component::Staking,
+        rate::{BaseRateData, RateData},
+        state_key as stake_state_key,
+        validator::{self, Validator},
+        CurrentConsensusKeys, FundingStreams, View as _,
+    };
+
+    // Redelegations are scheduled for unquarantine a full unbonding period after the current
+    // epoch, so slashing the source validator must reach that far ahead to roll them back, and
+    // the redelegated stake must be moved back from the destination pool to the source pool.
+    #[tokio::test]
+    async fn slashing_source_validator_rolls_back_quarantined_redelegation() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::load(dir.path().join("shielded-pool-testing.db"))
+            .await
+            .unwrap();
+        let state = storage.state().await.unwrap();
+
+        let params = ChainParameters {
+            epoch_duration: 10,
+            unbonding_epochs: 3,
+            ..Default::default()
+        };
+        state.put_chain_params(params.clone()).await;
+        // The last block of epoch 2, so the slashing is followed by the epoch transition.
+        let height = 29;
+        state.put_block_height(height).await;
+
+        // Two bonded validators, each with 1000 delegation tokens at an exchange rate of 1.
+        let base_rate = BaseRateData {
+            epoch_index: 2,
+            base_reward_rate: 0,
+            base_exchange_rate: 1_0000_0000,
+        };
+        state
+            .set_base_rates(
+                base_rate.clone(),
+                BaseRateData {
+                    epoch_index: 3,
+                    ..base_rate.clone()
+                },
+            )
+            .await;
+        let mut validators = Vec::new();
+        for _ in 0..2 {
+            let identity_key = IdentityKey(SigningKey::<SpendAuth>::new(OsRng).into());
+            let rate = RateData {
+                identity_key,
+                epoch_index: 2,
+                validator_reward_rate: 0,
+                validator_exchange_rate: 1_0000_0000,
+            };
+            let consensus_key =
+                tendermint::PrivateKey::Ed25519(ed25519_consensus::SigningKey::new(OsRng))
+                    .public_key();
+            state
+                .add_validator_inner(
+                    Validator {
+                        identity_key,
+                        governance_key: GovernanceKey(identity_key.0),
+                        consensus_key,
+                        name: String::new(),
+                        website: String::new(),
+                        description: String::new(),
+                        enabled: true,
+                        funding_streams: FundingStreams::default(),
+                        sequence_number: 0,
+                    },
+                    rate.clone(),
+                    RateData {
+                        epoch_index: 3,
+                        ..rate.clone()
+                    },
+                    validator::State::Active,
+                    validator::BondingState::Bonded,
+                    rate.voting_power(1000, &base_rate),
+                )
+                .await
+                .unwrap();
+            state
+                .update_token_supply(&DelegationToken::from(identity_key).id(), 1000)
+                .await
+                .unwrap();
+            validators.push(identity_key);
+        }
+        let (source_validator, destination_validator) = (validators[0], validators[1]);
+        for h in 20..height {
+            state
+                .set_delegation_changes(h.try_into().unwrap(), Default::default())
+                .await;
+        }
+        state
+            .put_domain(
+                stake_state_key::current_consensus_keys().into(),
+                CurrentConsensusKeys::default(),
+            )
+            .await;
+
+        // Redelegate 100 delegation tokens from the source to the destination, spending a
+        // source delegation note under quarantine.
+        let mut staking = Staking::new(state.clone()).await;
+        let redelegation = Transaction {
+            transaction_body: TransactionBody {
+                actions: vec![Action::Redelegate(Redelegate {
+                    source_validator,
+                    destination_validator,
+                    epoch_index: 2,
+                    source_delegation_amount: 100u64.into(),
+                    unbonded_amount: 100u64.into(),
+                    destination_delegation_amount: 100u64.into(),
+                })],
+                expiry_height: 0,
+                chain_id: String::new(),
+                fee: Default::default(),
+                fmd_clues: Vec::new(),
+                memo: None,
+            },
+            binding_sig: [0u8; 64].into(),
+            anchor: tct::Tree::new().root(),
+        };
+        staking.execute_tx(Context::new(), &redelegation).await;
+
+        let mut shielded_pool = ShieldedPool::new(state.clone(), tct::Tree::new()).await;
+        let nullifier = Nullifier(Fq::from(1u64));
+        let unbonding_epoch = shielded_pool.epoch().await.index + params.unbonding_epochs;
+
+        shielded_pool
+            .quarantined_spend_nullifier(
+                unbonding_epoch,
+                source_validator,
+                nullifier,
+                NoteSource::Transaction { id: [0; 32] },
+            )
+            .await;
+        shielded_pool.schedule_unquarantine().await;
+        assert!(state
+            .scheduled_to_apply(unbonding_epoch)
+            .await
+            .unwrap()
+            .iter()
+            .any(|(identity_key, _)| *identity_key == source_validator));
+
+        // Slash the source validator, then end the block as the app does, with the staking
+        // component before the shielded pool.
+        state.record_slashing(source_validator).await.unwrap();
+        staking
+            .end_block(
+                Context::new(),
+                &abci::request::EndBlock {
+                    height: height as i64,
+                },
+            )
+            .await;
+        shielded_pool.process_slashing().await;
+
+        assert!(state
+            .scheduled_to_apply(unbonding_epoch)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(state
+            .get_domain::<Delible<NoteSource>, _>(
+                state_key::quarantined_spent_nullifier_lookup(nullifier).into()
+            )
+            .await
+            .unwrap()
+            .and_then(Option::from)
+            .is_none());
+        assert_eq!(shielded_pool.compact_block.slashed, vec![source_validator]);
+
+        // The rollback restores the source delegation note and drops the destination one, so
+        // the epoch transition leaves both pools' delegation token supply and voting power as
+        // they were before the redelegation.
+        for identity_key in [source_validator, destination_validator] {
+            assert_eq!(
+                state
+                    .token_supply(&DelegationToken::from(identity_key).id())
+                    .await
+                    .unwrap(),
+                Some(1000)
+            );
+            assert_eq!(
+                state.validator_power(&identity_key).await.unwrap(),
+                Some(1000)
+            );
+        }
+    }
+}
@@ -29,6 +29,10 @@ pub fn anchor_by_height(height: u64) -> String {
     format!("shielded_pool/anchor/{}", height)
 }
 
+pub fn nct_position() -> &'static str {
+    "shielded_pool/nct_position"
+}
+
 pub fn anchor_lookup(anchor: Root) -> String {
     format!("shielded_pool/valid_anchors/{}", anchor)
 }
@@ -19,7 +19,7 @@ use sha2::{Digest, Sha256};
 use tendermint::{
     abci::{
         self,
-        types::{Evidence, LastCommitInfo, ValidatorUpdate},
+        types::{Evidence, EvidenceKind, LastCommitInfo, ValidatorUpdate},
     },
     block, PublicKey,
 };
@@ -30,10 +30,11 @@ use crate::stake::{
     rate::{BaseRateData, RateData},
     state_key,
     validator::{self, Validator},
-    DelegationChanges, Uptime,
+    DelegationChanges, JailRecord, PendingFundingStreams, SlashingEvent, SlashingHistory,
+    SlashingReason, Uptime,
 };
 
-use super::CurrentConsensusKeys;
+use super::{power_cap::cap_voting_power, CurrentConsensusKeys};
 
 // Max validator power is 1152921504606846975 (i64::MAX / 8)
 // https://github.com/tendermint/tendermint/blob/master/types/validator_set.go#L25
@@ -80,6 +81,18 @@ impl Staking {
         &mut self,
         identity_key: &IdentityKey,
         new_state: validator::State,
+    ) -> Result<()> {
+        self.set_validator_state_with_evidence(identity_key, new_state, None)
+            .await
+    }
+
+    /// Like [`Self::set_validator_state`], but records the misbehavior evidence
+    /// that triggered the transition in the validator's slashing history.
+    async fn set_validator_state_with_evidence(
+        &mut self,
+        identity_key: &IdentityKey,
+        new_state: validator::State,
+        evidence: Option<&Evidence>,
     ) -> Result<()> {
         let cur_state = self
             .state
@@ -91,7 +104,7 @@ impl Staking {
 
         // Delegating to an inner method here lets us create a span that has both states,
         // without having to manage span entry/exit in async code.
-        self.set_validator_state_inner(identity_key, cur_state, new_state)
+        self.set_validator_state_inner(identity_key, cur_state, new_state, evidence)
             .await
     }
 
@@ -105,6 +118,7 @@ impl Staking {
         identity_key: &IdentityKey,
         cur_state: validator::State,
         new_state: validator::State,
+        evidence: Option<&Evidence>,
     ) -> Result<()> {
         let state_key = state_key::state_by_validator(identity_key).into();
 
@@ -214,15 +228,22 @@ impl Staking {
                 Ok(())
             }
             (Active, Jailed) => {
-                let penalty = self
-                    .state
-                    .get_chain_params()
-                    .await?
-                    .slashing_penalty_downtime_bps;
+                let params = self.state.get_chain_params().await?;
+                let current_epoch = self.state.get_current_epoch().await?;
+
+                // Repeat offenders are penalized more heavily, and jailed for longer.
+                let mut jail_record = self.state.jail_record(identity_key).await?;
+                let penalty = jail_record.record_offense(
+                    current_epoch.index,
+                    params.slashing_penalty_downtime_bps,
+                    params.minimum_jail_epochs,
+                );
+                tracing::debug!(?jail_record, penalty, "jailing validator");
+                self.state.set_jail_record(identity_key, jail_record).await;
 
                 // Apply the penalty to the validator's current exchange rate.
                 self.state
-                    .apply_slashing_penalty(identity_key, penalty)
+                    .apply_slashing_penalty(identity_key, penalty, SlashingReason::Downtime, None)
                     .await?;
 
                 // The validator's delegation pool begins unbonding.  Jailed
@@ -250,9 +271,21 @@ impl Staking {
                     .await?
                     .slashing_penalty_misbehavior_bps;
 
+                let (reason, infraction_height) = match evidence {
+                    Some(evidence) => (
+                        match evidence.kind {
+                            EvidenceKind::DuplicateVote => SlashingReason::DuplicateVote,
+                            EvidenceKind::LightClientAttack => SlashingReason::LightClientAttack,
+                            _ => SlashingReason::UnknownMisbehavior,
+                        },
+                        Some(evidence.height.value()),
+                    ),
+                    None => (SlashingReason::UnknownMisbehavior, None),
+                };
+
                 // Apply the penalty to the validator's current exchange rate.
                 self.state
-                    .apply_slashing_penalty(identity_key, penalty)
+                    .apply_slashing_penalty(identity_key, penalty, reason, infraction_height)
                     .await?;
 
                 // Regardless of its current bonding state, the validator's
@@ -349,10 +382,23 @@ impl Staking {
             })?;
             tracing::debug!(?validator, "processing validator rate updates");
 
-            let funding_streams = validator.funding_streams;
+            // The funding streams in effect during the ending epoch determine its commission,
+            // but announced funding streams are used as soon as we compute the rates for the
+            // epoch in which they take effect.
+            let funding_streams = validator.funding_streams.clone();
+            let pending_funding_streams = self.state.pending_funding_streams(v).await?;
+            let next_funding_streams = match &pending_funding_streams {
+                Some(pending) if pending.effective_epoch <= epoch_to_end.index + 2 => {
+                    pending.funding_streams.clone()
+                }
+                _ => funding_streams.clone(),
+            };
 
-            let next_rate =
-                current_rate.next(&next_base_rate, funding_streams.as_ref(), &validator_state);
+            let next_rate = current_rate.next(
+                &next_base_rate,
+                next_funding_streams.as_ref(),
+                &validator_state,
+            );
             assert!(next_rate.epoch_index == epoch_to_end.index + 2);
 
             let total_delegations = delegations_by_validator
@@ -433,6 +479,21 @@ impl Staking {
                 }
             }
 
+            // Once the epoch in which the announced funding streams take effect begins, they
+            // become the validator's funding streams.
+            if let Some(pending) = pending_funding_streams {
+                if pending.effective_epoch <= epoch_to_end.index + 1
+                    && pending.funding_streams != validator.funding_streams
+                {
+                    tracing::debug!(?pending, "applying announced funding streams");
+                    let mut validator = validator.clone();
+                    validator.funding_streams = pending.funding_streams;
+                    self.state
+                        .put_domain(state_key::validator_by_id(v).into(), validator)
+                        .await;
+                }
+            }
+
             // rename to curr_rate so it lines up with next_rate (same # chars)
             let delegation_denom = DelegationToken::from(v).denom();
             tracing::debug!(curr_rate = ?current_rate);
@@ -530,6 +591,61 @@ impl Staking {
         Ok(())
     }
 
+    /// Reverses the delegation changes of the quarantined redelegations from
+    /// validators slashed in this block.
+    ///
+    /// The shielded pool rolls these redelegations back, restoring the source
+    /// delegation tokens they spent and dropping the destination delegation
+    /// tokens they would have released, so the delegation token supply and
+    /// voting power of both validators must be restored as well.
+    async fn reverse_slashed_redelegations(&mut self) -> Result<()> {
+        let height = self.state.get_block_height().await?;
+        let slashed: Slashed = self
+            .state
+            .get_domain(state_key::slashed_validators(height).into())
+            .await?
+            .unwrap_or_default();
+        if slashed.validators.is_empty() {
+            return Ok(());
+        }
+
+        // Quarantined redelegations are released at most `unbonding_epochs`
+        // after the current epoch, as in the shielded pool's rollback.
+        let current_epoch = self.state.get_current_epoch().await?.index;
+        let unbonding_epochs = self.state.get_chain_params().await?.unbonding_epochs;
+        for identity_key in &slashed.validators {
+            for epoch in current_epoch..=current_epoch + unbonding_epochs {
+                let redelegations = self
+                    .state
+                    .quarantined_redelegations(epoch, identity_key)
+                    .await?;
+                for d in redelegations.delegations {
+                    tracing::debug!(?d, "reversing slashed redelegation to destination");
+                    self.delegation_changes.undelegations.push(Undelegate {
+                        validator_identity: d.validator_identity,
+                        epoch_index: d.epoch_index,
+                        unbonded_amount: d.unbonded_amount,
+                        delegation_amount: d.delegation_amount,
+                    });
+                }
+                for u in redelegations.undelegations {
+                    tracing::debug!(?u, "reversing slashed redelegation from source");
+                    self.delegation_changes.delegations.push(Delegate {
+                        validator_identity: u.validator_identity,
+                        epoch_index: u.epoch_index,
+                        unbonded_amount: u.unbonded_amount,
+                        delegation_amount: u.delegation_amount,
+                    });
+                }
+                self.state
+                    .set_quarantined_redelegations(epoch, identity_key, Default::default())
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns a list of validator updates to send to Tendermint.
     ///
     /// This should only be called after `end_block`.
@@ -582,6 +698,27 @@ impl Staking {
             voting_power_by_consensus_key.insert(info.validator.consensus_key, effective_power);
         }
 
+        // Limit the share of the consensus power held by any single validator.  This only
+        // affects the power reported to Tendermint: the excess stake still earns rewards.
+        let max_share_bps = self
+            .state
+            .get_chain_params()
+            .await?
+            .max_validator_power_share_bps;
+        let capped_powers = cap_voting_power(
+            &voting_power_by_consensus_key
+                .values()
+                .copied()
+                .collect::<Vec<_>>(),
+            max_share_bps,
+        );
+        for (voting_power, capped_power) in voting_power_by_consensus_key
+            .values_mut()
+            .zip(capped_powers)
+        {
+            *voting_power = capped_power;
+        }
+
         // Next, filter that mapping to exclude any zero-power validators, UNLESS they
         // were already known to Tendermint.
         voting_power_by_consensus_key.retain(|consensus_key, voting_power| {
@@ -790,7 +927,8 @@ impl Staking {
                 self.set_validator_state(id, Inactive).await?;
             }
             (Jailed, true) => {
-                // Treat updates to jailed validators as unjail requests.
+                // Treat updates to jailed validators as unjail requests.  These
+                // are rejected by `check_tx_stateful` until the jail period is over.
                 self.set_validator_state(id, Inactive).await?;
             }
             (Active | Inactive | Jailed | Disabled, false) => {
@@ -811,6 +949,30 @@ impl Staking {
             .register_consensus_key(&validator.identity_key, &validator.consensus_key)
             .await;
 
+        // The new funding streams don't take effect immediately: they are
+        // announced, and replace the current ones after the commission change
+        // delay. Announcing the current funding streams cancels any pending change.
+        let existing = self
+            .state
+            .validator(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("updated validator not found in JMT"))?;
+        let current_epoch = self.state.get_current_epoch().await?;
+        let delay = self
+            .state
+            .get_chain_params()
+            .await?
+            .commission_change_delay_epochs;
+        let pending = PendingFundingStreams::announce(
+            self.state.pending_funding_streams(id).await?,
+            validator.funding_streams.clone(),
+            current_epoch.index,
+            delay,
+        );
+        self.state.set_pending_funding_streams(id, pending).await;
+
+        let mut validator = validator;
+        validator.funding_streams = existing.funding_streams;
         self.state
             .put_domain(state_key::validator_by_id(id).into(), validator)
             .await;
@@ -825,8 +987,12 @@ impl Staking {
             .await?
             .ok_or_else(|| anyhow::anyhow!("attempted to slash unknown validator"))?;
 
-        self.set_validator_state(&validator.identity_key, validator::State::Tombstoned)
-            .await
+        self.set_validator_state_with_evidence(
+            &validator.identity_key,
+            validator::State::Tombstoned,
+            Some(evidence),
+        )
+        .await
     }
 }
 
@@ -911,9 +1077,12 @@ impl Component for Staking {
     #[instrument(name = "staking", skip(_ctx, tx))]
     fn check_tx_stateless(_ctx: Context, tx: &Transaction) -> Result<()> {
         // Check that the transaction undelegates from at most one validator.
+        // Redelegations count as undelegations from their source validator,
+        // since their outputs are quarantined in the same way.
         let undelegation_identities = tx
             .undelegations()
             .map(|u| u.validator_identity.clone())
+            .chain(tx.redelegations().map(|r| r.source_validator.clone()))
             .collect::<BTreeSet<_>>();
 
         if undelegation_identities.len() > 1 {
@@ -923,17 +1092,30 @@ impl Component for Staking {
             ));
         }
 
-        // We prohibit actions other than `Spend`, `Delegate`, `Output` and `Undelegate` in
-        // transactions that contain `Undelegate`, to avoid having to quarantine them.
+        // We prohibit actions other than `Spend`, `Delegate`, `Output`, `Undelegate` and
+        // `Redelegate` in transactions that contain `Undelegate` or `Redelegate`, to avoid
+        // having to quarantine them.
         if undelegation_identities.len() == 1 {
             use Action::*;
             for action in tx.transaction_body().actions {
-                if !matches!(action, Undelegate(_) | Delegate(_) | Spend(_) | Output(_)) {
-                    return Err(anyhow::anyhow!("transaction contains an undelegation, but also contains an action other than Spend, Delegate, Output or Undelegate"));
+                if !matches!(
+                    action,
+                    Undelegate(_) | Redelegate(_) | Delegate(_) | Spend(_) | Output(_)
+                ) {
+                    return Err(anyhow::anyhow!("transaction contains an undelegation, but also contains an action other than Spend, Delegate, Output, Undelegate or Redelegate"));
                 }
             }
         }
 
+        for r in tx.redelegations() {
+            if r.source_validator == r.destination_validator {
+                return Err(anyhow::anyhow!(
+                    "redelegation source and destination are both {}",
+                    r.source_validator
+                ));
+            }
+        }
+
         // Check that validator definitions are correctly signed and well-formed:
         for definition in tx.validator_definitions() {
             let definition = validator::Definition::try_from(definition.clone())
@@ -1103,6 +1285,91 @@ impl Component for Staking {
             }
         }
 
+        for r in tx.redelegations() {
+            let source_rate_data = self
+                .state
+                .next_validator_rate(&r.source_validator)
+                .await?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("unknown validator identity {}", r.source_validator)
+                })?;
+            let destination_rate_data = self
+                .state
+                .next_validator_rate(&r.destination_validator)
+                .await?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("unknown validator identity {}", r.destination_validator)
+                })?;
+
+            // Both rates are for the same (next) epoch, so checking one suffices.
+            if r.epoch_index != source_rate_data.epoch_index {
+                return Err(anyhow::anyhow!(
+                    "redelegation was prepared for next epoch {} but the next epoch is {}",
+                    r.epoch_index,
+                    source_rate_data.epoch_index
+                ));
+            }
+
+            // The destination must accept delegations, exactly as for a `Delegate`.
+            let destination = self
+                .state
+                .validator(&r.destination_validator)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing definition for validator"))?;
+            let destination_state = self
+                .state
+                .validator_state(&r.destination_validator)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing state for validator"))?;
+
+            use validator::State::*;
+            if !destination.enabled {
+                return Err(anyhow::anyhow!(
+                    "redelegations are only allowed to enabled validators, but {} is disabled",
+                    r.destination_validator,
+                ));
+            }
+            if !matches!(destination_state, Inactive | Active) {
+                return Err(anyhow::anyhow!(
+                    "redelegations are only allowed to active or inactive validators, but {} is in state {:?}",
+                    r.destination_validator,
+                    destination_state,
+                ));
+            }
+
+            // A redelegation is an undelegation followed by a delegation, so
+            // we enforce the same rounding directions as for those actions:
+            // source delegation tokens -> unbonded stake -> destination
+            // delegation tokens.
+            let expected_unbonded_amount =
+                source_rate_data.unbonded_amount(r.source_delegation_amount.into());
+            if expected_unbonded_amount != u64::from(r.unbonded_amount) {
+                return Err(anyhow::anyhow!(
+                    "given {} delegation tokens, expected {} unbonded stake but redelegation produces {}",
+                    r.source_delegation_amount,
+                    expected_unbonded_amount,
+                    r.unbonded_amount,
+                ));
+            }
+            let expected_delegation_amount =
+                destination_rate_data.delegation_amount(r.unbonded_amount.into());
+            if expected_delegation_amount != u64::from(r.destination_delegation_amount) {
+                return Err(anyhow::anyhow!(
+                    "given {} unbonded stake, expected {} delegation tokens but redelegation produces {}",
+                    r.unbonded_amount,
+                    expected_delegation_amount,
+                    r.destination_delegation_amount
+                ));
+            }
+
+            *delegation_changes
+                .entry(r.source_validator.clone())
+                .or_insert(0) -= i64::try_from(r.source_delegation_amount).unwrap();
+            *delegation_changes
+                .entry(r.destination_validator.clone())
+                .or_insert(0) += i64::try_from(r.destination_delegation_amount).unwrap();
+        }
+
         // Check that the sequence numbers of updated validators are correct.
         for v in tx.validator_definitions() {
             let v = validator::Definition::try_from(v.clone())
@@ -1119,6 +1386,52 @@ impl Component for Staking {
                         current_seq
                     ));
                 }
+
+                // Ensure that the announced commission doesn't increase by more than
+                // the maximum allowed change over the commission in effect at the start
+                // of the current epoch, rather than over any earlier announcement, so
+                // that repeated announcements can't compound within an epoch.
+                let max_change = self
+                    .state
+                    .get_chain_params()
+                    .await?
+                    .max_commission_change_bps;
+                let current_epoch = self.state.get_current_epoch().await?;
+                let pending = self
+                    .state
+                    .pending_funding_streams(&v.validator.identity_key)
+                    .await?;
+                let current_bps = match &pending {
+                    Some(pending) => {
+                        pending.in_effect_at(current_epoch.index, &existing_v.funding_streams)
+                    }
+                    None => &existing_v.funding_streams,
+                }
+                .total_rate_bps();
+                let new_bps = v.validator.funding_streams.total_rate_bps();
+                if new_bps > current_bps + max_change {
+                    return Err(anyhow::anyhow!(
+                        "validator commission may increase by at most {}bps, but would increase from {}bps to {}bps",
+                        max_change,
+                        current_bps,
+                        new_bps,
+                    ));
+                }
+            }
+
+            // Ensure that a validator jailed for downtime isn't re-enabled before its
+            // jail period is over.  This also applies to validators that were disabled
+            // while jailed, so that disabling doesn't shortcut the jail period.
+            if v.validator.enabled {
+                let jail_record = self.state.jail_record(&v.validator.identity_key).await?;
+                let current_epoch = self.state.get_current_epoch().await?;
+                if !jail_record.may_unjail_at(current_epoch.index) {
+                    return Err(anyhow::anyhow!(
+                        "validator is jailed until epoch {}, but the current epoch is {}",
+                        jail_record.unjail_epoch,
+                        current_epoch.index,
+                    ));
+                }
             }
 
             // Check whether the consensus key has already been used by another validator.
@@ -1167,6 +1480,55 @@ impl Component for Staking {
                     tracing::debug!(?u, "queuing undelegation for next epoch");
                     self.delegation_changes.undelegations.push(u.clone());
                 }
+                Action::Redelegate(r) => {
+                    // For the purposes of the epoch transition, a redelegation is
+                    // an undelegation from the source and a delegation to the
+                    // destination, so the destination pool starts accruing
+                    // rewards at the next epoch. Slashing liability for the
+                    // source is tracked by the shielded pool's quarantine.
+                    tracing::debug!(?r, "queuing redelegation for next epoch");
+                    let redelegation = DelegationChanges {
+                        delegations: vec![Delegate {
+                            validator_identity: r.destination_validator.clone(),
+                            epoch_index: r.epoch_index,
+                            unbonded_amount: r.unbonded_amount,
+                            delegation_amount: r.destination_delegation_amount,
+                        }],
+                        undelegations: vec![Undelegate {
+                            validator_identity: r.source_validator.clone(),
+                            epoch_index: r.epoch_index,
+                            unbonded_amount: r.unbonded_amount,
+                            delegation_amount: r.source_delegation_amount,
+                        }],
+                    };
+                    self.delegation_changes
+                        .delegations
+                        .extend(redelegation.delegations.iter().cloned());
+                    self.delegation_changes
+                        .undelegations
+                        .extend(redelegation.undelegations.iter().cloned());
+
+                    // If the source validator is slashed while the redelegation
+                    // is quarantined, the shielded pool rolls it back, so we
+                    // have to be able to reverse its delegation changes.
+                    if let Some(epoch) = self
+                        .state
+                        .quarantine_end_epoch(&r.source_validator)
+                        .await
+                        .unwrap()
+                    {
+                        let mut quarantined = self
+                            .state
+                            .quarantined_redelegations(epoch, &r.source_validator)
+                            .await
+                            .unwrap();
+                        quarantined.delegations.extend(redelegation.delegations);
+                        quarantined.undelegations.extend(redelegation.undelegations);
+                        self.state
+                            .set_quarantined_redelegations(epoch, &r.source_validator, quarantined)
+                            .await;
+                    }
+                }
                 _ => {}
             }
         }
@@ -1217,6 +1579,9 @@ impl Component for Staking {
 
     #[instrument(name = "staking", skip(self, _ctx, end_block))]
     async fn end_block(&mut self, _ctx: Context, end_block: &abci::request::EndBlock) {
+        // Undo the redelegations the shielded pool rolls back in this block.
+        self.reverse_slashed_redelegations().await.unwrap();
+
         // Write the delegation changes for this block.
         self.state
             .set_delegation_changes(
@@ -1370,6 +1735,8 @@ pub trait View: StateExt {
         &self,
         identity_key: &IdentityKey,
         slashing_penalty_bps: u64,
+        reason: SlashingReason,
+        infraction_height: Option<u64>,
     ) -> Result<()> {
         let mut cur_rate = self
             .current_validator_rate(identity_key)
@@ -1377,9 +1744,28 @@ pub trait View: StateExt {
             .ok_or_else(|| {
                 anyhow::anyhow!("validator to be slashed did not have current rate in JMT")
             })?;
+        let exchange_rate_before = cur_rate.validator_exchange_rate;
 
         // Apply the slashing penalty to the current rate...
         cur_rate = cur_rate.slash(slashing_penalty_bps);
+
+        // ...record it in the validator's slashing history...
+        let mut history = self.slashing_history(identity_key).await?;
+        history.events.push(SlashingEvent {
+            height: self.get_block_height().await?,
+            epoch_index: self.get_current_epoch().await?.index,
+            reason,
+            infraction_height,
+            penalty_bps: slashing_penalty_bps,
+            exchange_rate_before,
+            exchange_rate_after: cur_rate.validator_exchange_rate,
+        });
+        self.put_domain(
+            state_key::slashing_history_by_validator(identity_key).into(),
+            history,
+        )
+        .await;
+
         // ...and ensure they're held constant at the penalized rate.
         let next_rate = {
             let mut rate = cur_rate.clone();
@@ -1397,6 +1783,28 @@ pub trait View: StateExt {
         Ok(())
     }
 
+    async fn slashing_history(&self, identity_key: &IdentityKey) -> Result<SlashingHistory> {
+        Ok(self
+            .get_domain(state_key::slashing_history_by_validator(identity_key).into())
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn jail_record(&self, identity_key: &IdentityKey) -> Result<JailRecord> {
+        Ok(self
+            .get_domain(state_key::jail_record_by_validator(identity_key).into())
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn set_jail_record(&self, identity_key: &IdentityKey, record: JailRecord) {
+        self.put_domain(
+            state_key::jail_record_by_validator(identity_key).into(),
+            record,
+        )
+        .await
+    }
+
     async fn record_slashing(&self, identity_key: IdentityKey) -> Result<()> {
         let height = self.get_block_height().await?;
         let key = super::state_key::slashed_validators(height).into();
@@ -1549,6 +1957,26 @@ pub trait View: StateExt {
             .await
     }
 
+    async fn pending_funding_streams(
+        &self,
+        identity_key: &IdentityKey,
+    ) -> Result<Option<PendingFundingStreams>> {
+        self.get_domain(state_key::pending_funding_streams_by_validator(identity_key).into())
+            .await
+    }
+
+    async fn set_pending_funding_streams(
+        &self,
+        identity_key: &IdentityKey,
+        pending: PendingFundingStreams,
+    ) {
+        self.put_domain(
+            state_key::pending_funding_streams_by_validator(identity_key).into(),
+            pending,
+        )
+        .await
+    }
+
     async fn set_validator_bonding_state(
         &self,
         identity_key: &IdentityKey,
@@ -1576,6 +2004,48 @@ pub trait View: StateExt {
 
         Ok(current_epoch.index + unbonding_epochs)
     }
+
+    /// Returns the epoch at the end of which the outputs of an undelegation
+    /// from the given validator are released from quarantine, or `None` if
+    /// the validator is unbonded and they need not be quarantined.
+    async fn quarantine_end_epoch(&self, identity_key: &IdentityKey) -> Result<Option<u64>> {
+        let bonding_state = self
+            .validator_bonding_state(identity_key)
+            .await?
+            .ok_or_else(|| anyhow!("validator {} has no bonding state", identity_key))?;
+
+        Ok(match bonding_state {
+            validator::BondingState::Unbonded => None,
+            validator::BondingState::Unbonding { unbonding_epoch } => Some(unbonding_epoch),
+            validator::BondingState::Bonded => Some(self.current_unbonding_end_epoch().await?),
+        })
+    }
+
+    /// Returns the delegation changes of the redelegations from the given
+    /// validator that are quarantined until the end of `epoch`.
+    async fn quarantined_redelegations(
+        &self,
+        epoch: u64,
+        source: &IdentityKey,
+    ) -> Result<DelegationChanges> {
+        Ok(self
+            .get_domain(state_key::quarantined_redelegations(epoch, source).into())
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn set_quarantined_redelegations(
+        &self,
+        epoch: u64,
+        source: &IdentityKey,
+        changes: DelegationChanges,
+    ) {
+        self.put_domain(
+            state_key::quarantined_redelegations(epoch, source).into(),
+            changes,
+        )
+        .await
+    }
 }
 
 impl<T: StateExt + Send + Sync> View for T {}
@@ -89,6 +89,14 @@ impl FundingStreams {
     pub fn iter(&self) -> impl Iterator<Item = &FundingStream> {
         self.funding_streams.iter()
     }
+
+    /// The total commission of these funding streams, in basis points.
+    pub fn total_rate_bps(&self) -> u64 {
+        self.funding_streams
+            .iter()
+            .map(|fs| fs.rate_bps as u64)
+            .sum()
+    }
 }
 
 impl TryFrom<Vec<FundingStream>> for FundingStreams {
@@ -125,3 +133,123 @@ impl IntoIterator for FundingStreams {
         self.funding_streams.into_iter()
     }
 }
+
+/// Funding streams announced by a validator, which replace its current funding streams once
+/// `effective_epoch` is reached.
+///
+/// Announcing new funding streams doesn't change a validator's commission immediately, so that
+/// delegators have time to react to the change before it affects their rewards.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PendingFundingStreams {
+    /// The announced funding streams.
+    pub funding_streams: FundingStreams,
+    /// The first epoch whose rates are computed using the announced funding streams.
+    pub effective_epoch: u64,
+}
+
+impl PendingFundingStreams {
+    /// Announces `funding_streams` in the epoch `epoch_index`, to take effect after `delay`
+    /// epochs, replacing the `pending` announcement, if any.
+    ///
+    /// Re-announcing the funding streams which are already pending keeps their effective epoch, so
+    /// that updating other parts of a validator's definition doesn't postpone a commission change.
+    pub fn announce(
+        pending: Option<Self>,
+        funding_streams: FundingStreams,
+        epoch_index: u64,
+        delay: u64,
+    ) -> Self {
+        match pending {
+            Some(pending) if pending.funding_streams == funding_streams => pending,
+            _ => Self {
+                funding_streams,
+                effective_epoch: epoch_index + delay,
+            },
+        }
+    }
+
+    /// The funding streams in effect in the epoch `epoch_index`, given the funding streams
+    /// `previous` that were in effect before these were announced.
+    pub fn in_effect_at<'a>(
+        &'a self,
+        epoch_index: u64,
+        previous: &'a FundingStreams,
+    ) -> &'a FundingStreams {
+        if self.effective_epoch <= epoch_index {
+            &self.funding_streams
+        } else {
+            previous
+        }
+    }
+}
+
+impl Protobuf<pb::PendingFundingStreams> for PendingFundingStreams {}
+
+impl From<PendingFundingStreams> for pb::PendingFundingStreams {
+    fn from(p: PendingFundingStreams) -> Self {
+        pb::PendingFundingStreams {
+            funding_streams: p.funding_streams.into_iter().map(Into::into).collect(),
+            effective_epoch: p.effective_epoch,
+        }
+    }
+}
+
+impl TryFrom<pb::PendingFundingStreams> for PendingFundingStreams {
+    type Error = anyhow::Error;
+
+    fn try_from(p: pb::PendingFundingStreams) -> Result<Self, Self::Error> {
+        Ok(PendingFundingStreams {
+            funding_streams: p
+                .funding_streams
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<FundingStream>, _>>()?
+                .try_into()?,
+            effective_epoch: p.effective_epoch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_crypto::keys::{SeedPhrase, SpendKey};
+    use rand_core::OsRng;
+
+    use super::*;
+
+    fn funding_streams(rate_bps: u16) -> FundingStreams {
+        let sk = SpendKey::from_seed_phrase(SeedPhrase::generate(&mut OsRng), 0);
+        let (address, _dtk) = sk
+            .full_viewing_key()
+            .incoming()
+            .payment_address(0u64.into());
+        vec![FundingStream { address, rate_bps }]
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn reannouncing_keeps_effective_epoch() {
+        let announced = funding_streams(200);
+        let pending = PendingFundingStreams::announce(None, announced.clone(), 10, 2);
+        assert_eq!(pending.effective_epoch, 12);
+
+        // Re-announcing the same funding streams in a later epoch doesn't delay them.
+        let pending = PendingFundingStreams::announce(Some(pending), announced, 11, 2);
+        assert_eq!(pending.effective_epoch, 12);
+
+        // Announcing different funding streams restarts the delay.
+        let pending = PendingFundingStreams::announce(Some(pending), funding_streams(300), 11, 2);
+        assert_eq!(pending.effective_epoch, 13);
+    }
+
+    #[test]
+    fn in_effect_from_effective_epoch() {
+        let previous = funding_streams(100);
+        let pending = PendingFundingStreams::announce(None, funding_streams(200), 10, 2);
+
+        assert_eq!(pending.in_effect_at(11, &previous).total_rate_bps(), 100);
+        assert_eq!(pending.in_effect_at(12, &previous).total_rate_bps(), 200);
+        assert_eq!(pending.in_effect_at(13, &previous).total_rate_bps(), 200);
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use penumbra_proto::{core::stake::v1alpha1 as pb, Protobuf};
+
+/// The number of repeat offenses after which downtime penalties and jail durations stop doubling.
+const MAX_ESCALATIONS: u64 = 10;
+
+/// A validator's record of being jailed for downtime.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JailRecord {
+    /// The number of times the validator has been jailed for downtime.
+    pub offense_count: u64,
+    /// The index of the epoch in which the validator was most recently jailed.
+    pub last_jailed_epoch: u64,
+    /// The first epoch in which the validator may be re-enabled after its most recent jailing.
+    pub unjail_epoch: u64,
+}
+
+impl JailRecord {
+    /// Records a downtime offense in the given epoch, returning the penalty to apply, in basis
+    /// points.
+    ///
+    /// Both the penalty and the jail duration double with each repeat offense.
+    pub fn record_offense(
+        &mut self,
+        epoch_index: u64,
+        downtime_penalty_bps: u64,
+        minimum_jail_epochs: u64,
+    ) -> u64 {
+        let escalation = 1u64 << self.offense_count.min(MAX_ESCALATIONS);
+
+        self.offense_count += 1;
+        self.last_jailed_epoch = epoch_index;
+        self.unjail_epoch =
+            epoch_index.saturating_add(minimum_jail_epochs.saturating_mul(escalation));
+
+        downtime_penalty_bps.saturating_mul(escalation).min(10_000)
+    }
+
+    /// Whether a validator with this record may be re-enabled in the given epoch.
+    pub fn may_unjail_at(&self, epoch_index: u64) -> bool {
+        epoch_index >= self.unjail_epoch
+    }
+}
+
+impl Protobuf<pb::JailRecord> for JailRecord {}
+
+impl From<JailRecord> for pb::JailRecord {
+    fn from(record: JailRecord) -> Self {
+        pb::JailRecord {
+            offense_count: record.offense_count,
+            last_jailed_epoch: record.last_jailed_epoch,
+            unjail_epoch: record.unjail_epoch,
+        }
+    }
+}
+
+impl TryFrom<pb::JailRecord> for JailRecord {
+    type Error = anyhow::Error;
+    fn try_from(record: pb::JailRecord) -> Result<Self> {
+        Ok(JailRecord {
+            offense_count: record.offense_count,
+            last_jailed_epoch: record.last_jailed_epoch,
+            unjail_epoch: record.unjail_epoch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_offenses_escalate() {
+        let mut record = JailRecord::default();
+        assert!(record.may_unjail_at(0));
+
+        assert_eq!(record.record_offense(5, 10, 2), 10);
+        assert_eq!(record.unjail_epoch, 7);
+        assert!(!record.may_unjail_at(6));
+        assert!(record.may_unjail_at(7));
+
+        assert_eq!(record.record_offense(10, 10, 2), 20);
+        assert_eq!(record.unjail_epoch, 14);
+
+        assert_eq!(record.record_offense(20, 10, 2), 40);
+        assert_eq!(record.unjail_epoch, 28);
+        assert_eq!(record.offense_count, 3);
+    }
+
+    #[test]
+    fn penalty_is_capped() {
+        let mut record = JailRecord {
+            offense_count: 100,
+            ..Default::default()
+        };
+        assert_eq!(record.record_offense(0, 5000, 1), 10_000);
+        assert_eq!(record.unjail_epoch, 1 << MAX_ESCALATIONS);
+    }
+}
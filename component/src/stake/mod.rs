@@ -4,7 +4,10 @@ use penumbra_crypto::IdentityKey;
 mod changes;
 mod current_consensus_keys;
 mod funding_stream;
+mod jail;
 mod metrics;
+mod power_cap;
+mod slashing;
 mod uptime;
 
 pub mod component;
@@ -16,5 +19,7 @@ pub use self::metrics::register_metrics;
 pub use changes::DelegationChanges;
 pub use component::View;
 pub use current_consensus_keys::CurrentConsensusKeys;
-pub use funding_stream::{FundingStream, FundingStreams};
+pub use funding_stream::{FundingStream, FundingStreams, PendingFundingStreams};
+pub use jail::JailRecord;
+pub use slashing::{SlashingEvent, SlashingHistory, SlashingReason};
 pub use uptime::Uptime;
@@ -0,0 +1,88 @@
+/// Caps the given voting powers so that no single validator holds more than
+/// `max_share_bps` basis points of the total (capped) power.
+///
+/// Validators above the cap all receive the same capped power, and validators
+/// below it are unaffected.  If the cap can't be satisfied, because there are
+/// too few validators for every one of them to stay below it, all validators
+/// receive equal power.  A `max_share_bps` of 0 (or 10000) disables the cap.
+pub fn cap_voting_power(powers: &[u64], max_share_bps: u64) -> Vec<u64> {
+    if max_share_bps == 0 || max_share_bps >= 10_000 {
+        return powers.to_vec();
+    }
+
+    let mut sorted = powers
+        .iter()
+        .copied()
+        .filter(|power| *power > 0)
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    // Suppose the `capped` most powerful validators are held at the cap `c`, and
+    // the remaining validators have total power `rest`.  Then the cap must satisfy
+    //
+    //     c = share * (capped * c + rest)
+    //
+    // and is consistent if the most powerful remaining validator is below it.
+    let share = max_share_bps as u128;
+    let mut rest = sorted.iter().map(|power| *power as u128).sum::<u128>();
+    let mut cap = None;
+    for (capped, power) in sorted.iter().enumerate() {
+        let capped = capped as u128;
+        if capped * share >= 10_000 {
+            break;
+        }
+        let c = share * rest / (10_000 - capped * share);
+        if *power as u128 <= c {
+            cap = Some(c as u64);
+            break;
+        }
+        rest -= *power as u128;
+    }
+
+    // If no cap is consistent, the best we can do is to make all validators equal.
+    let cap = cap.unwrap_or_else(|| sorted.last().copied().unwrap_or(0));
+
+    powers.iter().map(|power| (*power).min(cap)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_cap_when_disabled() {
+        let powers = vec![90, 5, 5];
+        assert_eq!(cap_voting_power(&powers, 0), powers);
+        assert_eq!(cap_voting_power(&powers, 10_000), powers);
+    }
+
+    #[test]
+    fn no_change_below_cap() {
+        let powers = vec![30, 30, 20, 20];
+        assert_eq!(cap_voting_power(&powers, 3333), powers);
+    }
+
+    #[test]
+    fn caps_dominant_validator() {
+        let capped = cap_voting_power(&[10, 60, 10, 10, 10, 0], 2500);
+        assert_eq!(capped, vec![10, 13, 10, 10, 10, 0]);
+
+        let total = capped.iter().sum::<u64>();
+        assert!(capped.iter().all(|power| power * 10_000 <= total * 2500));
+    }
+
+    #[test]
+    fn caps_several_validators() {
+        let capped = cap_voting_power(&[40, 35, 10, 10, 5], 3000);
+        let total = capped.iter().sum::<u64>();
+        assert!(capped.iter().all(|power| power * 10_000 <= total * 3000));
+        // The validators below the cap are unaffected.
+        assert_eq!(&capped[2..], &[10, 10, 5]);
+        assert_eq!(capped[0], capped[1]);
+    }
+
+    #[test]
+    fn equalizes_when_unsatisfiable() {
+        assert_eq!(cap_voting_power(&[50, 30, 20], 3000), vec![20, 20, 20]);
+    }
+}
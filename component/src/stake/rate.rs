@@ -1,7 +1,7 @@
 //! Staking reward and delegation token exchange rates.
 
 use penumbra_proto::{core::stake::v1alpha1 as pb, Protobuf};
-use penumbra_transaction::action::{Delegate, Undelegate};
+use penumbra_transaction::action::{Delegate, Redelegate, Undelegate};
 use serde::{Deserialize, Serialize};
 
 use crate::stake::{validator::State, FundingStream, IdentityKey};
@@ -155,6 +155,22 @@ impl RateData {
             validator_identity: self.identity_key.clone(),
         }
     }
+
+    /// Uses this `RateData` and the `RateData` of the `destination` validator
+    /// to build a `Redelegate` transaction action that moves
+    /// `delegation_amount` of this validator's delegation tokens into the
+    /// destination's delegation pool.
+    pub fn build_redelegate(&self, destination: &RateData, delegation_amount: u64) -> Redelegate {
+        let unbonded_amount = self.unbonded_amount(delegation_amount);
+        Redelegate {
+            source_validator: self.identity_key.clone(),
+            destination_validator: destination.identity_key.clone(),
+            epoch_index: self.epoch_index,
+            source_delegation_amount: delegation_amount.into(),
+            unbonded_amount: unbonded_amount.into(),
+            destination_delegation_amount: destination.delegation_amount(unbonded_amount).into(),
+        }
+    }
 }
 
 /// Describes the base reward and exchange rates in some epoch.
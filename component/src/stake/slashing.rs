@@ -0,0 +1,126 @@
+use anyhow::Result;
+use penumbra_proto::{core::stake::v1alpha1 as pb, Protobuf};
+
+/// The reason a validator was slashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlashingReason {
+    /// The validator missed too many blocks and was jailed.
+    Downtime,
+    /// The validator was reported for signing conflicting votes.
+    DuplicateVote,
+    /// The validator was reported for participating in a light client attack.
+    LightClientAttack,
+    /// The validator was reported for misbehavior of a kind unknown to this node.
+    UnknownMisbehavior,
+}
+
+impl std::fmt::Display for SlashingReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SlashingReason::Downtime => write!(f, "Downtime"),
+            SlashingReason::DuplicateVote => write!(f, "Duplicate Vote"),
+            SlashingReason::LightClientAttack => write!(f, "Light Client Attack"),
+            SlashingReason::UnknownMisbehavior => write!(f, "Unknown Misbehavior"),
+        }
+    }
+}
+
+/// A record of a slashing penalty applied to a validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlashingEvent {
+    /// The height at which the penalty was applied.
+    pub height: u64,
+    /// The index of the epoch in which the penalty was applied.
+    pub epoch_index: u64,
+    /// The reason the validator was slashed.
+    pub reason: SlashingReason,
+    /// For misbehavior, the height at which the reported infraction occurred.
+    pub infraction_height: Option<u64>,
+    /// The penalty applied, in basis points.
+    pub penalty_bps: u64,
+    /// The validator's exchange rate before the penalty was applied.
+    pub exchange_rate_before: u64,
+    /// The validator's exchange rate after the penalty was applied.
+    pub exchange_rate_after: u64,
+}
+
+impl Protobuf<pb::SlashingEvent> for SlashingEvent {}
+
+impl From<SlashingEvent> for pb::SlashingEvent {
+    fn from(event: SlashingEvent) -> Self {
+        use pb::slashing_event::SlashingReasonEnum;
+        pb::SlashingEvent {
+            height: event.height,
+            epoch_index: event.epoch_index,
+            reason: match event.reason {
+                SlashingReason::Downtime => SlashingReasonEnum::Downtime,
+                SlashingReason::DuplicateVote => SlashingReasonEnum::DuplicateVote,
+                SlashingReason::LightClientAttack => SlashingReasonEnum::LightClientAttack,
+                SlashingReason::UnknownMisbehavior => SlashingReasonEnum::UnknownMisbehavior,
+            } as i32,
+            infraction_height: event.infraction_height.unwrap_or_default(),
+            penalty_bps: event.penalty_bps,
+            exchange_rate_before: event.exchange_rate_before,
+            exchange_rate_after: event.exchange_rate_after,
+        }
+    }
+}
+
+impl TryFrom<pb::SlashingEvent> for SlashingEvent {
+    type Error = anyhow::Error;
+    fn try_from(event: pb::SlashingEvent) -> Result<Self> {
+        use pb::slashing_event::SlashingReasonEnum;
+        let reason = match SlashingReasonEnum::from_i32(event.reason)
+            .ok_or_else(|| anyhow::anyhow!("invalid slashing reason"))?
+        {
+            SlashingReasonEnum::Downtime => SlashingReason::Downtime,
+            SlashingReasonEnum::DuplicateVote => SlashingReason::DuplicateVote,
+            SlashingReasonEnum::LightClientAttack => SlashingReason::LightClientAttack,
+            SlashingReasonEnum::UnknownMisbehavior => SlashingReason::UnknownMisbehavior,
+        };
+
+        Ok(SlashingEvent {
+            height: event.height,
+            epoch_index: event.epoch_index,
+            reason,
+            // Downtime isn't reported with an infraction height.
+            infraction_height: if reason == SlashingReason::Downtime {
+                None
+            } else {
+                Some(event.infraction_height)
+            },
+            penalty_bps: event.penalty_bps,
+            exchange_rate_before: event.exchange_rate_before,
+            exchange_rate_after: event.exchange_rate_after,
+        })
+    }
+}
+
+/// All the slashing penalties applied to a validator, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlashingHistory {
+    pub events: Vec<SlashingEvent>,
+}
+
+impl Protobuf<pb::SlashingHistory> for SlashingHistory {}
+
+impl From<SlashingHistory> for pb::SlashingHistory {
+    fn from(history: SlashingHistory) -> Self {
+        pb::SlashingHistory {
+            events: history.events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::SlashingHistory> for SlashingHistory {
+    type Error = anyhow::Error;
+    fn try_from(history: pb::SlashingHistory) -> Result<Self> {
+        Ok(SlashingHistory {
+            events: history
+                .events
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
@@ -30,6 +30,10 @@ pub fn next_rate_by_validator(id: &IdentityKey) -> String {
     format!("staking/validator/{}/rate/next", id)
 }
 
+pub fn pending_funding_streams_by_validator(id: &IdentityKey) -> String {
+    format!("staking/validator/{}/funding_streams/pending", id)
+}
+
 pub fn power_by_validator(id: &IdentityKey) -> String {
     format!("staking/validator/{}/power", id)
 }
@@ -42,6 +46,14 @@ pub fn uptime_by_validator(id: &IdentityKey) -> String {
     format!("staking/validator/{}/uptime", id)
 }
 
+pub fn slashing_history_by_validator(id: &IdentityKey) -> String {
+    format!("staking/validator/{}/slashing_history", id)
+}
+
+pub fn jail_record_by_validator(id: &IdentityKey) -> String {
+    format!("staking/validator/{}/jail_record", id)
+}
+
 pub fn slashed_validators(height: u64) -> String {
     format!("staking/slashed_validators/{}", height)
 }
@@ -61,6 +73,10 @@ pub fn delegation_changes_by_height(height: u64) -> String {
     format!("staking/delegation_changes/{}", height)
 }
 
+pub fn quarantined_redelegations(epoch: u64, source: &IdentityKey) -> String {
+    format!("staking/quarantined_redelegations/{}/{}", epoch, source)
+}
+
 pub fn current_consensus_keys() -> &'static str {
     "staking/current_consensus_keys"
 }
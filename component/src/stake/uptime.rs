@@ -81,6 +81,34 @@ impl Uptime {
     pub fn num_missed_blocks(&self) -> usize {
         self.signatures.iter_zeros().len()
     }
+
+    /// Returns the heights of the blocks the validator missed within the window, in ascending order.
+    pub fn missed_blocks(&self) -> impl Iterator<Item = u64> + '_ {
+        let window_len = self.signatures.len() as u64;
+        let mut heights = self
+            .signatures
+            .iter_zeros()
+            .filter_map(|index| {
+                // The record for height `h` is stored at index `h % window_len`, and the window
+                // ends at the current height, so each index corresponds to exactly one height.
+                let offset =
+                    (self.as_of_block_height % window_len + window_len - index as u64) % window_len;
+                self.as_of_block_height.checked_sub(offset)
+            })
+            .collect::<Vec<_>>();
+        heights.sort_unstable();
+        heights.into_iter()
+    }
+
+    /// The height of the last block recorded by this tracker.
+    pub fn as_of_block_height(&self) -> u64 {
+        self.as_of_block_height
+    }
+
+    /// The number of blocks in the window tracked by this tracker.
+    pub fn window_len(&self) -> usize {
+        self.signatures.len()
+    }
 }
 
 impl Protobuf<pb::Uptime> for Uptime {}
@@ -139,6 +167,24 @@ mod tests {
         assert!(uptime.mark_height_as_signed(0, true).is_err());
     }
 
+    #[test]
+    fn reports_missed_heights() {
+        let window = 10;
+        let mut uptime = Uptime::new(0, window);
+
+        // Miss every 3rd block for a window and a half
+        for h in 1..16u64 {
+            uptime.mark_height_as_signed(h, h % 3 != 0).unwrap();
+        }
+
+        // Only the misses in the last `window` blocks (6..=15) are reported
+        assert_eq!(
+            uptime.missed_blocks().collect::<Vec<_>>(),
+            vec![6, 9, 12, 15]
+        );
+        assert_eq!(uptime.missed_blocks().count(), uptime.num_missed_blocks());
+    }
+
     #[test]
     fn proto_round_trip() {
         // make a weird size window
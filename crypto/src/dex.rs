@@ -1,6 +1,6 @@
 pub mod lp;
 pub mod swap;
-pub use swap::BatchSwapOutputData;
+pub use swap::{BatchSwapOutputData, LimitPrices, LimitTiers};
 
 mod trading_pair;
 pub use trading_pair::TradingPair;
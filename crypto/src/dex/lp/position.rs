@@ -2,7 +2,7 @@ use anyhow::Context;
 use penumbra_proto::{core::dex::v1alpha1 as pb, serializers::bech32str, Protobuf};
 use serde::{Deserialize, Serialize};
 
-use super::{super::TradingPair, TradingFunction};
+use super::{super::TradingPair, Reserves, TradingFunction};
 
 /// Data identifying a position.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +40,19 @@ impl Position {
     }
 }
 
+/// The metadata of a position, recording its current [`State`] and [`Reserves`].
+///
+/// Unlike the [`Position`] itself, which is immutable, the metadata evolves over
+/// the lifetime of the position, as trades are executed against it and as it is
+/// closed and withdrawn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "pb::PositionMetadata", into = "pb::PositionMetadata")]
+pub struct Metadata {
+    pub position: Position,
+    pub state: State,
+    pub reserves: Reserves,
+}
+
 /// A hash of a [`Position`].
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize)]
 #[serde(try_from = "pb::PositionId", into = "pb::PositionId")]
@@ -153,6 +166,39 @@ impl From<Position> for pb::Position {
     }
 }
 
+impl Protobuf<pb::PositionMetadata> for Metadata {}
+
+impl TryFrom<pb::PositionMetadata> for Metadata {
+    type Error = anyhow::Error;
+
+    fn try_from(value: pb::PositionMetadata) -> Result<Self, Self::Error> {
+        Ok(Self {
+            position: value
+                .position
+                .ok_or_else(|| anyhow::anyhow!("missing position"))?
+                .try_into()?,
+            state: value
+                .state
+                .ok_or_else(|| anyhow::anyhow!("missing position state"))?
+                .try_into()?,
+            reserves: value
+                .reserves
+                .ok_or_else(|| anyhow::anyhow!("missing reserves"))?
+                .try_into()?,
+        })
+    }
+}
+
+impl From<Metadata> for pb::PositionMetadata {
+    fn from(value: Metadata) -> Self {
+        Self {
+            position: Some(value.position.into()),
+            state: Some(value.state.into()),
+            reserves: Some(value.reserves.into()),
+        }
+    }
+}
+
 impl Protobuf<pb::PositionId> for Id {}
 
 impl TryFrom<pb::PositionId> for Id {
@@ -1,5 +1,6 @@
-use crate::asset::Amount;
+use crate::{asset::Amount, dex::TradingPair, Balance, Value};
 use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+use serde::{Deserialize, Serialize};
 
 /// The reserves of a position.
 ///
@@ -7,12 +8,31 @@ use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
 /// between assets 1 and 2, without specifying what those assets are, to avoid
 /// duplicating data (each asset ID alone is four times the size of the
 /// reserves).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "pb::Reserves", into = "pb::Reserves")]
 pub struct Reserves {
     pub r1: Amount,
     pub r2: Amount,
 }
 
+impl Reserves {
+    /// Returns the [`Balance`] of these reserves, interpreted as the reserves
+    /// of a position on the given `pair`.
+    pub fn balance(&self, pair: &TradingPair) -> Balance {
+        let r1 = Value {
+            amount: self.r1,
+            asset_id: pair.asset_1(),
+        };
+
+        let r2 = Value {
+            amount: self.r2,
+            asset_id: pair.asset_2(),
+        };
+
+        Balance::from(r1) + r2
+    }
+}
+
 impl Protobuf<pb::Reserves> for Reserves {}
 
 impl TryFrom<pb::Reserves> for Reserves {
@@ -1,6 +1,12 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigUint;
 use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
 use serde::{Deserialize, Serialize};
 
+use super::Reserves;
+use crate::asset::Amount;
+
 /// The data describing a trading function.
 ///
 /// This implicitly treats the trading function as being between assets 1 and 2,
@@ -10,16 +16,199 @@ use serde::{Deserialize, Serialize};
 /// The trading function is `phi(R) = p*R_1 + q*R_2`.
 /// This is used as a CFMM with constant `k` and fee `fee` (gamma).
 ///
-/// NOTE: the use of floats here is a placeholder ONLY, so we can stub out the implementation,
-/// and then decide what type of fixed-point, deterministic arithmetic should be used.
+/// All the parameters are integers, so that execution is deterministic: prices
+/// are expressed as the ratio `p / q`, and the fee in basis points.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(try_from = "pb::TradingFunction", into = "pb::TradingFunction")]
 
 pub struct TradingFunction {
-    pub fee: f64,
-    pub k: f64,
-    pub p: f64,
-    pub q: f64,
+    /// The fee charged on the input of each trade, in basis points.
+    pub fee: u32,
+    pub k: Amount,
+    pub p: Amount,
+    pub q: Amount,
+}
+
+/// The denominator of fees expressed in basis points.
+const BPS: u32 = 10_000;
+
+impl TradingFunction {
+    /// Returns `true` if the trading function describes a position that can be
+    /// traded against: both prices must be positive, and the fee must be less
+    /// than 100%.
+    pub fn is_valid(&self) -> bool {
+        self.p.inner > 0 && self.q.inner > 0 && self.fee < BPS
+    }
+
+    /// Evaluates the trading function on the given reserves.
+    pub fn phi(&self, reserves: &Reserves) -> u128 {
+        (u128::from(self.p.inner) * u128::from(reserves.r1.inner))
+            .saturating_add(u128::from(self.q.inner) * u128::from(reserves.r2.inner))
+    }
+
+    /// The amount of asset 2 the position pays out per unit of asset 1 it
+    /// receives, net of fees.
+    ///
+    /// This is approximate, and only meant for display: execution compares
+    /// prices exactly, using [`TradingFunction::cmp_price_1_to_2`].
+    pub fn effective_price_1_to_2(&self) -> f64 {
+        self.gamma() * self.p.inner as f64 / self.q.inner as f64
+    }
+
+    /// The amount of asset 1 the position pays out per unit of asset 2 it
+    /// receives, net of fees.
+    ///
+    /// This is approximate, and only meant for display: execution compares
+    /// prices exactly, using [`TradingFunction::cmp_price_2_to_1`].
+    pub fn effective_price_2_to_1(&self) -> f64 {
+        self.gamma() * self.q.inner as f64 / self.p.inner as f64
+    }
+
+    fn gamma(&self) -> f64 {
+        f64::from(BPS.saturating_sub(self.fee)) / f64::from(BPS)
+    }
+
+    /// Compares the effective prices at which `self` and `other` trade asset 1
+    /// for asset 2, net of fees.
+    pub fn cmp_price_1_to_2(&self, other: &Self) -> Ordering {
+        cmp_price(
+            (self.fee, self.p.inner, self.q.inner),
+            (other.fee, other.p.inner, other.q.inner),
+        )
+    }
+
+    /// Compares the effective prices at which `self` and `other` trade asset 2
+    /// for asset 1, net of fees.
+    pub fn cmp_price_2_to_1(&self, other: &Self) -> Ordering {
+        cmp_price(
+            (self.fee, self.q.inner, self.p.inner),
+            (other.fee, other.q.inner, other.p.inner),
+        )
+    }
+
+    /// Fills as much of an input `delta_1` of asset 1 as possible against a
+    /// position with the given `reserves`.
+    ///
+    /// Since the trading function is linear, trades preserve
+    /// `p * (R_1 + gamma * Delta_1) + q * (R_2 - Lambda_2) = p * R_1 + q * R_2`,
+    /// so the output is `Lambda_2 = gamma * p * Delta_1 / q`, capped by the
+    /// position's reserves of asset 2.
+    ///
+    /// Returns the unfilled part of the input, the new reserves of the position,
+    /// and the output `Lambda_2`.
+    pub fn fill_1_to_2(&self, delta_1: Amount, reserves: &Reserves) -> (Amount, Reserves, Amount) {
+        let (unfilled, r1, r2, lambda_2) = self.fill(
+            self.p.inner,
+            self.q.inner,
+            delta_1.into(),
+            reserves.r1.into(),
+            reserves.r2.into(),
+        );
+        (
+            unfilled.into(),
+            Reserves {
+                r1: r1.into(),
+                r2: r2.into(),
+            },
+            lambda_2.into(),
+        )
+    }
+
+    /// Fills as much of an input `delta_2` of asset 2 as possible against a
+    /// position with the given `reserves`.
+    ///
+    /// This is the mirror image of [`TradingFunction::fill_1_to_2`].
+    ///
+    /// Returns the unfilled part of the input, the new reserves of the position,
+    /// and the output `Lambda_1`.
+    pub fn fill_2_to_1(&self, delta_2: Amount, reserves: &Reserves) -> (Amount, Reserves, Amount) {
+        let (unfilled, r2, r1, lambda_1) = self.fill(
+            self.q.inner,
+            self.p.inner,
+            delta_2.into(),
+            reserves.r2.into(),
+            reserves.r1.into(),
+        );
+        (
+            unfilled.into(),
+            Reserves {
+                r1: r1.into(),
+                r2: r2.into(),
+            },
+            lambda_1.into(),
+        )
+    }
+
+    /// Trades `delta` of an input asset for an output asset, against reserves
+    /// `r_in` and `r_out`, where `p_in` and `p_out` are the coefficients of the
+    /// input and output assets in the trading function. Returns
+    /// `(unfilled, r_in', r_out', lambda)`.
+    ///
+    /// The position can only be traded against while its reserves satisfy
+    /// `phi(R) >= k`. Every fill preserves this, since outputs are rounded
+    /// *down* and consumed inputs are rounded *up*, so that rounding (like the
+    /// fee) always favors the position.
+    fn fill(
+        &self,
+        p_in: u64,
+        p_out: u64,
+        delta: u64,
+        r_in: u64,
+        r_out: u64,
+    ) -> (u64, u64, u64, u64) {
+        let unfilled = (delta, r_in, r_out, 0);
+        if !self.is_valid() {
+            return unfilled;
+        }
+
+        let gamma = u128::from(BPS - self.fee);
+        let (p_in, p_out) = (u128::from(p_in), u128::from(p_out));
+
+        let phi = (p_in * u128::from(r_in)).saturating_add(p_out * u128::from(r_out));
+        if phi < u128::from(self.k.inner) {
+            return unfilled;
+        }
+
+        // The input net of fees, and the output it buys, both rounded down.
+        // Neither product can overflow, since each factor fits in a u64.
+        let net = u128::from(delta) * gamma / u128::from(BPS);
+        let lambda = p_in * net / p_out;
+
+        if lambda <= u128::from(r_out) {
+            // The position has enough reserves to fill the entire input.
+            let r_in = match r_in.checked_add(delta) {
+                Some(r_in) => r_in,
+                None => return unfilled,
+            };
+            let lambda = lambda as u64;
+            (0, r_in, r_out - lambda, lambda)
+        } else {
+            // The position can only fill part of the input: it pays out all of
+            // its reserves of the output asset, and takes only as much input as
+            // needed. Here `r_out < lambda`, so the net input needed is at most
+            // `net`, and the consumed input is at most `delta`.
+            let net = div_ceil(u128::from(r_out) * p_out, p_in);
+            let consumed = div_ceil(net * u128::from(BPS), gamma).min(u128::from(delta)) as u64;
+            let r_in = match r_in.checked_add(consumed) {
+                Some(r_in) => r_in,
+                None => return unfilled,
+            };
+            (delta - consumed, r_in, 0, r_out)
+        }
+    }
+}
+
+/// Compares the prices `gamma_a * p_a / q_a` and `gamma_b * p_b / q_b`, where
+/// each `gamma` is `1 - fee`, by cross-multiplying.
+fn cmp_price((fee_a, p_a, q_a): (u32, u64, u64), (fee_b, p_b, q_b): (u32, u64, u64)) -> Ordering {
+    let scaled = |fee: u32, p: u64, q: u64| {
+        BigUint::from(BPS.saturating_sub(fee)) * BigUint::from(p) * BigUint::from(q)
+    };
+    scaled(fee_a, p_a, q_b).cmp(&scaled(fee_b, p_b, q_a))
+}
+
+fn div_ceil(numerator: u128, denominator: u128) -> u128 {
+    numerator / denominator + u128::from(numerator % denominator != 0)
 }
 
 impl Protobuf<pb::TradingFunction> for TradingFunction {}
@@ -30,9 +219,18 @@ impl TryFrom<pb::TradingFunction> for TradingFunction {
     fn try_from(value: pb::TradingFunction) -> Result<Self, Self::Error> {
         Ok(Self {
             fee: value.fee,
-            k: value.k,
-            p: value.p,
-            q: value.q,
+            k: value
+                .k
+                .ok_or_else(|| anyhow::anyhow!("missing k"))?
+                .try_into()?,
+            p: value
+                .p
+                .ok_or_else(|| anyhow::anyhow!("missing p"))?
+                .try_into()?,
+            q: value
+                .q
+                .ok_or_else(|| anyhow::anyhow!("missing q"))?
+                .try_into()?,
         })
     }
 }
@@ -41,9 +239,130 @@ impl From<TradingFunction> for pb::TradingFunction {
     fn from(value: TradingFunction) -> Self {
         Self {
             fee: value.fee,
-            k: value.k,
-            p: value.p,
-            q: value.q,
+            k: Some(value.k.into()),
+            p: Some(value.p.into()),
+            q: Some(value.q.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phi(p: u64, q: u64, fee: u32) -> TradingFunction {
+        TradingFunction {
+            fee,
+            k: 0u64.into(),
+            p: p.into(),
+            q: q.into(),
+        }
+    }
+
+    fn reserves(r1: u64, r2: u64) -> Reserves {
+        Reserves {
+            r1: r1.into(),
+            r2: r2.into(),
         }
     }
+
+    #[test]
+    fn fill_within_reserves() {
+        // 1 unit of asset 1 is worth 2 units of asset 2, with no fee.
+        let phi = phi(2, 1, 0);
+
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(10u64.into(), &reserves(0, 100));
+        assert_eq!(unfilled, 0u64.into());
+        assert_eq!(lambda_2, 20u64.into());
+        assert_eq!(new_reserves, reserves(10, 80));
+
+        let (unfilled, new_reserves, lambda_1) = phi.fill_2_to_1(20u64.into(), &new_reserves);
+        assert_eq!(unfilled, 0u64.into());
+        assert_eq!(lambda_1, 10u64.into());
+        assert_eq!(new_reserves, reserves(0, 100));
+    }
+
+    #[test]
+    fn fill_exhausts_reserves() {
+        let phi = phi(1, 1, 0);
+
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(150u64.into(), &reserves(0, 100));
+        assert_eq!(unfilled, 50u64.into());
+        assert_eq!(lambda_2, 100u64.into());
+        assert_eq!(new_reserves, reserves(100, 0));
+    }
+
+    #[test]
+    fn fee_is_retained_by_position() {
+        // 100 basis points = 1%
+        let phi = phi(1, 1, 100);
+
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(100u64.into(), &reserves(0, 1000));
+        assert_eq!(unfilled, 0u64.into());
+        assert_eq!(lambda_2, 99u64.into());
+        assert_eq!(new_reserves, reserves(100, 901));
+    }
+
+    #[test]
+    fn rounding_favors_position() {
+        // 1 unit of asset 1 is worth 1/3 of a unit of asset 2.
+        let phi = phi(1, 3, 0);
+
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(10u64.into(), &reserves(0, 100));
+        assert_eq!(unfilled, 0u64.into());
+        assert_eq!(lambda_2, 3u64.into());
+        assert_eq!(new_reserves, reserves(10, 97));
+
+        // Exhausting the reserves consumes just enough input to pay for them.
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(10u64.into(), &reserves(0, 2));
+        assert_eq!(unfilled, 4u64.into());
+        assert_eq!(lambda_2, 2u64.into());
+        assert_eq!(new_reserves, reserves(6, 0));
+        assert_eq!(phi.phi(&new_reserves), phi.phi(&reserves(0, 2)));
+    }
+
+    #[test]
+    fn fill_honors_k() {
+        let mut phi = phi(1, 1, 0);
+        phi.k = 100u64.into();
+
+        // A position whose reserves satisfy the invariant can be traded against,
+        // and trading preserves the invariant.
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(40u64.into(), &reserves(0, 100));
+        assert_eq!(unfilled, 0u64.into());
+        assert_eq!(lambda_2, 40u64.into());
+        assert!(phi.phi(&new_reserves) >= 100);
+
+        // A position whose reserves fall short of the invariant cannot.
+        let (unfilled, new_reserves, lambda_2) = phi.fill_1_to_2(40u64.into(), &reserves(0, 99));
+        assert_eq!(unfilled, 40u64.into());
+        assert_eq!(lambda_2, 0u64.into());
+        assert_eq!(new_reserves, reserves(0, 99));
+    }
+
+    #[test]
+    fn compares_prices_net_of_fees() {
+        assert_eq!(
+            phi(2, 1, 0).cmp_price_1_to_2(&phi(1, 1, 0)),
+            Ordering::Greater
+        );
+        assert_eq!(phi(2, 1, 0).cmp_price_2_to_1(&phi(1, 1, 0)), Ordering::Less);
+        assert_eq!(
+            phi(2, 2, 0).cmp_price_1_to_2(&phi(1, 1, 0)),
+            Ordering::Equal
+        );
+        assert_eq!(phi(1, 1, 1).cmp_price_1_to_2(&phi(1, 1, 0)), Ordering::Less);
+        assert_eq!(
+            phi(u64::MAX, 1, 0).cmp_price_1_to_2(&phi(u64::MAX - 1, 1, 0)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn invalid_trading_functions() {
+        assert!(phi(1, 1, 0).is_valid());
+        assert!(!phi(0, 1, 0).is_valid());
+        assert!(!phi(1, 0, 0).is_valid());
+        assert!(!phi(1, 1, 10_000).is_valid());
+    }
 }
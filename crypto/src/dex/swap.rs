@@ -1,4 +1,5 @@
 mod ciphertext;
+mod limit_prices;
 mod plaintext;
 
 use anyhow::{anyhow, Result};
@@ -6,6 +7,7 @@ use ark_ff::PrimeField;
 use blake2b_simd::Hash;
 pub use ciphertext::SwapCiphertext;
 use decaf377::Fq;
+pub use limit_prices::{LimitPrices, LimitTiers, PRICE_SCALE};
 pub use plaintext::SwapPlaintext;
 
 use once_cell::sync::Lazy;
@@ -15,9 +17,9 @@ use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
 use super::TradingPair;
 
 // Swap ciphertext byte length
-pub const SWAP_CIPHERTEXT_BYTES: usize = 216;
+pub const SWAP_CIPHERTEXT_BYTES: usize = 232;
 // Swap plaintext byte length
-pub const SWAP_LEN_BYTES: usize = 200;
+pub const SWAP_LEN_BYTES: usize = 216;
 
 pub const OVK_WRAPPED_LEN_BYTES: usize = 80;
 
@@ -33,13 +35,34 @@ pub struct BatchSwapOutputData {
     pub height: u64,
     pub trading_pair: TradingPair,
     pub success: bool,
+    /// The highest limit prices of the swaps that were executed.
+    ///
+    /// Swaps with a higher limit were excluded from the batch before it was
+    /// executed, so `delta_1` and `delta_2` do not include their inputs.
+    pub limit_thresholds: LimitPrices,
+    /// The limit tiers whose swaps were too few to be decrypted on their own.
+    ///
+    /// These swaps were refunded without being decrypted or executed.
+    pub refunded_tiers: LimitTiers,
 }
 
 impl BatchSwapOutputData {
     /// Given a user's inputs `(delta_1_i, delta_2_i)`, compute their pro rata share
     /// of the batch output `(lambda_1_i, lambda_2_i)`.
-    pub fn pro_rata_outputs(&self, (delta_1_i, delta_2_i): (u64, u64)) -> (u64, u64) {
-        if self.success {
+    ///
+    /// If the user's `limit_prices` exceed the batch's limit thresholds, or
+    /// are in one of the refunded tiers, the swap was excluded from the batch,
+    /// so it is treated as unfilled, exactly as if the whole batch had failed,
+    /// and the inputs are refunded.
+    pub fn pro_rata_outputs(
+        &self,
+        (delta_1_i, delta_2_i): (u64, u64),
+        limit_prices: LimitPrices,
+    ) -> (u64, u64) {
+        if self.success
+            && limit_prices.satisfied_by(&self.limit_thresholds)
+            && !self.refunded_tiers.contains(&limit_prices)
+        {
             // The swap succeeded, so the pro rata share is a share of the output amount of
             // the opposite token type.
             // The pro rata fraction is delta_j_i / delta_j, which we can multiply through:
@@ -58,8 +81,9 @@ impl BatchSwapOutputData {
 
             (lambda_1_i as u64, lambda_2_i as u64)
         } else {
-            // The swap failed, so the pro rata share is a share of the input amount of
-            // the same token type. But this is exactly the delta_j_i.
+            // The swap failed, or was excluded from the batch, so the pro rata
+            // share is a share of the input amount of the same token type. But
+            // this is exactly the delta_j_i.
             (delta_1_i, delta_2_i)
         }
     }
@@ -75,6 +99,11 @@ impl BatchSwapOutputData {
             .update(&self.height.to_le_bytes())
             .update(self.trading_pair.auth_hash().as_bytes())
             .update(&(self.success as i64).to_le_bytes())
+            .update(&self.limit_thresholds.min_price_1.to_le_bytes())
+            .update(&self.limit_thresholds.min_price_2.to_le_bytes())
+            .update(&(self.refunded_tiers.unlimited as i64).to_le_bytes())
+            .update(&self.refunded_tiers.tiers_1.to_le_bytes())
+            .update(&self.refunded_tiers.tiers_2.to_le_bytes())
             .finalize()
     }
 }
@@ -91,6 +120,8 @@ impl From<BatchSwapOutputData> for pb::BatchSwapOutputData {
             success: s.success,
             trading_pair: Some(s.trading_pair.into()),
             height: s.height,
+            limit_thresholds: Some(s.limit_thresholds.into()),
+            refunded_tiers: Some(s.refunded_tiers.into()),
         }
     }
 }
@@ -109,6 +140,92 @@ impl TryFrom<pb::BatchSwapOutputData> for BatchSwapOutputData {
                 .trading_pair
                 .ok_or_else(|| anyhow!("Missing trading_pair"))?
                 .try_into()?,
+            limit_thresholds: s
+                .limit_thresholds
+                .ok_or_else(|| anyhow!("Missing limit_thresholds"))?
+                .try_into()?,
+            refunded_tiers: s
+                .refunded_tiers
+                .ok_or_else(|| anyhow!("Missing refunded_tiers"))?
+                .try_into()?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset;
+
+    fn output_data() -> BatchSwapOutputData {
+        let gm = asset::REGISTRY.parse_unit("gm");
+        let gn = asset::REGISTRY.parse_unit("gn");
+        BatchSwapOutputData {
+            delta_1: 100,
+            delta_2: 0,
+            lambda_1: 0,
+            lambda_2: 200,
+            height: 1,
+            trading_pair: TradingPair::canonical_order_for((gm.id(), gn.id())).unwrap(),
+            success: true,
+            limit_thresholds: LimitPrices {
+                min_price_1: u64::MAX,
+                min_price_2: 1 << 21,
+            },
+            refunded_tiers: LimitTiers {
+                tiers_2: 1 << 20,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn limit(min_price_2: u64) -> LimitPrices {
+        LimitPrices {
+            min_price_1: 0,
+            min_price_2,
+        }
+    }
+
+    #[test]
+    fn pro_rata_outputs_respect_limit_thresholds() {
+        let output_data = output_data();
+
+        assert_eq!(output_data.pro_rata_outputs((10, 0), limit(0)), (0, 20));
+        assert_eq!(
+            output_data.pro_rata_outputs((10, 0), limit(1 << 21)),
+            (0, 20)
+        );
+        // The swap's limit is above the threshold, so it was excluded from the
+        // batch and the input is refunded.
+        assert_eq!(
+            output_data.pro_rata_outputs((10, 0), limit(1 << 22)),
+            (10, 0)
+        );
+    }
+
+    #[test]
+    fn pro_rata_outputs_refund_refunded_tiers() {
+        let output_data = output_data();
+
+        // The swap's limit is below the threshold, but its tier had too few
+        // swaps to be decrypted, so the input is refunded.
+        assert_eq!(
+            output_data.pro_rata_outputs((10, 0), limit(1 << 20)),
+            (10, 0)
+        );
+        assert_eq!(
+            output_data.pro_rata_outputs((10, 0), limit(1 << 19)),
+            (0, 20)
+        );
+    }
+
+    #[test]
+    fn failed_batch_refunds_inputs() {
+        let output_data = BatchSwapOutputData {
+            success: false,
+            ..output_data()
+        };
+
+        assert_eq!(output_data.pro_rata_outputs((10, 0), limit(0)), (10, 0));
+    }
+}
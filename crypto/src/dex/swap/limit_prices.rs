@@ -0,0 +1,227 @@
+use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+
+/// The fixed-point scale of limit prices: a price of `PRICE_SCALE` is one
+/// unit of output per unit of input.
+pub const PRICE_SCALE: u64 = 1_000_000;
+
+/// The worst clearing prices a swap will accept, in units of [`PRICE_SCALE`].
+///
+/// A limit of zero means no limit.  Limit prices are public, so that a batch
+/// can exclude the swaps whose limits its clearing prices would violate before
+/// executing, and refund them in full.
+///
+/// Swaps with the same limit prices are decrypted together, so to keep them
+/// from identifying a swap, limit prices are restricted to a few coarse tiers:
+/// a swap either has no limit, or limits one direction to a power of two.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LimitPrices {
+    /// The minimum amount of asset 1 output per unit of asset 2 input.
+    pub min_price_1: u64,
+    /// The minimum amount of asset 2 output per unit of asset 1 input.
+    pub min_price_2: u64,
+}
+
+impl LimitPrices {
+    /// Limit prices that no other limit prices exceed.
+    pub const MAX: LimitPrices = LimitPrices {
+        min_price_1: u64::MAX,
+        min_price_2: u64::MAX,
+    };
+
+    /// Returns the lowest limit tier at which an input of `input` pays out at
+    /// least `min_output`, rounding up to the next power of two.
+    ///
+    /// Prices above the highest tier saturate at that tier.
+    pub fn min_price(input: u64, min_output: u64) -> u64 {
+        if input == 0 || min_output == 0 {
+            return 0;
+        }
+        let price = (min_output as u128 * PRICE_SCALE as u128 + input as u128 - 1) / input as u128;
+        u64::try_from(price)
+            .ok()
+            .and_then(u64::checked_next_power_of_two)
+            .unwrap_or(1 << 63)
+    }
+
+    /// Returns whether these limit prices are one of the limit tiers: either
+    /// no limit, or a power-of-two limit on a single direction.
+    pub fn is_tier(&self) -> bool {
+        match (self.min_price_1, self.min_price_2) {
+            (0, 0) => true,
+            (price, 0) | (0, price) => price.is_power_of_two(),
+            _ => false,
+        }
+    }
+
+    /// Returns the clearing prices of a batch with inputs `(delta_1, delta_2)`
+    /// and outputs `(lambda_1, lambda_2)`, rounding down.
+    ///
+    /// A direction with no input has no clearing price, so it satisfies any
+    /// limit.
+    pub fn clearing_prices(
+        (delta_1, delta_2): (u64, u64),
+        (lambda_1, lambda_2): (u64, u64),
+    ) -> Self {
+        let price = |input: u64, output: u64| -> u64 {
+            (output as u128 * PRICE_SCALE as u128)
+                .checked_div(input as u128)
+                .map_or(u64::MAX, |price| price.try_into().unwrap_or(u64::MAX))
+        };
+        LimitPrices {
+            min_price_1: price(delta_2, lambda_1),
+            min_price_2: price(delta_1, lambda_2),
+        }
+    }
+
+    /// Returns whether neither limit exceeds the corresponding price in
+    /// `prices`.
+    pub fn satisfied_by(&self, prices: &LimitPrices) -> bool {
+        self.min_price_1 <= prices.min_price_1 && self.min_price_2 <= prices.min_price_2
+    }
+
+    /// Returns the lower of each price in `self` and `other`.
+    pub fn min(&self, other: &LimitPrices) -> LimitPrices {
+        LimitPrices {
+            min_price_1: self.min_price_1.min(other.min_price_1),
+            min_price_2: self.min_price_2.min(other.min_price_2),
+        }
+    }
+}
+
+/// A set of limit tiers, as a bit set of the power-of-two limits on each
+/// direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LimitTiers {
+    /// Whether the set contains the tier with no limit.
+    pub unlimited: bool,
+    /// The limits on asset 1 output in the set, one bit per tier.
+    pub tiers_1: u64,
+    /// The limits on asset 2 output in the set, one bit per tier.
+    pub tiers_2: u64,
+}
+
+impl LimitTiers {
+    /// Adds the tier of `limit_prices`, which must be one of the limit tiers,
+    /// to the set.
+    pub fn insert(&mut self, limit_prices: &LimitPrices) {
+        debug_assert!(limit_prices.is_tier());
+        self.unlimited |= *limit_prices == LimitPrices::default();
+        self.tiers_1 |= limit_prices.min_price_1;
+        self.tiers_2 |= limit_prices.min_price_2;
+    }
+
+    /// Returns whether the set contains the tier of `limit_prices`, which must
+    /// be one of the limit tiers.
+    pub fn contains(&self, limit_prices: &LimitPrices) -> bool {
+        if *limit_prices == LimitPrices::default() {
+            self.unlimited
+        } else {
+            self.tiers_1 & limit_prices.min_price_1 != 0
+                || self.tiers_2 & limit_prices.min_price_2 != 0
+        }
+    }
+}
+
+impl Protobuf<pb::LimitPrices> for LimitPrices {}
+
+impl From<LimitPrices> for pb::LimitPrices {
+    fn from(prices: LimitPrices) -> Self {
+        pb::LimitPrices {
+            min_price_1: prices.min_price_1,
+            min_price_2: prices.min_price_2,
+        }
+    }
+}
+
+impl TryFrom<pb::LimitPrices> for LimitPrices {
+    type Error = anyhow::Error;
+
+    fn try_from(prices: pb::LimitPrices) -> Result<Self, Self::Error> {
+        Ok(LimitPrices {
+            min_price_1: prices.min_price_1,
+            min_price_2: prices.min_price_2,
+        })
+    }
+}
+
+impl Protobuf<pb::LimitTiers> for LimitTiers {}
+
+impl From<LimitTiers> for pb::LimitTiers {
+    fn from(tiers: LimitTiers) -> Self {
+        pb::LimitTiers {
+            unlimited: tiers.unlimited,
+            tiers_1: tiers.tiers_1,
+            tiers_2: tiers.tiers_2,
+        }
+    }
+}
+
+impl TryFrom<pb::LimitTiers> for LimitTiers {
+    type Error = anyhow::Error;
+
+    fn try_from(tiers: pb::LimitTiers) -> Result<Self, Self::Error> {
+        Ok(LimitTiers {
+            unlimited: tiers.unlimited,
+            tiers_1: tiers.tiers_1,
+            tiers_2: tiers.tiers_2,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_price_pays_out_min_output() {
+        // 3 units of input for at least 10 units of output need a price of at
+        // least 3_333_334, which rounds up to the next tier.
+        let price = LimitPrices::min_price(3, 10);
+        assert_eq!(price, 1 << 22);
+        assert!(3 * price / PRICE_SCALE >= 10);
+        assert!(LimitPrices {
+            min_price_1: 0,
+            min_price_2: price,
+        }
+        .is_tier());
+    }
+
+    #[test]
+    fn limit_tiers_contain_inserted_tiers() {
+        let tier = |min_price_1, min_price_2| LimitPrices {
+            min_price_1,
+            min_price_2,
+        };
+        assert!(!tier(3, 0).is_tier());
+        assert!(!tier(4, 4).is_tier());
+
+        let mut tiers = LimitTiers::default();
+        tiers.insert(&tier(4, 0));
+        tiers.insert(&tier(0, 1 << 20));
+        assert!(tiers.contains(&tier(4, 0)));
+        assert!(tiers.contains(&tier(0, 1 << 20)));
+        assert!(!tiers.contains(&tier(0, 4)));
+        assert!(!tiers.contains(&tier(8, 0)));
+        assert!(!tiers.contains(&tier(0, 0)));
+
+        tiers.insert(&tier(0, 0));
+        assert!(tiers.contains(&tier(0, 0)));
+    }
+
+    #[test]
+    fn no_input_satisfies_any_limit() {
+        let prices = LimitPrices::clearing_prices((100, 0), (0, 50));
+        assert_eq!(prices.min_price_1, u64::MAX);
+        assert_eq!(prices.min_price_2, PRICE_SCALE / 2);
+        assert!(LimitPrices {
+            min_price_1: 1,
+            min_price_2: PRICE_SCALE / 2,
+        }
+        .satisfied_by(&prices));
+        assert!(!LimitPrices {
+            min_price_1: 0,
+            min_price_2: PRICE_SCALE / 2 + 1,
+        }
+        .satisfied_by(&prices));
+    }
+}
@@ -5,7 +5,7 @@ use anyhow::{anyhow, Error, Result};
 use ark_ff::PrimeField;
 use decaf377::Fq;
 use penumbra_proto::{core::crypto::v1alpha1 as pb_crypto, core::dex::v1alpha1 as pb, Protobuf};
-use poseidon377::{hash_4, hash_6};
+use poseidon377::hash_6;
 
 use crate::dex::TradingPair;
 use crate::{
@@ -16,7 +16,8 @@ use crate::{
 };
 
 use super::{
-    SwapCiphertext, DOMAIN_SEPARATOR, OVK_WRAPPED_LEN_BYTES, SWAP_CIPHERTEXT_BYTES, SWAP_LEN_BYTES,
+    LimitPrices, SwapCiphertext, DOMAIN_SEPARATOR, OVK_WRAPPED_LEN_BYTES, SWAP_CIPHERTEXT_BYTES,
+    SWAP_LEN_BYTES,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -31,6 +32,8 @@ pub struct SwapPlaintext {
     pub claim_fee: Fee,
     // Address to receive the Swap NFT and SwapClaim outputs
     pub claim_address: Address,
+    // Worst clearing prices the swap accepts, above which it is refunded
+    pub limit_prices: LimitPrices,
 }
 
 impl SwapPlaintext {
@@ -48,13 +51,15 @@ impl SwapPlaintext {
                     .vartime_compress_to_field(),
                 *self.claim_address.transmission_key_s(),
                 Fq::from_le_bytes_mod_order(&self.claim_address.clue_key().0[..]),
-                hash_4(
+                hash_6(
                     &DOMAIN_SEPARATOR,
                     (
                         self.trading_pair.asset_1().0,
                         self.trading_pair.asset_2().0,
                         self.delta_1_i.into(),
                         self.delta_2_i.into(),
+                        self.limit_prices.min_price_1.into(),
+                        self.limit_prices.min_price_2.into(),
                     ),
                 ),
             ),
@@ -118,6 +123,7 @@ impl SwapPlaintext {
         delta_2_i: Amount,
         claim_fee: Fee,
         claim_address: Address,
+        limit_prices: LimitPrices,
     ) -> Result<Self, Error> {
         Ok(SwapPlaintext {
             trading_pair,
@@ -125,6 +131,7 @@ impl SwapPlaintext {
             delta_2_i,
             claim_fee,
             claim_address,
+            limit_prices,
         })
     }
 }
@@ -156,6 +163,12 @@ impl TryFrom<pb::SwapPlaintext> for SwapPlaintext {
                 .trading_pair
                 .ok_or_else(|| anyhow::anyhow!("missing trading pair in SwapPlaintext"))?
                 .try_into()?,
+            // Absent limit prices mean the swap has no limit.
+            limit_prices: plaintext
+                .limit_prices
+                .map(TryInto::try_into)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 }
@@ -168,6 +181,7 @@ impl From<SwapPlaintext> for pb::SwapPlaintext {
             claim_fee: Some(plaintext.claim_fee.into()),
             claim_address: Some(plaintext.claim_address.into()),
             trading_pair: Some(plaintext.trading_pair.into()),
+            limit_prices: Some(plaintext.limit_prices.into()),
         }
     }
 }
@@ -182,6 +196,8 @@ impl From<&SwapPlaintext> for [u8; SWAP_LEN_BYTES] {
         bytes[88..120].copy_from_slice(&swap.claim_fee.0.asset_id.to_bytes());
         let pb_address = pb_crypto::Address::from(swap.claim_address);
         bytes[120..200].copy_from_slice(&pb_address.inner);
+        bytes[200..208].copy_from_slice(&swap.limit_prices.min_price_1.to_le_bytes());
+        bytes[208..216].copy_from_slice(&swap.limit_prices.min_price_2.to_le_bytes());
         bytes
     }
 }
@@ -218,6 +234,12 @@ impl TryFrom<&[u8]> for SwapPlaintext {
         let address_bytes: [u8; 80] = bytes[120..200]
             .try_into()
             .map_err(|_| anyhow!("error fetching address bytes"))?;
+        let min_price_1_bytes: [u8; 8] = bytes[200..208]
+            .try_into()
+            .map_err(|_| anyhow!("error fetching min price 1 bytes"))?;
+        let min_price_2_bytes: [u8; 8] = bytes[208..216]
+            .try_into()
+            .map_err(|_| anyhow!("error fetching min price 2 bytes"))?;
         let pb_address = pb_crypto::Address {
             inner: address_bytes.to_vec(),
         };
@@ -233,6 +255,10 @@ impl TryFrom<&[u8]> for SwapPlaintext {
                 asset_id: asset::Id::try_from(fee_asset_id_bytes)?,
             }),
             pb_address.try_into()?,
+            LimitPrices {
+                min_price_1: u64::from_le_bytes(min_price_1_bytes),
+                min_price_2: u64::from_le_bytes(min_price_2_bytes),
+            },
         )
     }
 }
@@ -279,6 +305,10 @@ mod tests {
                 asset_id: asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
             }),
             claim_address: dest,
+            limit_prices: LimitPrices {
+                min_price_1: 0,
+                min_price_2: 5,
+            },
         };
         let esk = ka::Secret::new(&mut rng);
 
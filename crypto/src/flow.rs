@@ -1,69 +1,154 @@
 use std::ops::{Add, AddAssign, Deref, DerefMut};
 
+use anyhow::anyhow;
+use penumbra_eddy::{
+    proofs::{EncryptionProof, TransparentEncryptionProof},
+    Ciphertext, DecryptionShare, DecryptionTable, EncryptionKey, PrivateKeyShare, PublicKeyShare,
+    Unverified, Verified,
+};
 use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
+use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(try_from = "pb::MockFlowCiphertext", into = "pb::MockFlowCiphertext")]
-pub struct MockFlowCiphertext(u64);
+use crate::dex::{LimitPrices, TradingPair};
+
+/// An amount encrypted to the [`FlowEncryptionCommittee`], which can be
+/// aggregated homomorphically with other flow ciphertexts and then jointly
+/// decrypted by the committee, without revealing any individual amount.
+#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(try_from = "pb::FlowCiphertext", into = "pb::FlowCiphertext")]
+pub struct FlowCiphertext(Ciphertext);
 
-// Fake implementation for now, TODO: replace w/ additively homomorphic encryption impl
-// once Eddy impl available
-impl MockFlowCiphertext {
-    pub fn new(plaintext: u64) -> Self {
-        // TODO: do encryption stuff here
-        Self(plaintext)
+impl FlowCiphertext {
+    /// Encrypts `amount` to the committee's encryption key, with a proof that
+    /// the resulting ciphertext is well-formed.
+    ///
+    /// Also returns the opening of the ciphertext, which reveals `amount` and
+    /// is only used as a witness for the transparent proofs of the action the
+    /// ciphertext belongs to.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        amount: u64,
+        encryption_key: &EncryptionKey,
+        rng: R,
+    ) -> anyhow::Result<(Self, EncryptionProof, TransparentEncryptionProof)> {
+        let (ciphertext, proof, opening) = penumbra_eddy::Value::from(amount)
+            .encrypt_with_opening(encryption_key, &mut Self::encryption_transcript(), rng)?;
+        Ok((Self(ciphertext), proof, opening))
     }
 
-    pub fn mock_decrypt(&self) -> u64 {
-        // TODO: do decryption stuff here
-        self.0
+    /// Verifies a proof that this ciphertext is a well-formed encryption to the
+    /// committee's encryption key.
+    pub fn verify_encryption_proof(
+        &self,
+        proof: &EncryptionProof,
+        encryption_key: &EncryptionKey,
+    ) -> anyhow::Result<()> {
+        proof.verify(&self.0, encryption_key, &mut Self::encryption_transcript())
+    }
+
+    /// Verifies that `opening` opens this ciphertext, as encrypted to the
+    /// committee's encryption key.
+    pub fn verify_opening(
+        &self,
+        opening: &TransparentEncryptionProof,
+        encryption_key: &EncryptionKey,
+    ) -> anyhow::Result<()> {
+        opening.verify(&self.0, encryption_key)
+    }
+
+    /// Encodes this ciphertext as 256 bytes.
+    pub fn to_bytes(&self) -> [u8; 256] {
+        self.0.to_bytes()
+    }
+
+    /// Computes a committee member's share of the decryption of this ciphertext.
+    pub fn decryption_share<R: RngCore + CryptoRng>(
+        &self,
+        key_share: &PrivateKeyShare,
+        rng: R,
+    ) -> DecryptionShare<Unverified> {
+        key_share.decryption_share(&self.0, &mut Self::transcript(), rng)
+    }
+
+    /// Verifies a decryption share of this ciphertext against the public key
+    /// share of the committee member that claims to have produced it.
+    pub fn verify_decryption_share(
+        &self,
+        share: &DecryptionShare<Unverified>,
+        committee: &FlowEncryptionCommittee,
+    ) -> anyhow::Result<DecryptionShare<Verified>> {
+        let public_key_share = committee.public_key_share(share.participant_index())?;
+        share.verify(&self.0, &public_key_share, &mut Self::transcript())
+    }
+
+    /// Decrypts this ciphertext using verified decryption shares from at least
+    /// the committee's threshold of members, recovering the (aggregate) amount
+    /// with the given [`DecryptionTable`].
+    pub async fn decrypt(
+        &self,
+        shares: Vec<DecryptionShare<Verified>>,
+        committee: &FlowEncryptionCommittee,
+        table: &dyn DecryptionTable,
+    ) -> anyhow::Result<u64> {
+        let value = self.0.decrypt(shares, committee.threshold, table).await?;
+        value
+            .0
+            .try_into()
+            .map_err(|_| anyhow!("decrypted flow does not fit in 64 bits"))
+    }
+
+    fn transcript() -> merlin::Transcript {
+        merlin::Transcript::new(b"penumbra.flow-decryption")
     }
-}
 
-impl Default for MockFlowCiphertext {
-    fn default() -> Self {
-        Self::new(0)
+    fn encryption_transcript() -> merlin::Transcript {
+        merlin::Transcript::new(b"penumbra.flow-encryption")
     }
 }
 
-impl Add for MockFlowCiphertext {
+impl Add for FlowCiphertext {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self(self.0 + other.0)
+        Self(&self.0 + &other.0)
     }
 }
 
-impl AddAssign for MockFlowCiphertext {
+impl AddAssign for FlowCiphertext {
     fn add_assign(&mut self, other: Self) {
-        *self = self.clone() + other;
+        self.0 += &other.0;
     }
 }
 
-impl Protobuf<pb::MockFlowCiphertext> for MockFlowCiphertext {}
+impl Protobuf<pb::FlowCiphertext> for FlowCiphertext {}
 
-impl From<MockFlowCiphertext> for pb::MockFlowCiphertext {
-    fn from(ik: MockFlowCiphertext) -> Self {
-        pb::MockFlowCiphertext { value: ik.0 }
+impl From<FlowCiphertext> for pb::FlowCiphertext {
+    fn from(ct: FlowCiphertext) -> Self {
+        pb::FlowCiphertext {
+            inner: Some(ct.0.into()),
+        }
     }
 }
 
-impl TryFrom<pb::MockFlowCiphertext> for MockFlowCiphertext {
+impl TryFrom<pb::FlowCiphertext> for FlowCiphertext {
     type Error = anyhow::Error;
-    fn try_from(ct: pb::MockFlowCiphertext) -> Result<Self, Self::Error> {
-        Ok(Self(ct.value))
+    fn try_from(ct: pb::FlowCiphertext) -> Result<Self, Self::Error> {
+        Ok(Self(
+            ct.inner
+                .ok_or_else(|| anyhow!("missing flow ciphertext"))?
+                .try_into()?,
+        ))
     }
 }
 
 // Tuple represents:
 // ((amount of asset 1 being exchanged for asset 2),
 //  (amount of asset 2 being exchanged for asset 1))
-#[derive(Default, Clone)]
-pub struct SwapFlow((MockFlowCiphertext, MockFlowCiphertext));
+#[derive(Default, Clone, Debug)]
+pub struct SwapFlow((FlowCiphertext, FlowCiphertext));
 
 impl Deref for SwapFlow {
-    type Target = (MockFlowCiphertext, MockFlowCiphertext);
+    type Target = (FlowCiphertext, FlowCiphertext);
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -75,3 +160,211 @@ impl DerefMut for SwapFlow {
         &mut self.0
     }
 }
+
+impl Protobuf<pb::SwapFlow> for SwapFlow {}
+
+impl From<SwapFlow> for pb::SwapFlow {
+    fn from(flow: SwapFlow) -> Self {
+        let (delta_1, delta_2) = flow.0;
+        pb::SwapFlow {
+            delta_1: Some(delta_1.into()),
+            delta_2: Some(delta_2.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::SwapFlow> for SwapFlow {
+    type Error = anyhow::Error;
+    fn try_from(flow: pb::SwapFlow) -> Result<Self, Self::Error> {
+        Ok(Self((
+            flow.delta_1
+                .ok_or_else(|| anyhow!("missing delta_1"))?
+                .try_into()?,
+            flow.delta_2
+                .ok_or_else(|| anyhow!("missing delta_2"))?
+                .try_into()?,
+        )))
+    }
+}
+
+/// The committee of validators that jointly decrypt batch swap flows.
+///
+/// Any `threshold` members of the committee can decrypt a flow, but no smaller
+/// set of members learns anything about it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    try_from = "pb::FlowEncryptionCommittee",
+    into = "pb::FlowEncryptionCommittee"
+)]
+pub struct FlowEncryptionCommittee {
+    /// The key that swap flows are encrypted to.
+    pub encryption_key: EncryptionKey,
+    /// The public key share of each member of the committee.
+    pub public_key_shares: Vec<PublicKeyShare>,
+    /// The number of decryption shares required to decrypt a flow.
+    pub threshold: u32,
+}
+
+impl FlowEncryptionCommittee {
+    /// Returns the public key share of the member with the given index.
+    pub fn public_key_share(&self, participant_index: u32) -> anyhow::Result<PublicKeyShare> {
+        self.public_key_shares
+            .iter()
+            .find(|share| share.participant_index() == participant_index)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown committee member {}", participant_index))
+    }
+}
+
+impl Protobuf<pb::FlowEncryptionCommittee> for FlowEncryptionCommittee {}
+
+impl From<FlowEncryptionCommittee> for pb::FlowEncryptionCommittee {
+    fn from(committee: FlowEncryptionCommittee) -> Self {
+        pb::FlowEncryptionCommittee {
+            encryption_key: committee.encryption_key.to_bytes().to_vec(),
+            public_key_shares: committee
+                .public_key_shares
+                .into_iter()
+                .map(|share| pb::flow_encryption_committee::PublicKeyShare {
+                    inner: share.to_bytes().to_vec(),
+                })
+                .collect(),
+            threshold: committee.threshold,
+        }
+    }
+}
+
+impl TryFrom<pb::FlowEncryptionCommittee> for FlowEncryptionCommittee {
+    type Error = anyhow::Error;
+    fn try_from(committee: pb::FlowEncryptionCommittee) -> Result<Self, Self::Error> {
+        Ok(Self {
+            encryption_key: committee.encryption_key[..].try_into()?,
+            public_key_shares: committee
+                .public_key_shares
+                .iter()
+                .map(|share| share.inner[..].try_into())
+                .collect::<Result<_, _>>()?,
+            threshold: committee.threshold,
+        })
+    }
+}
+
+/// One committee member's decryption shares for every batch swap flow at a
+/// given height.
+#[derive(Clone, Debug)]
+pub struct BatchDecryptionShares {
+    /// The height of the batch being decrypted.
+    pub height: u64,
+    /// The index of the committee member that produced the shares.
+    pub participant_index: u32,
+    /// The decryption shares of each asset's flow, by trading pair and the
+    /// limit prices of the swaps in the flow.
+    pub flows: Vec<(
+        TradingPair,
+        LimitPrices,
+        DecryptionShare<Unverified>,
+        DecryptionShare<Unverified>,
+    )>,
+}
+
+impl Protobuf<pb::BatchDecryptionShares> for BatchDecryptionShares {}
+
+impl From<BatchDecryptionShares> for pb::BatchDecryptionShares {
+    fn from(shares: BatchDecryptionShares) -> Self {
+        pb::BatchDecryptionShares {
+            height: shares.height,
+            participant_index: shares.participant_index,
+            flows: shares
+                .flows
+                .into_iter()
+                .map(|(trading_pair, limit_prices, delta_1, delta_2)| {
+                    pb::batch_decryption_shares::FlowShares {
+                        trading_pair: Some(trading_pair.into()),
+                        limit_prices: Some(limit_prices.into()),
+                        delta_1: Some(delta_1.into()),
+                        delta_2: Some(delta_2.into()),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<pb::BatchDecryptionShares> for BatchDecryptionShares {
+    type Error = anyhow::Error;
+    fn try_from(shares: pb::BatchDecryptionShares) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: shares.height,
+            participant_index: shares.participant_index,
+            flows: shares
+                .flows
+                .into_iter()
+                .map(|flow| {
+                    Ok((
+                        flow.trading_pair
+                            .ok_or_else(|| anyhow!("missing trading_pair"))?
+                            .try_into()?,
+                        flow.limit_prices
+                            .ok_or_else(|| anyhow!("missing limit_prices"))?
+                            .try_into()?,
+                        flow.delta_1
+                            .ok_or_else(|| anyhow!("missing delta_1"))?
+                            .try_into()?,
+                        flow.delta_2
+                            .ok_or_else(|| anyhow!("missing delta_2"))?
+                            .try_into()?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_eddy::MockDecryptionTable;
+    use rand_core::OsRng;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn aggregate_flow_decrypts_with_threshold_shares() {
+        let (encryption_key, key_shares) = penumbra_eddy::dkg::deal(2, 3, OsRng).unwrap();
+        let committee = FlowEncryptionCommittee {
+            encryption_key,
+            public_key_shares: key_shares.iter().map(|s| s.public_key_share()).collect(),
+            threshold: 2,
+        };
+
+        let mut flow = FlowCiphertext::default();
+        for amount in [100u64, 2000, 30000] {
+            let (ciphertext, proof, opening) =
+                FlowCiphertext::encrypt(amount, &committee.encryption_key, OsRng).unwrap();
+            ciphertext
+                .verify_encryption_proof(&proof, &committee.encryption_key)
+                .unwrap();
+            ciphertext
+                .verify_opening(&opening, &committee.encryption_key)
+                .unwrap();
+            assert_eq!(opening.value(), amount);
+            flow += ciphertext;
+        }
+
+        // Shares survive a round trip through the wire encoding.
+        let shares = key_shares[1..]
+            .iter()
+            .map(|key_share| {
+                let share = flow.decryption_share(key_share, OsRng);
+                let share: DecryptionShare<Unverified> = share.to_bytes()[..].try_into().unwrap();
+                flow.verify_decryption_share(&share, &committee).unwrap()
+            })
+            .collect();
+
+        let table = MockDecryptionTable::default();
+        table.initialize(18).await.unwrap();
+        assert_eq!(
+            flow.decrypt(shares, &committee, &table).await.unwrap(),
+            32100
+        );
+    }
+}
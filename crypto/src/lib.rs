@@ -4,13 +4,13 @@ pub use decaf377::{FieldExt, Fq, Fr};
 pub use decaf377_fmd as fmd;
 pub use decaf377_ka as ka;
 pub use decaf377_rdsa as rdsa;
+pub use penumbra_eddy as eddy;
 
 mod address;
 pub mod asset;
 pub mod balance;
 mod delegation_token;
 pub mod dex;
-pub mod eddy;
 mod flow;
 mod governance_key;
 mod identity_key;
@@ -30,7 +30,7 @@ pub use asset::Amount;
 pub use asset::Asset;
 pub use balance::Balance;
 pub use delegation_token::DelegationToken;
-pub use flow::{MockFlowCiphertext, SwapFlow};
+pub use flow::{BatchDecryptionShares, FlowCiphertext, FlowEncryptionCommittee, SwapFlow};
 pub use governance_key::GovernanceKey;
 pub use identity_key::IdentityKey;
 pub use keys::FullViewingKey;
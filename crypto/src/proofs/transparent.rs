@@ -6,16 +6,17 @@ use std::convert::{TryFrom, TryInto};
 
 use decaf377::FieldExt;
 use decaf377_rdsa::{SpendAuth, VerificationKey};
+use penumbra_eddy::{proofs::TransparentEncryptionProof, EncryptionKey};
 use penumbra_proto::{core::transparent_proofs::v1alpha1 as transparent_proofs, Message, Protobuf};
 use penumbra_tct as tct;
 
 use super::transparent_gadgets as gadgets;
 use crate::{
     asset, balance,
-    dex::{BatchSwapOutputData, TradingPair},
+    dex::{BatchSwapOutputData, LimitPrices, TradingPair},
     ka, keys, note,
     transaction::Fee,
-    Address, Fq, Fr, Note, Nullifier, Value,
+    Address, FlowCiphertext, Fq, Fr, Note, Nullifier, Value,
 };
 
 /// Transparent proof for spending existing notes.
@@ -93,6 +94,79 @@ impl SpendProof {
     }
 }
 
+/// Transparent proof for voting on a proposal with staked notes.
+///
+/// This structure keeps track of the auxiliary (private) inputs.
+#[derive(Clone, Debug)]
+pub struct DelegatorVoteProof {
+    // Inclusion proof for the note commitment.
+    pub note_commitment_proof: tct::Proof,
+    // The staked note being voted with.
+    pub note: Note,
+    // The randomizer used for generating the randomized spend auth key.
+    pub spend_auth_randomizer: Fr,
+    // The spend authorization key.
+    pub ak: VerificationKey<SpendAuth>,
+    // The nullifier deriving key.
+    pub nk: keys::NullifierKey,
+}
+
+impl DelegatorVoteProof {
+    /// Called to verify the proof using the provided public inputs.
+    ///
+    /// The public inputs are:
+    /// * the merkle root of the note commitment tree,
+    /// * the position in the note commitment tree at which the proposal started,
+    /// * the value of the note voted with,
+    /// * nullifier of the note voted with,
+    /// * the randomized verification spend key,
+    pub fn verify(
+        &self,
+        anchor: tct::Root,
+        start_position: tct::Position,
+        value: Value,
+        nullifier: Nullifier,
+        rk: VerificationKey<SpendAuth>,
+    ) -> anyhow::Result<()> {
+        gadgets::note_commitment_integrity(
+            self.note.clone(),
+            self.note_commitment_proof.commitment(),
+        )?;
+
+        // Merkle path integrity.
+        self.note_commitment_proof
+            .verify(anchor)
+            .map_err(|_| anyhow!("merkle root mismatch"))?;
+
+        // The note must have existed before the proposal started.
+        if self.note_commitment_proof.position() >= start_position {
+            return Err(anyhow!("note was created after the proposal started"));
+        }
+
+        if self.note.value() != value {
+            return Err(anyhow!("value mismatch"));
+        }
+
+        gadgets::diversified_basepoint_not_identity(self.note.diversified_generator().clone())?;
+        if self.ak.is_identity() {
+            return Err(anyhow!("unexpected identity"));
+        }
+
+        gadgets::nullifier_integrity(
+            nullifier,
+            self.nk,
+            self.note_commitment_proof.position(),
+            self.note_commitment_proof.commitment(),
+        )?;
+
+        gadgets::rk_integrity(self.spend_auth_randomizer, rk, self.ak)?;
+
+        gadgets::diversified_address_integrity(self.ak, self.nk, self.note.clone())?;
+
+        Ok(())
+    }
+}
+
 /// Transparent proof for new note creation.
 ///
 /// This structure keeps track of the auxiliary (private) inputs.
@@ -206,6 +280,85 @@ impl TryFrom<transparent_proofs::SpendProof> for SpendProof {
     }
 }
 
+impl Protobuf<transparent_proofs::DelegatorVoteProof> for DelegatorVoteProof {}
+
+impl From<DelegatorVoteProof> for transparent_proofs::DelegatorVoteProof {
+    fn from(msg: DelegatorVoteProof) -> Self {
+        let ak_bytes: [u8; 32] = msg.ak.into();
+        let nk_bytes: [u8; 32] = msg.nk.0.to_bytes();
+        transparent_proofs::DelegatorVoteProof {
+            note_commitment_proof: Some(msg.note_commitment_proof.into()),
+            note: Some(msg.note.into()),
+            spend_auth_randomizer: msg.spend_auth_randomizer.to_bytes().to_vec(),
+            ak: ak_bytes.into(),
+            nk: nk_bytes.into(),
+        }
+    }
+}
+
+impl TryFrom<transparent_proofs::DelegatorVoteProof> for DelegatorVoteProof {
+    type Error = Error;
+
+    fn try_from(
+        proto: transparent_proofs::DelegatorVoteProof,
+    ) -> anyhow::Result<Self, Self::Error> {
+        let ak_bytes: [u8; 32] = (proto.ak[..])
+            .try_into()
+            .map_err(|_| anyhow!("proto malformed"))?;
+        let ak = ak_bytes
+            .try_into()
+            .map_err(|_| anyhow!("proto malformed"))?;
+
+        Ok(DelegatorVoteProof {
+            note_commitment_proof: proto
+                .note_commitment_proof
+                .ok_or_else(|| anyhow!("proto malformed"))?
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
+            note: proto
+                .note
+                .ok_or_else(|| anyhow!("proto malformed"))?
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
+            spend_auth_randomizer: Fr::from_bytes(
+                proto.spend_auth_randomizer[..]
+                    .try_into()
+                    .map_err(|_| anyhow!("proto malformed"))?,
+            )
+            .map_err(|_| anyhow!("proto malformed"))?,
+            ak,
+            nk: keys::NullifierKey(
+                Fq::from_bytes(
+                    proto.nk[..]
+                        .try_into()
+                        .map_err(|_| anyhow!("proto malformed"))?,
+                )
+                .map_err(|_| anyhow!("proto malformed"))?,
+            ),
+        })
+    }
+}
+
+impl From<DelegatorVoteProof> for Vec<u8> {
+    fn from(delegator_vote_proof: DelegatorVoteProof) -> Vec<u8> {
+        let protobuf_serialized_proof: transparent_proofs::DelegatorVoteProof =
+            delegator_vote_proof.into();
+        protobuf_serialized_proof.encode_to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for DelegatorVoteProof {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<DelegatorVoteProof, Self::Error> {
+        let protobuf_serialized_proof = transparent_proofs::DelegatorVoteProof::decode(bytes)
+            .map_err(|_| anyhow!("proto malformed"))?;
+        protobuf_serialized_proof
+            .try_into()
+            .map_err(|_| anyhow!("proto malformed"))
+    }
+}
+
 impl Protobuf<transparent_proofs::OutputProof> for OutputProof {}
 
 impl From<OutputProof> for transparent_proofs::OutputProof {
@@ -309,6 +462,7 @@ pub struct SwapClaimProof {
     pub trading_pair: TradingPair,
     pub delta_1_i: u64,
     pub delta_2_i: u64,
+    pub limit_prices: LimitPrices,
 
     // Describes output amounts
     pub lambda_1: u64,
@@ -370,6 +524,7 @@ impl SwapClaimProof {
             self.delta_2_i,
             fee,
             self.claim_address,
+            self.limit_prices,
         )?;
 
         // Validate the note commitment's height matches the output data's height.
@@ -389,6 +544,17 @@ impl SwapClaimProof {
             return Err(anyhow::anyhow!("trading pair mismatch"));
         }
 
+        // Validate that the output amounts are the pro rata share of the batch
+        // output, or a refund of the inputs if the batch failed or the swap
+        // was excluded from it for its limit prices.
+        if (self.lambda_1, self.lambda_2)
+            != output_data.pro_rata_outputs((self.delta_1_i, self.delta_2_i), self.limit_prices)
+        {
+            return Err(anyhow::anyhow!(
+                "output amounts do not match pro rata outputs"
+            ));
+        }
+
         // At this point, we've:
         // * verified the note commitment is in the TCT,
         // * verified the note commitment commits to the swap NFT for correct SwapPlaintext,
@@ -474,6 +640,7 @@ impl From<SwapClaimProof> for transparent_proofs::SwapClaimProof {
             trading_pair: Some(msg.trading_pair.into()),
             delta_1_i: msg.delta_1_i,
             delta_2_i: msg.delta_2_i,
+            limit_prices: Some(msg.limit_prices.into()),
             lambda_1: msg.lambda_1,
             lambda_2: msg.lambda_2,
             note_blinding_1: msg.note_blinding_1.to_bytes().to_vec(),
@@ -513,6 +680,11 @@ impl TryFrom<transparent_proofs::SwapClaimProof> for SwapClaimProof {
             lambda_1: proto.lambda_1,
             delta_2_i: proto.delta_2_i,
             delta_1_i: proto.delta_1_i,
+            limit_prices: proto
+                .limit_prices
+                .ok_or_else(|| anyhow!("proto malformed"))?
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
             trading_pair: proto
                 .trading_pair
                 .ok_or_else(|| anyhow!("proto malformed"))?
@@ -578,11 +750,14 @@ pub struct SwapProof {
     pub note_blinding: Fq,
     // The ephemeral secret key that corresponds to the public key.
     pub esk: ka::Secret,
-    // TODO: no value commitments for delta 1/delta 2 until flow encryption is available
-    // // The blinding factor used for generating the value commitment for delta 1.
-    // pub delta_1_blinding: Fr,
-    // // The blinding factor used for generating the value commitment for delta 2.
-    // pub delta_2_blinding: Fr,
+    // The blinding factor used for generating the value commitment for delta 1.
+    pub delta_1_blinding: Fr,
+    // The blinding factor used for generating the value commitment for delta 2.
+    pub delta_2_blinding: Fr,
+    // The opening of the encryption of delta 1 to the flow encryption committee.
+    pub enc_delta_1_opening: TransparentEncryptionProof,
+    // The opening of the encryption of delta 2 to the flow encryption committee.
+    pub enc_delta_2_opening: TransparentEncryptionProof,
 }
 
 impl SwapProof {
@@ -593,14 +768,22 @@ impl SwapProof {
     /// * value commitment of the asset 2's contribution to the transaction,
     /// * value commitment of the fee's contribution to the transaction,
     /// * note commitment of the new swap NFT note,
-    /// * the ephemeral public key used to generate the new swap NFT note.
+    /// * the ephemeral public key used to generate the new swap NFT note,
+    /// * the limit prices of the swap,
+    /// * the key of the flow encryption committee,
+    /// * the encryptions of the asset 1 and asset 2 amounts to the committee.
+    #[allow(clippy::too_many_arguments)]
     pub fn verify(
         &self,
-        _value_1_commitment: balance::Commitment,
-        _value_2_commitment: balance::Commitment,
+        value_1_commitment: balance::Commitment,
+        value_2_commitment: balance::Commitment,
         value_fee_commitment: balance::Commitment,
         note_commitment: note::Commitment,
         epk: ka::Public,
+        limit_prices: LimitPrices,
+        flow_encryption_key: &EncryptionKey,
+        enc_delta_1: &FlowCiphertext,
+        enc_delta_2: &FlowCiphertext,
     ) -> anyhow::Result<(), Error> {
         // Checks the note commitment of the Swap NFT.
         gadgets::note_commitment_integrity(
@@ -616,15 +799,42 @@ impl SwapProof {
             note_commitment,
         )?;
 
-        // TODO: no value commitment checks until flow encryption is available
-        // // Value commitment integrity.
-        // if value_1_commitment != -self.value_t1.commit(self.delta_1_blinding) {
-        //     return Err(anyhow!("value commitment mismatch"));
-        // }
+        // Checks that the Swap NFT asset ID commits to the swap's inputs, fee,
+        // claim address and limit prices.
+        gadgets::swap_nft_asset_id_integrity(
+            self.swap_nft_asset_id,
+            TradingPair::new(self.value_t1.asset_id, self.value_t2.asset_id)?,
+            self.value_t1.amount.into(),
+            self.value_t2.amount.into(),
+            self.fee_delta.clone(),
+            self.claim_address,
+            limit_prices,
+        )?;
 
-        // if value_2_commitment != -self.value_t2.commit(self.delta_2_blinding) {
-        //     return Err(anyhow!("value commitment mismatch"));
-        // }
+        // Checks that the value commitments and the flow ciphertexts are for
+        // the same amounts.
+        gadgets::balance_commitment_integrity(
+            value_1_commitment,
+            self.delta_1_blinding,
+            self.value_t1,
+        )?;
+        gadgets::balance_commitment_integrity(
+            value_2_commitment,
+            self.delta_2_blinding,
+            self.value_t2,
+        )?;
+        gadgets::flow_ciphertext_integrity(
+            enc_delta_1,
+            &self.enc_delta_1_opening,
+            flow_encryption_key,
+            self.value_t1,
+        )?;
+        gadgets::flow_ciphertext_integrity(
+            enc_delta_2,
+            &self.enc_delta_2_opening,
+            flow_encryption_key,
+            self.value_t2,
+        )?;
 
         gadgets::balance_commitment_integrity(
             value_fee_commitment,
@@ -659,9 +869,10 @@ impl From<SwapProof> for transparent_proofs::SwapProof {
             fee: Some(msg.fee_delta.into()),
             fee_blinding: msg.fee_blinding.to_bytes().to_vec(),
             swap_nft_asset_id: msg.swap_nft_asset_id.0.to_bytes().to_vec(),
-            // TODO: no value commitments for delta 1/delta 2 until flow encryption is available
-            // delta_1_blinding: msg.delta_1_blinding.to_bytes().to_vec(),
-            // delta_2_blinding: msg.delta_2_blinding.to_bytes().to_vec(),
+            delta_1_blinding: msg.delta_1_blinding.to_bytes().to_vec(),
+            delta_2_blinding: msg.delta_2_blinding.to_bytes().to_vec(),
+            enc_delta_1_opening: Some(msg.enc_delta_1_opening.into()),
+            enc_delta_2_opening: Some(msg.enc_delta_2_opening.into()),
             note_blinding: msg.note_blinding.to_bytes().to_vec(),
             esk: msg.esk.to_bytes().to_vec(),
         }
@@ -672,12 +883,12 @@ impl TryFrom<transparent_proofs::SwapProof> for SwapProof {
     type Error = Error;
 
     fn try_from(proto: transparent_proofs::SwapProof) -> anyhow::Result<Self, Self::Error> {
-        // let delta_1_blinding_bytes: [u8; 32] = proto.delta_1_blinding[..]
-        //     .try_into()
-        //     .map_err(|_| anyhow!("proto malformed"))?;
-        // let delta_2_blinding_bytes: [u8; 32] = proto.delta_2_blinding[..]
-        //     .try_into()
-        //     .map_err(|_| anyhow!("proto malformed"))?;
+        let delta_1_blinding_bytes: [u8; 32] = proto.delta_1_blinding[..]
+            .try_into()
+            .map_err(|_| anyhow!("proto malformed"))?;
+        let delta_2_blinding_bytes: [u8; 32] = proto.delta_2_blinding[..]
+            .try_into()
+            .map_err(|_| anyhow!("proto malformed"))?;
 
         let fee_blinding_bytes: [u8; 32] = proto.fee_blinding[..]
             .try_into()
@@ -737,11 +948,20 @@ impl TryFrom<transparent_proofs::SwapProof> for SwapProof {
                 )
                 .map_err(|_| anyhow!("proto malformed"))?,
             ),
-            // TODO: no value commitment checks until flow encryption is available
-            // delta_1_blinding: Fr::from_bytes(delta_1_blinding_bytes)
-            //     .map_err(|_| anyhow!("proto malformed"))?,
-            // delta_2_blinding: Fr::from_bytes(delta_2_blinding_bytes)
-            //     .map_err(|_| anyhow!("proto malformed"))?,
+            delta_1_blinding: Fr::from_bytes(delta_1_blinding_bytes)
+                .map_err(|_| anyhow!("proto malformed"))?,
+            delta_2_blinding: Fr::from_bytes(delta_2_blinding_bytes)
+                .map_err(|_| anyhow!("proto malformed"))?,
+            enc_delta_1_opening: proto
+                .enc_delta_1_opening
+                .ok_or_else(|| anyhow!("proto malformed"))?
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
+            enc_delta_2_opening: proto
+                .enc_delta_2_opening
+                .ok_or_else(|| anyhow!("proto malformed"))?
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
             note_blinding: Fq::from_bytes(
                 proto.note_blinding[..]
                     .try_into()
@@ -1087,4 +1307,236 @@ mod tests {
             .verify(anchor, value_to_send.commit(v_blinding), incorrect_nf, rk)
             .is_err());
     }
+
+    #[test]
+    fn test_delegator_vote_proof_verification_success() {
+        let mut rng = OsRng;
+
+        let seed_phrase = SeedPhrase::generate(&mut rng);
+        let sk_sender = SpendKey::from_seed_phrase(seed_phrase, 0);
+        let fvk_sender = sk_sender.full_viewing_key();
+        let ivk_sender = fvk_sender.incoming();
+        let (sender, _dtk_d) = ivk_sender.payment_address(0u64.into());
+
+        let value_to_vote = Value {
+            amount: 10u64.into(),
+            asset_id: asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
+        };
+
+        let note = Note::generate(&mut rng, &sender, value_to_vote);
+        let note_commitment = note.commit();
+        let spend_auth_randomizer = Fr::rand(&mut rng);
+        let rsk = sk_sender.spend_auth_key().randomize(&spend_auth_randomizer);
+        let nk = *sk_sender.nullifier_key();
+        let ak = sk_sender.spend_auth_key().into();
+        let mut nct = tct::Tree::new();
+        nct.insert(tct::Witness::Keep, note_commitment).unwrap();
+        let start_position = nct.position().unwrap();
+        let anchor = nct.root();
+        let note_commitment_proof = nct.witness(note_commitment).unwrap();
+
+        let proof = DelegatorVoteProof {
+            note_commitment_proof,
+            note,
+            spend_auth_randomizer,
+            ak,
+            nk,
+        };
+
+        let rk: VerificationKey<SpendAuth> = rsk.into();
+        let nf = nk.derive_nullifier(0.into(), &note_commitment);
+        assert!(proof
+            .verify(anchor, start_position, value_to_vote, nf, rk)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_delegator_vote_proof_verification_start_position_failure() {
+        let mut rng = OsRng;
+
+        let seed_phrase = SeedPhrase::generate(&mut rng);
+        let sk_sender = SpendKey::from_seed_phrase(seed_phrase, 0);
+        let fvk_sender = sk_sender.full_viewing_key();
+        let ivk_sender = fvk_sender.incoming();
+        let (sender, _dtk_d) = ivk_sender.payment_address(0u64.into());
+
+        let value_to_vote = Value {
+            amount: 10u64.into(),
+            asset_id: asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
+        };
+
+        let note = Note::generate(&mut rng, &sender, value_to_vote);
+        let note_commitment = note.commit();
+        let spend_auth_randomizer = Fr::rand(&mut rng);
+        let rsk = sk_sender.spend_auth_key().randomize(&spend_auth_randomizer);
+        let nk = *sk_sender.nullifier_key();
+        let ak = sk_sender.spend_auth_key().into();
+        let mut nct = tct::Tree::new();
+        // The proposal started before the note was created.
+        let start_position = nct.position().unwrap();
+        nct.insert(tct::Witness::Keep, note_commitment).unwrap();
+        let anchor = nct.root();
+        let note_commitment_proof = nct.witness(note_commitment).unwrap();
+
+        let proof = DelegatorVoteProof {
+            note_commitment_proof,
+            note,
+            spend_auth_randomizer,
+            ak,
+            nk,
+        };
+
+        let rk: VerificationKey<SpendAuth> = rsk.into();
+        let nf = nk.derive_nullifier(0.into(), &note_commitment);
+        assert!(proof
+            .verify(anchor, start_position, value_to_vote, nf, rk)
+            .is_err());
+    }
+
+    /// A swap proof, with the public inputs it should verify against.
+    struct SwapProofInputs {
+        proof: SwapProof,
+        value_1_commitment: balance::Commitment,
+        value_2_commitment: balance::Commitment,
+        fee_commitment: balance::Commitment,
+        note_commitment: note::Commitment,
+        epk: ka::Public,
+        limit_prices: LimitPrices,
+        flow_encryption_key: EncryptionKey,
+        enc_delta_1: FlowCiphertext,
+        enc_delta_2: FlowCiphertext,
+    }
+
+    impl SwapProofInputs {
+        fn new() -> Self {
+            let mut rng = OsRng;
+
+            let seed_phrase = SeedPhrase::generate(&mut rng);
+            let sk = SpendKey::from_seed_phrase(seed_phrase, 0);
+            let (claim_address, _dtk_d) = sk
+                .full_viewing_key()
+                .incoming()
+                .payment_address(0u64.into());
+            let (flow_encryption_key, _) = penumbra_eddy::dkg::deal(1, 1, &mut rng).unwrap();
+
+            let trading_pair = TradingPair::new(
+                asset::REGISTRY.parse_denom("nala").unwrap().id(),
+                asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
+            )
+            .unwrap();
+            let value_t1 = Value {
+                amount: 100u64.into(),
+                asset_id: trading_pair.asset_1(),
+            };
+            let value_t2 = Value {
+                amount: 0u64.into(),
+                asset_id: trading_pair.asset_2(),
+            };
+            let fee_delta = Fee::from_staking_token_amount(3u64.into());
+            let limit_prices = LimitPrices {
+                min_price_1: 0,
+                min_price_2: 500_000,
+            };
+            let swap_plaintext = crate::dex::swap::SwapPlaintext::from_parts(
+                trading_pair,
+                value_t1.amount,
+                value_t2.amount,
+                fee_delta.clone(),
+                claim_address,
+                limit_prices,
+            )
+            .unwrap();
+            let swap_nft_asset_id = swap_plaintext.asset_id();
+            let note_blinding = Fq::rand(&mut rng);
+            let note = Note::from_parts(
+                claim_address,
+                Value {
+                    amount: 1u64.into(),
+                    asset_id: swap_nft_asset_id,
+                },
+                note_blinding,
+            )
+            .unwrap();
+            let esk = ka::Secret::new(&mut rng);
+            let epk = esk.diversified_public(&note.diversified_generator());
+
+            let fee_blinding = Fr::rand(&mut rng);
+            let delta_1_blinding = Fr::rand(&mut rng);
+            let delta_2_blinding = Fr::rand(&mut rng);
+            let (enc_delta_1, _, enc_delta_1_opening) =
+                FlowCiphertext::encrypt(100, &flow_encryption_key, &mut rng).unwrap();
+            let (enc_delta_2, _, enc_delta_2_opening) =
+                FlowCiphertext::encrypt(0, &flow_encryption_key, &mut rng).unwrap();
+
+            SwapProofInputs {
+                proof: SwapProof {
+                    claim_address,
+                    value_t1,
+                    value_t2,
+                    fee_delta: fee_delta.clone(),
+                    fee_blinding,
+                    swap_nft_asset_id,
+                    note_blinding,
+                    esk,
+                    delta_1_blinding,
+                    delta_2_blinding,
+                    enc_delta_1_opening,
+                    enc_delta_2_opening,
+                },
+                value_1_commitment: value_t1.commit(delta_1_blinding),
+                value_2_commitment: value_t2.commit(delta_2_blinding),
+                fee_commitment: fee_delta.0.commit(fee_blinding),
+                note_commitment: note.commit(),
+                epk,
+                limit_prices,
+                flow_encryption_key,
+                enc_delta_1,
+                enc_delta_2,
+            }
+        }
+
+        fn verify(&self) -> anyhow::Result<()> {
+            self.proof.verify(
+                self.value_1_commitment,
+                self.value_2_commitment,
+                self.fee_commitment,
+                self.note_commitment,
+                self.epk,
+                self.limit_prices,
+                &self.flow_encryption_key,
+                &self.enc_delta_1,
+                &self.enc_delta_2,
+            )
+        }
+    }
+
+    #[test]
+    fn test_swap_proof_verification_success() {
+        assert!(SwapProofInputs::new().verify().is_ok());
+    }
+
+    #[test]
+    fn test_swap_proof_verification_balance_commitment_integrity_failure() {
+        let mut inputs = SwapProofInputs::new();
+        inputs.value_1_commitment = inputs.proof.value_t1.commit(Fr::rand(&mut OsRng));
+        assert!(inputs.verify().is_err());
+    }
+
+    #[test]
+    fn test_swap_proof_verification_limit_prices_integrity_failure() {
+        let mut inputs = SwapProofInputs::new();
+        // The swap NFT commits to a higher limit than the one made public.
+        inputs.limit_prices = LimitPrices::default();
+        assert!(inputs.verify().is_err());
+    }
+
+    #[test]
+    fn test_swap_proof_verification_flow_ciphertext_integrity_failure() {
+        let mut inputs = SwapProofInputs::new();
+        // A well-formed encryption of a different amount than the swap pays for.
+        let (enc_delta_1, _, _) =
+            FlowCiphertext::encrypt(1_000_000, &inputs.flow_encryption_key, OsRng).unwrap();
+        inputs.enc_delta_1 = enc_delta_1;
+        assert!(inputs.verify().is_err());
+    }
 }
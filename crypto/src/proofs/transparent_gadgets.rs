@@ -1,9 +1,11 @@
 use anyhow::{anyhow, Result};
 use decaf377_rdsa::{SpendAuth, VerificationKey};
+use penumbra_eddy::{proofs::TransparentEncryptionProof, EncryptionKey};
 use penumbra_tct as tct;
 
 use crate::{
-    asset, balance, dex, ka, keys, note, transaction::Fee, Address, Fr, Note, Nullifier, Value,
+    asset, balance, dex, ka, keys, note, transaction::Fee, Address, FlowCiphertext, Fr, Note,
+    Nullifier, Value,
 };
 
 /// Check the integrity of the nullifier.
@@ -53,6 +55,24 @@ pub(crate) fn balance_commitment_integrity(
     }
 }
 
+/// Check the integrity of a flow ciphertext, i.e. that it encrypts `value`'s
+/// amount to the flow encryption committee.
+pub(crate) fn flow_ciphertext_integrity(
+    ciphertext: &FlowCiphertext,
+    opening: &TransparentEncryptionProof,
+    encryption_key: &EncryptionKey,
+    value: Value,
+) -> Result<()> {
+    ciphertext
+        .verify_opening(opening, encryption_key)
+        .map_err(|_| anyhow!("flow ciphertext mismatch"))?;
+    if opening.value() != u64::from(value.amount) {
+        Err(anyhow!("flow ciphertext amount mismatch"))
+    } else {
+        Ok(())
+    }
+}
+
 /// Check the integrity of an ephemeral public key.
 pub(crate) fn ephemeral_public_key_integrity(
     public_key: ka::Public,
@@ -91,6 +111,7 @@ pub(crate) fn swap_nft_asset_id_integrity(
     delta_2_i: u64,
     fee: Fee,
     claim_address: Address,
+    limit_prices: dex::LimitPrices,
 ) -> Result<()> {
     let expected_plaintext = dex::swap::SwapPlaintext::from_parts(
         trading_pair,
@@ -100,6 +121,7 @@ pub(crate) fn swap_nft_asset_id_integrity(
         // This should ensure that the claim address matches the address
         // used to construct the Swap NFT.
         claim_address,
+        limit_prices,
     )
     .map_err(|_| anyhow!("error generating expected swap plaintext"))?;
     let expected_asset_id = expected_plaintext.asset_id();
@@ -1,4 +1,7 @@
-use std::ops::{Add, AddAssign};
+use std::{
+    collections::BTreeSet,
+    ops::{Add, AddAssign},
+};
 
 use crate::{
     decryption_share::Verified, limb, DecryptionShare, DecryptionTable, TableLookupError, Value,
@@ -20,9 +23,22 @@ pub struct Ciphertext {
 }
 
 impl Ciphertext {
+    /// Encodes this ciphertext as 256 bytes: the encodings of each limb
+    /// ciphertext, from least to most significant.
+    pub fn to_bytes(&self) -> [u8; 256] {
+        let mut bytes = [0u8; 256];
+        for (i, limb) in [self.c0, self.c1, self.c2, self.c3].iter().enumerate() {
+            bytes[64 * i..64 * (i + 1)].copy_from_slice(&limb.to_bytes());
+        }
+        bytes
+    }
+
     /// Use the provided [`DecryptionShare`]s to decrypt the ciphertext,
     /// recovering the value with the given [`DecryptionTable`].
     ///
+    /// The shares must come from at least `threshold` distinct participants,
+    /// where `threshold` is the threshold the key was shared with.
+    ///
     /// # Errors
     ///
     /// - [`InsufficientSharesError`] if insufficiently many decryption shares were supplied;
@@ -31,10 +47,23 @@ impl Ciphertext {
     pub async fn decrypt(
         &self,
         shares: Vec<DecryptionShare<Verified>>,
+        threshold: u32,
         table: &dyn DecryptionTable,
     ) -> anyhow::Result<Value> {
-        // TODO: how do we know if we have sufficient shares?
-        // How do we return InsufficientSharesError?
+        // Interpolating with fewer shares than the threshold would silently
+        // produce garbage, and duplicate shares would break interpolation.
+        let participants = shares
+            .iter()
+            .map(|s| s.participant_index)
+            .collect::<BTreeSet<_>>();
+        if participants.len() != shares.len() {
+            return Err(anyhow::anyhow!(
+                "duplicate decryption shares from the same participant"
+            ));
+        }
+        if participants.len() < threshold as usize {
+            return Err(InsufficientSharesError {}.into());
+        }
 
         let limb0_shares = shares.iter().map(|s| &s.share0).collect();
         let limb1_shares = shares.iter().map(|s| &s.share1).collect();
@@ -72,6 +101,23 @@ impl Ciphertext {
     }
 }
 
+impl TryFrom<&[u8]> for Ciphertext {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 256 {
+            return Err(anyhow::anyhow!("ciphertext must be 256 bytes"));
+        }
+        let limb = |i: usize| limb::Ciphertext::from_bytes(bytes[64 * i..64 * (i + 1)].try_into()?);
+        Ok(Ciphertext {
+            c0: limb(0)?,
+            c1: limb(1)?,
+            c2: limb(2)?,
+            c3: limb(3)?,
+        })
+    }
+}
+
 impl Add<&Ciphertext> for &Ciphertext {
     type Output = Ciphertext;
     fn add(self, rhs: &Ciphertext) -> Self::Output {
@@ -65,6 +65,53 @@ impl PrivateKeyShare {
     }
 }
 
+impl<S: VerificationStatus> DecryptionShare<S> {
+    /// The index of the participant that (supposedly) generated this share.
+    pub fn participant_index(&self) -> u32 {
+        self.participant_index
+    }
+
+    /// Encodes this decryption share as 388 bytes: the participant index,
+    /// followed by the share and proof for each limb, from least to most
+    /// significant.
+    pub fn to_bytes(&self) -> [u8; 388] {
+        let mut bytes = [0u8; 388];
+        bytes[0..4].copy_from_slice(&self.participant_index.to_le_bytes());
+        for (i, share) in [&self.share0, &self.share1, &self.share2, &self.share3]
+            .iter()
+            .enumerate()
+        {
+            bytes[4 + 96 * i..4 + 96 * (i + 1)].copy_from_slice(&share.to_bytes());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for DecryptionShare<Unverified> {
+    type Error = anyhow::Error;
+
+    /// Decodes a decryption share, which must then be verified before use.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 388 {
+            return Err(anyhow::anyhow!("decryption share must be 388 bytes"));
+        }
+        let participant_index = u32::from_le_bytes(bytes[0..4].try_into()?);
+        let share = |i: usize| {
+            limb::DecryptionShare::from_bytes(
+                participant_index,
+                bytes[4 + 96 * i..4 + 96 * (i + 1)].try_into()?,
+            )
+        };
+        Ok(DecryptionShare {
+            participant_index,
+            share0: share(0)?,
+            share1: share(1)?,
+            share2: share(2)?,
+            share3: share(3)?,
+        })
+    }
+}
+
 impl DecryptionShare<Unverified> {
     /// Verify this decryption share against the [`PublicKeyShare`] that
     /// (supposedly) generated it, so that it can be used in decryption.
@@ -1,8 +1,550 @@
-//! Types used to perform distributed key generation (WIP).
+//! Distributed key generation.
+//!
+//! This implements the Pedersen DKG (joint Feldman verifiable secret sharing),
+//! in which every participant acts as a dealer of a random secret, and the
+//! joint decryption key is the sum of the secrets of the qualified dealers.
+//! No participant ever learns the joint decryption key.
+//!
+//! The protocol runs in three rounds, with every message except dealt shares
+//! sent over a broadcast channel:
+//!
+//! 1. Each participant broadcasts a [`DealerCommitment`] to its secret
+//!    polynomial, and privately sends a [`DealtShare`] to every participant.
+//! 2. Each participant checks the shares it received against the dealers'
+//!    commitments, and broadcasts a [`Complaint`] against every dealer whose
+//!    share was missing or invalid.
+//! 3. Each dealer answers the complaints against it by broadcasting a
+//!    [`ComplaintResponse`] revealing the disputed share.
+//!
+//! Dealers that fail to answer a complaint with a valid share are
+//! disqualified, and each participant then combines the shares from the
+//! remaining dealers with [`Participant::finalize`].
 
-use crate::PublicKeyShare;
+use std::collections::{BTreeMap, BTreeSet};
+
+use ark_ff::{UniformRand, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{EncryptionKey, PrivateKeyShare, PublicKeyShare};
 
 pub struct Committee {
     pub shares: Vec<PublicKeyShare>,
     pub threshold: u32,
 }
+
+/// A dealer's commitment to the coefficients of its secret polynomial.
+#[derive(Debug, Clone)]
+pub struct DealerCommitment {
+    pub dealer_index: u32,
+    pub commitments: Vec<decaf377::Element>,
+}
+
+impl DealerCommitment {
+    /// Checks a share dealt to `recipient_index` against this commitment.
+    fn verify(&self, recipient_index: u32, share: &decaf377::Fr) -> bool {
+        *share * decaf377::basepoint() == self.evaluate(recipient_index)
+    }
+
+    /// Evaluates the committed polynomial "in the exponent" at `index`.
+    fn evaluate(&self, index: u32) -> decaf377::Element {
+        let x = decaf377::Fr::from(index);
+        self.commitments
+            .iter()
+            .rev()
+            .fold(decaf377::Element::default(), |acc, commitment| {
+                acc * x + *commitment
+            })
+    }
+}
+
+/// A share of a dealer's secret, sent privately to its recipient.
+#[derive(Debug, Clone)]
+pub struct DealtShare {
+    pub dealer_index: u32,
+    pub recipient_index: u32,
+    pub share: decaf377::Fr,
+}
+
+/// A participant's complaint that a dealer's share was missing or invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Complaint {
+    pub complainer_index: u32,
+    pub dealer_index: u32,
+}
+
+/// A dealer's answer to a complaint, revealing the disputed share.
+#[derive(Debug, Clone)]
+pub struct ComplaintResponse {
+    pub dealer_index: u32,
+    pub recipient_index: u32,
+    pub share: decaf377::Fr,
+}
+
+/// The result of distributed key generation, for a single participant.
+pub struct DkgOutput {
+    /// The participant's private key share.
+    pub key_share: PrivateKeyShare,
+    /// The joint encryption key.
+    pub encryption_key: EncryptionKey,
+    /// The public key shares of every participant.
+    pub committee: Committee,
+    /// The dealers whose secrets were combined into the key.
+    pub qualified_dealers: BTreeSet<u32>,
+}
+
+/// A participant in distributed key generation.
+pub struct Participant {
+    index: u32,
+    n: u32,
+    threshold: u32,
+    coefficients: Vec<decaf377::Fr>,
+    commitments: BTreeMap<u32, DealerCommitment>,
+    shares: BTreeMap<u32, decaf377::Fr>,
+}
+
+impl Participant {
+    /// Creates the participant with the given index (in `1..=n`), for a key
+    /// shared among `n` participants such that any `threshold` of them can
+    /// decrypt.
+    pub fn new<R: RngCore + CryptoRng>(
+        index: u32,
+        n: u32,
+        threshold: u32,
+        mut rng: R,
+    ) -> anyhow::Result<Self> {
+        check_parameters(threshold, n)?;
+        if index == 0 || index > n {
+            return Err(anyhow::anyhow!(
+                "participant index {} is not in 1..={}",
+                index,
+                n
+            ));
+        }
+
+        Ok(Participant {
+            index,
+            n,
+            threshold,
+            coefficients: random_polynomial(threshold, &mut rng),
+            commitments: BTreeMap::new(),
+            shares: BTreeMap::new(),
+        })
+    }
+
+    /// The index of this participant.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Round 1: the commitment to this participant's secret polynomial, to be
+    /// broadcast to all participants.
+    pub fn commitment(&self) -> DealerCommitment {
+        DealerCommitment {
+            dealer_index: self.index,
+            commitments: self
+                .coefficients
+                .iter()
+                .map(|coefficient| *coefficient * decaf377::basepoint())
+                .collect(),
+        }
+    }
+
+    /// Round 1: the shares of this participant's secret, to be sent privately
+    /// to each participant (including this one).
+    pub fn deal(&self) -> Vec<DealtShare> {
+        (1..=self.n)
+            .map(|recipient_index| DealtShare {
+                dealer_index: self.index,
+                recipient_index,
+                share: evaluate(&self.coefficients, recipient_index),
+            })
+            .collect()
+    }
+
+    /// Records a dealer's broadcast commitment.
+    pub fn receive_commitment(&mut self, commitment: DealerCommitment) -> anyhow::Result<()> {
+        if commitment.dealer_index == 0 || commitment.dealer_index > self.n {
+            return Err(anyhow::anyhow!(
+                "unknown dealer {}",
+                commitment.dealer_index
+            ));
+        }
+        if commitment.commitments.len() != self.threshold as usize {
+            return Err(anyhow::anyhow!(
+                "dealer {} committed to a polynomial of the wrong degree",
+                commitment.dealer_index
+            ));
+        }
+        if self.commitments.contains_key(&commitment.dealer_index) {
+            return Err(anyhow::anyhow!(
+                "dealer {} already sent a commitment",
+                commitment.dealer_index
+            ));
+        }
+        self.commitments.insert(commitment.dealer_index, commitment);
+        Ok(())
+    }
+
+    /// Records a share dealt to this participant, if it is consistent with the
+    /// dealer's commitment, which must already have been received.
+    ///
+    /// Invalid shares are discarded, and complained about in round 2.
+    pub fn receive_share(&mut self, share: DealtShare) -> anyhow::Result<()> {
+        if share.recipient_index != self.index {
+            return Err(anyhow::anyhow!(
+                "share was dealt to participant {}, not {}",
+                share.recipient_index,
+                self.index
+            ));
+        }
+        let commitment = self
+            .commitments
+            .get(&share.dealer_index)
+            .ok_or_else(|| anyhow::anyhow!("no commitment from dealer {}", share.dealer_index))?;
+        if !commitment.verify(self.index, &share.share) {
+            return Err(anyhow::anyhow!(
+                "share from dealer {} does not match its commitment",
+                share.dealer_index
+            ));
+        }
+        self.shares.insert(share.dealer_index, share.share);
+        Ok(())
+    }
+
+    /// Round 2: complaints against every dealer that committed to a secret but
+    /// did not deal this participant a valid share, to be broadcast to all
+    /// participants.
+    pub fn complaints(&self) -> Vec<Complaint> {
+        self.commitments
+            .keys()
+            .filter(|dealer_index| !self.shares.contains_key(dealer_index))
+            .map(|&dealer_index| Complaint {
+                complainer_index: self.index,
+                dealer_index,
+            })
+            .collect()
+    }
+
+    /// Round 3: responses to the complaints against this participant, to be
+    /// broadcast to all participants.
+    pub fn respond(&self, complaints: &[Complaint]) -> Vec<ComplaintResponse> {
+        complaints
+            .iter()
+            .filter(|complaint| complaint.dealer_index == self.index)
+            .map(|complaint| ComplaintResponse {
+                dealer_index: self.index,
+                recipient_index: complaint.complainer_index,
+                share: evaluate(&self.coefficients, complaint.complainer_index),
+            })
+            .collect()
+    }
+
+    /// Combines the secrets of the qualified dealers into this participant's
+    /// key share, given every complaint and response that was broadcast.
+    ///
+    /// A dealer is qualified if it broadcast a commitment, and answered every
+    /// complaint against it with a share consistent with that commitment.
+    /// Every honest participant computes the same set of qualified dealers,
+    /// and so the same encryption key.
+    pub fn finalize(
+        mut self,
+        complaints: &[Complaint],
+        responses: &[ComplaintResponse],
+    ) -> anyhow::Result<DkgOutput> {
+        let mut qualified_dealers = BTreeSet::new();
+        'dealers: for (&dealer_index, commitment) in self.commitments.iter() {
+            for complaint in complaints
+                .iter()
+                .filter(|complaint| complaint.dealer_index == dealer_index)
+            {
+                let response = responses.iter().find(|response| {
+                    response.dealer_index == dealer_index
+                        && response.recipient_index == complaint.complainer_index
+                        && commitment.verify(response.recipient_index, &response.share)
+                });
+                match response {
+                    Some(response) => {
+                        // A revealed share replaces the one we complained about.
+                        if response.recipient_index == self.index {
+                            self.shares.insert(dealer_index, response.share);
+                        }
+                    }
+                    None => continue 'dealers,
+                }
+            }
+            if self.shares.contains_key(&dealer_index) {
+                qualified_dealers.insert(dealer_index);
+            }
+        }
+
+        if qualified_dealers.len() < self.threshold as usize {
+            return Err(anyhow::anyhow!(
+                "only {} dealers qualified, but at least {} are required",
+                qualified_dealers.len(),
+                self.threshold
+            ));
+        }
+
+        let key_share = qualified_dealers
+            .iter()
+            .map(|dealer_index| self.shares[dealer_index])
+            .fold(decaf377::Fr::zero(), |acc, share| acc + share);
+        let encryption_key = qualified_dealers
+            .iter()
+            .map(|dealer_index| self.commitments[dealer_index].commitments[0])
+            .fold(decaf377::Element::default(), |acc, commitment| {
+                acc + commitment
+            });
+        let shares = (1..=self.n)
+            .map(|participant_index| PublicKeyShare {
+                participant_index,
+                pub_key_share: qualified_dealers
+                    .iter()
+                    .map(|dealer_index| self.commitments[dealer_index].evaluate(participant_index))
+                    .fold(decaf377::Element::default(), |acc, share| acc + share),
+            })
+            .collect();
+
+        Ok(DkgOutput {
+            key_share: PrivateKeyShare::new(self.index, key_share),
+            encryption_key: EncryptionKey(encryption_key),
+            committee: Committee {
+                shares,
+                threshold: self.threshold,
+            },
+            qualified_dealers,
+        })
+    }
+}
+
+/// Deal key shares for a committee of `n` participants as a trusted dealer,
+/// such that any `threshold` of them can decrypt.
+///
+/// Unlike distributed key generation, the dealer learns the decryption key,
+/// and must be trusted to forget it, so this is only suitable when a single
+/// operator runs every participant (e.g., a local testnet).
+pub fn deal<R: RngCore + CryptoRng>(
+    threshold: u32,
+    n: u32,
+    mut rng: R,
+) -> anyhow::Result<(EncryptionKey, Vec<PrivateKeyShare>)> {
+    check_parameters(threshold, n)?;
+
+    // A random polynomial of degree `threshold - 1`, whose constant term is
+    // the decryption key.
+    let coefficients = random_polynomial(threshold, &mut rng);
+
+    let shares = (1..=n)
+        .map(|participant_index| {
+            PrivateKeyShare::new(
+                participant_index,
+                evaluate(&coefficients, participant_index),
+            )
+        })
+        .collect();
+
+    Ok((
+        EncryptionKey(coefficients[0] * decaf377::basepoint()),
+        shares,
+    ))
+}
+
+fn check_parameters(threshold: u32, n: u32) -> anyhow::Result<()> {
+    if threshold == 0 || threshold > n {
+        return Err(anyhow::anyhow!(
+            "threshold {} is invalid for {} participants",
+            threshold,
+            n
+        ));
+    }
+    Ok(())
+}
+
+/// A random polynomial of degree `threshold - 1`.
+fn random_polynomial<R: RngCore + CryptoRng>(threshold: u32, rng: &mut R) -> Vec<decaf377::Fr> {
+    (0..threshold)
+        .map(|_| decaf377::Fr::rand(&mut *rng))
+        .collect()
+}
+
+/// Evaluates the polynomial with the given coefficients at `index`.
+fn evaluate(coefficients: &[decaf377::Fr], index: u32) -> decaf377::Fr {
+    let x = decaf377::Fr::from(index);
+    coefficients
+        .iter()
+        .rev()
+        .fold(decaf377::Fr::zero(), |acc, coefficient| {
+            acc * x + coefficient
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use merlin::Transcript;
+
+    use super::*;
+    use crate::{
+        Ciphertext, DecryptionShare, DecryptionTable, InsufficientSharesError, MockDecryptionTable,
+        Value,
+    };
+
+    const N: u32 = 5;
+    const THRESHOLD: u32 = 3;
+
+    /// Runs the DKG among `N` local participants. Dealer 2 deals a bad share
+    /// to participant 4, but answers the complaint; dealer 5 deals a bad share
+    /// to participant 1, and ignores the complaint.
+    fn simulate_dkg() -> Vec<DkgOutput> {
+        let mut rng = rand::thread_rng();
+        let mut participants = (1..=N)
+            .map(|index| Participant::new(index, N, THRESHOLD, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+
+        // Round 1: broadcast commitments and deal shares.
+        let commitments = participants
+            .iter()
+            .map(|p| p.commitment())
+            .collect::<Vec<_>>();
+        let mut dealt = participants
+            .iter()
+            .flat_map(|p| p.deal())
+            .collect::<Vec<_>>();
+        for share in dealt.iter_mut() {
+            if (share.dealer_index, share.recipient_index) == (2, 4)
+                || (share.dealer_index, share.recipient_index) == (5, 1)
+            {
+                share.share += decaf377::Fr::from(1u32);
+            }
+        }
+        for p in participants.iter_mut() {
+            for commitment in commitments.iter() {
+                p.receive_commitment(commitment.clone()).unwrap();
+            }
+            for share in dealt.iter().filter(|s| s.recipient_index == p.index()) {
+                let is_tampered = (share.dealer_index, share.recipient_index) == (2, 4)
+                    || (share.dealer_index, share.recipient_index) == (5, 1);
+                assert_eq!(p.receive_share(share.clone()).is_err(), is_tampered);
+            }
+        }
+
+        // Round 2: broadcast complaints.
+        let complaints = participants
+            .iter()
+            .flat_map(|p| p.complaints())
+            .collect::<Vec<_>>();
+        assert_eq!(complaints.len(), 2);
+
+        // Round 3: broadcast responses, except from dealer 5.
+        let responses = participants
+            .iter()
+            .filter(|p| p.index() != 5)
+            .flat_map(|p| p.respond(&complaints))
+            .collect::<Vec<_>>();
+
+        participants
+            .into_iter()
+            .map(|p| p.finalize(&complaints, &responses).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn dkg_key_shares_decrypt() {
+        let outputs = simulate_dkg();
+
+        // Every participant agrees on the qualified dealers and the keys.
+        for output in outputs.iter() {
+            assert_eq!(
+                output.qualified_dealers,
+                [1, 2, 3, 4].into_iter().collect::<BTreeSet<_>>()
+            );
+            assert_eq!(output.encryption_key, outputs[0].encryption_key);
+            assert_eq!(output.committee.shares, outputs[0].committee.shares);
+            assert_eq!(
+                output.key_share.public_key_share(),
+                output.committee.shares[output.key_share.participant_index() as usize - 1]
+            );
+        }
+
+        let mut rng = rand::thread_rng();
+        let (ciphertext, _) = Value::from(1234u64)
+            .transparent_encrypt(&outputs[0].encryption_key, &mut rng)
+            .unwrap();
+
+        let shares = |outputs: &[DkgOutput]| {
+            outputs
+                .iter()
+                .map(|output| {
+                    let share = output.key_share.decryption_share(
+                        &ciphertext,
+                        &mut Transcript::new(b"test"),
+                        rand::thread_rng(),
+                    );
+                    share
+                        .verify(
+                            &ciphertext,
+                            &output.key_share.public_key_share(),
+                            &mut Transcript::new(b"test"),
+                        )
+                        .unwrap()
+                })
+                .collect::<Vec<DecryptionShare<_>>>()
+        };
+
+        let table = MockDecryptionTable::default();
+        table.initialize(16).await.unwrap();
+
+        // Any threshold of participants can decrypt...
+        for subset in [&outputs[0..3], &outputs[2..5]] {
+            let value = ciphertext
+                .decrypt(shares(subset), THRESHOLD, &table)
+                .await
+                .unwrap();
+            assert!(value == Value::from(1234u64));
+        }
+
+        // ... but fewer cannot.
+        let err = Ciphertext::decrypt(&ciphertext, shares(&outputs[0..2]), THRESHOLD, &table)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<InsufficientSharesError>().is_some());
+    }
+
+    #[test]
+    fn dkg_fails_with_too_few_qualified_dealers() {
+        let mut rng = rand::thread_rng();
+        let mut participants = (1..=3)
+            .map(|index| Participant::new(index, 3, 3, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+
+        // Dealer 3 never deals any shares.
+        let commitments = participants
+            .iter()
+            .map(|p| p.commitment())
+            .collect::<Vec<_>>();
+        let dealt = participants[0..2]
+            .iter()
+            .flat_map(|p| p.deal())
+            .collect::<Vec<_>>();
+        for p in participants.iter_mut() {
+            for commitment in commitments.iter() {
+                p.receive_commitment(commitment.clone()).unwrap();
+            }
+            for share in dealt.iter().filter(|s| s.recipient_index == p.index()) {
+                p.receive_share(share.clone()).unwrap();
+            }
+        }
+
+        let complaints = participants
+            .iter()
+            .flat_map(|p| p.complaints())
+            .collect::<Vec<_>>();
+        let responses = participants[0..2]
+            .iter()
+            .flat_map(|p| p.respond(&complaints))
+            .collect::<Vec<_>>();
+
+        for p in participants {
+            assert!(p.finalize(&complaints, &responses).is_err());
+        }
+    }
+}
@@ -1,4 +1,7 @@
+use decaf377::FieldExt;
+
 /// A decryptor's private key share.
+#[derive(Clone)]
 pub struct PrivateKeyShare {
     pub(crate) participant_index: u32,
     pub(crate) key_share: decaf377::Fr,
@@ -6,7 +9,88 @@ pub struct PrivateKeyShare {
 }
 
 /// A decryptor's public key share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PublicKeyShare {
     pub(crate) participant_index: u32,
     pub(crate) pub_key_share: decaf377::Element,
 }
+
+impl PrivateKeyShare {
+    pub(crate) fn new(participant_index: u32, key_share: decaf377::Fr) -> Self {
+        PrivateKeyShare {
+            participant_index,
+            key_share,
+            cached_pub: PublicKeyShare {
+                participant_index,
+                pub_key_share: key_share * decaf377::basepoint(),
+            },
+        }
+    }
+
+    /// The index of the participant holding this key share.
+    pub fn participant_index(&self) -> u32 {
+        self.participant_index
+    }
+
+    /// The public key share corresponding to this private key share.
+    pub fn public_key_share(&self) -> PublicKeyShare {
+        self.cached_pub
+    }
+
+    /// Encodes this key share as 36 bytes: the participant index, followed by
+    /// the secret share.
+    pub fn to_bytes(&self) -> [u8; 36] {
+        let mut bytes = [0u8; 36];
+        bytes[0..4].copy_from_slice(&self.participant_index.to_le_bytes());
+        bytes[4..36].copy_from_slice(&self.key_share.to_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for PrivateKeyShare {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 36 {
+            return Err(anyhow::anyhow!("private key share must be 36 bytes"));
+        }
+        let participant_index = u32::from_le_bytes(bytes[0..4].try_into()?);
+        let key_share = decaf377::Fr::from_bytes(bytes[4..36].try_into()?)
+            .map_err(|_| anyhow::anyhow!("invalid private key share encoding"))?;
+        Ok(PrivateKeyShare::new(participant_index, key_share))
+    }
+}
+
+impl PublicKeyShare {
+    /// The index of the participant holding the corresponding private key share.
+    pub fn participant_index(&self) -> u32 {
+        self.participant_index
+    }
+
+    /// Encodes this key share as 36 bytes: the participant index, followed by
+    /// the public share.
+    pub fn to_bytes(&self) -> [u8; 36] {
+        let mut bytes = [0u8; 36];
+        bytes[0..4].copy_from_slice(&self.participant_index.to_le_bytes());
+        bytes[4..36].copy_from_slice(&self.pub_key_share.vartime_compress().0);
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for PublicKeyShare {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 36 {
+            return Err(anyhow::anyhow!("public key share must be 36 bytes"));
+        }
+        let participant_index = u32::from_le_bytes(bytes[0..4].try_into()?);
+        let pub_key_share = decaf377::Encoding(bytes[4..36].try_into()?)
+            .vartime_decompress()
+            .map_err(|_| anyhow::anyhow!("invalid public key share encoding"))?;
+        Ok(PublicKeyShare {
+            participant_index,
+            pub_key_share,
+        })
+    }
+}
@@ -1,7 +1,9 @@
 mod ciphertext;
 mod decryption_share;
+mod encryption_proof;
 mod value;
 
 pub use ciphertext::Ciphertext;
 pub use decryption_share::DecryptionShare;
+pub use encryption_proof::{EncryptionProof, ENCRYPTION_PROOF_BYTES};
 pub use value::Value;
@@ -26,6 +26,23 @@ fn lagrange_coefficient(participant_index: u32, participant_indices: &[u32]) ->
 }
 
 impl Ciphertext {
+    pub(crate) fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[0..32].copy_from_slice(&self.c1.vartime_compress().0);
+        bytes[32..64].copy_from_slice(&self.c2.vartime_compress().0);
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8; 64]) -> anyhow::Result<Self> {
+        let c1 = decaf377::Encoding(bytes[0..32].try_into()?)
+            .vartime_decompress()
+            .map_err(|_| anyhow::anyhow!("invalid limb ciphertext encoding"))?;
+        let c2 = decaf377::Encoding(bytes[32..64].try_into()?)
+            .vartime_decompress()
+            .map_err(|_| anyhow::anyhow!("invalid limb ciphertext encoding"))?;
+        Ok(Ciphertext { c1, c2 })
+    }
+
     pub fn decrypt(&self, shares: Vec<&DecryptionShare<Verified>>) -> decaf377::Element {
         let indices = shares
             .iter()
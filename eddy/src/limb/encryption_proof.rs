@@ -0,0 +1,326 @@
+use ark_ff::Zero;
+use ark_std::UniformRand;
+use decaf377::{FieldExt, Fr};
+use rand_core::{CryptoRng, RngCore};
+
+use super::{Ciphertext, Value};
+use crate::{EncryptionKey, TranscriptProtocol};
+
+/// The number of bits in a limb.
+pub(crate) const LIMB_BITS: usize = 16;
+
+/// A proof that a limb ciphertext is a well-formed encryption of a 16-bit value
+/// to a given encryption key.
+///
+/// The value is encrypted bit by bit, and the limb ciphertext is the weighted
+/// sum of the bit ciphertexts.  Each bit ciphertext comes with a proof that it
+/// encrypts either 0 or 1, so the limb ciphertext must encrypt a value in
+/// `[0, 2^16)`.  This bounds the sum of limbs that have to be decrypted with a
+/// decryption table.
+#[derive(Debug, Clone)]
+pub struct EncryptionProof {
+    /// The challenge scalar, shared by the proofs for every bit.
+    c: Fr,
+    /// The ciphertext and proof for each bit, from least to most significant.
+    bits: [BitProof; LIMB_BITS],
+}
+
+/// A proof that a bit ciphertext `(c1, c2)` encrypts 0 or 1.
+///
+/// This is a disjunction of proofs that `(c1, c2)` or `(c1, c2 - B)` is an
+/// encryption of zero, only one of which can be completed by the prover; the
+/// other is simulated by choosing its challenge in advance.
+#[derive(Debug, Clone, Copy, Default)]
+struct BitProof {
+    ciphertext: Ciphertext,
+    /// The challenge for the branch where the bit is 0.  The challenge for the
+    /// branch where the bit is 1 is `c - c0`.
+    c0: Fr,
+    /// The response for the branch where the bit is 0.
+    s0: Fr,
+    /// The response for the branch where the bit is 1.
+    s1: Fr,
+}
+
+/// The size of the encoding of a [`BitProof`].
+const BIT_PROOF_BYTES: usize = 160;
+
+/// The size of the encoding of an [`EncryptionProof`].
+pub(crate) const ENCRYPTION_PROOF_BYTES: usize = 32 + LIMB_BITS * BIT_PROOF_BYTES;
+
+/// The weight of each bit in a limb, as a scalar.
+fn bit_weight(bit: usize) -> Fr {
+    Fr::from(1u32 << bit)
+}
+
+/// The weighted sum of the bit ciphertexts, which is the limb ciphertext.
+fn limb_ciphertext(bits: &[BitProof; LIMB_BITS]) -> Ciphertext {
+    let mut ciphertext = Ciphertext::default();
+    for (i, bit) in bits.iter().enumerate() {
+        ciphertext.c1 += bit.ciphertext.c1 * bit_weight(i);
+        ciphertext.c2 += bit.ciphertext.c2 * bit_weight(i);
+    }
+    ciphertext
+}
+
+/// Computes the commitments of the proof that `ciphertext` encrypts `bit`,
+/// from the challenge and response for that branch.
+#[allow(non_snake_case)]
+fn branch_commitments(
+    ciphertext: &Ciphertext,
+    bit: u32,
+    challenge: Fr,
+    response: Fr,
+    encryption_key: &EncryptionKey,
+) -> (decaf377::Element, decaf377::Element) {
+    let B = decaf377::basepoint();
+    // If `ciphertext` encrypts `bit` with blinding factor `r`, then
+    // `(c1, c2 - bit * B)` is `(r * B, r * D)`.
+    let shifted_c2 = ciphertext.c2 - B * Fr::from(bit);
+    (
+        B * response + ciphertext.c1 * challenge,
+        encryption_key.0 * response + shifted_c2 * challenge,
+    )
+}
+
+impl Value {
+    /// Encrypt this limb, proving that the ciphertext encrypts a 16-bit value.
+    ///
+    /// Also returns the blinding factor of the limb ciphertext, which is the
+    /// weighted sum of the blinding factors of the bit ciphertexts.
+    ///
+    /// The limb must be less than `2^16`.
+    #[allow(non_snake_case)]
+    pub(crate) fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        encryption_key: &EncryptionKey,
+        transcript: &mut merlin::Transcript,
+        mut rng: R,
+    ) -> (Ciphertext, EncryptionProof, Fr) {
+        debug_assert!(self.0 < 1 << LIMB_BITS, "limb out of range");
+
+        let mut bits = [BitProof::default(); LIMB_BITS];
+        let mut blindings = [Fr::zero(); LIMB_BITS];
+        for (i, (bit, blinding)) in bits.iter_mut().zip(blindings.iter_mut()).enumerate() {
+            let (ciphertext, bit_blinding) =
+                Value((self.0 >> i) & 1).transparent_encrypt(encryption_key, &mut rng);
+            bit.ciphertext = ciphertext;
+            *blinding = bit_blinding;
+        }
+        let ciphertext = limb_ciphertext(&bits);
+
+        // Start feeding public data into the transcript
+        transcript.begin_limb_encryption();
+        transcript.append_limb_ciphertext(&ciphertext);
+        for bit in bits.iter() {
+            transcript.append_limb_ciphertext(&bit.ciphertext);
+        }
+
+        // Generate the blinding factors for the proof, and the challenges and
+        // responses of the simulated branches, from the Merlin transcript RNG,
+        // so that they're bound to the public context, the witness, and fresh
+        // randomness from the provided RNG.
+        let mut transcript_rng = blindings
+            .iter()
+            .fold(
+                transcript
+                    .build_rng()
+                    .rekey_with_witness_bytes(b"value", &Fr::from(self.0).to_bytes()),
+                |builder, blinding| {
+                    builder.rekey_with_witness_bytes(b"blinding", &blinding.to_bytes())
+                },
+            )
+            .finalize(&mut rng);
+
+        let B = decaf377::basepoint();
+        let mut nonces = [Fr::zero(); LIMB_BITS];
+        let mut simulated_challenges = [Fr::zero(); LIMB_BITS];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let value = (self.0 >> i) & 1;
+            nonces[i] = Fr::rand(&mut transcript_rng);
+            let real = (B * nonces[i], encryption_key.0 * nonces[i]);
+
+            // The branch for the other value is simulated, by choosing its
+            // challenge and response up front.
+            simulated_challenges[i] = Fr::rand(&mut transcript_rng);
+            let simulated_response = Fr::rand(&mut transcript_rng);
+            let simulated = branch_commitments(
+                &bit.ciphertext,
+                1 - value,
+                simulated_challenges[i],
+                simulated_response,
+                encryption_key,
+            );
+
+            let (commitments_0, commitments_1) = if value == 0 {
+                bit.s1 = simulated_response;
+                (real, simulated)
+            } else {
+                bit.s0 = simulated_response;
+                (simulated, real)
+            };
+            transcript.append_blinding_commitment(b"kB0", &commitments_0.0);
+            transcript.append_blinding_commitment(b"kD0", &commitments_0.1);
+            transcript.append_blinding_commitment(b"kB1", &commitments_1.0);
+            transcript.append_blinding_commitment(b"kD1", &commitments_1.1);
+        }
+
+        let challenge = transcript.challenge_scalar(b"c");
+
+        // The challenge of the real branch is whatever the simulated one
+        // leaves over, so that the two sum to the transcript challenge.
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let real_challenge = challenge - simulated_challenges[i];
+            let real_response = nonces[i] - real_challenge * blindings[i];
+            if (self.0 >> i) & 1 == 0 {
+                bit.c0 = real_challenge;
+                bit.s0 = real_response;
+            } else {
+                bit.c0 = simulated_challenges[i];
+                bit.s1 = real_response;
+            }
+        }
+
+        let blinding = blindings
+            .iter()
+            .enumerate()
+            .fold(Fr::zero(), |sum, (i, blinding)| {
+                sum + *blinding * bit_weight(i)
+            });
+
+        (ciphertext, EncryptionProof { c: challenge, bits }, blinding)
+    }
+}
+
+impl EncryptionProof {
+    pub(crate) fn to_bytes(&self) -> [u8; ENCRYPTION_PROOF_BYTES] {
+        let mut bytes = [0u8; ENCRYPTION_PROOF_BYTES];
+        bytes[0..32].copy_from_slice(&self.c.to_bytes());
+        for (i, bit) in self.bits.iter().enumerate() {
+            let offset = 32 + BIT_PROOF_BYTES * i;
+            bytes[offset..offset + 64].copy_from_slice(&bit.ciphertext.to_bytes());
+            bytes[offset + 64..offset + 96].copy_from_slice(&bit.c0.to_bytes());
+            bytes[offset + 96..offset + 128].copy_from_slice(&bit.s0.to_bytes());
+            bytes[offset + 128..offset + 160].copy_from_slice(&bit.s1.to_bytes());
+        }
+        bytes
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8; ENCRYPTION_PROOF_BYTES]) -> anyhow::Result<Self> {
+        let scalar = |offset: usize| {
+            Fr::from_bytes(bytes[offset..offset + 32].try_into()?)
+                .map_err(|_| anyhow::anyhow!("invalid encryption proof encoding"))
+        };
+        let mut bits = [BitProof::default(); LIMB_BITS];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let offset = 32 + BIT_PROOF_BYTES * i;
+            *bit = BitProof {
+                ciphertext: Ciphertext::from_bytes(bytes[offset..offset + 64].try_into()?)?,
+                c0: scalar(offset + 64)?,
+                s0: scalar(offset + 96)?,
+                s1: scalar(offset + 128)?,
+            };
+        }
+        Ok(EncryptionProof {
+            c: scalar(0)?,
+            bits,
+        })
+    }
+
+    pub(crate) fn verify(
+        &self,
+        ciphertext: &Ciphertext,
+        encryption_key: &EncryptionKey,
+        transcript: &mut merlin::Transcript,
+    ) -> anyhow::Result<()> {
+        let expected = limb_ciphertext(&self.bits);
+        if expected.c1 != ciphertext.c1 || expected.c2 != ciphertext.c2 {
+            return Err(anyhow::anyhow!(
+                "limb ciphertext is not the sum of its bit ciphertexts"
+            ));
+        }
+
+        transcript.begin_limb_encryption();
+        transcript.append_limb_ciphertext(ciphertext);
+        for bit in self.bits.iter() {
+            transcript.append_limb_ciphertext(&bit.ciphertext);
+        }
+        for bit in self.bits.iter() {
+            let commitments_0 =
+                branch_commitments(&bit.ciphertext, 0, bit.c0, bit.s0, encryption_key);
+            let commitments_1 =
+                branch_commitments(&bit.ciphertext, 1, self.c - bit.c0, bit.s1, encryption_key);
+            transcript.append_blinding_commitment(b"kB0", &commitments_0.0);
+            transcript.append_blinding_commitment(b"kD0", &commitments_0.1);
+            transcript.append_blinding_commitment(b"kB1", &commitments_1.0);
+            transcript.append_blinding_commitment(b"kD1", &commitments_1.1);
+        }
+
+        let challenge = transcript.challenge_scalar(b"c");
+
+        if self.c == challenge {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Recomputed challenge {:?} did not match expected challenge {:?}",
+                challenge,
+                self.c
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use merlin::Transcript;
+    use rand_core::OsRng;
+
+    fn encryption_key() -> EncryptionKey {
+        EncryptionKey(decaf377::basepoint() * Fr::rand(&mut OsRng))
+    }
+
+    #[test]
+    fn proves_limbs_in_range() {
+        let encryption_key = encryption_key();
+        for limb in [0u32, 1, 0x8000, 0xffff] {
+            let (ciphertext, proof, blinding) =
+                Value(limb).encrypt(&encryption_key, &mut Transcript::new(b"test"), OsRng);
+            assert_eq!(ciphertext.c1, decaf377::basepoint() * blinding);
+            let proof = EncryptionProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert!(proof
+                .verify(&ciphertext, &encryption_key, &mut Transcript::new(b"test"))
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn rejects_limbs_out_of_range() {
+        let encryption_key = encryption_key();
+        let (ciphertext, mut proof, _) =
+            Value(0xffff).encrypt(&encryption_key, &mut Transcript::new(b"test"), OsRng);
+
+        // Shifting the ciphertext to encrypt 2^16 more breaks the sum of the
+        // bit ciphertexts...
+        let overflow = decaf377::basepoint() * Fr::from(1u32 << LIMB_BITS);
+        let shifted = Ciphertext {
+            c1: ciphertext.c1,
+            c2: ciphertext.c2 + overflow,
+        };
+        assert!(proof
+            .verify(&shifted, &encryption_key, &mut Transcript::new(b"test"))
+            .is_err());
+
+        // ... and so does shifting the top bit's ciphertext to encrypt 2 to
+        // make up for it, because it's no longer an encryption of 0 or 1.
+        proof.bits[LIMB_BITS - 1].ciphertext.c2 += decaf377::basepoint();
+        let shifted = Ciphertext {
+            c1: ciphertext.c1,
+            c2: ciphertext.c2 + decaf377::basepoint() * bit_weight(LIMB_BITS - 1),
+        };
+        assert!(proof
+            .verify(&shifted, &encryption_key, &mut Transcript::new(b"test"))
+            .is_err());
+    }
+}
@@ -1,11 +1,92 @@
-//! Encryption correctness proofs (WIP: currently, only placeholder "transparent
-//! proofs").
+//! Encryption correctness proofs.
 
-use crate::{Ciphertext, EncryptionKey, Value};
+use decaf377::FieldExt;
+
+use crate::{limb, Ciphertext, EncryptionKey, TranscriptProtocol, Value};
+
+/// The size of the encoding of a limb's encryption proof.
+const LIMB_PROOF_BYTES: usize = limb::ENCRYPTION_PROOF_BYTES;
+
+/// The size of the encoding of an [`EncryptionProof`].
+pub const ENCRYPTION_PROOF_BYTES: usize = 4 * LIMB_PROOF_BYTES;
+
+/// A proof that a [`Ciphertext`] is a well-formed encryption of some value to
+/// a given [`EncryptionKey`], produced by [`Value::encrypt`].
+///
+/// The proof consists of a range proof for each limb ciphertext, showing that
+/// it encrypts a 16-bit value, and reveals nothing about the value.  This
+/// ensures that the sum of up to `2^k` well-formed ciphertexts can be decrypted
+/// with a table of `2^(16 + k)` entries.
+#[derive(Debug, Clone)]
+pub struct EncryptionProof {
+    pub(crate) proof0: limb::EncryptionProof,
+    pub(crate) proof1: limb::EncryptionProof,
+    pub(crate) proof2: limb::EncryptionProof,
+    pub(crate) proof3: limb::EncryptionProof,
+}
+
+impl EncryptionProof {
+    /// Verify that `ctxt` is a well-formed encryption to `encryption_key`.
+    pub fn verify(
+        &self,
+        ctxt: &Ciphertext,
+        encryption_key: &EncryptionKey,
+        transcript: &mut merlin::Transcript,
+    ) -> anyhow::Result<()> {
+        transcript.begin_encryption();
+        transcript.append_encryption_key(encryption_key);
+
+        self.proof0.verify(&ctxt.c0, encryption_key, transcript)?;
+        self.proof1.verify(&ctxt.c1, encryption_key, transcript)?;
+        self.proof2.verify(&ctxt.c2, encryption_key, transcript)?;
+        self.proof3.verify(&ctxt.c3, encryption_key, transcript)?;
+
+        Ok(())
+    }
+
+    /// Encodes this proof as 10368 bytes: the proof for each limb, from least
+    /// to most significant.
+    pub fn to_bytes(&self) -> [u8; ENCRYPTION_PROOF_BYTES] {
+        let mut bytes = [0u8; ENCRYPTION_PROOF_BYTES];
+        for (i, proof) in [&self.proof0, &self.proof1, &self.proof2, &self.proof3]
+            .iter()
+            .enumerate()
+        {
+            bytes[LIMB_PROOF_BYTES * i..LIMB_PROOF_BYTES * (i + 1)]
+                .copy_from_slice(&proof.to_bytes());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for EncryptionProof {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != ENCRYPTION_PROOF_BYTES {
+            return Err(anyhow::anyhow!(
+                "encryption proof must be {} bytes",
+                ENCRYPTION_PROOF_BYTES
+            ));
+        }
+        let proof = |i: usize| {
+            limb::EncryptionProof::from_bytes(
+                bytes[LIMB_PROOF_BYTES * i..LIMB_PROOF_BYTES * (i + 1)].try_into()?,
+            )
+        };
+        Ok(EncryptionProof {
+            proof0: proof(0)?,
+            proof1: proof(1)?,
+            proof2: proof(2)?,
+            proof3: proof(3)?,
+        })
+    }
+}
 
 /// Placeholder for a zk-SNARK proof that the encryption is well-formed.
 ///
 /// Note: this proof reveals the ciphertext!!!
+#[derive(Debug, Clone)]
 pub struct TransparentEncryptionProof {
     value: u64,
     blindings: [decaf377::Fr; 4],
@@ -16,6 +97,11 @@ impl TransparentEncryptionProof {
         TransparentEncryptionProof { value, blindings }
     }
 
+    /// The value that the proof opens the ciphertext to.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
     pub fn verify(&self, ctxt: &Ciphertext, encryption_key: &EncryptionKey) -> anyhow::Result<()> {
         let limbs = Value::from(self.value as u64).to_limbs()?;
         let ctxts = [ctxt.c0, ctxt.c1, ctxt.c2, ctxt.c3];
@@ -38,4 +124,37 @@ impl TransparentEncryptionProof {
 
         Ok(())
     }
+
+    /// Encodes this proof as 136 bytes: the value, followed by the blinding
+    /// factor for each limb, from least to most significant.
+    pub fn to_bytes(&self) -> [u8; 136] {
+        let mut bytes = [0u8; 136];
+        bytes[0..8].copy_from_slice(&self.value.to_le_bytes());
+        for (i, blinding) in self.blindings.iter().enumerate() {
+            bytes[8 + 32 * i..8 + 32 * (i + 1)].copy_from_slice(&blinding.to_bytes());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for TransparentEncryptionProof {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 136 {
+            return Err(anyhow::anyhow!(
+                "transparent encryption proof must be 136 bytes"
+            ));
+        }
+        let mut blindings: [decaf377::Fr; 4] = Default::default();
+        for (i, blinding) in blindings.iter_mut().enumerate() {
+            let encoding = bytes[8 + 32 * i..8 + 32 * (i + 1)].try_into()?;
+            *blinding = decaf377::Fr::from_bytes(encoding)
+                .map_err(|_| anyhow::anyhow!("invalid transparent encryption proof encoding"))?;
+        }
+        Ok(TransparentEncryptionProof {
+            value: u64::from_le_bytes(bytes[0..8].try_into()?),
+            blindings,
+        })
+    }
 }
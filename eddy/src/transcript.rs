@@ -1,8 +1,11 @@
-use crate::{limb, PublicKeyShare};
+use crate::{limb, EncryptionKey, PublicKeyShare};
 
 pub trait TranscriptProtocol {
     fn begin_decryption(&mut self);
     fn begin_limb_decryption(&mut self);
+    fn begin_encryption(&mut self);
+    fn begin_limb_encryption(&mut self);
+    fn append_encryption_key(&mut self, key: &EncryptionKey);
     fn append_public_key_share(&mut self, share: &PublicKeyShare);
     fn append_limb_ciphertext(&mut self, ciphertext: &limb::Ciphertext);
     fn append_decryption_share_point(&mut self, point: &decaf377::Element);
@@ -18,6 +21,15 @@ impl TranscriptProtocol for merlin::Transcript {
     fn begin_limb_decryption(&mut self) {
         self.append_message(b"dom-sep", b"begin-limb");
     }
+    fn begin_encryption(&mut self) {
+        self.append_message(b"dom-sep", b"eddy-decaf377-encrypt");
+    }
+    fn begin_limb_encryption(&mut self) {
+        self.append_message(b"dom-sep", b"begin-limb");
+    }
+    fn append_encryption_key(&mut self, key: &EncryptionKey) {
+        self.append_message(b"encryption-key", &key.to_bytes());
+    }
     fn append_public_key_share(&mut self, share: &PublicKeyShare) {
         self.append_message(b"dom-sep", b"public-key-share");
         self.append_message(b"index", &share.participant_index.to_le_bytes());
@@ -1,6 +1,6 @@
 use rand_core::{CryptoRng, RngCore};
 
-use crate::{limb, proofs, Ciphertext, EncryptionKey};
+use crate::{limb, proofs, Ciphertext, EncryptionKey, TranscriptProtocol};
 
 /// A plaintext integer value.
 ///
@@ -40,6 +40,62 @@ impl Value {
         Value(x0 + (x1 << 16) + (x2 << 32) + (x3 << 48))
     }
 
+    /// Encrypt this value to the given [`EncryptionKey`], producing a
+    /// [`Ciphertext`] and a [`proofs::EncryptionProof`] that it is well-formed.
+    ///
+    /// The proof must be verified with a transcript in the same state as the
+    /// one provided here.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        encryption_key: &EncryptionKey,
+        transcript: &mut merlin::Transcript,
+        rng: R,
+    ) -> anyhow::Result<(Ciphertext, proofs::EncryptionProof)> {
+        let (ciphertext, proof, _) = self.encrypt_with_opening(encryption_key, transcript, rng)?;
+        Ok((ciphertext, proof))
+    }
+
+    /// Encrypt this value as in [`Value::encrypt`], also returning the opening
+    /// of the ciphertext as a [`proofs::TransparentEncryptionProof`].
+    ///
+    /// The opening reveals the value, so it must not be published, but it can
+    /// serve as the witness for proofs of statements about the encrypted value.
+    pub fn encrypt_with_opening<R: RngCore + CryptoRng>(
+        &self,
+        encryption_key: &EncryptionKey,
+        transcript: &mut merlin::Transcript,
+        mut rng: R,
+    ) -> anyhow::Result<(
+        Ciphertext,
+        proofs::EncryptionProof,
+        proofs::TransparentEncryptionProof,
+    )> {
+        let value = u64::try_from(self.0)?;
+        let limbs = self.to_limbs()?;
+
+        transcript.begin_encryption();
+        transcript.append_encryption_key(encryption_key);
+
+        let (c0, proof0, blinding0) = limbs[0].encrypt(encryption_key, transcript, &mut rng);
+        let (c1, proof1, blinding1) = limbs[1].encrypt(encryption_key, transcript, &mut rng);
+        let (c2, proof2, blinding2) = limbs[2].encrypt(encryption_key, transcript, &mut rng);
+        let (c3, proof3, blinding3) = limbs[3].encrypt(encryption_key, transcript, &mut rng);
+
+        Ok((
+            Ciphertext { c0, c1, c2, c3 },
+            proofs::EncryptionProof {
+                proof0,
+                proof1,
+                proof2,
+                proof3,
+            },
+            proofs::TransparentEncryptionProof::new(
+                value,
+                [blinding0, blinding1, blinding2, blinding3],
+            ),
+        ))
+    }
+
     /// Encrypt this value to the given [`EncryptionKey`], producing a
     /// [`Ciphertext`] and a (transparent) encryption proof.
     ///
@@ -80,6 +136,7 @@ mod tests {
     use super::*;
 
     use ark_ff::UniformRand;
+    use merlin::Transcript;
     use proptest::prelude::*;
 
     proptest! {
@@ -118,5 +175,44 @@ mod tests {
 
             assert!(proof.verify(&ciphertext, &encryption_key).is_ok());
         }
+
+        #[test]
+        fn encryption_proof_roundtrip(value: u64) {
+            let mut rng = rand::thread_rng();
+            let encryption_key = EncryptionKey(decaf377::basepoint() * decaf377::Fr::rand(&mut rng));
+            let other_key = EncryptionKey(decaf377::basepoint() * decaf377::Fr::rand(&mut rng));
+            let (ciphertext, proof) = Value::from(value)
+                .encrypt(&encryption_key, &mut Transcript::new(b"test"), &mut rng)
+                .unwrap();
+
+            // The proof survives a round trip through its encoding...
+            let proof: proofs::EncryptionProof = proof.to_bytes()[..].try_into().unwrap();
+            assert!(proof
+                .verify(&ciphertext, &encryption_key, &mut Transcript::new(b"test"))
+                .is_ok());
+
+            // ... and only verifies for the ciphertext and key it was made for.
+            assert!(proof
+                .verify(&ciphertext, &other_key, &mut Transcript::new(b"test"))
+                .is_err());
+            let (other_ciphertext, _) = Value::from(value)
+                .encrypt(&encryption_key, &mut Transcript::new(b"test"), &mut rng)
+                .unwrap();
+            assert!(proof
+                .verify(&other_ciphertext, &encryption_key, &mut Transcript::new(b"test"))
+                .is_err());
+        }
+
+        #[test]
+        fn encryption_opening_roundtrip(value: u64) {
+            let mut rng = rand::thread_rng();
+            let encryption_key = EncryptionKey(decaf377::basepoint() * decaf377::Fr::rand(&mut rng));
+            let (ciphertext, _, opening) = Value::from(value)
+                .encrypt_with_opening(&encryption_key, &mut Transcript::new(b"test"), &mut rng)
+                .unwrap();
+
+            assert_eq!(opening.value(), value);
+            assert!(opening.verify(&ciphertext, &encryption_key).is_ok());
+        }
     }
 }
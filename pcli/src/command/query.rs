@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use penumbra_proto::client::v1alpha1::KeyValueResponse;
 
 mod shielded_pool;
 use shielded_pool::ShieldedPool;
@@ -21,6 +22,13 @@ pub enum QueryCmd {
     Key {
         /// The key to query.
         key: String,
+        /// The height at which to query the key [default: latest].
+        #[clap(long)]
+        height: Option<u64>,
+        /// Verify a proof of the value (or of its absence) against the app hash
+        /// in the block header fetched from Tendermint.
+        #[clap(long)]
+        prove: bool,
     },
     /// Queries shielded pool data.
     #[clap(subcommand)]
@@ -73,12 +81,18 @@ impl QueryCmd {
                 unreachable!("query handled in guard");
             }
             QueryCmd::ShieldedPool(p) => p.key().as_bytes().to_vec(),
-            QueryCmd::Key { key } => key.as_bytes().to_vec(),
+            QueryCmd::Key { key, .. } => key.as_bytes().to_vec(),
+        };
+        let (height, prove) = match self {
+            QueryCmd::Key { height, prove, .. } => (height.unwrap_or_default(), *prove),
+            _ => (0, false),
         };
 
         let mut client = app.specific_client().await?;
         let req = penumbra_proto::client::v1alpha1::KeyValueRequest {
-            key,
+            key: key.clone(),
+            proof: prove,
+            height,
             ..Default::default()
         };
 
@@ -86,6 +100,19 @@ impl QueryCmd {
 
         let rsp = client.key_value(req).await?.into_inner();
 
+        if prove {
+            verify_key_value(app, &key, &rsp).await?;
+            println!(
+                "verified {} of key at height {}",
+                if rsp.exists {
+                    "inclusion"
+                } else {
+                    "non-inclusion"
+                },
+                rsp.height
+            );
+        }
+
         self.display_value(&rsp.value)?;
         Ok(())
     }
@@ -108,3 +135,41 @@ impl QueryCmd {
         Ok(())
     }
 }
+
+/// Checks the JMT proof in a [`KeyValueResponse`] against the app hash committed
+/// to by the block header fetched from Tendermint.
+async fn verify_key_value(app: &App, key: &[u8], rsp: &KeyValueResponse) -> Result<()> {
+    use tendermint_rpc::{Client, HttpClient};
+
+    let proof: jmt::proof::SparseMerkleProof = bincode::deserialize(&rsp.sparse_merkle_proof)
+        .context("could not decode sparse merkle proof")?;
+    let jmt_root = jmt::RootHash(
+        rsp.jmt_root
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow!("jmt root must be 32 bytes"))?,
+    );
+
+    // The app hash resulting from executing the block at `height` is only
+    // committed to by the header of the following block.
+    let header_height = tendermint::block::Height::try_from(rsp.height + 1)?;
+
+    // generic bounds on HttpClient::new are not well-constructed, so we have to
+    // render the URL as a String, then borrow it, then re-parse the borrowed &str
+    let client = HttpClient::new(app.tendermint_url.to_string().as_ref()).unwrap();
+    let commit = client.commit(header_height).await.with_context(|| {
+        format!(
+            "could not fetch header at height {}; if the value was read from the latest height, retry once the next block is committed",
+            header_height
+        )
+    })?;
+    let app_hash = commit.signed_header.header.app_hash.value();
+
+    let value = if rsp.exists {
+        Some(rsp.value.as_slice())
+    } else {
+        None
+    };
+    penumbra_storage::verify_sparse_proof(&app_hash, jmt_root, key, value, &proof)
+        .context("proof verification failed")
+}
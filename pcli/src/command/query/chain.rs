@@ -74,6 +74,22 @@ impl ChainCmd {
                 "Missed Blocks Max",
                 &format!("{}", params.missed_blocks_maximum),
             ])
+            .add_row(vec![
+                "Max Commission Change (bps)",
+                &format!("{}", params.max_commission_change_bps),
+            ])
+            .add_row(vec![
+                "Commission Change Delay (epochs)",
+                &format!("{}", params.commission_change_delay_epochs),
+            ])
+            .add_row(vec![
+                "Minimum Jail Duration (epochs)",
+                &format!("{}", params.minimum_jail_epochs),
+            ])
+            .add_row(vec![
+                "Max Validator Power Share (bps)",
+                &format!("{}", params.max_validator_power_share_bps),
+            ])
             .add_row(vec!["IBC Enabled", &format!("{}", params.ibc_enabled)])
             .add_row(vec![
                 "Inbound ICS-20 Enabled",
@@ -1,18 +1,23 @@
 use anyhow::{Context, Result};
 use comfy_table::{presets, Table};
-use penumbra_crypto::dex::{lp::Reserves, BatchSwapOutputData, TradingPair};
-use penumbra_proto::client::v1alpha1::{BatchSwapOutputDataRequest, StubCpmmReservesRequest};
+use futures::TryStreamExt;
+use penumbra_crypto::{
+    asset::{self, Amount},
+    dex::{
+        lp::position::{self, Metadata},
+        BatchSwapOutputData, TradingPair,
+    },
+};
+use penumbra_proto::client::v1alpha1::{
+    BatchSwapOutputDataRequest, LiquidityDepthRequest, LiquidityPositionByIdRequest,
+    LiquidityPositionsRequest,
+};
 use penumbra_view::ViewClient;
 
 use crate::App;
 
 #[derive(Debug, clap::Subcommand)]
 pub enum DexCmd {
-    /// Display information about constant-pair market maker reserves.
-    CPMMReserves {
-        /// The trading pair to query for CPMM Reserves.
-        trading_pair: TradingPair,
-    },
     /// Display information about a specific trading pair & height's batch swap.
     BatchOutputs {
         /// The height to query for batch outputs.
@@ -21,90 +26,167 @@ pub enum DexCmd {
         /// The trading pair to query for batch outputs.
         trading_pair: TradingPair,
     },
+    /// Display the open liquidity positions on a trading pair.
+    Positions {
+        /// The trading pair to list positions for, e.g. `gm:gn`.
+        trading_pair: TradingPair,
+    },
+    /// Display the state and reserves of a liquidity position.
+    Position {
+        /// The ID of the position.
+        id: position::Id,
+    },
+    /// Display the liquidity available at each price on a trading pair.
+    Depth {
+        /// The trading pair to display the depth of, e.g. `gm:gn`.
+        trading_pair: TradingPair,
+    },
 }
 
 impl DexCmd {
-    pub async fn print_cpmm_reserves(
+    pub async fn get_batch_outputs(
         &self,
         app: &mut App,
+        height: &u64,
         trading_pair: &TradingPair,
-    ) -> Result<()> {
+    ) -> Result<BatchSwapOutputData> {
         let mut client = app.specific_client().await?;
-        let reserves_data: Reserves = client
-            .stub_cpmm_reserves(StubCpmmReservesRequest {
+        client
+            .batch_swap_output_data(BatchSwapOutputDataRequest {
+                height: *height,
                 trading_pair: Some((*trading_pair).into()),
             })
             .await?
             .into_inner()
             .try_into()
-            .context("cannot parse stub CPMM reserves data")?;
-        println!("Constant-Product Market Maker Reserves:");
-        let mut table = Table::new();
-        let view_client: &mut dyn ViewClient = &mut app.view;
-        let asset_cache = view_client.assets().await?;
-        let asset_1 = asset_cache
-            .get(&trading_pair.asset_1())
-            .map(|base_denom| {
-                let display_denom = base_denom.best_unit_for(reserves_data.r1);
-                (
-                    format!("{}", display_denom),
-                    display_denom.format_value(reserves_data.r1),
-                )
-            })
-            .unwrap_or_else(|| {
-                (
-                    format!("{}", trading_pair.asset_1()),
-                    reserves_data.r1.to_string(),
-                )
-            });
-        let asset_2 = asset_cache
-            .get(&trading_pair.asset_2())
-            .map(|base_denom| {
-                let display_denom = base_denom.best_unit_for(reserves_data.r2);
-                (
-                    format!("{}", display_denom),
-                    display_denom.format_value(reserves_data.r2),
-                )
-            })
-            .unwrap_or_else(|| {
-                (
-                    format!("{}", trading_pair.asset_2()),
-                    reserves_data.r2.to_string(),
-                )
-            });
-        table.load_preset(presets::NOTHING);
-        table
-            .set_header(vec!["Denomination", "Reserve Amount"])
-            .add_row(vec![asset_1.0, asset_1.1])
-            .add_row(vec![asset_2.0, asset_2.1]);
-
-        println!("{}", table);
-
-        Ok(())
+            .context("cannot parse batch swap output data")
     }
 
-    pub async fn get_batch_outputs(
+    pub async fn get_positions(
         &self,
         app: &mut App,
-        height: &u64,
         trading_pair: &TradingPair,
-    ) -> Result<BatchSwapOutputData> {
+    ) -> Result<Vec<Metadata>> {
+        let chain_id = app.view().chain_params().await?.chain_id;
         let mut client = app.specific_client().await?;
         client
-            .batch_swap_output_data(BatchSwapOutputDataRequest {
-                height: *height,
+            .liquidity_positions(LiquidityPositionsRequest {
+                chain_id,
                 trading_pair: Some((*trading_pair).into()),
             })
             .await?
             .into_inner()
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|metadata| metadata.try_into().context("cannot parse position"))
+            .collect()
+    }
+
+    pub async fn get_position(&self, app: &mut App, id: &position::Id) -> Result<Metadata> {
+        let chain_id = app.view().chain_params().await?.chain_id;
+        let mut client = app.specific_client().await?;
+        client
+            .liquidity_position_by_id(LiquidityPositionByIdRequest {
+                chain_id,
+                position_id: Some((*id).into()),
+            })
+            .await?
+            .into_inner()
             .try_into()
-            .context("cannot parse batch swap output data")
+            .context("cannot parse position")
+    }
+
+    /// Renders the given positions as a table.
+    fn position_table(asset_cache: &asset::Cache, positions: &[Metadata]) -> Table {
+        let mut table = Table::new();
+        table.load_preset(presets::NOTHING);
+        table.set_header(vec![
+            "ID",
+            "State",
+            "Reserves",
+            "Price (1 to 2)",
+            "Fee (bps)",
+        ]);
+        for metadata in positions {
+            let pair = metadata.position.pair;
+            table.add_row(vec![
+                format!("{}", metadata.position.id()),
+                format!("{}", metadata.state),
+                format!(
+                    "{}, {}",
+                    format_amount(asset_cache, pair.asset_1(), metadata.reserves.r1),
+                    format_amount(asset_cache, pair.asset_2(), metadata.reserves.r2),
+                ),
+                format!("{}", metadata.position.phi.effective_price_1_to_2()),
+                format!("{}", metadata.position.phi.fee),
+            ]);
+        }
+        table
     }
 
     pub async fn exec(&self, app: &mut App) -> Result<()> {
         match self {
-            DexCmd::CPMMReserves { trading_pair } => {
-                self.print_cpmm_reserves(app, trading_pair).await?;
+            DexCmd::Positions { trading_pair } => {
+                let positions = self.get_positions(app, trading_pair).await?;
+                let view_client: &mut dyn ViewClient = &mut app.view;
+                let asset_cache = view_client.assets().await?;
+
+                println!("{}", Self::position_table(&asset_cache, &positions));
+            }
+            DexCmd::Position { id } => {
+                let position = self.get_position(app, id).await?;
+                let view_client: &mut dyn ViewClient = &mut app.view;
+                let asset_cache = view_client.assets().await?;
+
+                println!("{}", Self::position_table(&asset_cache, &[position]));
+            }
+            DexCmd::Depth { trading_pair } => {
+                let chain_id = app.view().chain_params().await?.chain_id;
+                let mut client = app.specific_client().await?;
+                let depth = client
+                    .liquidity_depth(LiquidityDepthRequest {
+                        chain_id,
+                        trading_pair: Some((*trading_pair).into()),
+                    })
+                    .await?
+                    .into_inner();
+
+                let view_client: &mut dyn ViewClient = &mut app.view;
+                let asset_cache = view_client.assets().await?;
+
+                for (levels, input, output) in [
+                    (
+                        depth.levels_1_to_2,
+                        trading_pair.asset_1(),
+                        trading_pair.asset_2(),
+                    ),
+                    (
+                        depth.levels_2_to_1,
+                        trading_pair.asset_2(),
+                        trading_pair.asset_1(),
+                    ),
+                ] {
+                    println!(
+                        "Liquidity for {} to {}:",
+                        format_asset(&asset_cache, input),
+                        format_asset(&asset_cache, output)
+                    );
+                    let mut table = Table::new();
+                    table.load_preset(presets::NOTHING);
+                    table.set_header(vec!["Price", "Reserves"]);
+                    for level in levels {
+                        let reserves: Amount = level
+                            .reserves
+                            .ok_or_else(|| anyhow::anyhow!("missing reserves"))?
+                            .try_into()?;
+                        table.add_row(vec![
+                            format!("{}", level.price),
+                            format_amount(&asset_cache, output, reserves),
+                        ]);
+                    }
+                    println!("{}", table);
+                }
             }
             DexCmd::BatchOutputs {
                 height,
@@ -175,3 +257,22 @@ impl DexCmd {
         Ok(())
     }
 }
+
+/// Formats an amount of the given asset in its best display unit, if known.
+fn format_amount(asset_cache: &asset::Cache, asset_id: asset::Id, amount: Amount) -> String {
+    match asset_cache.get(&asset_id) {
+        Some(base_denom) => {
+            let display_denom = base_denom.best_unit_for(amount);
+            format!("{}{}", display_denom.format_value(amount), display_denom)
+        }
+        None => format!("{}{}", amount, asset_id),
+    }
+}
+
+/// Formats the name of the given asset, if known.
+fn format_asset(asset_cache: &asset::Cache, asset_id: asset::Id) -> String {
+    match asset_cache.get(&asset_id) {
+        Some(base_denom) => base_denom.default_unit().to_string(),
+        None => asset_id.to_string(),
+    }
+}
@@ -12,8 +12,14 @@ use penumbra_component::{
     },
     stake::validator,
 };
-use penumbra_crypto::IdentityKey;
-use penumbra_proto::client::v1alpha1::MutableParametersRequest;
+use penumbra_crypto::{
+    asset::{self, Amount},
+    IdentityKey,
+};
+use penumbra_proto::{
+    client::v1alpha1::{KeyValueRequest, MutableParametersRequest},
+    Message, Protobuf,
+};
 use penumbra_transaction::action::{Proposal, ProposalPayload, Vote};
 use penumbra_view::ViewClient;
 use serde::Serialize;
@@ -39,6 +45,12 @@ pub enum GovernanceCmd {
     },
     /// Query for the governance-modifiable chain parameters.
     Parameters,
+    /// Query for the balance of the DAO treasury in a particular asset.
+    DaoBalance {
+        /// The denomination of the asset to query.
+        #[clap(default_value = "upenumbra")]
+        denom: String,
+    },
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -76,14 +88,64 @@ impl GovernanceCmd {
 
                 json(&params)?;
             }
+            GovernanceCmd::DaoBalance { denom } => {
+                let denom = asset::REGISTRY
+                    .parse_denom(denom)
+                    .ok_or_else(|| anyhow::anyhow!("invalid denomination: {}", denom))?;
+
+                let rsp = client
+                    .key_value(KeyValueRequest {
+                        key: dao_balance(&denom.id()).into_bytes(),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                // The DAO has never held this asset if there is no balance recorded for it
+                let balance = if rsp.exists {
+                    Amount::decode(rsp.value.as_slice())?
+                } else {
+                    Amount::zero()
+                };
+
+                json(&json!({
+                    "denom": denom.to_string(),
+                    "balance": balance.inner,
+                }))?;
+            }
             GovernanceCmd::ListProposals { inactive } => {
                 let proposal_id_list: Vec<u64> = if *inactive {
-                    let latest: u64 = client.key_proto(latest_proposal_id()).await?;
-                    (0..=latest).collect()
+                    let rsp = client
+                        .key_value(KeyValueRequest {
+                            key: latest_proposal_id().as_bytes().to_vec(),
+                            ..Default::default()
+                        })
+                        .await?
+                        .into_inner();
+
+                    // No proposal has ever been submitted if there is no latest proposal id
+                    if rsp.exists {
+                        let latest = u64::decode(rsp.value.as_slice())?;
+                        (0..=latest).collect()
+                    } else {
+                        Vec::new()
+                    }
                 } else {
-                    let unfinished: ProposalList =
-                        client.key_domain(unfinished_proposals()).await?;
-                    unfinished.proposals.into_iter().collect()
+                    let rsp = client
+                        .key_value(KeyValueRequest {
+                            key: unfinished_proposals().as_bytes().to_vec(),
+                            ..Default::default()
+                        })
+                        .await?
+                        .into_inner();
+
+                    // There are no unfinished proposals if no proposal has ever been submitted
+                    if rsp.exists {
+                        let unfinished = ProposalList::decode(rsp.value.as_slice())?;
+                        unfinished.proposals.into_iter().collect()
+                    } else {
+                        Vec::new()
+                    }
                 };
 
                 let mut writer = stdout();
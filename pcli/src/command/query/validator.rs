@@ -3,9 +3,20 @@ use std::{fs::File, io::Write};
 use anyhow::{Context, Result};
 use comfy_table::{presets, Table};
 use futures::TryStreamExt;
-use penumbra_component::stake::validator;
+use penumbra_component::stake::{
+    state_key::{jail_record_by_validator, pending_funding_streams_by_validator},
+    validator::{self, Validator},
+    JailRecord, PendingFundingStreams, SlashingHistory,
+};
 use penumbra_crypto::IdentityKey;
-use penumbra_proto::client::v1alpha1::ValidatorInfoRequest;
+use penumbra_proto::{
+    client::v1alpha1::{
+        specific_query_client::SpecificQueryClient, KeyValueRequest, SlashingHistoryRequest,
+        ValidatorInfoRequest, ValidatorUptimeRequest,
+    },
+    Protobuf,
+};
+use tonic::transport::Channel;
 
 use crate::App;
 
@@ -29,6 +40,16 @@ pub enum ValidatorCmd {
         /// The identity key of the validator to fetch.
         identity_key: String,
     },
+    /// Show the slashing penalties applied to a particular validator.
+    SlashingHistory {
+        /// The identity key of the validator to fetch.
+        identity_key: String,
+    },
+    /// Show the blocks a particular validator missed within the signed blocks window.
+    Uptime {
+        /// The identity key of the validator to fetch.
+        identity_key: String,
+    },
 }
 
 impl ValidatorCmd {
@@ -39,6 +60,7 @@ impl ValidatorCmd {
                 detailed,
             } => {
                 let mut client = app.oblivious_client().await?;
+                let mut specific_client = app.specific_client().await?;
 
                 let mut validators = client
                     .validator_info(ValidatorInfoRequest {
@@ -86,7 +108,9 @@ impl ValidatorCmd {
                     "Voting Power",
                     "Share",
                     "Commission",
+                    "Pending Commission",
                     "State",
+                    "Jail History",
                     "Bonding State",
                     "Validator Info",
                 ]);
@@ -107,12 +131,36 @@ impl ValidatorCmd {
                         .iter()
                         .map(|fs| fs.rate_bps)
                         .sum::<u16>();
+                    let pending_commission =
+                        pending_funding_streams(&mut specific_client, &v.validator)
+                            .await?
+                            .map(|pending| {
+                                format!(
+                                    "{}bps from epoch {}",
+                                    pending.funding_streams.total_rate_bps(),
+                                    pending.effective_epoch
+                                )
+                            })
+                            .unwrap_or_default();
+                    let jail_record = jail_record(&mut specific_client, &v.validator).await?;
+                    let jail_history = if jail_record.offense_count == 0 {
+                        String::new()
+                    } else {
+                        format!(
+                            "jailed {}x, last in epoch {}, unjail from epoch {}",
+                            jail_record.offense_count,
+                            jail_record.last_jailed_epoch,
+                            jail_record.unjail_epoch
+                        )
+                    };
 
                     table.add_row(vec![
                         format!("{:.3}", voting_power),
                         format!("{:.2}%", power_percent),
                         format!("{}bps", commission_bps),
+                        pending_commission,
                         v.status.state.to_string(),
+                        jail_history,
                         v.status.bonding_state.to_string(),
                         // TODO: consider rewriting this with term colors
                         // at some point, when we get around to it
@@ -124,6 +172,8 @@ impl ValidatorCmd {
                         "".into(),
                         "".into(),
                         "".into(),
+                        "".into(),
+                        "".into(),
                         format!("  \x1b[1;92m{}\x1b[0m", v.validator.name),
                     ]);
                     if *detailed {
@@ -133,6 +183,8 @@ impl ValidatorCmd {
                             "".into(),
                             "".into(),
                             "".into(),
+                            "".into(),
+                            "".into(),
                             format!("  {}", v.validator.description),
                         ]);
                         table.add_row(vec![
@@ -141,6 +193,8 @@ impl ValidatorCmd {
                             "".into(),
                             "".into(),
                             "".into(),
+                            "".into(),
+                            "".into(),
                             format!("  {}", v.validator.website),
                         ]);
                     }
@@ -186,13 +240,22 @@ impl ValidatorCmd {
                     .map(TryInto::try_into)
                     .collect::<Result<Vec<validator::Info>, _>>()?;
 
-                let validator = validators
+                let mut validator = validators
                     .iter()
                     .map(|info| &info.validator)
                     .find(|v| v.identity_key == identity_key)
                     .cloned()
                     .ok_or_else(|| anyhow::anyhow!("Could not find validator {}", identity_key))?;
 
+                // Start from the most recently announced funding streams, so that
+                // re-uploading this definition doesn't cancel a pending change.
+                let mut specific_client = app.specific_client().await?;
+                if let Some(pending) =
+                    pending_funding_streams(&mut specific_client, &validator).await?
+                {
+                    validator.funding_streams = pending.funding_streams;
+                }
+
                 if let Some(file) = file {
                     File::create(file)
                         .with_context(|| format!("cannot create file {:?}", file))?
@@ -202,8 +265,176 @@ impl ValidatorCmd {
                     println!("{}", serde_json::to_string_pretty(&validator)?);
                 }
             }
+            ValidatorCmd::SlashingHistory { identity_key } => {
+                let identity_key = identity_key.parse::<IdentityKey>()?;
+
+                let mut client = app.specific_client().await?;
+                let history: SlashingHistory = client
+                    .slashing_history(SlashingHistoryRequest {
+                        identity_key: Some(identity_key.into()),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner()
+                    .try_into()?;
+
+                if history.events.is_empty() {
+                    println!("Validator {} has never been slashed.", identity_key);
+                    return Ok(());
+                }
+
+                let mut table = Table::new();
+                table.load_preset(presets::NOTHING);
+                table.set_header(vec![
+                    "Height",
+                    "Epoch",
+                    "Reason",
+                    "Infraction Height",
+                    "Penalty",
+                    "Exchange Rate",
+                ]);
+
+                for event in history.events {
+                    table.add_row(vec![
+                        event.height.to_string(),
+                        event.epoch_index.to_string(),
+                        event.reason.to_string(),
+                        event
+                            .infraction_height
+                            .map(|height| height.to_string())
+                            .unwrap_or_default(),
+                        format!("{}bps", event.penalty_bps),
+                        // Exchange rates are expressed in units of 1e-8.
+                        format!(
+                            "{:.8} -> {:.8}",
+                            event.exchange_rate_before as f64 * 1e-8,
+                            event.exchange_rate_after as f64 * 1e-8,
+                        ),
+                    ]);
+                }
+
+                println!("{}", table);
+            }
+            ValidatorCmd::Uptime { identity_key } => {
+                let identity_key = identity_key.parse::<IdentityKey>()?;
+
+                let mut client = app.specific_client().await?;
+                let uptime = client
+                    .validator_uptime(ValidatorUptimeRequest {
+                        identity_key: Some(identity_key.into()),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                let missed = uptime.missed_blocks.len() as u64;
+
+                let mut table = Table::new();
+                table.load_preset(presets::NOTHING);
+                table.add_row(vec![
+                    "As Of Height".to_string(),
+                    uptime.as_of_block_height.to_string(),
+                ]);
+                table.add_row(vec![
+                    "Signed Blocks Window".to_string(),
+                    uptime.window_len.to_string(),
+                ]);
+                table.add_row(vec![
+                    "Missed Blocks".to_string(),
+                    format!("{} / {}", missed, uptime.missed_blocks_maximum),
+                ]);
+                table.add_row(vec![
+                    "Missed Heights".to_string(),
+                    format_height_ranges(&uptime.missed_blocks),
+                ]);
+                println!("{}", table);
+
+                // Warn once the validator has used up 90% of its allowance.
+                if missed > 0 && missed * 10 >= uptime.missed_blocks_maximum * 9 {
+                    println!(
+                        "Warning: validator {} is close to being jailed for downtime.",
+                        identity_key
+                    );
+                }
+            }
         }
 
         Ok(())
     }
 }
+
+/// Format a sorted list of heights as comma-separated ranges, e.g. `3-5, 9`.
+fn format_height_ranges(heights: &[u64]) -> String {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for &height in heights {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == height => *end = height,
+            _ => ranges.push((height, height)),
+        }
+    }
+
+    if ranges.is_empty() {
+        return "none".to_string();
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Fetch the funding streams announced by a validator, if they differ from its current ones.
+async fn pending_funding_streams(
+    client: &mut SpecificQueryClient<Channel>,
+    validator: &Validator,
+) -> Result<Option<PendingFundingStreams>> {
+    let value = client
+        .key_value(KeyValueRequest {
+            key: pending_funding_streams_by_validator(&validator.identity_key).into_bytes(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .value;
+
+    // The validator has never announced new funding streams.
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let pending = PendingFundingStreams::decode(value.as_slice())?;
+    if pending.funding_streams == validator.funding_streams {
+        Ok(None)
+    } else {
+        Ok(Some(pending))
+    }
+}
+
+/// Fetch a validator's record of being jailed for downtime.
+async fn jail_record(
+    client: &mut SpecificQueryClient<Channel>,
+    validator: &Validator,
+) -> Result<JailRecord> {
+    let value = client
+        .key_value(KeyValueRequest {
+            key: jail_record_by_validator(&validator.identity_key).into_bytes(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .value;
+
+    // The validator has never been jailed.
+    if value.is_empty() {
+        return Ok(JailRecord::default());
+    }
+
+    JailRecord::decode(value.as_slice())
+}
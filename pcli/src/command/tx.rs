@@ -3,21 +3,31 @@ use std::{fs::File, io::Write};
 use anyhow::{anyhow, Context, Result};
 use penumbra_component::stake::rate::RateData;
 use penumbra_crypto::{
-    asset, dex::BatchSwapOutputData, transaction::Fee, Address, DelegationToken, IdentityKey,
-    Value, STAKING_TOKEN_ASSET_ID,
+    asset,
+    dex::{
+        lp::{
+            position::{self, Position},
+            Reserves, TradingFunction,
+        },
+        BatchSwapOutputData, TradingPair,
+    },
+    transaction::Fee,
+    Address, DelegationToken, FlowEncryptionCommittee, IdentityKey, Value, STAKING_TOKEN_ASSET_ID,
 };
 use penumbra_proto::{
-    client::v1alpha1::{BatchSwapOutputDataRequest, KeyValueRequest},
-    Protobuf,
+    client::v1alpha1::{BatchSwapOutputDataRequest, KeyValueRequest, SimulateTradeRequest},
+    Message, Protobuf,
 };
 use penumbra_transaction::action::Proposal;
 use penumbra_view::ViewClient;
 use penumbra_wallet::plan;
-use rand_core::OsRng;
+use rand_core::{OsRng, RngCore};
 
 use crate::App;
 
+mod position;
 mod proposal;
+use position::PositionCmd;
 use proposal::ProposalCmd;
 
 #[derive(Debug, clap::Subcommand)]
@@ -76,7 +86,7 @@ pub enum TxCmd {
         /// The identity key of the validator to delegate to.
         #[clap(long)]
         to: String,
-        /// The amount of stake to delegate.
+        /// The amount of delegation tokens to redelegate.
         amount: String,
         /// The transaction fee (paid in upenumbra).
         #[clap(long, default_value = "0")]
@@ -100,6 +110,14 @@ pub enum TxCmd {
         /// The denomination to swap the input into.
         #[clap(long)]
         into: String,
+        /// Optional. The minimum output to accept, written as a typed value 1.87penumbra, 12cubes, etc.
+        ///
+        /// If the batch clears at a price that would produce less than this
+        /// amount, the swap is not filled and the input is refunded instead.
+        /// This is enforced through a limit price, which is public, and is
+        /// rounded up to a power of two.
+        #[clap(long)]
+        min_output: Option<String>,
         /// The transaction fee (paid in upenumbra).
         ///
         /// A swap generates two transactions; the fee will be split equally over both.
@@ -108,7 +126,14 @@ pub enum TxCmd {
         /// Optional. Only spend funds originally received by the given address index.
         #[clap(long)]
         source: Option<u64>,
+        /// Only simulate the swap against the current liquidity and print the
+        /// expected output, without submitting any transactions.
+        #[clap(long)]
+        dry_run: bool,
     },
+    /// Open, close, or withdraw a liquidity position on the DEX.
+    #[clap(display_order = 350, subcommand)]
+    Position(PositionCmd),
     /// Submit or withdraw a governance proposal.
     #[clap(display_order = 400, subcommand)]
     Proposal(ProposalCmd),
@@ -134,6 +159,7 @@ impl TxCmd {
             TxCmd::Delegate { .. } => true,
             TxCmd::Undelegate { .. } => true,
             TxCmd::Redelegate { .. } => true,
+            TxCmd::Position(position_cmd) => position_cmd.needs_sync(),
             TxCmd::Proposal(proposal_cmd) => proposal_cmd.needs_sync(),
         }
     }
@@ -191,26 +217,128 @@ impl TxCmd {
             TxCmd::Swap {
                 input,
                 into,
+                min_output,
                 fee,
                 source,
+                dry_run,
             } => {
                 let input = input.parse::<Value>()?;
                 let into = asset::REGISTRY.parse_unit(into.as_str()).base();
+                let min_output = match min_output {
+                    Some(min_output) => {
+                        let min_output = min_output.parse::<Value>()?;
+                        if min_output.asset_id != into.id() {
+                            return Err(anyhow::anyhow!(
+                                "minimum output must be denominated in {}",
+                                into
+                            ));
+                        }
+                        min_output.amount
+                    }
+                    None => 0u64.into(),
+                };
+
+                if *dry_run {
+                    let trading_pair =
+                        TradingPair::canonical_order_for((input.asset_id, into.id()))?;
+                    let (delta_1, delta_2) = if trading_pair.asset_1() == input.asset_id {
+                        (input.amount.into(), 0)
+                    } else {
+                        (0, input.amount.into())
+                    };
+
+                    let chain_id = app.view().chain_params().await?.chain_id;
+                    let mut client = app.specific_client().await?;
+                    let simulation = client
+                        .simulate_trade(SimulateTradeRequest {
+                            chain_id,
+                            trading_pair: Some(trading_pair.into()),
+                            delta_1,
+                            delta_2,
+                        })
+                        .await?
+                        .into_inner();
+                    let output_data: BatchSwapOutputData = simulation
+                        .output
+                        .ok_or_else(|| anyhow!("missing simulated output data"))?
+                        .try_into()
+                        .context("cannot parse batch swap output data")?;
+                    let (output, price) = if trading_pair.asset_1() == input.asset_id {
+                        (output_data.lambda_2, simulation.price_1_to_2)
+                    } else {
+                        (output_data.lambda_1, simulation.price_2_to_1)
+                    };
+
+                    let view_client: &mut dyn ViewClient = &mut app.view;
+                    let asset_cache = view_client.assets().await?;
+
+                    if !output_data.success {
+                        println!(
+                            "Swap would be unsuccessful: there is not enough liquidity to fill {}",
+                            input.format(&asset_cache)
+                        );
+                    } else {
+                        println!(
+                            "Swapping {} would output {}, at an effective price of {} {} per {}",
+                            input.format(&asset_cache),
+                            Value {
+                                amount: output.into(),
+                                asset_id: into.id(),
+                            }
+                            .format(&asset_cache),
+                            price,
+                            into,
+                            asset_cache
+                                .get(&input.asset_id)
+                                .map(|denom| denom.to_string())
+                                .unwrap_or_else(|| input.asset_id.to_string()),
+                        );
+                        if output < min_output.into() {
+                            println!(
+                                "This is below your minimum output, so the swap would be unfilled"
+                            );
+                        }
+                    }
+                    println!(
+                        "Other swaps in the same batch, and changes to liquidity before the batch executes, may change the outcome."
+                    );
+                    return Ok(());
+                }
 
                 // Since the swap command consists of two transactions (the swap and the swap claim),
                 // the fee is split equally over both for now.
                 let swap_fee = fee / 2;
                 let swap_claim_fee = fee / 2;
 
+                // The swap amounts are encrypted to the flow encryption
+                // committee, so fetch its key from the chain.
+                let chain_id = app.view().chain_params().await?.chain_id;
+                let mut client = app.specific_client().await?;
+                let committee = FlowEncryptionCommittee::decode(
+                    &client
+                        .key_value(KeyValueRequest {
+                            chain_id,
+                            key: penumbra_component::dex::state_key::flow_encryption_committee()
+                                .into(),
+                            proof: false,
+                        })
+                        .await?
+                        .into_inner()
+                        .value[..],
+                )
+                .context("could not fetch the flow encryption committee from the chain")?;
+
                 let swap_plan = plan::swap(
                     &app.fvk,
                     &mut app.view,
                     OsRng,
                     input,
                     into,
+                    min_output,
                     Fee::from_staking_token_amount(swap_fee.into()),
                     Fee::from_staking_token_amount(swap_claim_fee.into()),
                     *source,
+                    committee.encryption_key,
                 )
                 .await?;
                 let swap_plan_inner = swap_plan
@@ -244,31 +372,57 @@ impl TxCmd {
                 // This batch swap output data comes from the client, it's necessary because
                 // the client has to encrypt the SwapPlaintext, however the validators *must*
                 // validate that the BatchSwapOutputData is correct when processing the SwapClaim!
+                //
+                // The batch is only executed once the flow encryption committee has
+                // decrypted it, a few blocks after the swap, so wait for it to be available.
                 let mut client = app.specific_client().await?;
-                let output_data: BatchSwapOutputData = client
-                    .batch_swap_output_data(BatchSwapOutputDataRequest {
-                        height: swap_nft_record.height_created,
-                        trading_pair: Some(swap_plaintext.trading_pair.into()),
+                let request = BatchSwapOutputDataRequest {
+                    height: swap_nft_record.height_created,
+                    trading_pair: Some(swap_plaintext.trading_pair.into()),
+                };
+                let output_data: BatchSwapOutputData =
+                    tokio::time::timeout(std::time::Duration::from_secs(60), async {
+                        loop {
+                            match client.batch_swap_output_data(request.clone()).await {
+                                Ok(rsp) => return Ok(rsp.into_inner()),
+                                Err(status) if status.code() == tonic::Code::NotFound => {
+                                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                                }
+                                Err(status) => return Err(anyhow::Error::from(status)),
+                            }
+                        }
                     })
-                    .await?
-                    .into_inner()
+                    .await
+                    .context("timeout waiting for the batch swap to be executed")??
                     .try_into()
                     .context("cannot parse batch swap output data")?;
 
                 let view_client: &mut dyn ViewClient = &mut app.view;
                 let asset_cache = view_client.assets().await?;
 
-                let pro_rata_outputs = output_data.pro_rata_outputs((
+                let inputs = (
                     swap_plaintext.delta_1_i.into(),
                     swap_plaintext.delta_2_i.into(),
-                ));
+                );
+                let pro_rata_outputs =
+                    output_data.pro_rata_outputs(inputs, swap_plaintext.limit_prices);
                 println!("Swap submitted and batch confirmed!");
                 println!(
                     "Swap was: {}",
-                    if output_data.success {
-                        "successful"
-                    } else {
+                    if !output_data.success {
                         "unsuccessful"
+                    } else if !swap_plaintext
+                        .limit_prices
+                        .satisfied_by(&output_data.limit_thresholds)
+                    {
+                        "unfilled, since the clearing price was below your minimum output"
+                    } else if output_data
+                        .refunded_tiers
+                        .contains(&swap_plaintext.limit_prices)
+                    {
+                        "unfilled, since too few swaps shared your limit price to keep their amounts private"
+                    } else {
+                        "successful"
                     }
                 );
                 println!(
@@ -437,8 +591,224 @@ impl TxCmd {
                 let tx = app.build_transaction(undelegate_plan).await?;
                 app.submit_transaction(&tx, None).await?;
             }
-            TxCmd::Redelegate { .. } => {
-                println!("Sorry, this command is not yet implemented");
+            TxCmd::Redelegate {
+                from,
+                to,
+                amount,
+                fee,
+                source,
+            } => {
+                let from = from.parse::<IdentityKey>()?;
+                let to = to.parse::<IdentityKey>()?;
+
+                let (self_address, _dtk) = app
+                    .fvk
+                    .incoming()
+                    .payment_address(source.unwrap_or(0).into());
+
+                let delegation_value = amount.parse::<Value>()?;
+                if delegation_value.asset_id != DelegationToken::new(from).id() {
+                    return Err(anyhow!(
+                        "redelegation amount must be denominated in the delegation token of {}",
+                        from
+                    ));
+                }
+                let fee = Fee::from_staking_token_amount((*fee as u64).into());
+
+                let mut client = app.specific_client().await?;
+                let source_rate_data: RateData = client
+                    .next_validator_rate(tonic::Request::new(from.into()))
+                    .await?
+                    .into_inner()
+                    .try_into()?;
+                let destination_rate_data: RateData = client
+                    .next_validator_rate(tonic::Request::new(to.into()))
+                    .await?
+                    .into_inner()
+                    .try_into()?;
+
+                // As with undelegation, first split the input notes into exact change, so that
+                // the redelegation doesn't quarantine more than it has to.
+                let split_plan = plan::send(
+                    &app.fvk,
+                    &mut app.view,
+                    OsRng,
+                    &[delegation_value],
+                    fee.clone(),
+                    self_address,
+                    *source,
+                    None,
+                )
+                .await?;
+
+                let delegation_note_commitment = split_plan
+                    .output_plans()
+                    .find_map(|output| {
+                        let note = output.output_note();
+                        if note.value() == delegation_value
+                            && app.fvk.incoming().views_address(&output.dest_address)
+                        {
+                            Some(note.commit())
+                        } else {
+                            None
+                        }
+                    })
+                    .expect("there must be an exact output for the amount we are expecting");
+
+                app.build_and_submit_transaction(split_plan).await?;
+
+                let delegation_notes = vec![
+                    app.view
+                        .await_note_by_commitment(app.fvk.hash(), delegation_note_commitment)
+                        .await?,
+                ];
+
+                let redelegate_plan = plan::redelegate(
+                    &app.fvk,
+                    &mut app.view,
+                    OsRng,
+                    source_rate_data,
+                    destination_rate_data,
+                    delegation_notes,
+                    fee,
+                    *source,
+                )
+                .await?;
+
+                // The new delegation tokens are quarantined until the source validator's
+                // unbonding period has passed, so we won't detect them yet: don't await them.
+                let tx = app.build_transaction(redelegate_plan).await?;
+                app.submit_transaction(&tx, None).await?;
+            }
+            TxCmd::Position(PositionCmd::Open {
+                reserves_1,
+                reserves_2,
+                price,
+                spread,
+                fee,
+                source,
+            }) => {
+                let reserves_1 = reserves_1.parse::<Value>()?;
+                let reserves_2 = reserves_2.parse::<Value>()?;
+                let pair =
+                    TradingPair::canonical_order_for((reserves_1.asset_id, reserves_2.asset_id))?;
+
+                if !(*price > 0.0 && price.is_finite() && (0.0..1.0).contains(spread)) {
+                    return Err(anyhow!(
+                        "price must be positive and spread must be in [0, 1)"
+                    ));
+                }
+                // The trading function's prices are integers, so the price is
+                // represented as a ratio with a fixed denominator.
+                const PRICE_DENOMINATOR: f64 = 1_000_000.0;
+                let price: asset::Amount = ((*price * PRICE_DENOMINATOR).round() as u64).into();
+                let denominator: asset::Amount = (PRICE_DENOMINATOR as u64).into();
+
+                // The trading function is written in terms of the canonical
+                // ordering of the assets, so flip the price and reserves if the
+                // assets were provided in the opposite order.
+                let (reserves, p, q) = if pair.asset_1() == reserves_1.asset_id {
+                    (
+                        Reserves {
+                            r1: reserves_1.amount,
+                            r2: reserves_2.amount,
+                        },
+                        price,
+                        denominator,
+                    )
+                } else {
+                    (
+                        Reserves {
+                            r1: reserves_2.amount,
+                            r2: reserves_1.amount,
+                        },
+                        denominator,
+                        price,
+                    )
+                };
+
+                let mut nonce = [0u8; 32];
+                OsRng.fill_bytes(&mut nonce);
+                let position = Position {
+                    pair,
+                    phi: TradingFunction {
+                        fee: (*spread * 10_000.0).round() as u32,
+                        k: 0u64.into(),
+                        p,
+                        q,
+                    },
+                    nonce,
+                };
+                if !position.phi.is_valid() {
+                    return Err(anyhow!("price or spread cannot be represented"));
+                }
+                let position_id = position.id();
+
+                let fee = Fee::from_staking_token_amount((*fee as u64).into());
+                let plan = plan::position_open(
+                    &app.fvk,
+                    &mut app.view,
+                    OsRng,
+                    position,
+                    reserves,
+                    fee,
+                    *source,
+                )
+                .await?;
+                app.build_and_submit_transaction(plan).await?;
+
+                println!("Opened position {}", position_id);
+            }
+            TxCmd::Position(PositionCmd::Close {
+                position_id,
+                fee,
+                source,
+            }) => {
+                let position_id = position_id.parse::<position::Id>()?;
+                let fee = Fee::from_staking_token_amount((*fee as u64).into());
+                let plan =
+                    plan::position_close(&app.fvk, &mut app.view, OsRng, position_id, fee, *source)
+                        .await?;
+                app.build_and_submit_transaction(plan).await?;
+            }
+            TxCmd::Position(PositionCmd::Withdraw {
+                position_id,
+                fee,
+                source,
+            }) => {
+                let position_id = position_id.parse::<position::Id>()?;
+
+                // The withdrawal must commit to the exact final reserves of the
+                // position, so fetch them from the chain.
+                let chain_id = app.view().chain_params().await?.chain_id;
+                let mut client = app.specific_client().await?;
+                let metadata = position::Metadata::decode(
+                    &client
+                        .key_value(KeyValueRequest {
+                            chain_id,
+                            key: penumbra_component::dex::state_key::position_by_id(&position_id)
+                                .into(),
+                            proof: false,
+                        })
+                        .await?
+                        .into_inner()
+                        .value[..],
+                )
+                .context("could not fetch position from the chain")?;
+
+                let fee = Fee::from_staking_token_amount((*fee as u64).into());
+                let plan = plan::position_withdraw(
+                    &app.fvk,
+                    &mut app.view,
+                    OsRng,
+                    position_id,
+                    metadata.position.pair,
+                    metadata.reserves,
+                    fee,
+                    *source,
+                )
+                .await?;
+                app.build_and_submit_transaction(plan).await?;
             }
             TxCmd::Proposal(ProposalCmd::Submit { file, fee, source }) => {
                 let proposal: Proposal = serde_json::from_reader(File::open(&file)?)?;
@@ -504,24 +874,46 @@ impl TxCmd {
                 }
             }
             TxCmd::Proposal(ProposalCmd::Vote {
-                proposal_id: _,
-                vote: _,
-                fee: _,
-                source: _,
+                proposal_id,
+                vote,
+                fee,
+                source,
             }) => {
-                println!("Sorry, delegator voting is not yet implemented");
-                // TODO: fill this in for delegator votes
-                // let plan = plan::delegator_vote(
-                //     &app.fvk,
-                //     &mut app.view,
-                //     OsRng,
-                //     *proposal_id,
-                //     *vote,
-                //     *fee,
-                //     *source,
-                // )
-                // .await?;
-                // app.build_and_submit_transaction(plan).await?;
+                // Download the position in the note commitment tree at which the proposal started,
+                // since only notes created before then can be used to vote
+                let chain_id = app.view().chain_params().await?.chain_id;
+                let mut client = app.specific_client().await?;
+                // TODO: convert this into an actual query method?
+                let start_position = client
+                    .key_value(KeyValueRequest {
+                        chain_id,
+                        key: penumbra_component::governance::state_key::proposal_voting_start_position(
+                            *proposal_id,
+                        ).into(),
+                        proof: false,
+                    })
+                    .await?
+                    .into_inner()
+                    .value;
+                if start_position.is_empty() {
+                    anyhow::bail!("proposal {} does not exist", proposal_id);
+                }
+                let start_position = u64::decode(&start_position[..])?.into();
+
+                let fee = Fee::from_staking_token_amount((*fee as u64).into());
+                let plan = plan::delegator_vote(
+                    &app.fvk,
+                    &mut app.view,
+                    OsRng,
+                    *proposal_id,
+                    start_position,
+                    *vote,
+                    fee,
+                    *source,
+                )
+                .await?;
+
+                app.build_and_submit_transaction(plan).await?;
             }
         }
         Ok(())
@@ -0,0 +1,57 @@
+#[derive(Debug, clap::Subcommand)]
+pub enum PositionCmd {
+    /// Open a new liquidity position, depositing the given reserves.
+    ///
+    /// The position trades the two assets against each other at a fixed
+    /// price, until its reserves of the output asset are exhausted.
+    Open {
+        /// The reserves of the first asset, written as a typed value 100gm, 0gn, etc.
+        reserves_1: String,
+        /// The reserves of the second asset, written as a typed value 100gm, 0gn, etc.
+        reserves_2: String,
+        /// The price of the first asset, in units of the second asset.
+        #[clap(long)]
+        price: f64,
+        /// The fee charged by the position on each trade, as a fraction of the input.
+        #[clap(long, default_value = "0")]
+        spread: f64,
+        /// The transaction fee (paid in upenumbra).
+        #[clap(long, default_value = "0")]
+        fee: u64,
+        /// Optional. Only spend funds originally received by the given address index.
+        #[clap(long)]
+        source: Option<u64>,
+    },
+    /// Close a liquidity position, so that it can no longer be traded against.
+    Close {
+        /// The ID of the position to close.
+        position_id: String,
+        /// The transaction fee (paid in upenumbra).
+        #[clap(long, default_value = "0")]
+        fee: u64,
+        /// Optional. Only spend funds originally received by the given address index.
+        #[clap(long)]
+        source: Option<u64>,
+    },
+    /// Withdraw the reserves of a closed liquidity position.
+    Withdraw {
+        /// The ID of the position to withdraw.
+        position_id: String,
+        /// The transaction fee (paid in upenumbra).
+        #[clap(long, default_value = "0")]
+        fee: u64,
+        /// Optional. Only spend funds originally received by the given address index.
+        #[clap(long)]
+        source: Option<u64>,
+    },
+}
+
+impl PositionCmd {
+    pub fn needs_sync(&self) -> bool {
+        match self {
+            PositionCmd::Open { .. } => true,
+            PositionCmd::Close { .. } => true,
+            PositionCmd::Withdraw { .. } => true,
+        }
+    }
+}
@@ -16,7 +16,7 @@ use tower_abci::BoxError;
 use tracing::error_span;
 
 use super::{Message, Worker};
-use crate::RequestExt;
+use crate::{vote_extension::Extender, RequestExt};
 
 #[derive(Clone)]
 pub struct Consensus {
@@ -24,7 +24,11 @@ pub struct Consensus {
 }
 
 impl Consensus {
-    pub async fn new(storage: Storage) -> anyhow::Result<(Self, watch::Receiver<block::Height>)> {
+    pub async fn new(
+        storage: Storage,
+        extender: Option<Extender>,
+        resume_from_halt: Option<u64>,
+    ) -> anyhow::Result<(Self, watch::Receiver<block::Height>)> {
         let (queue_tx, queue_rx) = mpsc::channel(10);
         let initial_height = match storage.latest_version().await? {
             Some(version) => version.try_into().unwrap(),
@@ -34,7 +38,11 @@ impl Consensus {
 
         tokio::task::Builder::new()
             .name("consensus::Worker")
-            .spawn(Worker::new(storage, queue_rx, height_tx).await?.run())
+            .spawn(
+                Worker::new(storage, queue_rx, height_tx, extender, resume_from_halt)
+                    .await?
+                    .run(),
+            )
             .expect("failed to spawn consensus worker");
 
         Ok((
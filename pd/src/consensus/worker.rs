@@ -3,7 +3,7 @@ use anyhow::{anyhow, Result};
 use penumbra_proto::Protobuf;
 
 use penumbra_chain::genesis;
-use penumbra_component::{Component, Context};
+use penumbra_component::{governance::View as _, Component, Context};
 use penumbra_storage::Storage;
 use penumbra_transaction::Transaction;
 use tendermint::{
@@ -14,29 +14,42 @@ use tokio::sync::{mpsc, watch};
 use tracing::{instrument, Instrument};
 
 use super::Message;
-use crate::App;
+use crate::{vote_extension, vote_extension::Extender, App};
 
 pub struct Worker {
     queue: mpsc::Receiver<Message>,
     height_tx: watch::Sender<block::Height>,
     storage: Storage,
     app: App,
+    /// The storage version the `app` was built on, used to detect state sync restores.
+    version: Option<u64>,
+    extender: Option<Extender>,
+    resume_from_halt: Option<u64>,
 }
 
 impl Worker {
-    #[instrument(skip(storage, queue, height_tx), name = "consensus::Worker::new")]
+    #[instrument(
+        skip(storage, queue, height_tx, extender),
+        name = "consensus::Worker::new"
+    )]
     pub async fn new(
         storage: Storage,
         queue: mpsc::Receiver<Message>,
         height_tx: watch::Sender<block::Height>,
+        extender: Option<Extender>,
+        resume_from_halt: Option<u64>,
     ) -> Result<Self> {
         let app = App::new(storage.clone()).await;
+        let version = storage.latest_version().await?;
 
         Ok(Self {
             queue,
             height_tx,
             storage,
             app,
+            version,
+            extender,
+            resume_from_halt,
         })
     }
 
@@ -57,12 +70,29 @@ impl Worker {
                         .await
                         .expect("init_chain must succeed"),
                 ),
-                Request::BeginBlock(begin_block) => Response::BeginBlock(
-                    self.begin_block(begin_block)
-                        .instrument(span)
-                        .await
-                        .expect("begin_block must succeed"),
-                ),
+                Request::BeginBlock(begin_block) => {
+                    // If the chain was halted by an emergency proposal, refuse to process any
+                    // further blocks, unless the operator has chosen to resume from that halt.
+                    if let Some(proposal_id) = self.halted_by().await? {
+                        if self.resume_from_halt != Some(proposal_id) {
+                            tracing::error!(
+                                proposal = %proposal_id,
+                                "chain halted by emergency proposal, refusing to begin block"
+                            );
+                            return Err(anyhow!(
+                                "chain halted by emergency proposal {}",
+                                proposal_id
+                            ));
+                        }
+                    }
+
+                    Response::BeginBlock(
+                        self.begin_block(begin_block)
+                            .instrument(span)
+                            .await
+                            .expect("begin_block must succeed"),
+                    )
+                }
                 Request::DeliverTx(deliver_tx) => {
                     let ctx = Context::new();
                     let rsp = self
@@ -135,7 +165,8 @@ impl Worker {
         let validators = self.app.tendermint_validator_updates();
 
         // Note: App::commit resets internal components, so we don't need to do that ourselves.
-        let (app_hash, _) = self.app.commit(self.storage.clone()).await?;
+        let (app_hash, version) = self.app.commit(self.storage.clone()).await?;
+        self.version = Some(version);
 
         tracing::info!(
             consensus_params = ?init_chain.consensus_params,
@@ -151,10 +182,27 @@ impl Worker {
         })
     }
 
+    /// Returns the id of the emergency proposal which halted the chain, if it is halted.
+    async fn halted_by(&self) -> Result<Option<u64>> {
+        self.storage.state().await?.halted_by().await
+    }
+
     async fn begin_block(
         &mut self,
         begin_block: abci::request::BeginBlock,
     ) -> Result<abci::response::BeginBlock> {
+        // If the storage was restored from a state sync snapshot after the app
+        // was constructed, rebuild the app on top of the restored state.
+        let latest_version = self.storage.latest_version().await?;
+        if latest_version != self.version {
+            tracing::info!(
+                ?latest_version,
+                "storage was restored from a snapshot, reloading app"
+            );
+            self.app = App::new(self.storage.clone()).await;
+            self.version = latest_version;
+        }
+
         let ctx = Context::new();
         self.app.begin_block(ctx.clone(), &begin_block).await;
         Ok(abci::response::BeginBlock {
@@ -174,6 +222,14 @@ impl Worker {
         ctx: Context,
         deliver_tx: abci::request::DeliverTx,
     ) -> Result<()> {
+        // Decryption shares from the flow encryption committee are delivered
+        // alongside transactions, and checked in the same way.
+        if let Some(shares) = vote_extension::decode(&deliver_tx.tx)? {
+            self.app.check_decryption_shares(&shares).await?;
+            self.app.deliver_decryption_shares(shares).await;
+            return Ok(());
+        }
+
         // Verify the transaction is well-formed...
         let transaction = Transaction::decode(deliver_tx.tx)?;
         // ... and statelessly valid...
@@ -218,7 +274,8 @@ impl Worker {
         // Begin sidecar code
 
         // Note: App::commit resets internal components, so we don't need to do that ourselves.
-        let (jmt_root, _) = self.app.commit(self.storage.clone()).await?;
+        let (jmt_root, version) = self.app.commit(self.storage.clone()).await?;
+        self.version = Some(version);
         let app_hash = jmt_root.0.to_vec();
         let _ = self.height_tx.send(
             self.storage
@@ -231,6 +288,14 @@ impl Worker {
 
         tracing::info!(app_hash = ?hex::encode(&app_hash), "finished block commit");
 
+        // If we're a member of the flow encryption committee, contribute our
+        // decryption shares for the batches awaiting decryption.
+        if let Some(extender) = &mut self.extender {
+            if let Err(e) = extender.extend(&self.storage).await {
+                tracing::warn!(?e, "failed to compute decryption shares");
+            }
+        }
+
         Ok(abci::response::Commit {
             data: app_hash.into(),
             retain_height: 0u32.into(),
@@ -0,0 +1,41 @@
+//! Operator tooling for chains halted by an emergency proposal.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use penumbra_component::governance::View as _;
+use penumbra_storage::Storage;
+
+/// The name of the file in the `pd` home directory recording which halt to resume from.
+const RESUME_FROM_HALT_FILE: &str = "resume_from_halt";
+
+/// Returns the id of the emergency proposal which halted the chain, if it is halted.
+pub async fn halted_by(storage: &Storage) -> Result<Option<u64>> {
+    storage.state().await?.halted_by().await
+}
+
+/// Loads the id of the emergency proposal whose halt the operator has chosen to resume from, if
+/// any.
+pub fn load_resume_from_halt(home: &Path) -> Result<Option<u64>> {
+    let path = home.join(RESUME_FROM_HALT_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let proposal_id = contents
+        .trim()
+        .parse()
+        .with_context(|| format!("could not parse proposal id from {}", path.display()))?;
+
+    Ok(Some(proposal_id))
+}
+
+/// Records that the operator has chosen to resume from the halt caused by the given emergency
+/// proposal.
+pub fn save_resume_from_halt(home: &Path, proposal_id: u64) -> Result<()> {
+    let path = home.join(RESUME_FROM_HALT_FILE);
+    std::fs::write(&path, format!("{}\n", proposal_id))
+        .with_context(|| format!("could not write {}", path.display()))
+}
@@ -25,17 +25,55 @@ const ABCI_INFO_VERSION: &str = env!("VERGEN_GIT_SEMVER");
 pub struct Info {
     storage: Storage,
     height_rx: watch::Receiver<block::Height>,
+    /// The number of past blocks whose state may be queried, or `None` for all of them.
+    query_history_blocks: Option<u64>,
 }
 
 impl Info {
-    pub fn new(storage: Storage, height_rx: watch::Receiver<block::Height>) -> Self {
-        Self { storage, height_rx }
+    pub fn new(
+        storage: Storage,
+        height_rx: watch::Receiver<block::Height>,
+        query_history_blocks: Option<u64>,
+    ) -> Self {
+        Self {
+            storage,
+            height_rx,
+            query_history_blocks,
+        }
     }
 
     async fn state_tonic(&self) -> Result<State, tonic::Status> {
         self.storage.state_tonic().await
     }
 
+    /// Like [`Self::state_tonic`], but pins the state at the given height,
+    /// or the latest height if it is 0.
+    async fn state_at_height_tonic(&self, height: u64) -> Result<State, tonic::Status> {
+        if height == 0 {
+            return self.state_tonic().await;
+        }
+
+        let latest_height = self
+            .storage
+            .latest_version()
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .unwrap_or(0);
+        if let Some(history) = self.query_history_blocks {
+            if height.saturating_add(history) < latest_height {
+                return Err(tonic::Status::out_of_range(format!(
+                    "state at height {} is older than the {} blocks of history retained",
+                    height, history
+                )));
+            }
+        }
+
+        self.storage
+            .state_at(height)
+            .await
+            .map_err(|e| tonic::Status::out_of_range(e.to_string()))
+    }
+
     async fn info(&self, info: abci::request::Info) -> Result<abci::response::Info, anyhow::Error> {
         tracing::info!(?info);
 
@@ -1,20 +1,30 @@
+use std::pin::Pin;
+
+use async_stream::try_stream;
+use futures::{StreamExt, TryStreamExt};
 use penumbra_chain::View as _;
 use penumbra_component::dex::View as _;
 use penumbra_component::shielded_pool::View as _;
 use penumbra_component::stake::View as _;
+use penumbra_crypto::asset::Amount;
 use penumbra_proto::{
     self as proto,
     client::v1alpha1::{
-        specific_query_server::SpecificQuery, BatchSwapOutputDataRequest, KeyValueRequest,
-        KeyValueResponse, StubCpmmReservesRequest, ValidatorStatusRequest,
+        liquidity_depth_response::Level, specific_query_server::SpecificQuery,
+        BatchSwapOutputDataRequest, KeyValueRequest, KeyValueResponse, LiquidityDepthRequest,
+        LiquidityDepthResponse, LiquidityPositionByIdRequest, LiquidityPositionsRequest,
+        SimulateTradeRequest, SimulateTradeResponse, SlashingHistoryRequest,
+        ValidatorStatusRequest, ValidatorUptimeRequest, ValidatorUptimeResponse,
     },
     core::{
         chain::v1alpha1::NoteSource,
         crypto::v1alpha1::NoteCommitment,
-        dex::v1alpha1::{BatchSwapOutputData, Reserves},
-        stake::v1alpha1::ValidatorStatus,
+        dex::v1alpha1::{BatchSwapOutputData, PositionMetadata},
+        stake::v1alpha1::{SlashingHistory, ValidatorStatus},
     },
+    Protobuf,
 };
+use penumbra_storage::get_with_proofs;
 
 use tonic::Status;
 use tracing::instrument;
@@ -29,6 +39,9 @@ use super::Info;
 
 #[tonic::async_trait]
 impl SpecificQuery for Info {
+    type LiquidityPositionsStream =
+        Pin<Box<dyn futures::Stream<Item = Result<PositionMetadata, tonic::Status>> + Send>>;
+
     #[instrument(skip(self, request))]
     async fn transaction_by_note(
         &self,
@@ -100,28 +113,140 @@ impl SpecificQuery for Info {
     }
 
     #[instrument(skip(self, request))]
-    /// Get the batch swap data associated with a given trading pair and height.
-    async fn stub_cpmm_reserves(
+    /// Get the open liquidity positions on a given trading pair.
+    async fn liquidity_positions(
         &self,
-        request: tonic::Request<StubCpmmReservesRequest>,
-    ) -> Result<tonic::Response<Reserves>, Status> {
+        request: tonic::Request<LiquidityPositionsRequest>,
+    ) -> Result<tonic::Response<Self::LiquidityPositionsStream>, Status> {
         let state = self.state_tonic().await?;
-        let request_inner = request.into_inner();
-        let trading_pair = request_inner
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let trading_pair = request
+            .into_inner()
             .trading_pair
             .ok_or_else(|| Status::invalid_argument("missing trading_pair"))?
             .try_into()
             .map_err(|_| Status::invalid_argument("invalid trading_pair"))?;
 
-        let cpmm_reserves = state
-            .stub_cpmm_reserves(&trading_pair)
+        let position_ids = state
+            .positions_by_trading_pair(&trading_pair)
             .await
-            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+            .map_err(|e| Status::unavailable(format!("error listing positions: {}", e)))?;
 
-        match cpmm_reserves {
-            Some(o) => Ok(tonic::Response::new(o.into())),
-            None => Err(Status::not_found("CPMM reserves not found")),
-        }
+        let s = try_stream! {
+            for id in position_ids {
+                let metadata = state
+                    .position_by_id(&id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("indexed position {} not found", id))?;
+                yield metadata.to_proto();
+            }
+        };
+
+        Ok(tonic::Response::new(
+            s.map_err(|e: anyhow::Error| {
+                tonic::Status::unavailable(format!("error getting position: {}", e))
+            })
+            .boxed(),
+        ))
+    }
+
+    #[instrument(skip(self, request))]
+    /// Get the state and reserves of a liquidity position.
+    async fn liquidity_position_by_id(
+        &self,
+        request: tonic::Request<LiquidityPositionByIdRequest>,
+    ) -> Result<tonic::Response<PositionMetadata>, Status> {
+        let state = self.state_tonic().await?;
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let id = request
+            .into_inner()
+            .position_id
+            .ok_or_else(|| Status::invalid_argument("missing position_id"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid position_id"))?;
+
+        let metadata = state
+            .position_by_id(&id)
+            .await
+            .map_err(|e| Status::unavailable(format!("error getting position: {}", e)))?
+            .ok_or_else(|| Status::not_found("position not found"))?;
+
+        Ok(tonic::Response::new(metadata.into()))
+    }
+
+    #[instrument(skip(self, request))]
+    /// Get the aggregate liquidity available at each price on a trading pair.
+    async fn liquidity_depth(
+        &self,
+        request: tonic::Request<LiquidityDepthRequest>,
+    ) -> Result<tonic::Response<LiquidityDepthResponse>, Status> {
+        let state = self.state_tonic().await?;
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let trading_pair = request
+            .into_inner()
+            .trading_pair
+            .ok_or_else(|| Status::invalid_argument("missing trading_pair"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid trading_pair"))?;
+
+        let liquidity = state
+            .liquidity(&trading_pair)
+            .await
+            .map_err(|e| Status::unavailable(format!("error getting liquidity: {}", e)))?;
+
+        let levels = |depth: Vec<(f64, Amount)>| -> Vec<Level> {
+            depth
+                .into_iter()
+                .map(|(price, reserves)| Level {
+                    price,
+                    reserves: Some(reserves.into()),
+                })
+                .collect()
+        };
+
+        Ok(tonic::Response::new(LiquidityDepthResponse {
+            levels_1_to_2: levels(liquidity.depth_1_to_2()),
+            levels_2_to_1: levels(liquidity.depth_2_to_1()),
+        }))
+    }
+
+    #[instrument(skip(self, request))]
+    /// Simulate a batch swap against the current liquidity, without executing it.
+    async fn simulate_trade(
+        &self,
+        request: tonic::Request<SimulateTradeRequest>,
+    ) -> Result<tonic::Response<SimulateTradeResponse>, Status> {
+        let state = self.state_tonic().await?;
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let request = request.into_inner();
+        let trading_pair = request
+            .trading_pair
+            .ok_or_else(|| Status::invalid_argument("missing trading_pair"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid trading_pair"))?;
+
+        let output = state
+            .simulate_batch(trading_pair, (request.delta_1, request.delta_2))
+            .await
+            .map_err(|e| Status::unavailable(format!("error simulating trade: {}", e)))?;
+
+        let price = |lambda: u64, delta: u64| {
+            if output.success && delta > 0 {
+                lambda as f64 / delta as f64
+            } else {
+                0.0
+            }
+        };
+
+        Ok(tonic::Response::new(SimulateTradeResponse {
+            price_1_to_2: price(output.lambda_2, output.delta_1),
+            price_2_to_1: price(output.lambda_1, output.delta_2),
+            output: Some(output.into()),
+        }))
     }
 
     #[instrument(skip(self, request))]
@@ -146,12 +271,90 @@ impl SpecificQuery for Info {
         }
     }
 
+    #[instrument(skip(self, request))]
+    async fn slashing_history(
+        &self,
+        request: tonic::Request<SlashingHistoryRequest>,
+    ) -> Result<tonic::Response<SlashingHistory>, Status> {
+        let state = self.state_tonic().await?;
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let id = request
+            .into_inner()
+            .identity_key
+            .ok_or_else(|| Status::invalid_argument("missing identity key"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid identity key"))?;
+
+        if state
+            .validator(&id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .is_none()
+        {
+            return Err(Status::not_found("validator not found"));
+        }
+
+        let history = state
+            .slashing_history(&id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(tonic::Response::new(history.into()))
+    }
+
+    #[instrument(skip(self, request))]
+    async fn validator_uptime(
+        &self,
+        request: tonic::Request<ValidatorUptimeRequest>,
+    ) -> Result<tonic::Response<ValidatorUptimeResponse>, Status> {
+        let state = self.state_tonic().await?;
+        state.check_chain_id(&request.get_ref().chain_id).await?;
+
+        let id = request
+            .into_inner()
+            .identity_key
+            .ok_or_else(|| Status::invalid_argument("missing identity key"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("invalid identity key"))?;
+
+        let uptime = state
+            .validator_uptime(&id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found("validator uptime not found"))?;
+
+        let missed_blocks_maximum = state
+            .get_chain_params()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .missed_blocks_maximum;
+
+        Ok(tonic::Response::new(ValidatorUptimeResponse {
+            as_of_block_height: uptime.as_of_block_height(),
+            window_len: uptime.window_len() as u64,
+            missed_blocks: uptime.missed_blocks().collect(),
+            missed_blocks_maximum,
+        }))
+    }
+
     #[instrument(skip(self, request))]
     async fn key_value(
         &self,
         request: tonic::Request<KeyValueRequest>,
     ) -> Result<tonic::Response<KeyValueResponse>, Status> {
-        let state = self.state_tonic().await?;
+        // Resolve the height up front, so that the value and any proofs are
+        // all read from the same version even if a block is committed meanwhile.
+        let height = match request.get_ref().height {
+            0 => self
+                .storage
+                .latest_version()
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?
+                .unwrap_or(0),
+            height => height,
+        };
+        let state = self.state_at_height_tonic(height).await?;
         state.check_chain_id(&request.get_ref().chain_id).await?;
 
         let request = request.into_inner();
@@ -161,24 +364,36 @@ impl SpecificQuery for Info {
             return Err(Status::invalid_argument("key is empty"));
         }
 
-        let (value, proof) = state
-            .read()
-            .await
-            .get_with_proof(request.key)
-            .await
-            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let store = jmt::JellyfishMerkleTree::new(&self.storage);
+        let (value, existence_proof, jmt_root, sparse_proof) =
+            get_with_proofs(&store, &request.key, height)
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let exists = value.is_some();
 
-        let commitment_proof = ics23::CommitmentProof {
+        // Absent keys have no ics23 existence proof, only the sparse non-inclusion proof.
+        let commitment_proof = existence_proof.map(|proof| ics23::CommitmentProof {
             proof: Some(ics23::commitment_proof::Proof::Exist(proof)),
+        });
+
+        let (proof, sparse_merkle_proof, jmt_root) = if request.proof {
+            (
+                commitment_proof,
+                bincode::serialize(&sparse_proof)
+                    .map_err(|e| tonic::Status::internal(e.to_string()))?,
+                jmt_root.0.to_vec(),
+            )
+        } else {
+            (None, Vec::new(), Vec::new())
         };
 
         Ok(tonic::Response::new(KeyValueResponse {
-            value,
-            proof: if request.proof {
-                Some(commitment_proof)
-            } else {
-                None
-            },
+            value: value.unwrap_or_default(),
+            proof,
+            height,
+            exists,
+            sparse_merkle_proof,
+            jmt_root,
         }))
     }
 }
@@ -8,10 +8,13 @@ mod consensus;
 mod info;
 mod mempool;
 mod metrics;
+mod pruner;
 mod request_ext;
 mod snapshot;
 
+pub mod halt;
 pub mod testnet;
+pub mod vote_extension;
 
 use request_ext::RequestExt;
 
@@ -20,4 +23,5 @@ pub use consensus::Consensus;
 pub use info::Info;
 pub use mempool::Mempool;
 pub use penumbra_component::app::App;
+pub use pruner::Pruner;
 pub use snapshot::Snapshot;
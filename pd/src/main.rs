@@ -13,9 +13,10 @@ use anyhow::Context;
 use clap::{Parser, Subcommand};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use pd::testnet::{canonicalize_path, generate_tm_config, write_configs, ValidatorKeys};
+use pd::vote_extension::Extender;
 use penumbra_chain::{genesis::Allocation, params::ChainParameters};
 use penumbra_component::stake::{validator::Validator, FundingStream, FundingStreams};
-use penumbra_crypto::{keys::SpendKey, DelegationToken, GovernanceKey};
+use penumbra_crypto::{keys::SpendKey, DelegationToken, FlowEncryptionCommittee, GovernanceKey};
 use penumbra_proto::client::v1alpha1::{
     oblivious_query_server::ObliviousQueryServer, specific_query_server::SpecificQueryServer,
 };
@@ -60,6 +61,40 @@ enum RootCommand {
         /// Bind the metrics endpoint to this port.
         #[clap(short, long, default_value = "9000")]
         metrics_port: u16,
+        /// The URL of the Tendermint RPC endpoint, used to broadcast flow
+        /// decryption shares.
+        #[clap(long, default_value = "http://127.0.0.1:26657")]
+        tendermint_rpc_url: String,
+        /// The number of past blocks whose state may be queried, or all of them if unset.
+        #[clap(long)]
+        query_history_blocks: Option<u64>,
+        /// Take a state sync snapshot every this many blocks, or never if 0.
+        #[clap(long, default_value = "0")]
+        snapshot_interval: u64,
+        /// The number of recent state sync snapshots to keep.
+        #[clap(long, default_value = "2")]
+        snapshot_keep_recent: usize,
+        /// Prune all but this many recent versions of the state, or keep all
+        /// of them if unset.
+        #[clap(long)]
+        pruning_keep_recent: Option<u64>,
+        /// When pruning, also keep the state at every this many blocks, or
+        /// none of it if 0.
+        #[clap(long, default_value = "0")]
+        pruning_keep_every: u64,
+        /// When pruning, prune the state every this many blocks.
+        #[clap(long, default_value = "1000")]
+        pruning_interval: u64,
+    },
+
+    /// Inspect, export, or resume from a chain halted by an emergency proposal.
+    Halt {
+        /// The path used to store pd-releated data, including the Rocks database.
+        #[clap(long)]
+        home: PathBuf,
+
+        #[clap(subcommand)]
+        halt_cmd: HaltCommand,
     },
 
     /// Generate, join, or reset a testnet.
@@ -74,6 +109,25 @@ enum RootCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+enum HaltCommand {
+    /// Display the emergency proposal which halted the chain, and the height of the last block
+    /// before the halt.
+    Status,
+    /// Export a copy of the halted chain state, to keep as a backup before upgrading `pd`.
+    Export {
+        /// The path to export the Rocks database to. Must not exist.
+        #[clap(long)]
+        output: PathBuf,
+    },
+    /// Allow `pd` to resume block production past the halt caused by the given emergency proposal,
+    /// the next time it is started.
+    Resume {
+        /// The id of the emergency proposal which halted the chain.
+        proposal_id: u64,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 enum TestnetCommand {
     /// Generates a directory structure containing necessary files to run atestnet based on input
@@ -165,6 +219,13 @@ async fn main() -> anyhow::Result<()> {
             abci_port,
             grpc_port,
             metrics_port,
+            tendermint_rpc_url,
+            query_history_blocks,
+            snapshot_interval,
+            snapshot_keep_recent,
+            pruning_keep_recent,
+            pruning_keep_every,
+            pruning_interval,
         } => {
             tracing::info!(?host, ?abci_port, ?grpc_port, "starting pd");
 
@@ -175,10 +236,63 @@ async fn main() -> anyhow::Result<()> {
                 .await
                 .context("Unable to initialize RocksDB storage")?;
 
-            let (consensus, height_rx) = pd::Consensus::new(storage.clone()).await?;
+            // If this node is a member of the flow encryption committee, it
+            // contributes decryption shares for batch swaps.
+            let extender =
+                Extender::load_key_share(&home.join("flow_key_share.json"))?.map(|key_share| {
+                    tracing::info!(
+                        participant_index = key_share.participant_index(),
+                        "loaded flow key share"
+                    );
+                    Extender::new(key_share, tendermint_rpc_url)
+                });
+
+            // If the operator has chosen to resume from a chain halt, allow blocks to be processed
+            // past the halt caused by that emergency proposal.
+            let resume_from_halt = pd::halt::load_resume_from_halt(&home)?;
+            if let Some(proposal_id) = resume_from_halt {
+                tracing::info!(proposal = %proposal_id, "configured to resume from emergency chain halt");
+            }
+
+            // Build the table used to decrypt batch swap flows up front, so
+            // that decrypting the first batch doesn't stall block execution.
+            penumbra_component::dex::init_decryption_table().await;
+
+            let (consensus, height_rx) =
+                pd::Consensus::new(storage.clone(), extender, resume_from_halt).await?;
             let mempool = pd::Mempool::new(storage.clone(), height_rx.clone()).await?;
-            let info = pd::Info::new(storage.clone(), height_rx);
-            let snapshot = pd::Snapshot {};
+            let info = pd::Info::new(storage.clone(), height_rx.clone(), query_history_blocks);
+            let snapshot = pd::Snapshot::new(storage.clone(), home.join("snapshots"));
+            if snapshot_interval > 0 {
+                snapshot.spawn_snapshotter(
+                    height_rx.clone(),
+                    snapshot_interval,
+                    snapshot_keep_recent,
+                );
+            }
+            if let Some(keep_recent) = pruning_keep_recent {
+                if keep_recent == 0 || pruning_interval == 0 {
+                    return Err(anyhow::anyhow!(
+                        "--pruning-keep-recent and --pruning-interval must be at least 1"
+                    ));
+                }
+                // Never prune state that is still served to historical queries.
+                // The pruner also holds back for any snapshot being taken, whose
+                // height may fall behind the most recent versions while it's
+                // written out.
+                let keep_recent = match query_history_blocks {
+                    Some(history) => keep_recent.max(history + 1),
+                    None => keep_recent,
+                };
+                tracing::info!(keep_recent, pruning_keep_every, "pruning state");
+                pd::Pruner::new(
+                    storage.clone(),
+                    keep_recent,
+                    pruning_keep_every,
+                    snapshot.snapshot_height(),
+                )
+                .spawn(height_rx, pruning_interval);
+            }
 
             let abci_server = tokio::task::Builder::new()
                 .name("abci_server")
@@ -248,6 +362,53 @@ async fn main() -> anyhow::Result<()> {
             };
         }
 
+        RootCommand::Halt { home, halt_cmd } => {
+            let storage = Storage::load(home.join("rocksdb"))
+                .await
+                .context("Unable to initialize RocksDB storage")?;
+
+            match halt_cmd {
+                HaltCommand::Status => {
+                    let height = storage.latest_version().await?.unwrap_or_default();
+                    match pd::halt::halted_by(&storage).await? {
+                        Some(proposal_id) => println!(
+                            "chain halted by emergency proposal {} after block {}",
+                            proposal_id, height
+                        ),
+                        None => println!("chain is not halted (latest block {})", height),
+                    }
+                }
+                HaltCommand::Export { output } => {
+                    if output.exists() {
+                        return Err(anyhow::anyhow!(
+                            "output directory {:?} already exists, refusing to overwrite it",
+                            output
+                        ));
+                    }
+                    storage.export(output.clone()).await?;
+                    println!("exported chain state to {:?}", output);
+                }
+                HaltCommand::Resume { proposal_id } => {
+                    match pd::halt::halted_by(&storage).await? {
+                        Some(halted_by) if halted_by == proposal_id => {}
+                        Some(halted_by) => {
+                            return Err(anyhow::anyhow!(
+                                "chain was halted by emergency proposal {}, not {}",
+                                halted_by,
+                                proposal_id
+                            ))
+                        }
+                        None => return Err(anyhow::anyhow!("chain is not halted")),
+                    }
+                    pd::halt::save_resume_from_halt(&home, proposal_id)?;
+                    println!(
+                        "pd will resume from the halt caused by emergency proposal {} when next started",
+                        proposal_id
+                    );
+                }
+            }
+        }
+
         RootCommand::Testnet {
             tn_cmd: TestnetCommand::UnsafeResetAll {},
             testnet_dir,
@@ -321,7 +482,7 @@ async fn main() -> anyhow::Result<()> {
             };
             let tm_config = generate_tm_config(&node_name, &[(node_id, node)]);
 
-            write_configs(node_dir, &vk, &genesis, tm_config)?;
+            write_configs(node_dir, &vk, &genesis, tm_config, None)?;
         }
 
         RootCommand::Testnet {
@@ -513,6 +674,23 @@ async fn main() -> anyhow::Result<()> {
                 })
                 .collect::<Result<Vec<Validator>, anyhow::Error>>()?;
 
+            // Deal a flow key share to each validator, such that two thirds of
+            // them are needed to decrypt batch swap flows.
+            // Since we generate every validator's keys here anyways, a trusted
+            // dealer is no weaker than running `penumbra_eddy::dkg` among them.
+            let committee_size = num_validator_nodes as u32;
+            let threshold = committee_size * 2 / 3 + 1;
+            let (encryption_key, flow_key_shares) =
+                penumbra_eddy::dkg::deal(threshold, committee_size, OsRng)?;
+            let flow_encryption_committee = FlowEncryptionCommittee {
+                encryption_key,
+                public_key_shares: flow_key_shares
+                    .iter()
+                    .map(|key_share| key_share.public_key_share())
+                    .collect(),
+                threshold,
+            };
+
             let app_state = genesis::AppState {
                 allocations: allocations.clone(),
                 chain_params: ChainParameters {
@@ -523,6 +701,7 @@ async fn main() -> anyhow::Result<()> {
                     ..Default::default()
                 },
                 validators: validators.into_iter().map(Into::into).collect(),
+                flow_encryption_committee: Some(flow_encryption_committee),
             };
 
             // Create the genesis data shared by all nodes
@@ -590,7 +769,13 @@ async fn main() -> anyhow::Result<()> {
                     .collect::<Vec<_>>();
                 let tm_config = generate_tm_config(&node_name, &ips_minus_mine);
 
-                write_configs(node_dir, vk, &validator_genesis, tm_config)?;
+                write_configs(
+                    node_dir,
+                    vk,
+                    &validator_genesis,
+                    tm_config,
+                    Some(&flow_key_shares[n]),
+                )?;
             }
         }
     }
@@ -10,7 +10,7 @@ use tokio::sync::{mpsc, watch};
 use tracing::{instrument, Instrument};
 
 use super::Message;
-use crate::App;
+use crate::{vote_extension, App};
 
 pub struct Worker {
     queue: mpsc::Receiver<Message>,
@@ -42,6 +42,12 @@ impl Worker {
     /// that performs the stateless checks.  However, this probably isn't
     /// important to do until we know that it's a bottleneck.
     async fn check_and_execute_tx(&mut self, ctx: Context, tx_bytes: Bytes) -> Result<()> {
+        if let Some(shares) = vote_extension::decode(tx_bytes.as_ref())? {
+            self.app.check_decryption_shares(&shares).await?;
+            self.app.deliver_decryption_shares(shares).await;
+            return Ok(());
+        }
+
         let tx = Transaction::decode(tx_bytes.as_ref())?;
         App::check_tx_stateless(ctx.clone(), &tx)?;
         self.app.check_tx_stateful(ctx.clone(), &tx).await?;
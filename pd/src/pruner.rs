@@ -0,0 +1,83 @@
+use anyhow::Result;
+use penumbra_storage::{PruneStats, Storage};
+use tendermint::block;
+use tokio::sync::watch;
+use tracing::Instrument;
+
+/// Periodically deletes past versions of the state that are no longer needed.
+///
+/// The state at the `keep_recent` most recent heights is always retained, as is
+/// the state at every multiple of `keep_every`, if it is nonzero, and the state
+/// at the height of any snapshot that is being taken.
+#[derive(Clone, Debug)]
+pub struct Pruner {
+    storage: Storage,
+    keep_recent: u64,
+    keep_every: u64,
+    snapshot_height: watch::Receiver<Option<u64>>,
+}
+
+impl Pruner {
+    pub fn new(
+        storage: Storage,
+        keep_recent: u64,
+        keep_every: u64,
+        snapshot_height: watch::Receiver<Option<u64>>,
+    ) -> Self {
+        Self {
+            storage,
+            keep_recent,
+            keep_every,
+            snapshot_height,
+        }
+    }
+
+    /// Spawns a task that prunes the state every `interval` blocks.
+    pub fn spawn(self, mut height_rx: watch::Receiver<block::Height>, interval: u64) {
+        tokio::task::Builder::new()
+            .name("pruner")
+            .spawn(async move {
+                while height_rx.changed().await.is_ok() {
+                    let height = height_rx.borrow().value();
+                    if height == 0 || height % interval != 0 {
+                        continue;
+                    }
+
+                    let span = tracing::info_span!("prune", height);
+                    if let Err(e) = self.prune(height).instrument(span).await {
+                        tracing::warn!(?e, "failed to prune state");
+                    }
+                }
+            })
+            .expect("failed to spawn pruner");
+    }
+
+    /// Prunes the state given that `height` is the latest committed height.
+    async fn prune(&self, height: u64) -> Result<PruneStats> {
+        let horizon = self.horizon(height);
+        self.storage
+            .prune(horizon, self.kept_versions(horizon))
+            .await
+    }
+
+    /// The earliest height of the most recent versions to keep.
+    ///
+    /// A snapshot walks the tree at its height for as long as it takes to write
+    /// it out, which may be many blocks, so that height is never pruned until
+    /// the snapshot is done.
+    fn horizon(&self, height: u64) -> u64 {
+        let horizon = (height + 1).saturating_sub(self.keep_recent);
+        match *self.snapshot_height.borrow() {
+            Some(snapshot_height) => horizon.min(snapshot_height),
+            None => horizon,
+        }
+    }
+
+    /// The versions to keep before the horizon.
+    fn kept_versions(&self, horizon: u64) -> Vec<u64> {
+        if self.keep_every == 0 {
+            return Vec::new();
+        }
+        (0..horizon).step_by(self.keep_every as usize).collect()
+    }
+}
@@ -1,15 +1,520 @@
 use std::{
     future::Future,
+    io::{BufRead, BufReader, Read},
+    path::PathBuf,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
+use anyhow::{anyhow, Result};
 use futures::FutureExt;
-use tendermint::abci::{SnapshotRequest, SnapshotResponse};
+use jmt::KeyHash;
+use penumbra_component::shielded_pool::View as _;
+use penumbra_storage::Storage;
+use penumbra_tct as tct;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tendermint::{
+    abci::{
+        self,
+        response::{ApplySnapshotChunkResult, OfferSnapshot},
+        SnapshotRequest, SnapshotResponse,
+    },
+    block,
+};
+use tokio::sync::{watch, Mutex};
 use tower_abci::BoxError;
+use tracing::Instrument;
+
+use crate::RequestExt;
+
+/// The snapshot format produced and accepted by this version of `pd`.
+const SNAPSHOT_FORMAT: u32 = 1;
+
+/// The size of each snapshot chunk, comfortably below Tendermint's 16 MB limit.
+const CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
+/// The metadata sent to peers alongside each snapshot.
+#[derive(Debug, Serialize, Deserialize)]
+struct Metadata {
+    /// The SHA256 hash of each chunk, in order.
+    chunk_hashes: Vec<[u8; 32]>,
+}
+
+/// An entry in a snapshot's data, which is the concatenation of its entries,
+/// each bincode-serialized, split into chunks.
+#[derive(Debug, Serialize, Deserialize)]
+enum Entry {
+    /// The serialized NCT, which is the first entry.
+    Nct(Vec<u8>),
+    /// A key hash and value from the JMT.
+    Leaf([u8; 32], Vec<u8>),
+}
+
+/// Writes a snapshot's data to numbered chunk files as it's produced, so that
+/// only one chunk is held in memory at a time.
+struct ChunkWriter {
+    dir: PathBuf,
+    buf: Vec<u8>,
+    chunk_hashes: Vec<[u8; 32]>,
+    bytes: usize,
+}
+
+impl ChunkWriter {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            buf: Vec::with_capacity(CHUNK_SIZE),
+            chunk_hashes: Vec::new(),
+            bytes: 0,
+        }
+    }
+
+    async fn write_entry(&mut self, entry: &Entry) -> Result<()> {
+        bincode::serialize_into(&mut self.buf, entry)?;
+        while self.buf.len() >= CHUNK_SIZE {
+            let rest = self.buf.split_off(CHUNK_SIZE);
+            self.flush_chunk().await?;
+            self.buf = rest;
+        }
+        Ok(())
+    }
+
+    async fn flush_chunk(&mut self) -> Result<()> {
+        let index = self.chunk_hashes.len();
+        tokio::fs::write(self.dir.join(index.to_string()), &self.buf).await?;
+        self.chunk_hashes.push(Sha256::digest(&self.buf).into());
+        self.bytes += self.buf.len();
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Writes the last, partial chunk, returning the snapshot's metadata.
+    async fn finish(mut self) -> Result<(Metadata, usize)> {
+        if !self.buf.is_empty() {
+            self.flush_chunk().await?;
+        }
+        Ok((
+            Metadata {
+                chunk_hashes: self.chunk_hashes,
+            },
+            self.bytes,
+        ))
+    }
+}
+
+/// Reads a snapshot's data back from numbered chunk files, in order, so that
+/// only one chunk file is open at a time.
+struct ChunkReader {
+    dir: PathBuf,
+    chunks: usize,
+    next: usize,
+    file: Option<std::fs::File>,
+}
+
+impl ChunkReader {
+    fn new(dir: PathBuf, chunks: usize) -> Self {
+        Self {
+            dir,
+            chunks,
+            next: 0,
+            file: None,
+        }
+    }
+}
 
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if let Some(file) = &mut self.file {
+                let read = file.read(buf)?;
+                if read > 0 || buf.is_empty() {
+                    return Ok(read);
+                }
+            }
+            if self.next == self.chunks {
+                return Ok(0);
+            }
+            self.file = Some(std::fs::File::open(self.dir.join(self.next.to_string()))?);
+            self.next += 1;
+        }
+    }
+}
+
+/// Decodes a snapshot's data into its JMT leaves and its NCT.
+fn decode(mut reader: impl BufRead) -> Result<(Vec<(KeyHash, Vec<u8>)>, tct::Tree)> {
+    let nct = match bincode::deserialize_from(&mut reader)? {
+        Entry::Nct(nct) => bincode::deserialize(&nct)?,
+        Entry::Leaf(..) => return Err(anyhow!("snapshot does not start with the NCT")),
+    };
+    let mut leaves = Vec::new();
+    while !reader.fill_buf()?.is_empty() {
+        match bincode::deserialize_from(&mut reader)? {
+            Entry::Leaf(key_hash, value) => leaves.push((KeyHash(key_hash), value)),
+            Entry::Nct(_) => return Err(anyhow!("snapshot contains more than one NCT")),
+        }
+    }
+    Ok((leaves, nct))
+}
+
+/// A snapshot offered by a peer, which is being restored chunk by chunk.
+///
+/// Chunks are written to numbered files as they're received, rather than held
+/// in memory until the last one arrives.
+#[derive(Debug)]
+struct Restore {
+    height: u64,
+    app_hash: Vec<u8>,
+    chunk_hashes: Vec<[u8; 32]>,
+    dir: PathBuf,
+    received: Vec<bool>,
+}
+
+/// Serves state sync snapshots to peers, and restores snapshots received from them.
+///
+/// Snapshots are stored in a directory containing one subdirectory per snapshot
+/// height, which holds the snapshot's metadata and its numbered chunks.
 #[derive(Clone, Debug)]
-pub struct Snapshot {}
+pub struct Snapshot {
+    storage: Storage,
+    dir: PathBuf,
+    restoring: Arc<Mutex<Option<Restore>>>,
+    taking: Arc<watch::Sender<Option<u64>>>,
+}
+
+impl Snapshot {
+    pub fn new(storage: Storage, dir: PathBuf) -> Self {
+        Self {
+            storage,
+            dir,
+            restoring: Default::default(),
+            taking: Arc::new(watch::channel(None).0),
+        }
+    }
+
+    /// Watches the height of the snapshot being taken, if any, whose state must
+    /// not be pruned until it's done.
+    pub fn snapshot_height(&self) -> watch::Receiver<Option<u64>> {
+        self.taking.subscribe()
+    }
+
+    /// Spawns a task that takes a snapshot every `interval` blocks, keeping
+    /// only the `keep_recent` most recent snapshots.
+    pub fn spawn_snapshotter(
+        &self,
+        mut height_rx: watch::Receiver<block::Height>,
+        interval: u64,
+        keep_recent: usize,
+    ) {
+        let snapshot = self.clone();
+        tokio::task::Builder::new()
+            .name("snapshotter")
+            .spawn(async move {
+                while height_rx.changed().await.is_ok() {
+                    let height = height_rx.borrow().value();
+                    if height == 0 || height % interval != 0 {
+                        continue;
+                    }
+
+                    let span = tracing::info_span!("snapshot", height);
+                    async {
+                        snapshot.taking.send_replace(Some(height));
+                        let result = snapshot.take(height).await;
+                        snapshot.taking.send_replace(None);
+                        if let Err(e) = result {
+                            tracing::warn!(?e, "failed to take snapshot");
+                        }
+                        if let Err(e) = snapshot.prune(keep_recent).await {
+                            tracing::warn!(?e, "failed to prune snapshots");
+                        }
+                    }
+                    .instrument(span)
+                    .await;
+                }
+            })
+            .expect("failed to spawn snapshotter");
+    }
+
+    /// Takes a snapshot of the state at the given height, which must be the
+    /// latest committed height.
+    ///
+    /// The state is streamed from storage straight into chunk files, so the
+    /// snapshot is never held in memory in full.
+    async fn take(&self, height: u64) -> Result<()> {
+        let nct = self.storage.get_nct().await?;
+
+        // The NCT is stored outside of the JMT, and only for the latest height,
+        // so check that another block wasn't committed before we read it.
+        let anchor = self
+            .storage
+            .state_at(height)
+            .await?
+            .nct_anchor(height)
+            .await?
+            .ok_or_else(|| anyhow!("missing NCT anchor for height {}", height))?;
+        if nct.root() != anchor {
+            return Err(anyhow!(
+                "NCT changed while the snapshot was being taken, skipping it"
+            ));
+        }
+
+        // Write the snapshot to a temporary directory first, so that a partially
+        // written snapshot is never served.
+        let tmp_dir = self.dir.join(format!("{}.tmp", height));
+        if tmp_dir.exists() {
+            tokio::fs::remove_dir_all(&tmp_dir).await?;
+        }
+        tokio::fs::create_dir_all(&tmp_dir).await?;
+
+        let mut writer = ChunkWriter::new(tmp_dir.clone());
+        writer
+            .write_entry(&Entry::Nct(bincode::serialize(&nct)?))
+            .await?;
+        drop(nct);
+        let mut leaves = self.storage.snapshot_leaves(height);
+        while let Some(leaf) = leaves.recv().await {
+            let (key_hash, value) = leaf?;
+            writer.write_entry(&Entry::Leaf(key_hash.0, value)).await?;
+        }
+        let (metadata, bytes) = writer.finish().await?;
+
+        tokio::fs::write(tmp_dir.join("metadata"), bincode::serialize(&metadata)?).await?;
+        tokio::fs::rename(&tmp_dir, self.snapshot_dir(height)).await?;
+
+        tracing::info!(chunks = metadata.chunk_hashes.len(), bytes, "took snapshot");
+        Ok(())
+    }
+
+    /// Removes all but the `keep_recent` most recent snapshots.
+    async fn prune(&self, keep_recent: usize) -> Result<()> {
+        let mut heights = self.heights().await?;
+        heights.sort_unstable_by(|a, b| b.cmp(a));
+        for height in heights.into_iter().skip(keep_recent) {
+            tracing::debug!(height, "removing old snapshot");
+            tokio::fs::remove_dir_all(self.snapshot_dir(height)).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the heights of all the complete snapshots on disk.
+    async fn heights(&self) -> Result<Vec<u64>> {
+        let mut heights = Vec::new();
+        if !self.dir.exists() {
+            return Ok(heights);
+        }
+
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            // Skip partially written snapshots, whose names don't parse.
+            if let Some(height) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u64>().ok())
+            {
+                heights.push(height);
+            }
+        }
+        Ok(heights)
+    }
+
+    fn snapshot_dir(&self, height: u64) -> PathBuf {
+        self.dir.join(height.to_string())
+    }
+
+    /// The directory chunks of a snapshot being restored are written to, whose
+    /// name is not a height, so it's never served.
+    fn restore_dir(&self) -> PathBuf {
+        self.dir.join("restore")
+    }
+
+    async fn list_snapshots(&self) -> Result<abci::response::ListSnapshots> {
+        let mut snapshots = Vec::new();
+        for height in self.heights().await? {
+            let metadata = tokio::fs::read(self.snapshot_dir(height).join("metadata")).await?;
+            let chunks = bincode::deserialize::<Metadata>(&metadata)?
+                .chunk_hashes
+                .len();
+
+            snapshots.push(abci::types::Snapshot {
+                height: height.try_into()?,
+                format: SNAPSHOT_FORMAT,
+                chunks: chunks.try_into()?,
+                hash: Sha256::digest(&metadata).to_vec().into(),
+                metadata: metadata.into(),
+            });
+        }
+
+        Ok(abci::response::ListSnapshots { snapshots })
+    }
+
+    async fn offer_snapshot(&self, offer: abci::request::OfferSnapshot) -> Result<OfferSnapshot> {
+        let snapshot = offer.snapshot;
+        tracing::info!(height = ?snapshot.height, chunks = snapshot.chunks, "snapshot offered");
+
+        if snapshot.format != SNAPSHOT_FORMAT {
+            return Ok(OfferSnapshot::RejectFormat);
+        }
+        if self.storage.latest_version().await?.is_some() {
+            tracing::warn!("refusing to restore a snapshot over existing state");
+            return Ok(OfferSnapshot::Abort);
+        }
+
+        // The metadata commits to the hash of every chunk, so each chunk can be
+        // checked as soon as it's received.
+        let metadata = match bincode::deserialize::<Metadata>(&snapshot.metadata) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(OfferSnapshot::Reject),
+        };
+        if Sha256::digest(&snapshot.metadata).as_slice() != snapshot.hash.as_ref()
+            || metadata.chunk_hashes.len() != snapshot.chunks as usize
+            || metadata.chunk_hashes.is_empty()
+        {
+            return Ok(OfferSnapshot::Reject);
+        }
+
+        // Any chunks left over from a previously offered snapshot are stale.
+        let dir = self.restore_dir();
+        if dir.exists() {
+            tokio::fs::remove_dir_all(&dir).await?;
+        }
+        tokio::fs::create_dir_all(&dir).await?;
+
+        *self.restoring.lock().await = Some(Restore {
+            height: snapshot.height.value(),
+            app_hash: offer.app_hash.as_ref().to_vec(),
+            dir,
+            received: vec![false; metadata.chunk_hashes.len()],
+            chunk_hashes: metadata.chunk_hashes,
+        });
+
+        Ok(OfferSnapshot::Accept)
+    }
+
+    async fn load_snapshot_chunk(
+        &self,
+        load: abci::request::LoadSnapshotChunk,
+    ) -> Result<abci::response::LoadSnapshotChunk> {
+        if load.format != SNAPSHOT_FORMAT {
+            return Err(anyhow!("unknown snapshot format {}", load.format));
+        }
+
+        let path = self
+            .snapshot_dir(load.height.value())
+            .join(load.chunk.to_string());
+        let chunk = tokio::fs::read(path).await?;
+
+        Ok(abci::response::LoadSnapshotChunk {
+            chunk: chunk.into(),
+        })
+    }
+
+    async fn apply_snapshot_chunk(
+        &self,
+        apply: abci::request::ApplySnapshotChunk,
+    ) -> Result<abci::response::ApplySnapshotChunk> {
+        let response = |result| abci::response::ApplySnapshotChunk {
+            result,
+            ..Default::default()
+        };
+
+        let mut guard = self.restoring.lock().await;
+        let restore = match guard.as_mut() {
+            Some(restore) => restore,
+            None => return Ok(response(ApplySnapshotChunkResult::Abort)),
+        };
+
+        let index = apply.index as usize;
+        let expected_hash = restore
+            .chunk_hashes
+            .get(index)
+            .ok_or_else(|| anyhow!("chunk index {} out of range", index))?;
+        if Sha256::digest(&apply.chunk).as_slice() != expected_hash.as_slice() {
+            tracing::warn!(index, sender = %apply.sender, "received invalid snapshot chunk");
+            return Ok(abci::response::ApplySnapshotChunk {
+                result: ApplySnapshotChunkResult::Retry,
+                refetch_chunks: vec![apply.index],
+                reject_senders: vec![apply.sender],
+            });
+        }
+        tokio::fs::write(restore.dir.join(index.to_string()), &apply.chunk).await?;
+        restore.received[index] = true;
+
+        if restore.received.contains(&false) {
+            return Ok(response(ApplySnapshotChunkResult::Accept));
+        }
+
+        // We have every chunk, so restore the snapshot.
+        let restore = guard.take().expect("restore is in progress");
+        if self.storage.latest_version().await?.is_some() {
+            tracing::warn!("refusing to restore a snapshot over existing state");
+            return Ok(response(ApplySnapshotChunkResult::Abort));
+        }
+        let reader = ChunkReader::new(restore.dir.clone(), restore.received.len());
+        let decoded = tokio::task::Builder::new()
+            .name("decode_snapshot")
+            .spawn_blocking(move || decode(BufReader::new(reader)))
+            .unwrap()
+            .await?;
+        tokio::fs::remove_dir_all(&restore.dir).await?;
+        let (leaves, nct) = match decoded {
+            Ok(contents) => contents,
+            Err(e) => {
+                tracing::warn!(?e, "could not decode snapshot");
+                return Ok(response(ApplySnapshotChunkResult::RejectSnapshot));
+            }
+        };
+
+        match self
+            .restore_contents(leaves, &nct, restore.height, &restore.app_hash)
+            .await
+        {
+            Ok(()) => {
+                tracing::info!(height = restore.height, "restored snapshot");
+                Ok(response(ApplySnapshotChunkResult::Accept))
+            }
+            Err(e) => {
+                tracing::warn!(?e, "restored snapshot is invalid, discarding it");
+                self.storage.discard_snapshot().await?;
+                Ok(response(ApplySnapshotChunkResult::RejectSnapshot))
+            }
+        }
+    }
+
+    /// Rebuilds the state from the snapshot's leaves, checking it against the
+    /// trusted app hash supplied by Tendermint for its height.
+    async fn restore_contents(
+        &self,
+        leaves: Vec<(KeyHash, Vec<u8>)>,
+        nct: &tct::Tree,
+        height: u64,
+        app_hash: &[u8],
+    ) -> Result<()> {
+        let restored_app_hash = self.storage.restore_snapshot(height, leaves, nct).await?;
+        if restored_app_hash.0 != app_hash {
+            return Err(anyhow!(
+                "restored app hash {} does not match trusted app hash {}",
+                hex::encode(restored_app_hash.0),
+                hex::encode(app_hash)
+            ));
+        }
+
+        // The NCT is not part of the JMT, but its root is, and the JMT was just
+        // verified against the trusted app hash, so check the NCT against it.
+        let anchor = self
+            .storage
+            .state()
+            .await?
+            .nct_anchor(height)
+            .await?
+            .ok_or_else(|| anyhow!("missing NCT anchor for height {}", height))?;
+        if nct.root() != anchor {
+            return Err(anyhow!("restored NCT does not match the NCT anchor"));
+        }
+
+        Ok(())
+    }
+}
 
 impl tower_service::Service<SnapshotRequest> for Snapshot {
     type Response = SnapshotResponse;
@@ -22,17 +527,25 @@ impl tower_service::Service<SnapshotRequest> for Snapshot {
     }
 
     fn call(&mut self, req: SnapshotRequest) -> Self::Future {
-        // No-op, we don't implement snapshot support
+        let span = req.create_span();
+        let snapshot = self.clone();
         use SnapshotRequest as Request;
         use SnapshotResponse as Response;
         async move {
             Ok(match req {
-                Request::ListSnapshots => Response::ListSnapshots(Default::default()),
-                Request::OfferSnapshot(_) => Response::OfferSnapshot(Default::default()),
-                Request::LoadSnapshotChunk(_) => Response::LoadSnapshotChunk(Default::default()),
-                Request::ApplySnapshotChunk(_) => Response::ApplySnapshotChunk(Default::default()),
+                Request::ListSnapshots => Response::ListSnapshots(snapshot.list_snapshots().await?),
+                Request::OfferSnapshot(offer) => {
+                    Response::OfferSnapshot(snapshot.offer_snapshot(offer).await?)
+                }
+                Request::LoadSnapshotChunk(load) => {
+                    Response::LoadSnapshotChunk(snapshot.load_snapshot_chunk(load).await?)
+                }
+                Request::ApplySnapshotChunk(apply) => {
+                    Response::ApplySnapshotChunk(snapshot.apply_snapshot_chunk(apply).await?)
+                }
             })
         }
+        .instrument(span)
         .boxed()
     }
 }
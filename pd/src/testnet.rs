@@ -6,6 +6,7 @@ use penumbra_crypto::{
     rdsa::{SigningKey, SpendAuth, VerificationKey},
     Address,
 };
+use penumbra_eddy::PrivateKeyShare;
 use penumbra_wallet::KeyStore;
 use rand::Rng;
 use rand_core::OsRng;
@@ -22,6 +23,8 @@ use std::{
 use tendermint::{node::Id, Genesis, PrivateKey};
 use tendermint_config::{NodeKey, PrivValidatorKey};
 
+use crate::vote_extension::FlowKeyShareFile;
+
 /// Methods and types used for generating testnet configurations.
 
 pub fn parse_allocations(input: impl Read) -> Result<Vec<genesis::Allocation>> {
@@ -233,6 +236,7 @@ pub fn write_configs(
     vk: &ValidatorKeys,
     genesis: &Genesis<AppState>,
     tm_config: String,
+    flow_key_share: Option<&PrivateKeyShare>,
 ) -> anyhow::Result<()> {
     let mut pd_dir = node_dir.clone();
     let mut tm_dir = node_dir;
@@ -309,5 +313,17 @@ pub fn write_configs(
     validator_spend_key_file
         .write_all(serde_json::to_string_pretty(&validator_wallet)?.as_bytes())?;
 
+    // Write the validator's flow key share, if it's a member of the flow
+    // encryption committee:
+    if let Some(flow_key_share) = flow_key_share {
+        let mut flow_key_share_file_path = pd_dir.clone();
+        flow_key_share_file_path.push("flow_key_share.json");
+        tracing::info!(flow_key_share_file_path = %flow_key_share_file_path.display(), "writing flow key share");
+        let mut flow_key_share_file = File::create(flow_key_share_file_path)?;
+        flow_key_share_file.write_all(
+            serde_json::to_string_pretty(&FlowKeyShareFile::from(flow_key_share))?.as_bytes(),
+        )?;
+    }
+
     Ok(())
 }
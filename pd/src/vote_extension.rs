@@ -0,0 +1,161 @@
+//! Delivery of flow decryption shares from validators to the chain.
+//!
+//! Tendermint 0.34 has no vote extensions, so instead, after each block is
+//! committed, validators holding a flow key share compute their decryption
+//! shares for the batches awaiting decryption, and broadcast them to the
+//! mempool.  Shares are encoded with a distinguishing prefix, so that they
+//! can be told apart from transactions, and are delivered in a later block.
+
+use std::{collections::BTreeSet, path::Path};
+
+use anyhow::Context;
+use penumbra_component::dex::View as _;
+use penumbra_crypto::BatchDecryptionShares;
+use penumbra_eddy::PrivateKeyShare;
+use penumbra_proto::Protobuf;
+use penumbra_storage::Storage;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+/// The prefix distinguishing encoded decryption shares from transactions.
+pub const PREFIX: &[u8] = b"penumbra-flow-decryption-shares/";
+
+/// Encodes decryption shares for broadcast.
+pub fn encode(shares: &BatchDecryptionShares) -> Vec<u8> {
+    [PREFIX, &shares.encode_to_vec()].concat()
+}
+
+/// Decodes decryption shares, returning `None` if `bytes` is not an encoding
+/// of decryption shares (and so should be a transaction).
+pub fn decode(bytes: &[u8]) -> anyhow::Result<Option<BatchDecryptionShares>> {
+    match bytes.strip_prefix(PREFIX) {
+        Some(shares) => Ok(Some(BatchDecryptionShares::decode(shares)?)),
+        None => Ok(None),
+    }
+}
+
+/// The on-disk format of a validator's flow key share.
+#[derive(Serialize, Deserialize)]
+pub struct FlowKeyShareFile {
+    /// The hex-encoded private key share.
+    pub key_share: String,
+}
+
+impl From<&PrivateKeyShare> for FlowKeyShareFile {
+    fn from(key_share: &PrivateKeyShare) -> Self {
+        FlowKeyShareFile {
+            key_share: hex::encode(key_share.to_bytes()),
+        }
+    }
+}
+
+impl TryFrom<FlowKeyShareFile> for PrivateKeyShare {
+    type Error = anyhow::Error;
+
+    fn try_from(file: FlowKeyShareFile) -> Result<Self, Self::Error> {
+        hex::decode(file.key_share)?[..].try_into()
+    }
+}
+
+/// Computes and broadcasts this validator's decryption shares after each
+/// block is committed.
+pub struct Extender {
+    key_share: PrivateKeyShare,
+    tendermint_url: String,
+    // Heights of the batches we've already broadcast shares for.
+    submitted: BTreeSet<u64>,
+}
+
+impl Extender {
+    pub fn new(key_share: PrivateKeyShare, tendermint_url: String) -> Self {
+        Self {
+            key_share,
+            tendermint_url,
+            submitted: BTreeSet::new(),
+        }
+    }
+
+    /// Loads a flow key share from `path`, if it exists.
+    pub fn load_key_share(path: &Path) -> anyhow::Result<Option<PrivateKeyShare>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file: FlowKeyShareFile = serde_json::from_slice(&std::fs::read(path)?)
+            .with_context(|| format!("could not parse flow key share file {:?}", path))?;
+        Ok(Some(file.try_into()?))
+    }
+
+    /// Broadcasts decryption shares for every batch awaiting decryption that
+    /// we haven't already contributed to.
+    ///
+    /// This is called while tendermint is committing a block, during which the
+    /// mempool is locked, so the broadcast itself is done in the background.
+    pub async fn extend(&mut self, storage: &Storage) -> anyhow::Result<()> {
+        let state = storage.state().await?;
+        let batches = state.pending_batches().await?;
+
+        self.submitted
+            .retain(|height| batches.iter().any(|batch| batch.height == *height));
+
+        let participant_index = self.key_share.participant_index();
+        for batch in batches {
+            if self.submitted.contains(&batch.height)
+                || batch.flows.is_empty()
+                || batch
+                    .shares
+                    .iter()
+                    .any(|shares| shares.participant_index == participant_index)
+            {
+                continue;
+            }
+
+            let shares = BatchDecryptionShares {
+                height: batch.height,
+                participant_index,
+                flows: batch
+                    .flows
+                    .iter()
+                    .map(|((trading_pair, limit_prices), flow)| {
+                        (
+                            *trading_pair,
+                            *limit_prices,
+                            flow.0.decryption_share(&self.key_share, OsRng),
+                            flow.1.decryption_share(&self.key_share, OsRng),
+                        )
+                    })
+                    .collect(),
+            };
+            tracing::debug!(height = batch.height, "broadcasting decryption shares");
+            self.submitted.insert(batch.height);
+
+            let tendermint_url = self.tendermint_url.clone();
+            tokio::spawn(async move {
+                if let Err(e) = broadcast(&tendermint_url, encode(&shares)).await {
+                    tracing::warn!(?e, "failed to broadcast decryption shares");
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+async fn broadcast(tendermint_url: &str, bytes: Vec<u8>) -> anyhow::Result<()> {
+    let rsp: serde_json::Value = reqwest::Client::new()
+        .post(tendermint_url)
+        .json(&serde_json::json!(
+            {
+                "method": "broadcast_tx_async",
+                "params": { "tx": base64::encode(bytes) },
+                "id": 0,
+            }
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    tracing::debug!(%rsp, "broadcast decryption shares");
+
+    Ok(())
+}
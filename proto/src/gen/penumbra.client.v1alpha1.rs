@@ -48,6 +48,39 @@ pub struct ValidatorInfoRequest {
     #[prost(bool, tag="2")]
     pub show_inactive: bool,
 }
+/// Requests the slashing penalties applied to a validator.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SlashingHistoryRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub identity_key: ::core::option::Option<super::super::core::crypto::v1alpha1::IdentityKey>,
+}
+/// Requests the blocks a validator missed within the signed blocks window.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidatorUptimeRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub identity_key: ::core::option::Option<super::super::core::crypto::v1alpha1::IdentityKey>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidatorUptimeResponse {
+    /// The height of the last block recorded in the validator's uptime.
+    #[prost(uint64, tag="1")]
+    pub as_of_block_height: u64,
+    /// The number of blocks in the signed blocks window.
+    #[prost(uint64, tag="2")]
+    pub window_len: u64,
+    /// The heights of the blocks missed within the window, in ascending order.
+    #[prost(uint64, repeated, tag="3")]
+    pub missed_blocks: ::prost::alloc::vec::Vec<u64>,
+    /// The number of missed blocks within the window at which the validator is slashed.
+    #[prost(uint64, tag="4")]
+    pub missed_blocks_maximum: u64,
+}
 /// Requests batch swap data associated with a given height and trading pair from the view service.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct BatchSwapOutputDataRequest {
@@ -56,13 +89,88 @@ pub struct BatchSwapOutputDataRequest {
     #[prost(message, optional, tag="2")]
     pub trading_pair: ::core::option::Option<super::super::core::dex::v1alpha1::TradingPair>,
 }
-/// Requests CPMM reserves data associated with a given trading pair from the view service.
+/// Requests the open liquidity positions on a given trading pair.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct StubCpmmReservesRequest {
-    #[prost(message, optional, tag="1")]
+pub struct LiquidityPositionsRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub trading_pair: ::core::option::Option<super::super::core::dex::v1alpha1::TradingPair>,
+}
+/// Requests the state and reserves of a given liquidity position.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LiquidityPositionByIdRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub position_id: ::core::option::Option<super::super::core::dex::v1alpha1::PositionId>,
+}
+/// Requests the aggregate liquidity available at each price on a given trading pair.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LiquidityDepthRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
     pub trading_pair: ::core::option::Option<super::super::core::dex::v1alpha1::TradingPair>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LiquidityDepthResponse {
+    /// The levels at which asset 1 can be traded for asset 2, best price first.
+    #[prost(message, repeated, tag="1")]
+    pub levels_1_to_2: ::prost::alloc::vec::Vec<liquidity_depth_response::Level>,
+    /// The levels at which asset 2 can be traded for asset 1, best price first.
+    #[prost(message, repeated, tag="2")]
+    pub levels_2_to_1: ::prost::alloc::vec::Vec<liquidity_depth_response::Level>,
+}
+/// Nested message and enum types in `LiquidityDepthResponse`.
+pub mod liquidity_depth_response {
+    /// The total reserves offered by open positions at a given price.
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Level {
+        /// The effective price, net of fees, in units of the output asset per unit
+        /// of the input asset.
+        #[prost(double, tag="1")]
+        pub price: f64,
+        /// The total reserves of the output asset available at this price.
+        #[prost(message, optional, tag="2")]
+        pub reserves: ::core::option::Option<super::super::super::core::crypto::v1alpha1::Amount>,
+    }
+}
+/// Requests a simulation of a batch swap against the current liquidity on a
+/// given trading pair, without executing it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateTradeRequest {
+    /// The expected chain id (empty string if no expectation).
+    #[prost(string, tag="1")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub trading_pair: ::core::option::Option<super::super::core::dex::v1alpha1::TradingPair>,
+    /// The amount of asset 1 being exchanged for asset 2.
+    #[prost(uint64, tag="3")]
+    pub delta_1: u64,
+    /// The amount of asset 2 being exchanged for asset 1.
+    #[prost(uint64, tag="4")]
+    pub delta_2: u64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateTradeResponse {
+    /// The output data the batch would have if it were executed at the end of the
+    /// current block.
+    #[prost(message, optional, tag="1")]
+    pub output: ::core::option::Option<super::super::core::dex::v1alpha1::BatchSwapOutputData>,
+    /// The effective price at which asset 1 is exchanged, in units of asset 2 per
+    /// unit of asset 1, or zero if none is exchanged.
+    #[prost(double, tag="2")]
+    pub price_1_to_2: f64,
+    /// The effective price at which asset 2 is exchanged, in units of asset 1 per
+    /// unit of asset 2, or zero if none is exchanged.
+    #[prost(double, tag="3")]
+    pub price_2_to_1: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ValidatorStatusRequest {
     /// The expected chain id (empty string if no expectation).
     #[prost(string, tag="1")]
@@ -84,6 +192,9 @@ pub struct KeyValueRequest {
     /// whether to return a proof
     #[prost(bool, tag="3")]
     pub proof: bool,
+    /// The height at which to query the state, or 0 for the latest height.
+    #[prost(uint64, tag="4")]
+    pub height: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct KeyValueResponse {
@@ -91,6 +202,20 @@ pub struct KeyValueResponse {
     pub value: ::prost::alloc::vec::Vec<u8>,
     #[prost(message, optional, tag="2")]
     pub proof: ::core::option::Option<::ics23::CommitmentProof>,
+    /// The height of the state the value was read from.
+    #[prost(uint64, tag="3")]
+    pub height: u64,
+    /// Whether the key is present in the state at `height`.
+    #[prost(bool, tag="4")]
+    pub exists: bool,
+    /// If a proof was requested, the bincode-encoded JMT `SparseMerkleProof` of
+    /// the key's inclusion (or non-inclusion) under `jmt_root`.
+    #[prost(bytes="vec", tag="5")]
+    pub sparse_merkle_proof: ::prost::alloc::vec::Vec<u8>,
+    /// If a proof was requested, the JMT root hash at `height`, from which the
+    /// app hash committed in the header at `height + 1` is derived.
+    #[prost(bytes="vec", tag="6")]
+    pub jmt_root: ::prost::alloc::vec::Vec<u8>,
 }
 /// Generated client implementations.
 pub mod oblivious_query_client {
@@ -434,6 +559,47 @@ pub mod specific_query_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn slashing_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SlashingHistoryRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::core::stake::v1alpha1::SlashingHistory>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.client.v1alpha1.SpecificQuery/SlashingHistory",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn validator_uptime(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ValidatorUptimeRequest>,
+        ) -> Result<tonic::Response<super::ValidatorUptimeResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.client.v1alpha1.SpecificQuery/ValidatorUptime",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         pub async fn batch_swap_output_data(
             &mut self,
             request: impl tonic::IntoRequest<super::BatchSwapOutputDataRequest>,
@@ -458,11 +624,37 @@ pub mod specific_query_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
-        pub async fn stub_cpmm_reserves(
+        pub async fn liquidity_positions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LiquidityPositionsRequest>,
+        ) -> Result<
+            tonic::Response<
+                tonic::codec::Streaming<
+                    super::super::super::core::dex::v1alpha1::PositionMetadata,
+                >,
+            >,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityPositions",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
+        pub async fn liquidity_position_by_id(
             &mut self,
-            request: impl tonic::IntoRequest<super::StubCpmmReservesRequest>,
+            request: impl tonic::IntoRequest<super::LiquidityPositionByIdRequest>,
         ) -> Result<
-            tonic::Response<super::super::super::core::dex::v1alpha1::Reserves>,
+            tonic::Response<super::super::super::core::dex::v1alpha1::PositionMetadata>,
             tonic::Status,
         > {
             self.inner
@@ -476,7 +668,45 @@ pub mod specific_query_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/penumbra.client.v1alpha1.SpecificQuery/StubCPMMReserves",
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityPositionById",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn liquidity_depth(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LiquidityDepthRequest>,
+        ) -> Result<tonic::Response<super::LiquidityDepthResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityDepth",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn simulate_trade(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SimulateTradeRequest>,
+        ) -> Result<tonic::Response<super::SimulateTradeResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.client.v1alpha1.SpecificQuery/SimulateTrade",
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
@@ -902,6 +1132,17 @@ pub mod specific_query_server {
             tonic::Response<super::super::super::core::stake::v1alpha1::RateData>,
             tonic::Status,
         >;
+        async fn slashing_history(
+            &self,
+            request: tonic::Request<super::SlashingHistoryRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::core::stake::v1alpha1::SlashingHistory>,
+            tonic::Status,
+        >;
+        async fn validator_uptime(
+            &self,
+            request: tonic::Request<super::ValidatorUptimeRequest>,
+        ) -> Result<tonic::Response<super::ValidatorUptimeResponse>, tonic::Status>;
         async fn batch_swap_output_data(
             &self,
             request: tonic::Request<super::BatchSwapOutputDataRequest>,
@@ -911,13 +1152,34 @@ pub mod specific_query_server {
             >,
             tonic::Status,
         >;
-        async fn stub_cpmm_reserves(
+        /// Server streaming response type for the LiquidityPositions method.
+        type LiquidityPositionsStream: futures_core::Stream<
+                Item = Result<
+                    super::super::super::core::dex::v1alpha1::PositionMetadata,
+                    tonic::Status,
+                >,
+            >
+            + Send
+            + 'static;
+        async fn liquidity_positions(
             &self,
-            request: tonic::Request<super::StubCpmmReservesRequest>,
+            request: tonic::Request<super::LiquidityPositionsRequest>,
+        ) -> Result<tonic::Response<Self::LiquidityPositionsStream>, tonic::Status>;
+        async fn liquidity_position_by_id(
+            &self,
+            request: tonic::Request<super::LiquidityPositionByIdRequest>,
         ) -> Result<
-            tonic::Response<super::super::super::core::dex::v1alpha1::Reserves>,
+            tonic::Response<super::super::super::core::dex::v1alpha1::PositionMetadata>,
             tonic::Status,
         >;
+        async fn liquidity_depth(
+            &self,
+            request: tonic::Request<super::LiquidityDepthRequest>,
+        ) -> Result<tonic::Response<super::LiquidityDepthResponse>, tonic::Status>;
+        async fn simulate_trade(
+            &self,
+            request: tonic::Request<super::SimulateTradeRequest>,
+        ) -> Result<tonic::Response<super::SimulateTradeResponse>, tonic::Status>;
         /// General-purpose key-value state query API, that can be used to query
         /// arbitrary keys in the JMT storage.
         async fn key_value(
@@ -1115,6 +1377,86 @@ pub mod specific_query_server {
                     };
                     Box::pin(fut)
                 }
+                "/penumbra.client.v1alpha1.SpecificQuery/SlashingHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct SlashingHistorySvc<T: SpecificQuery>(pub Arc<T>);
+                    impl<
+                        T: SpecificQuery,
+                    > tonic::server::UnaryService<super::SlashingHistoryRequest>
+                    for SlashingHistorySvc<T> {
+                        type Response = super::super::super::core::stake::v1alpha1::SlashingHistory;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SlashingHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).slashing_history(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SlashingHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/penumbra.client.v1alpha1.SpecificQuery/ValidatorUptime" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidatorUptimeSvc<T: SpecificQuery>(pub Arc<T>);
+                    impl<
+                        T: SpecificQuery,
+                    > tonic::server::UnaryService<super::ValidatorUptimeRequest>
+                    for ValidatorUptimeSvc<T> {
+                        type Response = super::ValidatorUptimeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ValidatorUptimeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).validator_uptime(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ValidatorUptimeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/penumbra.client.v1alpha1.SpecificQuery/BatchSwapOutputData" => {
                     #[allow(non_camel_case_types)]
                     struct BatchSwapOutputDataSvc<T: SpecificQuery>(pub Arc<T>);
@@ -1155,25 +1497,147 @@ pub mod specific_query_server {
                     };
                     Box::pin(fut)
                 }
-                "/penumbra.client.v1alpha1.SpecificQuery/StubCPMMReserves" => {
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityPositions" => {
+                    #[allow(non_camel_case_types)]
+                    struct LiquidityPositionsSvc<T: SpecificQuery>(pub Arc<T>);
+                    impl<
+                        T: SpecificQuery,
+                    > tonic::server::ServerStreamingService<
+                        super::LiquidityPositionsRequest,
+                    > for LiquidityPositionsSvc<T> {
+                        type Response = super::super::super::core::dex::v1alpha1::PositionMetadata;
+                        type ResponseStream = T::LiquidityPositionsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LiquidityPositionsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).liquidity_positions(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LiquidityPositionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityPositionById" => {
+                    #[allow(non_camel_case_types)]
+                    struct LiquidityPositionByIdSvc<T: SpecificQuery>(pub Arc<T>);
+                    impl<
+                        T: SpecificQuery,
+                    > tonic::server::UnaryService<super::LiquidityPositionByIdRequest>
+                    for LiquidityPositionByIdSvc<T> {
+                        type Response = super::super::super::core::dex::v1alpha1::PositionMetadata;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LiquidityPositionByIdRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).liquidity_position_by_id(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LiquidityPositionByIdSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/penumbra.client.v1alpha1.SpecificQuery/LiquidityDepth" => {
+                    #[allow(non_camel_case_types)]
+                    struct LiquidityDepthSvc<T: SpecificQuery>(pub Arc<T>);
+                    impl<
+                        T: SpecificQuery,
+                    > tonic::server::UnaryService<super::LiquidityDepthRequest>
+                    for LiquidityDepthSvc<T> {
+                        type Response = super::LiquidityDepthResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LiquidityDepthRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).liquidity_depth(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LiquidityDepthSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/penumbra.client.v1alpha1.SpecificQuery/SimulateTrade" => {
                     #[allow(non_camel_case_types)]
-                    struct StubCPMMReservesSvc<T: SpecificQuery>(pub Arc<T>);
+                    struct SimulateTradeSvc<T: SpecificQuery>(pub Arc<T>);
                     impl<
                         T: SpecificQuery,
-                    > tonic::server::UnaryService<super::StubCpmmReservesRequest>
-                    for StubCPMMReservesSvc<T> {
-                        type Response = super::super::super::core::dex::v1alpha1::Reserves;
+                    > tonic::server::UnaryService<super::SimulateTradeRequest>
+                    for SimulateTradeSvc<T> {
+                        type Response = super::SimulateTradeResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::StubCpmmReservesRequest>,
+                            request: tonic::Request<super::SimulateTradeRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
                             let fut = async move {
-                                (*inner).stub_cpmm_reserves(request).await
+                                (*inner).simulate_trade(request).await
                             };
                             Box::pin(fut)
                         }
@@ -1183,7 +1647,7 @@ pub mod specific_query_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = StubCPMMReservesSvc(inner);
+                        let method = SimulateTradeSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -29,6 +29,20 @@ pub struct ChainParameters {
     /// The maximum number of blocks in the window each validator can miss signing without slashing.
     #[prost(uint64, tag="12")]
     pub missed_blocks_maximum: u64,
+    /// The maximum increase in a validator's total commission, in basis points, that a single
+    /// validator definition update may announce.
+    #[prost(uint64, tag="13")]
+    pub max_commission_change_bps: u64,
+    /// The number of epochs after the announcement of new funding streams before they take effect.
+    #[prost(uint64, tag="14")]
+    pub commission_change_delay_epochs: u64,
+    /// The minimum number of epochs a validator jailed for downtime must wait before being re-enabled.
+    #[prost(uint64, tag="15")]
+    pub minimum_jail_epochs: u64,
+    /// The maximum share of the consensus power any single validator may hold, in basis points, or 0
+    /// for no limit.
+    #[prost(uint64, tag="16")]
+    pub max_validator_power_share_bps: u64,
     /// Whether IBC (forming connections, processing IBC packets) is enabled.
     #[prost(bool, tag="6")]
     pub ibc_enabled: bool,
@@ -117,6 +131,9 @@ pub struct CompactBlock {
     /// If a proposal started voting in this block, this is set to `true`.
     #[prost(bool, tag="20")]
     pub proposal_started: bool,
+    /// Latest chain parameters, if they changed in this block.
+    #[prost(message, optional, tag="21")]
+    pub chain_parameters: ::core::option::Option<ChainParameters>,
     /// Latest Fuzzy Message Detection parameters.
     #[prost(message, optional, tag="100")]
     pub fmd_parameters: ::core::option::Option<FmdParameters>,
@@ -161,6 +178,9 @@ pub struct GenesisAppState {
     pub validators: ::prost::alloc::vec::Vec<super::super::stake::v1alpha1::Validator>,
     #[prost(message, repeated, tag="3")]
     pub allocations: ::prost::alloc::vec::Vec<genesis_app_state::Allocation>,
+    /// The committee that batch swap flows are encrypted to, if any.
+    #[prost(message, optional, tag="4")]
+    pub flow_encryption_committee: ::core::option::Option<super::super::dex::v1alpha1::FlowEncryptionCommittee>,
 }
 /// Nested message and enum types in `GenesisAppState`.
 pub mod genesis_app_state {
@@ -230,3 +230,43 @@ pub struct Clue {
     #[serde(with = "crate::serializers::hexstr")]
     pub inner: ::prost::alloc::vec::Vec<u8>,
 }
+/// A flow encryption ciphertext: the ElGamal encryption of each 16-bit limb of
+/// a value.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EddyCiphertext {
+    /// 256 bytes: the encoding of each limb ciphertext, from least to most significant.
+    #[prost(bytes="vec", tag="1")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub inner: ::prost::alloc::vec::Vec<u8>,
+}
+/// A flow encryption decryption share, with a proof that it was correctly
+/// computed from the participant's key share.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EddyDecryptionShare {
+    /// 388 bytes: the participant index, followed by the share and proof for each limb.
+    #[prost(bytes="vec", tag="1")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub inner: ::prost::alloc::vec::Vec<u8>,
+}
+/// A proof that a flow encryption ciphertext is well-formed, and that each of
+/// its limbs encrypts a 16-bit value, which reveals nothing about the value.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EddyEncryptionProof {
+    /// 10368 bytes: the range proof for each limb, from least to most significant.
+    #[prost(bytes="vec", tag="1")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub inner: ::prost::alloc::vec::Vec<u8>,
+}
+/// A placeholder proof that a flow encryption ciphertext is well-formed, which
+/// reveals the encrypted value.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EddyTransparentEncryptionProof {
+    /// 136 bytes: the value, followed by the blinding factor for each limb.
+    #[prost(bytes="vec", tag="1")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub inner: ::prost::alloc::vec::Vec<u8>,
+}
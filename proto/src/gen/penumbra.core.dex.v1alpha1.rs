@@ -6,11 +6,6 @@ pub struct Swap {
     #[prost(bytes="vec", tag="1")]
     #[serde(with = "crate::serializers::base64str")]
     pub proof: ::prost::alloc::vec::Vec<u8>,
-    /// MockFlowCiphertext dropped until flow encryption/ABCI++ available
-    /// // Encrypted amount of asset 1 of the trading pair.
-    /// MockFlowCiphertext enc_amount_1 = 2;
-    /// // Encrypted amount of asset 2 of the trading pair.
-    /// MockFlowCiphertext enc_amount_2 = 3;
     /// Encapsulates the authorized fields of the Swap action, used in signing.
     #[prost(message, optional, tag="4")]
     pub body: ::core::option::Option<SwapBody>,
@@ -76,17 +71,12 @@ pub struct SwapBody {
     /// The trading pair to swap.
     #[prost(message, optional, tag="1")]
     pub trading_pair: ::core::option::Option<TradingPair>,
-    /// @exclude These will become commitments when flow encryption/ABCI++ are available
-    /// The amount for asset 1.
-    #[prost(message, optional, tag="2")]
-    pub delta_1_i: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
-    /// The amount for asset 2.
-    #[prost(message, optional, tag="3")]
-    pub delta_2_i: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
-    /// @exclude // Commitment to the amount for asset 1 (delta 1).
-    /// @exclude bytes delta_1_commitment = 2;
-    /// @exclude // Commitment to the amount for asset 2 (delta 2).
-    /// @exclude bytes delta_2_commitment = 3;
+    /// Commitment to the amount for asset 1 (delta 1).
+    #[prost(bytes="vec", tag="2")]
+    pub delta_1_commitment: ::prost::alloc::vec::Vec<u8>,
+    /// Commitment to the amount for asset 2 (delta 2).
+    #[prost(bytes="vec", tag="3")]
+    pub delta_2_commitment: ::prost::alloc::vec::Vec<u8>,
     /// A commitment to a prepaid fee for the future SwapClaim.
     #[prost(bytes="vec", tag="4")]
     pub fee_commitment: ::prost::alloc::vec::Vec<u8>,
@@ -96,6 +86,28 @@ pub struct SwapBody {
     /// Encrypted version of the original `Swap`, symmetrically encrypted w/ viewing key.
     #[prost(bytes="vec", tag="6")]
     pub swap_ciphertext: ::prost::alloc::vec::Vec<u8>,
+    /// The key of the flow encryption committee that the amounts are encrypted to.
+    #[prost(bytes="vec", tag="7")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub flow_encryption_key: ::prost::alloc::vec::Vec<u8>,
+    /// The amount for asset 1, encrypted to the flow encryption committee.
+    #[prost(message, optional, tag="8")]
+    pub enc_delta_1: ::core::option::Option<FlowCiphertext>,
+    /// The amount for asset 2, encrypted to the flow encryption committee.
+    #[prost(message, optional, tag="9")]
+    pub enc_delta_2: ::core::option::Option<FlowCiphertext>,
+    /// A proof that `enc_delta_1` is a well-formed encryption to the committee.
+    #[prost(message, optional, tag="10")]
+    pub enc_delta_1_proof: ::core::option::Option<super::super::crypto::v1alpha1::EddyEncryptionProof>,
+    /// A proof that `enc_delta_2` is a well-formed encryption to the committee.
+    #[prost(message, optional, tag="11")]
+    pub enc_delta_2_proof: ::core::option::Option<super::super::crypto::v1alpha1::EddyEncryptionProof>,
+    /// The limit prices of the swap.
+    ///
+    /// These are public, so that the swap can be batched with swaps with the
+    /// same limits, and excluded from execution if its limits are not met.
+    #[prost(message, optional, tag="12")]
+    pub limit_prices: ::core::option::Option<LimitPrices>,
 }
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -115,14 +127,161 @@ pub struct SwapPlaintext {
     /// Address that will claim the swap outputs via SwapClaim.
     #[prost(message, optional, tag="5")]
     pub claim_address: ::core::option::Option<super::super::crypto::v1alpha1::Address>,
+    /// The limit prices of the swap.
+    #[prost(message, optional, tag="6")]
+    pub limit_prices: ::core::option::Option<LimitPrices>,
+}
+/// The worst clearing prices a swap will accept.
+///
+/// Prices are fixed-point, scaled by 10^6, and zero means no limit.  If the
+/// batch's clearing price would violate either limit, the swap is excluded from
+/// the batch before it is executed, and its inputs are refunded.
+///
+/// Limit prices are restricted to coarse tiers: either no limit, or a
+/// power-of-two limit on a single direction.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LimitPrices {
+    /// The minimum amount of asset 1 output per unit of asset 2 input.
+    #[prost(uint64, tag="1")]
+    pub min_price_1: u64,
+    /// The minimum amount of asset 2 output per unit of asset 1 input.
+    #[prost(uint64, tag="2")]
+    pub min_price_2: u64,
 }
+/// A set of limit tiers.
 #[derive(::serde::Deserialize, ::serde::Serialize)]
-#[serde(transparent)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct MockFlowCiphertext {
-    /// Represents this transaction's contribution to flow's value.
+pub struct LimitTiers {
+    /// Whether the set contains the tier with no limit.
+    #[prost(bool, tag="1")]
+    pub unlimited: bool,
+    /// The limits on asset 1 output in the set, one bit per power-of-two tier.
+    #[prost(uint64, tag="2")]
+    pub tiers_1: u64,
+    /// The limits on asset 2 output in the set, one bit per power-of-two tier.
+    #[prost(uint64, tag="3")]
+    pub tiers_2: u64,
+}
+/// A flow ciphertext: an amount encrypted to the flow encryption committee,
+/// which can be aggregated homomorphically with other flow ciphertexts.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlowCiphertext {
+    #[prost(message, optional, tag="1")]
+    pub inner: ::core::option::Option<super::super::crypto::v1alpha1::EddyCiphertext>,
+}
+/// The encrypted amounts of each asset of a trading pair being swapped.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapFlow {
+    /// The encrypted amount of asset 1 being exchanged for asset 2.
+    #[prost(message, optional, tag="1")]
+    pub delta_1: ::core::option::Option<FlowCiphertext>,
+    /// The encrypted amount of asset 2 being exchanged for asset 1.
+    #[prost(message, optional, tag="2")]
+    pub delta_2: ::core::option::Option<FlowCiphertext>,
+}
+/// The committee of validators that jointly decrypt batch swap flows.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlowEncryptionCommittee {
+    /// The key that swap flows are encrypted to.
+    #[prost(bytes="vec", tag="1")]
+    #[serde(with = "crate::serializers::hexstr")]
+    pub encryption_key: ::prost::alloc::vec::Vec<u8>,
+    /// The public key share of each member of the committee.
+    #[prost(message, repeated, tag="2")]
+    pub public_key_shares: ::prost::alloc::vec::Vec<flow_encryption_committee::PublicKeyShare>,
+    /// The number of decryption shares required to decrypt a flow.
+    #[prost(uint32, tag="3")]
+    pub threshold: u32,
+}
+/// Nested message and enum types in `FlowEncryptionCommittee`.
+pub mod flow_encryption_committee {
+    #[derive(::serde::Deserialize, ::serde::Serialize)]
+    #[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PublicKeyShare {
+        #[prost(bytes="vec", tag="1")]
+        #[serde(with = "crate::serializers::hexstr")]
+        pub inner: ::prost::alloc::vec::Vec<u8>,
+    }
+}
+/// One committee member's decryption shares for every batch swap flow at a
+/// given height.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchDecryptionShares {
+    /// The height of the batch being decrypted.
     #[prost(uint64, tag="1")]
-    pub value: u64,
+    pub height: u64,
+    /// The index of the committee member that produced the shares.
+    #[prost(uint32, tag="2")]
+    pub participant_index: u32,
+    #[prost(message, repeated, tag="3")]
+    pub flows: ::prost::alloc::vec::Vec<batch_decryption_shares::FlowShares>,
+}
+/// Nested message and enum types in `BatchDecryptionShares`.
+pub mod batch_decryption_shares {
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FlowShares {
+        #[prost(message, optional, tag="1")]
+        pub trading_pair: ::core::option::Option<super::TradingPair>,
+        /// The limit prices of the swaps in the flow.
+        #[prost(message, optional, tag="4")]
+        pub limit_prices: ::core::option::Option<super::LimitPrices>,
+        /// The decryption share of the amount of asset 1.
+        #[prost(message, optional, tag="2")]
+        pub delta_1: ::core::option::Option<super::super::super::crypto::v1alpha1::EddyDecryptionShare>,
+        /// The decryption share of the amount of asset 2.
+        #[prost(message, optional, tag="3")]
+        pub delta_2: ::core::option::Option<super::super::super::crypto::v1alpha1::EddyDecryptionShare>,
+    }
+}
+/// The encrypted batch swap flows of a block, awaiting decryption.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptedBatch {
+    /// The height of the block the swaps were included in.
+    #[prost(uint64, tag="1")]
+    pub height: u64,
+    #[prost(message, repeated, tag="2")]
+    pub flows: ::prost::alloc::vec::Vec<encrypted_batch::Flow>,
+    /// The decryption shares contributed so far.
+    #[prost(message, repeated, tag="3")]
+    pub shares: ::prost::alloc::vec::Vec<BatchDecryptionShares>,
+    /// The swaps that were too few to be decrypted without revealing their
+    /// amounts.
+    #[prost(message, repeated, tag="4")]
+    pub refunds: ::prost::alloc::vec::Vec<encrypted_batch::Refund>,
+}
+/// Nested message and enum types in `EncryptedBatch`.
+pub mod encrypted_batch {
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Flow {
+        #[prost(message, optional, tag="1")]
+        pub trading_pair: ::core::option::Option<super::TradingPair>,
+        /// The limit prices shared by every swap in the flow.
+        #[prost(message, optional, tag="3")]
+        pub limit_prices: ::core::option::Option<super::LimitPrices>,
+        #[prost(message, optional, tag="2")]
+        pub flow: ::core::option::Option<super::SwapFlow>,
+    }
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Refund {
+        #[prost(message, optional, tag="1")]
+        pub trading_pair: ::core::option::Option<super::TradingPair>,
+        /// The limit tiers whose swaps are refunded without being decrypted.
+        #[prost(message, optional, tag="2")]
+        pub tiers: ::core::option::Option<super::LimitTiers>,
+    }
+}
+/// A list of encrypted batches.
+///
+/// Used to track the batches awaiting decryption.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptedBatchList {
+    #[prost(message, repeated, tag="1")]
+    pub batches: ::prost::alloc::vec::Vec<EncryptedBatch>,
 }
 /// Holds two asset IDs. Ordering doesn't reflect trading direction, however
 /// since the `AssetId` type is `Ord + PartialOrd`, there can be only one
@@ -165,6 +324,15 @@ pub struct BatchSwapOutputData {
     /// The trading pair associated with the batch swap.
     #[prost(message, optional, tag="7")]
     pub trading_pair: ::core::option::Option<TradingPair>,
+    /// The highest limit prices of the swaps that were executed.
+    ///
+    /// Swaps with a higher limit were excluded from the batch, and are refunded.
+    #[prost(message, optional, tag="8")]
+    pub limit_thresholds: ::core::option::Option<LimitPrices>,
+    /// The limit tiers whose swaps were too few to be decrypted on their own, and
+    /// are refunded.
+    #[prost(message, optional, tag="9")]
+    pub refunded_tiers: ::core::option::Option<LimitTiers>,
 }
 /// The data describing a trading function.
 ///
@@ -177,17 +345,16 @@ pub struct BatchSwapOutputData {
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TradingFunction {
-    /// NOTE: the use of floats here is a placeholder, so we can stub out the
-    /// implementation and then decide what type of fixed-point, deterministic
-    /// arithmetic should be used.
-    #[prost(double, tag="2")]
-    pub fee: f64,
-    #[prost(double, tag="3")]
-    pub k: f64,
-    #[prost(double, tag="4")]
-    pub p: f64,
-    #[prost(double, tag="5")]
-    pub q: f64,
+    /// The fee charged on the input of each trade, in basis points.
+    #[prost(uint32, tag="2")]
+    pub fee: u32,
+    /// The invariant the position's reserves must satisfy to be traded against.
+    #[prost(message, optional, tag="3")]
+    pub k: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
+    #[prost(message, optional, tag="4")]
+    pub p: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
+    #[prost(message, optional, tag="5")]
+    pub q: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
 }
 /// The reserves of a position.
 ///
@@ -270,6 +437,39 @@ pub mod position_state {
         }
     }
 }
+/// The metadata of a position, recording its current state and reserves.
+///
+/// Unlike the `Position`, which is immutable, the metadata evolves over the
+/// lifetime of the position, as trades are executed against it and as it is
+/// closed and withdrawn.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionMetadata {
+    #[prost(message, optional, tag="1")]
+    pub position: ::core::option::Option<Position>,
+    #[prost(message, optional, tag="2")]
+    pub state: ::core::option::Option<PositionState>,
+    #[prost(message, optional, tag="3")]
+    pub reserves: ::core::option::Option<Reserves>,
+}
+/// A list of position IDs.
+///
+/// Used to index the open positions on a given trading pair.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionIdList {
+    #[prost(message, repeated, tag="1")]
+    pub position_ids: ::prost::alloc::vec::Vec<PositionId>,
+}
+/// A list of trading pairs.
+///
+/// Used to index the trading pairs that have open positions.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TradingPairList {
+    #[prost(message, repeated, tag="1")]
+    pub trading_pairs: ::prost::alloc::vec::Vec<TradingPair>,
+}
 /// An LPNFT tracking both ownership and state of a position.
 ///
 /// Tracking the state as part of the LPNFT means that all LP-related actions can
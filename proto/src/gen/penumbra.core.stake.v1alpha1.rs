@@ -54,6 +54,17 @@ pub struct FundingStream {
     #[prost(uint32, tag="2")]
     pub rate_bps: u32,
 }
+/// Funding streams announced by a validator, which replace its current funding
+/// streams once the given epoch is reached.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PendingFundingStreams {
+    /// The announced funding streams.
+    #[prost(message, repeated, tag="1")]
+    pub funding_streams: ::prost::alloc::vec::Vec<FundingStream>,
+    /// The first epoch whose rates are computed using the announced funding streams.
+    #[prost(uint64, tag="2")]
+    pub effective_epoch: u64,
+}
 /// Describes the reward and exchange rates and voting power for a validator in some epoch.
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -159,6 +170,89 @@ pub mod validator_state {
         }
     }
 }
+/// A record of a slashing penalty applied to a validator.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SlashingEvent {
+    /// The height at which the penalty was applied.
+    #[prost(uint64, tag="1")]
+    pub height: u64,
+    /// The index of the epoch in which the penalty was applied.
+    #[prost(uint64, tag="2")]
+    pub epoch_index: u64,
+    /// The reason the validator was slashed.
+    #[prost(enumeration="slashing_event::SlashingReasonEnum", tag="3")]
+    pub reason: i32,
+    /// For misbehavior, the height at which the reported infraction occurred.
+    #[prost(uint64, tag="4")]
+    pub infraction_height: u64,
+    /// The penalty applied, in basis points.
+    #[prost(uint64, tag="5")]
+    pub penalty_bps: u64,
+    /// The validator's exchange rate before the penalty was applied.
+    #[prost(uint64, tag="6")]
+    pub exchange_rate_before: u64,
+    /// The validator's exchange rate after the penalty was applied.
+    #[prost(uint64, tag="7")]
+    pub exchange_rate_after: u64,
+}
+/// Nested message and enum types in `SlashingEvent`.
+pub mod slashing_event {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum SlashingReasonEnum {
+        /// The validator missed too many blocks and was jailed.
+        Downtime = 0,
+        /// The validator was reported for signing conflicting votes.
+        DuplicateVote = 1,
+        /// The validator was reported for participating in a light client attack.
+        LightClientAttack = 2,
+        /// The validator was reported for misbehavior of a kind unknown to this node.
+        UnknownMisbehavior = 3,
+    }
+    impl SlashingReasonEnum {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                SlashingReasonEnum::Downtime => "DOWNTIME",
+                SlashingReasonEnum::DuplicateVote => "DUPLICATE_VOTE",
+                SlashingReasonEnum::LightClientAttack => "LIGHT_CLIENT_ATTACK",
+                SlashingReasonEnum::UnknownMisbehavior => "UNKNOWN_MISBEHAVIOR",
+            }
+        }
+        /// Creates an enum from field names used in the ProtoBuf definition.
+        pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+            match value {
+                "DOWNTIME" => Some(Self::Downtime),
+                "DUPLICATE_VOTE" => Some(Self::DuplicateVote),
+                "LIGHT_CLIENT_ATTACK" => Some(Self::LightClientAttack),
+                "UNKNOWN_MISBEHAVIOR" => Some(Self::UnknownMisbehavior),
+                _ => None,
+            }
+        }
+    }
+}
+/// All the slashing penalties applied to a validator, oldest first.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SlashingHistory {
+    #[prost(message, repeated, tag="1")]
+    pub events: ::prost::alloc::vec::Vec<SlashingEvent>,
+}
+/// A validator's record of being jailed for downtime.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JailRecord {
+    /// The number of times the validator has been jailed for downtime.
+    #[prost(uint64, tag="1")]
+    pub offense_count: u64,
+    /// The index of the epoch in which the validator was most recently jailed.
+    #[prost(uint64, tag="2")]
+    pub last_jailed_epoch: u64,
+    /// The first epoch in which the validator may be re-enabled after its most recent jailing.
+    #[prost(uint64, tag="3")]
+    pub unjail_epoch: u64,
+}
 /// Combines all validator info into a single packet.
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -226,6 +320,40 @@ pub struct Undelegate {
     #[prost(message, optional, tag="4")]
     pub delegation_amount: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
 }
+/// Moves stake from one validator's delegation pool to another's without
+/// passing through the unbonded staking token.
+///
+/// The destination delegation tokens are quarantined until the source
+/// validator's unbonding period has elapsed, so that they remain slashable for
+/// the source validator's misbehavior during that window.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Redelegate {
+    /// The identity key of the validator to redelegate from.
+    #[prost(message, optional, tag="1")]
+    pub source_validator: ::core::option::Option<super::super::crypto::v1alpha1::IdentityKey>,
+    /// The identity key of the validator to redelegate to.
+    #[prost(message, optional, tag="2")]
+    pub destination_validator: ::core::option::Option<super::super::crypto::v1alpha1::IdentityKey>,
+    /// The index of the epoch in which this redelegation was performed.
+    #[prost(uint64, tag="3")]
+    pub epoch_index: u64,
+    /// The amount of source delegation tokens consumed by this action.
+    #[prost(message, optional, tag="4")]
+    pub source_delegation_amount: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
+    /// The amount being redelegated, in units of unbonded stake.
+    ///
+    /// This is implied by the source validator's exchange rate in the specified
+    /// epoch (and should be checked in transaction validation!).
+    #[prost(message, optional, tag="5")]
+    pub unbonded_amount: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
+    /// The amount of destination delegation tokens produced by this action.
+    ///
+    /// This is implied by the destination validator's exchange rate in the
+    /// specified epoch (and should be checked in transaction validation!).
+    #[prost(message, optional, tag="6")]
+    pub destination_delegation_amount: ::core::option::Option<super::super::crypto::v1alpha1::Amount>,
+}
 /// A commission amount to be minted as part of processing the epoch transition.
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -53,7 +53,7 @@ pub struct TransactionBody {
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Action {
-    #[prost(oneof="action::Action", tags="1, 2, 3, 4, 5, 6, 16, 17, 18, 19, 20, 30, 31, 32, 34, 200")]
+    #[prost(oneof="action::Action", tags="1, 2, 3, 4, 5, 6, 7, 16, 17, 18, 19, 20, 21, 30, 31, 32, 34, 200")]
     pub action: ::core::option::Option<action::Action>,
 }
 /// Nested message and enum types in `Action`.
@@ -73,6 +73,8 @@ pub mod action {
         Swap(super::super::super::dex::v1alpha1::Swap),
         #[prost(message, tag="6")]
         SwapClaim(super::super::super::dex::v1alpha1::SwapClaim),
+        #[prost(message, tag="7")]
+        Redelegate(super::super::super::stake::v1alpha1::Redelegate),
         #[prost(message, tag="16")]
         ValidatorDefinition(super::super::super::stake::v1alpha1::ValidatorDefinition),
         #[prost(message, tag="17")]
@@ -82,9 +84,10 @@ pub mod action {
         ProposalSubmit(super::ProposalSubmit),
         #[prost(message, tag="19")]
         ProposalWithdraw(super::ProposalWithdraw),
-        /// DelegatorVote delegator_vote = 21;
         #[prost(message, tag="20")]
         ValidatorVote(super::ValidatorVote),
+        #[prost(message, tag="21")]
+        DelegatorVote(super::DelegatorVote),
         #[prost(message, tag="30")]
         PositionOpen(super::super::super::dex::v1alpha1::PositionOpen),
         #[prost(message, tag="31")]
@@ -151,7 +154,7 @@ pub struct SwapClaimView {
 /// A view of a specific state change action performed by a transaction.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActionView {
-    #[prost(oneof="action_view::Action", tags="1, 2, 3, 4, 5, 6, 16, 17, 18, 19, 20, 30, 31, 32, 34, 200")]
+    #[prost(oneof="action_view::Action", tags="1, 2, 3, 4, 5, 6, 7, 16, 17, 18, 19, 20, 21, 30, 31, 32, 34, 200")]
     pub action: ::core::option::Option<action_view::Action>,
 }
 /// Nested message and enum types in `ActionView`.
@@ -170,6 +173,8 @@ pub mod action_view {
         Swap(super::SwapView),
         #[prost(message, tag="6")]
         SwapClaim(super::SwapClaimView),
+        #[prost(message, tag="7")]
+        Redelegate(super::super::super::stake::v1alpha1::Redelegate),
         #[prost(message, tag="16")]
         ValidatorDefinition(super::super::super::stake::v1alpha1::ValidatorDefinition),
         #[prost(message, tag="17")]
@@ -179,9 +184,10 @@ pub mod action_view {
         ProposalSubmit(super::ProposalSubmit),
         #[prost(message, tag="19")]
         ProposalWithdraw(super::ProposalWithdraw),
-        /// DelegatorVote delegator_vote = 21;
         #[prost(message, tag="20")]
         ValidatorVote(super::ValidatorVote),
+        #[prost(message, tag="21")]
+        DelegatorVote(super::DelegatorVote),
         #[prost(message, tag="30")]
         PositionOpen(super::super::super::dex::v1alpha1::PositionOpen),
         #[prost(message, tag="31")]
@@ -333,6 +339,7 @@ pub struct DelegatorVote {
     pub auth_sig: ::core::option::Option<super::super::crypto::v1alpha1::SpendAuthSignature>,
     /// The vote proof is effecting data.
     #[prost(bytes="bytes", tag="3")]
+    #[serde(with = "crate::serializers::base64str_bytes")]
     pub proof: ::prost::bytes::Bytes,
 }
 #[derive(::serde::Deserialize, ::serde::Serialize)]
@@ -341,32 +348,25 @@ pub struct DelegatorVoteBody {
     /// The proposal being voted on.
     #[prost(uint64, tag="1")]
     pub proposal: u64,
+    /// The position in the note commitment tree at which the proposal started.
+    ///
+    /// Only notes created before this position may be used to vote.
+    #[prost(uint64, tag="2")]
+    pub start_position: u64,
+    /// The vote.
+    #[prost(message, optional, tag="3")]
+    pub vote: ::core::option::Option<super::super::governance::v1alpha1::Vote>,
+    /// The value of the staked note voted with, which must be a delegation token.
+    #[prost(message, optional, tag="4")]
+    pub value: ::core::option::Option<super::super::crypto::v1alpha1::Value>,
     /// The nullifier of the input note.
-    #[prost(bytes="bytes", tag="3")]
+    #[prost(bytes="bytes", tag="5")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
     pub nullifier: ::prost::bytes::Bytes,
     /// The randomized validating key for the spend authorization signature.
-    #[prost(bytes="bytes", tag="4")]
+    #[prost(bytes="bytes", tag="6")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
     pub rk: ::prost::bytes::Bytes,
-    /// A commitment to the value voted for "yes".
-    ///
-    /// A rational voter will place all their voting weight on one vote.
-    #[prost(message, optional, tag="5")]
-    pub yes_balance_commitment: ::core::option::Option<super::super::crypto::v1alpha1::BalanceCommitment>,
-    /// A commitment to the value voted for "no".
-    ///
-    /// A rational voter will place all their voting weight on one vote.
-    #[prost(message, optional, tag="6")]
-    pub no_balance_commitment: ::core::option::Option<super::super::crypto::v1alpha1::BalanceCommitment>,
-    /// A commitment to the value voted for "abstain".
-    ///
-    /// A rational voter will place all their voting weight on one vote.
-    #[prost(message, optional, tag="7")]
-    pub abstain_balance_commitment: ::core::option::Option<super::super::crypto::v1alpha1::BalanceCommitment>,
-    /// A commitment to the value voted for "no with veto".
-    ///
-    /// A rational voter will place all their voting weight on one vote.
-    #[prost(message, optional, tag="8")]
-    pub no_with_veto_balance_commitment: ::core::option::Option<super::super::crypto::v1alpha1::BalanceCommitment>,
 }
 /// The data required to authorize a transaction plan.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -382,6 +382,10 @@ pub struct AuthorizationData {
     /// ProposalWithdraw actions in the original request.
     #[prost(message, repeated, tag="3")]
     pub withdraw_proposal_auths: ::prost::alloc::vec::Vec<super::super::crypto::v1alpha1::SpendAuthSignature>,
+    /// The required delegator vote authorizations, returned in the same order as the
+    /// DelegatorVote actions in the original request.
+    #[prost(message, repeated, tag="4")]
+    pub delegator_vote_auths: ::prost::alloc::vec::Vec<super::super::crypto::v1alpha1::SpendAuthSignature>,
 }
 /// The data required for proving when building a transaction from a plan.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -418,7 +422,7 @@ pub struct TransactionPlan {
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActionPlan {
-    #[prost(oneof="action_plan::Action", tags="1, 2, 3, 4, 16, 17, 18, 19, 20, 21, 30, 31, 32, 34, 40, 41")]
+    #[prost(oneof="action_plan::Action", tags="1, 2, 3, 4, 7, 16, 17, 18, 19, 20, 21, 30, 31, 32, 34, 40, 41")]
     pub action: ::core::option::Option<action_plan::Action>,
 }
 /// Nested message and enum types in `ActionPlan`.
@@ -438,6 +442,9 @@ pub mod action_plan {
         /// because we don't yet use flow encryption.
         #[prost(message, tag="4")]
         Undelegate(super::super::super::stake::v1alpha1::Undelegate),
+        /// Redelegations are likewise public.
+        #[prost(message, tag="7")]
+        Redelegate(super::super::super::stake::v1alpha1::Redelegate),
         /// This is just a message relayed to the chain.
         #[prost(message, tag="16")]
         ValidatorDefinition(super::super::super::stake::v1alpha1::ValidatorDefinition),
@@ -458,9 +465,9 @@ pub mod action_plan {
         #[prost(message, tag="31")]
         PositionClose(super::super::super::dex::v1alpha1::PositionClose),
         #[prost(message, tag="32")]
-        PositionWithdraw(super::super::super::dex::v1alpha1::PositionWithdraw),
+        PositionWithdraw(super::PositionWithdrawPlan),
         #[prost(message, tag="34")]
-        PositionRewardClaim(super::super::super::dex::v1alpha1::PositionRewardClaim),
+        PositionRewardClaim(super::PositionRewardClaimPlan),
         #[prost(message, tag="40")]
         Swap(super::SwapPlan),
         #[prost(message, tag="41")]
@@ -550,6 +557,23 @@ pub struct SwapPlan {
     #[prost(bytes="bytes", tag="8")]
     #[serde(with = "crate::serializers::hexstr_bytes")]
     pub esk: ::prost::bytes::Bytes,
+    /// The blinding factor for the commitment to the amount of asset 1.
+    #[prost(bytes="bytes", tag="9")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
+    pub delta_1_blinding: ::prost::bytes::Bytes,
+    /// The blinding factor for the commitment to the amount of asset 2.
+    #[prost(bytes="bytes", tag="10")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
+    pub delta_2_blinding: ::prost::bytes::Bytes,
+    /// The key of the flow encryption committee to encrypt the amounts to.
+    #[prost(bytes="bytes", tag="11")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
+    pub flow_encryption_key: ::prost::bytes::Bytes,
+    /// The seed of the randomness used to encrypt the amounts and prove the
+    /// encryptions are well-formed.
+    #[prost(bytes="bytes", tag="12")]
+    #[serde(with = "crate::serializers::hexstr_bytes")]
+    pub flow_encryption_seed: ::prost::bytes::Bytes,
 }
 ///
 /// @exclude
@@ -589,6 +613,34 @@ pub struct SwapClaimPlan {
     #[prost(uint64, tag="20")]
     pub epoch_duration: u64,
 }
+/// Describes a plan for withdrawing funds from a closed position.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionWithdrawPlan {
+    /// The position to withdraw from.
+    #[prost(message, optional, tag="1")]
+    pub position_id: ::core::option::Option<super::super::dex::v1alpha1::PositionId>,
+    /// The trading pair of the position.
+    #[prost(message, optional, tag="2")]
+    pub pair: ::core::option::Option<super::super::dex::v1alpha1::TradingPair>,
+    /// The final reserves of the position, as recorded on-chain.
+    #[prost(message, optional, tag="3")]
+    pub reserves: ::core::option::Option<super::super::dex::v1alpha1::Reserves>,
+}
+/// Describes a plan for claiming the retroactive rewards of a withdrawn position.
+#[derive(::serde::Deserialize, ::serde::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionRewardClaimPlan {
+    /// The position to claim rewards for.
+    #[prost(message, optional, tag="1")]
+    pub position_id: ::core::option::Option<super::super::dex::v1alpha1::PositionId>,
+    /// The trading pair of the position.
+    #[prost(message, optional, tag="2")]
+    pub pair: ::core::option::Option<super::super::dex::v1alpha1::TradingPair>,
+    /// The rewards accumulated by the position, as recorded on-chain.
+    #[prost(message, optional, tag="3")]
+    pub rewards: ::core::option::Option<super::super::dex::v1alpha1::Reserves>,
+}
 #[derive(::serde::Deserialize, ::serde::Serialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ProposalWithdrawPlan {
@@ -617,6 +669,9 @@ pub struct DelegatorVotePlan {
     /// The randomizer to use for the proof of spend capability.
     #[prost(bytes="bytes", tag="5")]
     pub randomizer: ::prost::bytes::Bytes,
+    /// The position in the note commitment tree at which the proposal started.
+    #[prost(uint64, tag="6")]
+    pub start_position: u64,
 }
 // The reader may ask: why is this here, instead of in a separate `governance.v1alpha1.proto` file? It should
 // be, but protos can't have cyclic file dependencies, and most of the below induces a cycle
@@ -763,3 +818,23 @@ pub mod proposal {
         }
     }
 }
+/// The transactions scheduled by passed DAO spend proposals for execution at a particular height, in
+/// the order they will be executed.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DaoTransactions {
+    #[prost(message, repeated, tag="1")]
+    pub transactions: ::prost::alloc::vec::Vec<dao_transactions::ScheduledTransaction>,
+}
+/// Nested message and enum types in `DaoTransactions`.
+pub mod dao_transactions {
+    /// A transaction scheduled for execution, along with the proposal which scheduled it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ScheduledTransaction {
+        /// The proposal which scheduled the transaction.
+        #[prost(uint64, tag="1")]
+        pub proposal_id: u64,
+        /// The transaction to be executed.
+        #[prost(message, optional, tag="2")]
+        pub transaction: ::core::option::Option<super::TransactionPlan>,
+    }
+}
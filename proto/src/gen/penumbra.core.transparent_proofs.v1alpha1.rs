@@ -18,6 +18,24 @@ pub struct SpendProof {
     #[prost(bytes="vec", tag="11")]
     pub nk: ::prost::alloc::vec::Vec<u8>,
 }
+/// A Penumbra transparent delegator vote proof.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DelegatorVoteProof {
+    /// Auxiliary inputs
+    #[prost(message, optional, tag="1")]
+    pub note_commitment_proof: ::core::option::Option<super::super::crypto::v1alpha1::NoteCommitmentProof>,
+    /// *
+    /// @exclude
+    /// From the staked note being voted with
+    #[prost(message, optional, tag="2")]
+    pub note: ::core::option::Option<super::super::crypto::v1alpha1::Note>,
+    #[prost(bytes="vec", tag="3")]
+    pub spend_auth_randomizer: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="4")]
+    pub ak: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="5")]
+    pub nk: ::prost::alloc::vec::Vec<u8>,
+}
 /// A Penumbra transparent output proof.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OutputProof {
@@ -57,6 +75,9 @@ pub struct SwapClaimProof {
     pub delta_1_i: u64,
     #[prost(uint64, tag="12")]
     pub delta_2_i: u64,
+    /// The swap's limit prices
+    #[prost(message, optional, tag="13")]
+    pub limit_prices: ::core::option::Option<super::super::dex::v1alpha1::LimitPrices>,
     /// *
     /// @exclude
     /// Describes output amounts
@@ -104,14 +125,23 @@ pub struct SwapProof {
     /// Fee blinding factor.
     #[prost(bytes="vec", tag="11")]
     pub fee_blinding: ::prost::alloc::vec::Vec<u8>,
+    /// *
+    /// @exclude
+    /// Blinding factors for value commitments
+    #[prost(bytes="vec", tag="20")]
+    pub delta_1_blinding: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="21")]
+    pub delta_2_blinding: ::prost::alloc::vec::Vec<u8>,
     // *
     // @exclude
-    // Blinding factors for value commitments
-    // TODO: not included until flow encryption is available
-
-    // bytes delta_1_blinding = 20;
-    // bytes delta_2_blinding = 21;
+    // Openings of the flow ciphertexts
 
+    /// Opening of the encryption of the amount of asset 1
+    #[prost(message, optional, tag="22")]
+    pub enc_delta_1_opening: ::core::option::Option<super::super::crypto::v1alpha1::EddyTransparentEncryptionProof>,
+    /// Opening of the encryption of the amount of asset 2
+    #[prost(message, optional, tag="23")]
+    pub enc_delta_2_opening: ::core::option::Option<super::super::crypto::v1alpha1::EddyTransparentEncryptionProof>,
     // *
     // @exclude
     // Swap NFT data.
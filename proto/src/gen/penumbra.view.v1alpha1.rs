@@ -187,6 +187,26 @@ pub struct NullifierStatusResponse {
     #[prost(bool, tag="1")]
     pub spent: bool,
 }
+/// A query for unclaimed swaps whose batch has been executed.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimableSwapsRequest {
+    /// Identifies the FVK for the swaps to query.
+    #[prost(message, optional, tag="1")]
+    pub account_id: ::core::option::Option<super::super::core::crypto::v1alpha1::AccountId>,
+}
+/// An unclaimed swap, with everything needed to claim it.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClaimableSwapRecord {
+    /// The swap NFT note, which is consumed when the swap is claimed.
+    #[prost(message, optional, tag="1")]
+    pub swap_nft: ::core::option::Option<SpendableNoteRecord>,
+    /// The decrypted plaintext of the swap.
+    #[prost(message, optional, tag="2")]
+    pub swap_plaintext: ::core::option::Option<super::super::core::dex::v1alpha1::SwapPlaintext>,
+    /// The output data of the batch the swap was executed in.
+    #[prost(message, optional, tag="3")]
+    pub output_data: ::core::option::Option<super::super::core::dex::v1alpha1::BatchSwapOutputData>,
+}
 /// Generated client implementations.
 pub mod view_protocol_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -545,6 +565,29 @@ pub mod view_protocol_client {
             );
             self.inner.server_streaming(request.into_request(), path, codec).await
         }
+        /// Queries for unclaimed swaps whose batch has been executed, so that they can be claimed.
+        pub async fn claimable_swaps(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ClaimableSwapsRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::ClaimableSwapRecord>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/penumbra.view.v1alpha1.ViewProtocol/ClaimableSwaps",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -669,6 +712,17 @@ pub mod view_protocol_server {
             &self,
             request: tonic::Request<super::TransactionsRequest>,
         ) -> Result<tonic::Response<Self::TransactionsStream>, tonic::Status>;
+        /// Server streaming response type for the ClaimableSwaps method.
+        type ClaimableSwapsStream: futures_core::Stream<
+                Item = Result<super::ClaimableSwapRecord, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /// Queries for unclaimed swaps whose batch has been executed, so that they can be claimed.
+        async fn claimable_swaps(
+            &self,
+            request: tonic::Request<super::ClaimableSwapsRequest>,
+        ) -> Result<tonic::Response<Self::ClaimableSwapsStream>, tonic::Status>;
     }
     /// The view protocol is used by a view client, who wants to do some
     /// transaction-related actions, to request data from a view service, which is
@@ -1217,6 +1271,47 @@ pub mod view_protocol_server {
                     };
                     Box::pin(fut)
                 }
+                "/penumbra.view.v1alpha1.ViewProtocol/ClaimableSwaps" => {
+                    #[allow(non_camel_case_types)]
+                    struct ClaimableSwapsSvc<T: ViewProtocol>(pub Arc<T>);
+                    impl<
+                        T: ViewProtocol,
+                    > tonic::server::ServerStreamingService<super::ClaimableSwapsRequest>
+                    for ClaimableSwapsSvc<T> {
+                        type Response = super::ClaimableSwapRecord;
+                        type ResponseStream = T::ClaimableSwapsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ClaimableSwapsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).claimable_swaps(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ClaimableSwapsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -104,6 +104,26 @@ pub mod client {
             /// Prefer `key_domain` when applicable, because this gets the validated domain type,
             /// rather than just the raw translation of the protobuf.
             pub async fn key_proto<P>(&mut self, key: impl AsRef<str>) -> anyhow::Result<P>
+            where
+                P: prost::Message + Default + From<P>,
+                C: tonic::client::GrpcService<BoxBody> + 'static,
+                C::ResponseBody: Send,
+                <C as tonic::client::GrpcService<BoxBody>>::ResponseBody:
+                    tonic::codegen::Body<Data = bytes::Bytes>,
+                <C::ResponseBody as Body>::Error: Into<StdError> + Send,
+            {
+                self.key_proto_at_height(key, 0).await
+            }
+
+            /// Like `key_proto`, but fetches the value as of the given height, or the latest
+            /// height if it is 0.
+            ///
+            /// Returns an error if the key is not present in the state at that height.
+            pub async fn key_proto_at_height<P>(
+                &mut self,
+                key: impl AsRef<str>,
+                height: u64,
+            ) -> anyhow::Result<P>
             where
                 P: prost::Message + Default + From<P>,
                 C: tonic::client::GrpcService<BoxBody> + 'static,
@@ -114,16 +134,41 @@ pub mod client {
             {
                 let request = KeyValueRequest {
                     key: key.as_ref().as_bytes().to_vec(),
+                    height,
                     ..Default::default()
                 };
 
-                let t = P::decode(self.key_value(request).await?.into_inner().value.as_slice())?;
+                let value =
+                    present_value(key.as_ref(), self.key_value(request).await?.into_inner())?;
+                let t = P::decode(value.as_slice())?;
 
                 Ok(t)
             }
 
             /// Get the typed domain value corresponding to a state key.
             pub async fn key_domain<T, P>(&mut self, key: impl AsRef<str>) -> anyhow::Result<T>
+            where
+                T: crate::Protobuf<P> + TryFrom<P>,
+                T::Error: Into<anyhow::Error> + Send + Sync + 'static,
+                P: prost::Message + Default + From<T>,
+                C: tonic::client::GrpcService<BoxBody> + 'static,
+                C::ResponseBody: Send,
+                <C as tonic::client::GrpcService<BoxBody>>::ResponseBody:
+                    tonic::codegen::Body<Data = bytes::Bytes>,
+                <C::ResponseBody as Body>::Error: Into<StdError> + Send,
+            {
+                self.key_domain_at_height(key, 0).await
+            }
+
+            /// Like `key_domain`, but fetches the value as of the given height, or the latest
+            /// height if it is 0.
+            ///
+            /// Returns an error if the key is not present in the state at that height.
+            pub async fn key_domain_at_height<T, P>(
+                &mut self,
+                key: impl AsRef<str>,
+                height: u64,
+            ) -> anyhow::Result<T>
             where
                 T: crate::Protobuf<P> + TryFrom<P>,
                 T::Error: Into<anyhow::Error> + Send + Sync + 'static,
@@ -136,14 +181,61 @@ pub mod client {
             {
                 let request = KeyValueRequest {
                     key: key.as_ref().as_bytes().to_vec(),
+                    height,
                     ..Default::default()
                 };
 
-                let t = T::decode(self.key_value(request).await?.into_inner().value.as_slice())?;
+                let value =
+                    present_value(key.as_ref(), self.key_value(request).await?.into_inner())?;
+                let t = T::decode(value.as_slice())?;
 
                 Ok(t)
             }
         }
+
+        /// Get the value out of a `KeyValueResponse`, or an error if the key is absent.
+        ///
+        /// The value alone can't tell these apart, since many present values (such as a
+        /// zero `u64`) encode to no bytes at all.
+        fn present_value(key: &str, rsp: KeyValueResponse) -> anyhow::Result<Vec<u8>> {
+            if !rsp.exists {
+                return Err(anyhow::anyhow!(
+                    "key {} is not present in the state at height {}",
+                    key,
+                    rsp.height
+                ));
+            }
+            Ok(rsp.value)
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn absent_key_is_an_error() {
+                let rsp = KeyValueResponse {
+                    height: 10,
+                    exists: false,
+                    ..Default::default()
+                };
+                assert!(present_value("governance/latest_proposal_id", rsp).is_err());
+            }
+
+            #[test]
+            fn present_empty_value_decodes_to_default() {
+                let rsp = KeyValueResponse {
+                    height: 10,
+                    exists: true,
+                    ..Default::default()
+                };
+                let value = present_value("governance/latest_proposal_id", rsp).unwrap();
+                assert_eq!(
+                    <u64 as prost::Message>::decode(value.as_slice()).unwrap(),
+                    0
+                );
+            }
+        }
     }
 }
 
@@ -110,6 +110,80 @@ impl TryFrom<ProtoClue> for Clue {
     }
 }
 
+// Flow encryption
+use crate::core::crypto::v1alpha1::{
+    EddyCiphertext, EddyDecryptionShare, EddyEncryptionProof, EddyTransparentEncryptionProof,
+};
+use penumbra_eddy::{
+    proofs::{EncryptionProof, TransparentEncryptionProof},
+    Ciphertext, DecryptionShare, Unverified,
+};
+
+impl Protobuf<EddyCiphertext> for Ciphertext {}
+impl Protobuf<EddyDecryptionShare> for DecryptionShare<Unverified> {}
+impl Protobuf<EddyEncryptionProof> for EncryptionProof {}
+impl Protobuf<EddyTransparentEncryptionProof> for TransparentEncryptionProof {}
+
+impl From<Ciphertext> for EddyCiphertext {
+    fn from(ctxt: Ciphertext) -> Self {
+        Self {
+            inner: ctxt.to_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<DecryptionShare<Unverified>> for EddyDecryptionShare {
+    fn from(share: DecryptionShare<Unverified>) -> Self {
+        Self {
+            inner: share.to_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<EncryptionProof> for EddyEncryptionProof {
+    fn from(proof: EncryptionProof) -> Self {
+        Self {
+            inner: proof.to_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<TransparentEncryptionProof> for EddyTransparentEncryptionProof {
+    fn from(proof: TransparentEncryptionProof) -> Self {
+        Self {
+            inner: proof.to_bytes().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<EddyCiphertext> for Ciphertext {
+    type Error = anyhow::Error;
+    fn try_from(value: EddyCiphertext) -> Result<Self, Self::Error> {
+        value.inner.as_slice().try_into()
+    }
+}
+
+impl TryFrom<EddyDecryptionShare> for DecryptionShare<Unverified> {
+    type Error = anyhow::Error;
+    fn try_from(value: EddyDecryptionShare) -> Result<Self, Self::Error> {
+        value.inner.as_slice().try_into()
+    }
+}
+
+impl TryFrom<EddyEncryptionProof> for EncryptionProof {
+    type Error = anyhow::Error;
+    fn try_from(value: EddyEncryptionProof) -> Result<Self, Self::Error> {
+        value.inner.as_slice().try_into()
+    }
+}
+
+impl TryFrom<EddyTransparentEncryptionProof> for TransparentEncryptionProof {
+    type Error = anyhow::Error;
+    fn try_from(value: EddyTransparentEncryptionProof) -> Result<Self, Self::Error> {
+        value.inner.as_slice().try_into()
+    }
+}
+
 // Consensus key
 //
 // The tendermint-rs PublicKey type already has a tendermint-proto type;
@@ -1,5 +1,6 @@
+use anyhow::anyhow;
 use ibc::core::ics23_commitment::{commitment::CommitmentPrefix, specs::ProofSpecs};
-use jmt::{storage::TreeReader, RootHash};
+use jmt::{proof::SparseMerkleProof, storage::TreeReader, KeyHash, RootHash};
 use once_cell::sync::Lazy;
 use penumbra_proto::Message;
 use sha2::{Digest, Sha256};
@@ -108,6 +109,67 @@ pub async fn get_with_proof<'a, R: TreeReader>(
     ))
 }
 
+/// given a JMT, a key, and a height, return the value of the key (if any), the JMT root at that
+/// height, and a [`SparseMerkleProof`] of the key's inclusion or non-inclusion under that root.
+pub async fn get_with_sparse_proof<'a, R: TreeReader>(
+    store: &jmt::JellyfishMerkleTree<'a, R>,
+    key: &[u8],
+    height: u64,
+) -> anyhow::Result<(Option<Vec<u8>>, RootHash, SparseMerkleProof)> {
+    let jmt_root = store.get_root_hash(height).await?;
+    let (value, proof) = store.get_with_proof(KeyHash::from(key), height).await?;
+
+    Ok((value, jmt_root, proof))
+}
+
+/// given a JMT, a key, and a height, return the value of the key (if any), along with an ics23
+/// existence proof if the key is present, and the JMT root and a [`SparseMerkleProof`] of the
+/// key's inclusion or non-inclusion under that root.
+///
+/// ics23 proofs can only be constructed for keys which are present, so absent keys are proven only
+/// by the sparse non-inclusion proof.
+pub async fn get_with_proofs<'a, R: TreeReader>(
+    store: &jmt::JellyfishMerkleTree<'a, R>,
+    key: &[u8],
+    height: u64,
+) -> anyhow::Result<(
+    Option<Vec<u8>>,
+    Option<ics23::ExistenceProof>,
+    RootHash,
+    SparseMerkleProof,
+)> {
+    let (value, jmt_root, sparse_proof) = get_with_sparse_proof(store, key, height).await?;
+    let existence_proof = match value {
+        Some(_) => Some(store.get_with_ics23_proof(key.to_vec(), height).await?),
+        None => None,
+    };
+
+    Ok((value, existence_proof, jmt_root, sparse_proof))
+}
+
+/// Verifies a [`SparseMerkleProof`] returned by [`get_with_sparse_proof`] all the way up to an
+/// AppHash, e.g. the one committed to in a Tendermint header.
+///
+/// If `value` is `None`, this checks that the key is *not* present in the tree.
+pub fn verify_sparse_proof(
+    app_hash: &[u8],
+    jmt_root: RootHash,
+    key: &[u8],
+    value: Option<&[u8]>,
+    proof: &SparseMerkleProof,
+) -> anyhow::Result<()> {
+    let expected = AppHash::from(jmt_root);
+    if expected.0[..] != *app_hash {
+        return Err(anyhow!(
+            "jmt root {} does not match app hash {}",
+            hex::encode(jmt_root.0),
+            hex::encode(app_hash)
+        ));
+    }
+
+    proof.verify(jmt_root, KeyHash::from(key), value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -146,4 +208,60 @@ mod tests {
             .verify_membership(&PENUMBRA_PROOF_SPECS, root, merkle_path, val2, 0)
             .expect("couldn't verify chained merkle proof");
     }
+
+    #[tokio::test]
+    async fn test_sparse_proof() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("sparse-proof-test.db");
+        let storage = Storage::load(file_path).await.unwrap();
+        let state = storage.state().await.unwrap();
+
+        state.put_proto::<u64>(b"foo-key".into(), 1).await;
+        let (jmt_root, height) = state.write().await.commit(storage.clone()).await.unwrap();
+        let app_root: AppHash = jmt_root.into();
+        let store = jmt::JellyfishMerkleTree::new(&storage);
+
+        let (value, root, proof) = get_with_sparse_proof(&store, b"foo-key", height)
+            .await
+            .unwrap();
+        let value = value.expect("key is present");
+        assert_eq!(root, jmt_root);
+        verify_sparse_proof(&app_root.0, root, b"foo-key", Some(&value), &proof)
+            .expect("couldn't verify inclusion proof");
+        assert!(verify_sparse_proof(&app_root.0, root, b"foo-key", None, &proof).is_err());
+        assert!(verify_sparse_proof(&[0u8; 32], root, b"foo-key", Some(&value), &proof).is_err());
+
+        let (value, root, proof) = get_with_sparse_proof(&store, b"bar-key", height)
+            .await
+            .unwrap();
+        assert!(value.is_none());
+        verify_sparse_proof(&app_root.0, root, b"bar-key", None, &proof)
+            .expect("couldn't verify non-inclusion proof");
+    }
+
+    #[tokio::test]
+    async fn test_absent_key_proofs() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("absent-key-test.db");
+        let storage = Storage::load(file_path).await.unwrap();
+        let state = storage.state().await.unwrap();
+
+        state.put_proto::<u64>(b"foo-key".into(), 1).await;
+        let (jmt_root, height) = state.write().await.commit(storage.clone()).await.unwrap();
+        let app_root: AppHash = jmt_root.into();
+        let store = jmt::JellyfishMerkleTree::new(&storage);
+
+        let (value, existence_proof, root, proof) = get_with_proofs(&store, b"bar-key", height)
+            .await
+            .expect("querying an absent key succeeds");
+        assert!(value.is_none());
+        assert!(existence_proof.is_none());
+        verify_sparse_proof(&app_root.0, root, b"bar-key", None, &proof)
+            .expect("couldn't verify non-inclusion proof");
+
+        let (value, existence_proof, _, _) =
+            get_with_proofs(&store, b"foo-key", height).await.unwrap();
+        let existence_proof = existence_proof.expect("present key has an existence proof");
+        assert_eq!(Some(existence_proof.value), value);
+    }
 }
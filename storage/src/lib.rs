@@ -7,12 +7,16 @@ use tokio::sync::RwLock;
 
 mod app_hash;
 mod metrics;
+mod nct;
 mod overlay_ext;
 mod storage;
 
 pub use crate::metrics::register_metrics;
-pub use app_hash::{get_with_proof, AppHash, PENUMBRA_COMMITMENT_PREFIX, PENUMBRA_PROOF_SPECS};
+pub use app_hash::{
+    get_with_proof, get_with_proofs, get_with_sparse_proof, verify_sparse_proof, AppHash,
+    PENUMBRA_COMMITMENT_PREFIX, PENUMBRA_PROOF_SPECS,
+};
 pub use overlay_ext::StateExt;
-pub use storage::Storage;
+pub use storage::{PruneStats, Storage};
 
 pub type State = Arc<RwLock<WriteOverlay<Storage>>>;
@@ -15,12 +15,28 @@ pub use metrics::*;
 
 /// Registers all metrics used by this crate.
 pub fn register_metrics() {
-    register_gauge!(TCT_SIZE_BYTES);
+    register_gauge!(TCT_WRITE_BYTES);
     describe_gauge!(
-        TCT_SIZE_BYTES,
+        TCT_WRITE_BYTES,
         Unit::Bytes,
-        "The size of the serialized TCT in bytes"
+        "The size of the TCT changes written at the last commit in bytes"
+    );
+
+    register_counter!(PRUNED_JMT_NODES_TOTAL);
+    describe_counter!(
+        PRUNED_JMT_NODES_TOTAL,
+        Unit::Count,
+        "The number of stale JMT nodes deleted by pruning"
+    );
+
+    register_counter!(PRUNED_BYTES_TOTAL);
+    describe_counter!(
+        PRUNED_BYTES_TOTAL,
+        Unit::Bytes,
+        "The number of bytes of stale JMT nodes deleted by pruning"
     );
 }
 
-pub const TCT_SIZE_BYTES: &str = "penumbra_storage_tct_size_bytes";
+pub const TCT_WRITE_BYTES: &str = "penumbra_storage_tct_write_bytes";
+pub const PRUNED_JMT_NODES_TOTAL: &str = "penumbra_storage_pruned_jmt_nodes_total";
+pub const PRUNED_BYTES_TOTAL: &str = "penumbra_storage_pruned_bytes_total";
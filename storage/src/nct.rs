@@ -0,0 +1,288 @@
+use std::{ops::Range, pin::Pin, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::{stream, Stream};
+use rocksdb::{WriteBatch, DB};
+use tracing::Span;
+
+use penumbra_tct::{
+    self as tct,
+    storage::{Read, StoredPosition, Write},
+    structure::Hash,
+    Commitment, Forgotten, Position,
+};
+
+/// The key of the NCT in the legacy format, where the whole tree was
+/// bincode-serialized into a single value.
+pub(crate) const LEGACY_TCT_KEY: &[u8] = b"tct";
+
+const POSITION_KEY: &[u8] = b"position";
+const FORGOTTEN_KEY: &[u8] = b"forgotten";
+const HASH_PREFIX: u8 = b'h';
+const COMMITMENT_PREFIX: u8 = b'c';
+
+/// Hashes are keyed by height first, so that the hashes below a given height
+/// in a range of positions can be deleted with one range deletion per height.
+fn hash_key(height: u8, position: Position) -> Vec<u8> {
+    let mut key = vec![HASH_PREFIX, height];
+    key.extend_from_slice(&u64::from(position).to_be_bytes());
+    key
+}
+
+fn commitment_key(position: Position) -> Vec<u8> {
+    let mut key = vec![COMMITMENT_PREFIX];
+    key.extend_from_slice(&u64::from(position).to_be_bytes());
+    key
+}
+
+fn position_from_bytes(bytes: &[u8]) -> Result<Position> {
+    Ok(u64::from_be_bytes(
+        bytes
+            .try_into()
+            .map_err(|_| anyhow!("position was of incorrect length"))?,
+    )
+    .into())
+}
+
+/// Incrementally stores the NCT in the `nct` column family, using the TCT's
+/// [`Read`] and [`Write`] storage interface.
+///
+/// Writes are buffered into a single [`WriteBatch`], which is only applied to
+/// the database by [`NctStore::commit`], so that the stored NCT is never left
+/// partially updated.
+pub(crate) struct NctStore {
+    db: Arc<DB>,
+    position: StoredPosition,
+    forgotten: Forgotten,
+    hashes: Vec<(Position, u8, Hash)>,
+    commitments: Vec<(Position, Commitment)>,
+    batch: WriteBatch,
+}
+
+impl NctStore {
+    /// Loads the stored position and forgotten version of the NCT, which is
+    /// all that's needed to incrementally write to it.
+    pub async fn for_writing(db: Arc<DB>) -> Result<Self> {
+        Self::load(db, false).await
+    }
+
+    /// Loads the entire stored NCT, so that it can be deserialized.
+    pub async fn for_reading(db: Arc<DB>) -> Result<Self> {
+        Self::load(db, true).await
+    }
+
+    async fn load(db: Arc<DB>, load_tree: bool) -> Result<Self> {
+        let span = Span::current();
+        tokio::task::Builder::new()
+            .name("load_nct")
+            .spawn_blocking(move || {
+                span.in_scope(|| {
+                    let nct_cf = db.cf_handle("nct").expect("nct column family not found");
+
+                    let position = match db.get_cf(nct_cf, POSITION_KEY)? {
+                        Some(bytes) => bincode::deserialize::<Option<u64>>(&bytes)?
+                            .map(Position::from)
+                            .into(),
+                        None => StoredPosition::default(),
+                    };
+                    let forgotten = match db.get_cf(nct_cf, FORGOTTEN_KEY)? {
+                        Some(bytes) => u64::from_be_bytes(
+                            bytes
+                                .try_into()
+                                .map_err(|_| anyhow!("forgotten was of incorrect length"))?,
+                        )
+                        .into(),
+                        None => Forgotten::default(),
+                    };
+
+                    let mut hashes = Vec::new();
+                    let mut commitments = Vec::new();
+                    if load_tree {
+                        let mut iter = db.raw_iterator_cf(nct_cf);
+                        iter.seek([HASH_PREFIX]);
+                        while iter.valid() {
+                            let key = iter.key().unwrap();
+                            if key[0] != HASH_PREFIX {
+                                break;
+                            }
+                            let hash = <[u8; 32]>::try_from(iter.value().unwrap())
+                                .map_err(|_| anyhow!("hash was of incorrect length"))?;
+                            hashes.push((
+                                position_from_bytes(&key[2..])?,
+                                key[1],
+                                Hash::from_bytes(hash)?,
+                            ));
+                            iter.next();
+                        }
+                        iter.status()?;
+
+                        let mut iter = db.raw_iterator_cf(nct_cf);
+                        iter.seek([COMMITMENT_PREFIX]);
+                        while iter.valid() {
+                            let key = iter.key().unwrap();
+                            if key[0] != COMMITMENT_PREFIX {
+                                break;
+                            }
+                            let commitment = <[u8; 32]>::try_from(iter.value().unwrap())
+                                .map_err(|_| anyhow!("commitment was of incorrect length"))?;
+                            commitments.push((
+                                position_from_bytes(&key[1..])?,
+                                Commitment::try_from(commitment)?,
+                            ));
+                            iter.next();
+                        }
+                        iter.status()?;
+                    }
+
+                    Ok(Self {
+                        db: db.clone(),
+                        position,
+                        forgotten,
+                        hashes,
+                        commitments,
+                        batch: WriteBatch::default(),
+                    })
+                })
+            })
+            .unwrap()
+            .await?
+    }
+
+    /// Applies all the buffered writes to the database, returning the number
+    /// of bytes written.
+    pub async fn commit(self) -> Result<usize> {
+        let NctStore { db, mut batch, .. } = self;
+        let span = Span::current();
+        tokio::task::Builder::new()
+            .name("commit_nct")
+            .spawn_blocking(move || {
+                span.in_scope(|| {
+                    let nct_cf = db.cf_handle("nct").expect("nct column family not found");
+                    // The tree is now stored incrementally, so any copy left in
+                    // the legacy format is stale.
+                    batch.delete_cf(nct_cf, LEGACY_TCT_KEY);
+                    let size = batch.size_in_bytes();
+                    db.write(batch)?;
+                    Ok(size)
+                })
+            })
+            .unwrap()
+            .await?
+    }
+}
+
+#[async_trait]
+impl Read for NctStore {
+    type Error = anyhow::Error;
+
+    async fn position(&mut self) -> Result<StoredPosition, Self::Error> {
+        Ok(self.position)
+    }
+
+    async fn forgotten(&mut self) -> Result<Forgotten, Self::Error> {
+        Ok(self.forgotten)
+    }
+
+    fn hashes(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<(Position, u8, Hash), Self::Error>> + Send + '_>> {
+        Box::pin(stream::iter(self.hashes.iter().copied().map(Ok)))
+    }
+
+    fn commitments(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<(Position, Commitment), Self::Error>> + Send + '_>> {
+        Box::pin(stream::iter(self.commitments.iter().copied().map(Ok)))
+    }
+}
+
+#[async_trait]
+impl Write for NctStore {
+    async fn set_position(&mut self, position: StoredPosition) -> Result<(), Self::Error> {
+        let bytes = bincode::serialize(&Option::<Position>::from(position).map(u64::from))?;
+        let nct_cf = self
+            .db
+            .cf_handle("nct")
+            .expect("nct column family not found");
+        self.batch.put_cf(nct_cf, POSITION_KEY, bytes);
+        self.position = position;
+        Ok(())
+    }
+
+    async fn set_forgotten(&mut self, forgotten: Forgotten) -> Result<(), Self::Error> {
+        let nct_cf = self
+            .db
+            .cf_handle("nct")
+            .expect("nct column family not found");
+        self.batch
+            .put_cf(nct_cf, FORGOTTEN_KEY, u64::from(forgotten).to_be_bytes());
+        self.forgotten = forgotten;
+        Ok(())
+    }
+
+    async fn add_hash(
+        &mut self,
+        position: Position,
+        height: u8,
+        hash: Hash,
+        _essential: bool,
+    ) -> Result<(), Self::Error> {
+        let nct_cf = self
+            .db
+            .cf_handle("nct")
+            .expect("nct column family not found");
+        self.batch
+            .put_cf(nct_cf, hash_key(height, position), hash.to_bytes());
+        Ok(())
+    }
+
+    async fn add_commitment(
+        &mut self,
+        position: Position,
+        commitment: Commitment,
+    ) -> Result<(), Self::Error> {
+        let nct_cf = self
+            .db
+            .cf_handle("nct")
+            .expect("nct column family not found");
+        self.batch.put_cf(
+            nct_cf,
+            commitment_key(position),
+            <[u8; 32]>::from(commitment),
+        );
+        Ok(())
+    }
+
+    async fn delete_range(
+        &mut self,
+        below_height: u8,
+        positions: Range<Position>,
+    ) -> Result<(), Self::Error> {
+        let nct_cf = self
+            .db
+            .cf_handle("nct")
+            .expect("nct column family not found");
+        for height in 0..below_height {
+            self.batch.delete_range_cf(
+                nct_cf,
+                hash_key(height, positions.start),
+                hash_key(height, positions.end),
+            );
+        }
+        self.batch.delete_range_cf(
+            nct_cf,
+            commitment_key(positions.start),
+            commitment_key(positions.end),
+        );
+        Ok(())
+    }
+}
+
+/// Returns the NCT stored in the legacy format, if there is one.
+pub(crate) fn get_legacy(db: &DB) -> Result<Option<tct::Tree>> {
+    let nct_cf = db.cf_handle("nct").expect("nct column family not found");
+    db.get_cf(nct_cf, LEGACY_TCT_KEY)?
+        .map(|tct_bytes| Ok(bincode::deserialize(&tct_bytes)?))
+        .transpose()
+}
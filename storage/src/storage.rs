@@ -1,23 +1,39 @@
 use std::{path::PathBuf, sync::Arc};
 
-use ::metrics::gauge;
+use ::metrics::{counter, gauge};
 use anyhow::Result;
 use futures::future::BoxFuture;
 use jmt::{
     storage::{Node, NodeBatch, NodeKey, TreeReader, TreeWriter},
-    WriteOverlay,
+    types::nibble::Nibble,
+    KeyHash, WriteOverlay,
 };
-use rocksdb::{Options, DB};
-use tokio::sync::RwLock;
+use rocksdb::{checkpoint::Checkpoint, ColumnFamily, Options, WriteBatch, DB};
+use tokio::sync::{mpsc, RwLock};
 use tracing::Span;
 
 use penumbra_tct as tct;
 
-use crate::{metrics, State};
+use crate::{metrics, nct::NctStore, AppHash, State};
 
 #[derive(Clone, Debug)]
 pub struct Storage(Arc<DB>);
 
+/// The number of deletions [`Storage::prune`] writes to the database at once.
+const PRUNE_BATCH_SIZE: usize = 10_000;
+
+/// The number of leaves [`Storage::snapshot_leaves`] reads ahead of its consumer.
+const SNAPSHOT_LEAF_BUFFER: usize = 1024;
+
+/// Statistics about the JMT nodes deleted by [`Storage::prune`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PruneStats {
+    /// The number of nodes deleted.
+    pub nodes: u64,
+    /// The total size of the deleted keys and values, in bytes.
+    pub bytes: u64,
+}
+
 impl Storage {
     pub async fn load(path: PathBuf) -> Result<Self> {
         let span = Span::current();
@@ -63,6 +79,36 @@ impl Storage {
         ))))
     }
 
+    /// Returns a new [`State`] on top of a past version of the tree.
+    ///
+    /// Errors if the version is later than the latest version, or is no longer stored.
+    pub async fn state_at(&self, version: jmt::Version) -> Result<State> {
+        let latest_version = self
+            .latest_version()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("storage is empty"))?;
+        if version > latest_version {
+            return Err(anyhow::anyhow!(
+                "version {} is later than the latest version {}",
+                version,
+                latest_version
+            ));
+        }
+        if jmt::JellyfishMerkleTree::new(self)
+            .get_root_hash_option(version)
+            .await?
+            .is_none()
+        {
+            return Err(anyhow::anyhow!("version {} is not stored", version));
+        }
+
+        tracing::debug!("creating state for version {}", version);
+        Ok(Arc::new(RwLock::new(WriteOverlay::new(
+            self.clone(),
+            version,
+        ))))
+    }
+
     /// Like [`Self::state`], but bundles in a [`tonic`] error conversion.
     ///
     /// This is useful for implementing gRPC services that query the storage:
@@ -75,21 +121,17 @@ impl Storage {
             .map_err(|e| tonic::Status::internal(e.to_string()))
     }
 
-    pub async fn put_nct(&self, tct: &tct::Tree) -> Result<()> {
+    /// Exports a consistent copy of the entire database, including the JMT and the NCT, to a new
+    /// RocksDB database at the given path, which must not already exist.
+    pub async fn export(&self, path: PathBuf) -> Result<()> {
         let db = self.0.clone();
-
-        tracing::debug!("serializing TCT");
-        let tct_data = bincode::serialize(tct)?;
-        tracing::debug!(tct_bytes = tct_data.len(), "serialized TCT");
-        gauge!(metrics::TCT_SIZE_BYTES, tct_data.len() as f64);
-
         let span = Span::current();
         tokio::task::Builder::new()
-            .name("put_nct")
+            .name("export_rocksdb")
             .spawn_blocking(move || {
                 span.in_scope(|| {
-                    let nct_cf = db.cf_handle("nct").expect("nct column family not found");
-                    db.put_cf(nct_cf, "tct", &tct_data)?;
+                    tracing::info!(?path, "exporting rocksdb checkpoint");
+                    Checkpoint::new(&db)?.create_checkpoint(&path)?;
                     Ok::<_, anyhow::Error>(())
                 })
             })
@@ -97,24 +139,258 @@ impl Storage {
             .await?
     }
 
-    pub async fn get_nct(&self) -> Result<tct::Tree> {
+    /// Streams the key hashes and values of the state at the given version,
+    /// in key hash order, for a state sync snapshot.
+    ///
+    /// The JMT is walked down from its root at `version`, so only the nodes of
+    /// that version are read, and nothing is buffered beyond a small number of
+    /// leaves.  JMT nodes are never overwritten, so the stream is consistent
+    /// even if later versions are committed concurrently.
+    pub fn snapshot_leaves(
+        &self,
+        version: jmt::Version,
+    ) -> mpsc::Receiver<Result<(KeyHash, Vec<u8>)>> {
+        let (tx, rx) = mpsc::channel(SNAPSHOT_LEAF_BUFFER);
         let db = self.0.clone();
         let span = Span::current();
         tokio::task::Builder::new()
-            .name("get_nct")
+            .name("snapshot_rocksdb")
             .spawn_blocking(move || {
                 span.in_scope(|| {
+                    let walk = || {
+                        let jmt_cf = db.cf_handle("jmt").expect("jmt column family not found");
+                        let mut leaves = 0u64;
+                        let mut stack = vec![NodeKey::new_empty_path(version)];
+                        while let Some(node_key) = stack.pop() {
+                            let node = match db.get_pinned_cf(jmt_cf, node_key.encode()?)? {
+                                Some(node_bytes) => Node::decode(&node_bytes)?,
+                                None => {
+                                    return Err(anyhow::anyhow!(
+                                        "missing JMT node {:?} at version {}",
+                                        node_key,
+                                        version
+                                    ))
+                                }
+                            };
+                            match node {
+                                Node::Internal(internal_node) => {
+                                    // Push the children in reverse, so that they're
+                                    // popped in key hash order.
+                                    for nibble in (0..16u8).rev() {
+                                        let nibble = Nibble::from(nibble);
+                                        if let Some(child) = internal_node.child(nibble) {
+                                            stack.push(
+                                                node_key.gen_child_node_key(child.version, nibble),
+                                            );
+                                        }
+                                    }
+                                }
+                                Node::Leaf(leaf_node) => {
+                                    let leaf = (leaf_node.key_hash(), leaf_node.value().to_vec());
+                                    if tx.blocking_send(Ok(leaf)).is_err() {
+                                        // The consumer went away, so stop walking.
+                                        return Ok(leaves);
+                                    }
+                                    leaves += 1;
+                                }
+                                Node::Null => {}
+                            }
+                        }
+                        Ok(leaves)
+                    };
+
+                    match walk() {
+                        Ok(leaves) => tracing::info!(version, leaves, "read snapshot leaves"),
+                        Err(e) => {
+                            let _ = tx.blocking_send(Err(e));
+                        }
+                    }
+                })
+            })
+            .unwrap();
+        rx
+    }
+
+    /// Rebuilds the JMT at `version` in empty storage from the key hashes and
+    /// values of a state sync snapshot, and writes the snapshot's NCT,
+    /// returning the app hash of the restored state.
+    ///
+    /// Every node of the tree, and so every node hash, is recomputed from the
+    /// leaves, so checking the returned app hash against a trusted one verifies
+    /// the entire restored state.  The caller is responsible for that check, and
+    /// for calling [`Self::discard_snapshot`] if it fails.
+    ///
+    /// The whole tree is built in one batch, so all of the leaves and the nodes
+    /// built from them are held in memory until it's written.
+    pub async fn restore_snapshot(
+        &self,
+        version: jmt::Version,
+        leaves: Vec<(KeyHash, Vec<u8>)>,
+        nct: &tct::Tree,
+    ) -> Result<AppHash> {
+        if self.latest_version().await?.is_some() {
+            return Err(anyhow::anyhow!(
+                "snapshots can only be restored into empty storage"
+            ));
+        }
+
+        tracing::info!(version, leaves = leaves.len(), "restoring snapshot");
+        let (root, batch) = jmt::JellyfishMerkleTree::new(&EmptyBefore(version))
+            .put_value_set(leaves, version)
+            .await?;
+        self.clone().write_node_batch(&batch.node_batch).await?;
+        self.put_nct(nct).await?;
+
+        Ok(root.into())
+    }
+
+    /// Removes everything written by [`Self::restore_snapshot`], which only
+    /// restores into empty storage.
+    pub async fn discard_snapshot(&self) -> Result<()> {
+        let db = self.0.clone();
+        let span = Span::current();
+        tokio::task::Builder::new()
+            .name("discard_snapshot")
+            .spawn_blocking(move || {
+                span.in_scope(|| {
+                    tracing::info!("discarding restored snapshot");
+                    let jmt_cf = db.cf_handle("jmt").expect("jmt column family not found");
                     let nct_cf = db.cf_handle("nct").expect("nct column family not found");
-                    if let Some(tct_bytes) = db.get_cf(nct_cf, "tct")? {
-                        Ok(bincode::deserialize(&tct_bytes)?)
-                    } else {
-                        Ok(tct::Tree::new())
+
+                    let mut batch = WriteBatch::default();
+                    let mut iter = db.raw_iterator_cf(jmt_cf);
+                    iter.seek_to_first();
+                    while iter.valid() {
+                        batch.delete_cf(jmt_cf, iter.key().unwrap());
+                        iter.next();
                     }
+                    iter.status()?;
+                    // All keys in the nct column family start with an ASCII letter.
+                    batch.delete_range_cf(nct_cf, [0x00], [0xff]);
+                    db.write(batch)?;
+
+                    Ok::<_, anyhow::Error>(())
                 })
             })
             .unwrap()
             .await?
     }
+
+    /// Deletes all JMT nodes that are not needed to read the state at `horizon`
+    /// or any later version, nor at any of the `keep` versions before it, then
+    /// compacts the pruned range of the database.
+    ///
+    /// Nodes are never modified after being written, so a node which is part of
+    /// the tree at some version is also part of the tree at every version since
+    /// it was written.  This means a node older than `horizon` is needed exactly
+    /// when it's part of the tree at the first of `horizon` and the `keep`
+    /// versions from its own version on, which is checked by walking down to it
+    /// from that tree's root.  Only the path walked last is held in memory, and
+    /// versions committed concurrently are unaffected.
+    pub async fn prune(
+        &self,
+        horizon: jmt::Version,
+        keep: Vec<jmt::Version>,
+    ) -> Result<PruneStats> {
+        let db = self.0.clone();
+        let span = Span::current();
+        let stats = tokio::task::Builder::new()
+            .name("prune_rocksdb")
+            .spawn_blocking(move || {
+                span.in_scope(|| {
+                    let jmt_cf = db.cf_handle("jmt").expect("jmt column family not found");
+
+                    let mut kept = keep;
+                    kept.push(horizon);
+                    kept.sort_unstable();
+                    kept.dedup();
+                    let mut walker = PathWalker::new(kept[0]);
+
+                    // Sweep all the unneeded nodes older than the horizon.  Node
+                    // keys are ordered by version first, so these are all at the
+                    // start of the column family, and each kept tree is walked
+                    // for one contiguous range of them.
+                    let mut stats = PruneStats::default();
+                    let mut batch = WriteBatch::default();
+                    let mut iter = db.raw_iterator_cf(jmt_cf);
+                    iter.seek_to_first();
+                    while iter.valid() {
+                        let key = iter.key().unwrap();
+                        let node_key = NodeKey::decode(key)?;
+                        if node_key.version() >= horizon {
+                            break;
+                        }
+                        let kept_version =
+                            kept[kept.partition_point(|version| *version < node_key.version())];
+                        if walker.version != kept_version {
+                            walker = PathWalker::new(kept_version);
+                        }
+                        if !walker.contains(&db, jmt_cf, &node_key)? {
+                            stats.nodes += 1;
+                            stats.bytes += (key.len() + iter.value().unwrap().len()) as u64;
+                            batch.delete_cf(jmt_cf, key);
+                        }
+                        if batch.len() >= PRUNE_BATCH_SIZE {
+                            db.write(std::mem::take(&mut batch))?;
+                        }
+                        iter.next();
+                    }
+                    iter.status()?;
+                    db.write(batch)?;
+
+                    // Deleted keys only free disk space once compacted away.
+                    let horizon_key = NodeKey::new_empty_path(horizon).encode()?;
+                    db.compact_range_cf(jmt_cf, None::<&[u8]>, Some(&horizon_key));
+
+                    Ok::<_, anyhow::Error>(stats)
+                })
+            })
+            .unwrap()
+            .await??;
+
+        tracing::info!(
+            horizon,
+            nodes = stats.nodes,
+            bytes = stats.bytes,
+            "pruned storage"
+        );
+        counter!(metrics::PRUNED_JMT_NODES_TOTAL, stats.nodes);
+        counter!(metrics::PRUNED_BYTES_TOTAL, stats.bytes);
+        Ok(stats)
+    }
+
+    /// Writes the changes to the NCT since it was last stored.
+    ///
+    /// The NCT is stored incrementally, so the cost of this is proportional to
+    /// the number of commitments inserted (and forgotten) since the last call,
+    /// rather than to the size of the tree.
+    pub async fn put_nct(&self, tct: &tct::Tree) -> Result<()> {
+        let mut store = NctStore::for_writing(self.0.clone()).await?;
+        tct.serialize(&mut store).await?;
+        let written_bytes = store.commit().await?;
+
+        tracing::debug!(written_bytes, "stored TCT");
+        gauge!(metrics::TCT_WRITE_BYTES, written_bytes as f64);
+        Ok(())
+    }
+
+    pub async fn get_nct(&self) -> Result<tct::Tree> {
+        // Trees stored before the NCT was stored incrementally are kept in a
+        // single key, until they're next written.
+        let db = self.0.clone();
+        let span = Span::current();
+        let legacy = tokio::task::Builder::new()
+            .name("get_legacy_nct")
+            .spawn_blocking(move || span.in_scope(|| crate::nct::get_legacy(&db)))
+            .unwrap()
+            .await??;
+        if let Some(tct) = legacy {
+            return Ok(tct);
+        }
+
+        let mut store = NctStore::for_reading(self.0.clone()).await?;
+        tct::Tree::deserialize(&mut store).await
+    }
 }
 
 impl TreeWriter for Storage {
@@ -225,3 +501,254 @@ impl TreeReader for Storage {
         })
     }
 }
+
+/// A [`TreeReader`] for building a JMT from scratch at an arbitrary version, as
+/// if the tree were empty at every earlier version.
+struct EmptyBefore(jmt::Version);
+
+impl TreeReader for EmptyBefore {
+    fn get_node_option<'future, 'a: 'future, 'n: 'future>(
+        &'a self,
+        node_key: &'n NodeKey,
+    ) -> BoxFuture<'future, Result<Option<Node>>> {
+        let empty_root = self
+            .0
+            .checked_sub(1)
+            .map(NodeKey::new_empty_path)
+            .map_or(false, |root_key| &root_key == node_key);
+        Box::pin(async move { Ok(empty_root.then(Node::new_null)) })
+    }
+
+    fn get_rightmost_leaf<'future, 'a: 'future>(
+        &'a self,
+    ) -> BoxFuture<'future, Result<Option<(NodeKey, jmt::storage::LeafNode)>>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Finds whether nodes are part of the tree at some version, by walking down to
+/// them from its root.
+///
+/// [`Storage::prune`] looks up nodes in key order, so consecutive lookups share
+/// most of their path, and the nodes along the last path walked are kept to be
+/// reused.  This bounds the memory used by the depth of the tree.
+struct PathWalker {
+    version: jmt::Version,
+    path: Vec<(NodeKey, Node)>,
+}
+
+impl PathWalker {
+    fn new(version: jmt::Version) -> Self {
+        Self {
+            version,
+            path: Vec::new(),
+        }
+    }
+
+    /// Returns whether the node at `node_key` is part of the tree at `self.version`.
+    fn contains(&mut self, db: &DB, jmt_cf: &ColumnFamily, node_key: &NodeKey) -> Result<bool> {
+        let nibble_path = node_key.nibble_path();
+        let mut current = NodeKey::new_empty_path(self.version);
+        for depth in 0..nibble_path.num_nibbles() {
+            if self.path.get(depth).map(|(key, _)| key) != Some(&current) {
+                self.path.truncate(depth);
+                let node = match db.get_pinned_cf(jmt_cf, current.encode()?)? {
+                    Some(node_bytes) => Node::decode(&node_bytes)?,
+                    // This version was already pruned, or never written.
+                    None => return Ok(false),
+                };
+                self.path.push((current.clone(), node));
+            }
+
+            let nibble = nibble_path.get_nibble(depth);
+            current = match &self.path[depth].1 {
+                Node::Internal(internal_node) => match internal_node.child(nibble) {
+                    Some(child) => current.gen_child_node_key(child.version, nibble),
+                    None => return Ok(false),
+                },
+                _ => return Ok(false),
+            };
+            // Children are never newer than their parents, so once the path
+            // reaches an older subtree, the node can't be below it.
+            if current.version() < node_key.version() {
+                return Ok(false);
+            }
+        }
+        Ok(&current == node_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StateExt;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn prune_retains_horizon_and_kept_versions() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::load(dir.path().join("prune-test.db"))
+            .await
+            .unwrap();
+
+        // Write a key that is never overwritten, then overwrite another one at
+        // each version.
+        for version in 0..6u64 {
+            let state = storage.state().await.unwrap();
+            if version == 0 {
+                state.put_proto::<u64>(b"fixed".into(), 42).await;
+            }
+            state.put_proto::<u64>(b"counter".into(), version).await;
+            state.write().await.commit(storage.clone()).await.unwrap();
+        }
+
+        let stats = storage.prune(4, vec![2]).await.unwrap();
+        assert!(stats.nodes > 0);
+        assert!(stats.bytes > 0);
+
+        for version in [0, 1, 3] {
+            assert!(storage.state_at(version).await.is_err());
+        }
+        for version in [2, 4, 5] {
+            let state = storage.state_at(version).await.unwrap();
+            assert_eq!(
+                state.get_proto::<u64>(b"counter".into()).await.unwrap(),
+                Some(version)
+            );
+            assert_eq!(
+                state.get_proto::<u64>(b"fixed".into()).await.unwrap(),
+                Some(42)
+            );
+        }
+
+        // Pruning again with the same horizon has nothing left to delete.
+        assert_eq!(storage.prune(4, vec![2]).await.unwrap().nodes, 0);
+    }
+
+    async fn collect_leaves(storage: &Storage, version: jmt::Version) -> Vec<(KeyHash, Vec<u8>)> {
+        let mut rx = storage.snapshot_leaves(version);
+        let mut leaves = Vec::new();
+        while let Some(leaf) = rx.recv().await {
+            leaves.push(leaf.unwrap());
+        }
+        leaves
+    }
+
+    #[tokio::test]
+    async fn snapshot_restores_state_at_version() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::load(dir.path().join("snapshot-test.db"))
+            .await
+            .unwrap();
+        for version in 0..4u64 {
+            let state = storage.state().await.unwrap();
+            state.put_proto::<u64>(b"counter".into(), version).await;
+            state
+                .put_proto::<u64>(format!("key/{}", version).as_bytes().into(), version)
+                .await;
+            state.write().await.commit(storage.clone()).await.unwrap();
+        }
+        let root = jmt::JellyfishMerkleTree::new(&storage)
+            .get_root_hash(2)
+            .await
+            .unwrap();
+
+        // Only the state at the snapshot version is included, in key hash order.
+        let leaves = collect_leaves(&storage, 2).await;
+        assert_eq!(leaves.len(), 4);
+        assert!(leaves.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(storage.snapshot_leaves(7).recv().await.unwrap().is_err());
+
+        let restored_dir = tempdir().unwrap();
+        let restored = Storage::load(restored_dir.path().join("restored.db"))
+            .await
+            .unwrap();
+        let app_hash = restored
+            .restore_snapshot(2, leaves.clone(), &tct::Tree::new())
+            .await
+            .unwrap();
+        assert_eq!(app_hash, AppHash::from(root));
+        let state = restored.state().await.unwrap();
+        assert_eq!(
+            state.get_proto::<u64>(b"counter".into()).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(state.get_proto::<u64>(b"key/3".into()).await.unwrap(), None);
+
+        // Tampering with any value changes the rebuilt root.
+        restored.discard_snapshot().await.unwrap();
+        assert_eq!(restored.latest_version().await.unwrap(), None);
+        let mut tampered = leaves;
+        tampered[0].1.push(0);
+        let app_hash = restored
+            .restore_snapshot(2, tampered, &tct::Tree::new())
+            .await
+            .unwrap();
+        assert_ne!(app_hash, AppHash::from(root));
+    }
+
+    fn commitment(i: u8) -> tct::Commitment {
+        let mut bytes = [0u8; 32];
+        bytes[0] = i;
+        tct::Commitment::try_from(bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn nct_round_trips_incrementally() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::load(dir.path().join("nct-test.db")).await.unwrap();
+
+        let mut tree = storage.get_nct().await.unwrap();
+        assert_eq!(tree, tct::Tree::new());
+
+        for block in 0..4u8 {
+            for i in 0..3u8 {
+                let witness = if i == 1 {
+                    tct::Witness::Forget
+                } else {
+                    tct::Witness::Keep
+                };
+                tree.insert(witness, commitment(block * 3 + i)).unwrap();
+            }
+            if block == 2 {
+                assert!(tree.forget(commitment(0)));
+            }
+            tree.end_block().unwrap();
+
+            storage.put_nct(&tree).await.unwrap();
+            let stored = storage.get_nct().await.unwrap();
+            assert_eq!(stored.root(), tree.root());
+            assert_eq!(stored, tree);
+        }
+    }
+
+    #[tokio::test]
+    async fn nct_migrates_from_legacy_format() {
+        let dir = tempdir().unwrap();
+        let storage = Storage::load(dir.path().join("nct-legacy-test.db"))
+            .await
+            .unwrap();
+
+        let mut tree = tct::Tree::new();
+        tree.insert(tct::Witness::Keep, commitment(1)).unwrap();
+        let nct_cf = storage.0.cf_handle("nct").unwrap();
+        storage
+            .0
+            .put_cf(
+                nct_cf,
+                crate::nct::LEGACY_TCT_KEY,
+                bincode::serialize(&tree).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(storage.get_nct().await.unwrap(), tree);
+
+        tree.insert(tct::Witness::Keep, commitment(2)).unwrap();
+        storage.put_nct(&tree).await.unwrap();
+        assert!(storage
+            .0
+            .get_cf(nct_cf, crate::nct::LEGACY_TCT_KEY)
+            .unwrap()
+            .is_none());
+        assert_eq!(storage.get_nct().await.unwrap(), tree);
+    }
+}
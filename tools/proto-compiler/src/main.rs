@@ -128,6 +128,7 @@ static TYPE_ATTRIBUTES: &[(&str, &str)] = &[
     (".penumbra.core.stake.v1alpha1.BaseRateData", SERIALIZE),
     (".penumbra.core.stake.v1alpha1.Delegate", SERIALIZE),
     (".penumbra.core.stake.v1alpha1.Undelegate", SERIALIZE),
+    (".penumbra.core.stake.v1alpha1.Redelegate", SERIALIZE),
     (".penumbra.core.stake.v1alpha1.DelegationChanges", SERIALIZE),
     (".penumbra.core.stake.v1alpha1.CommissionAmount", SERIALIZE),
     (".penumbra.core.stake.v1alpha1.CommissionAmounts", SERIALIZE),
@@ -219,6 +220,13 @@ static TYPE_ATTRIBUTES: &[(&str, &str)] = &[
     (".penumbra.core.crypto.v1alpha1.Fee", SERIALIZE),
     (".penumbra.core.crypto.v1alpha1.Clue", SERIALIZE),
     (".penumbra.core.crypto.v1alpha1.Clue", SERDE_TRANSPARENT),
+    (".penumbra.core.crypto.v1alpha1.EddyCiphertext", SERIALIZE),
+    (".penumbra.core.crypto.v1alpha1.EddyDecryptionShare", SERIALIZE),
+    (".penumbra.core.crypto.v1alpha1.EddyEncryptionProof", SERIALIZE),
+    (
+        ".penumbra.core.crypto.v1alpha1.EddyTransparentEncryptionProof",
+        SERIALIZE,
+    ),
     (".penumbra.core.chain.v1alpha1.ChainParameters", SERIALIZE),
     (".penumbra.core.chain.v1alpha1.FmdParameters", SERIALIZE),
     (".penumbra.core.chain.v1alpha1.CompactBlock", SERIALIZE),
@@ -287,6 +295,14 @@ static TYPE_ATTRIBUTES: &[(&str, &str)] = &[
         ".penumbra.core.transaction.v1alpha1.ProposalWithdraw",
         SERIALIZE,
     ),
+    (
+        ".penumbra.core.transaction.v1alpha1.PositionWithdrawPlan",
+        SERIALIZE,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.PositionRewardClaimPlan",
+        SERIALIZE,
+    ),
     (
         ".penumbra.core.transaction.v1alpha1.ProposalWithdrawPlan",
         SERIALIZE,
@@ -321,18 +337,28 @@ static TYPE_ATTRIBUTES: &[(&str, &str)] = &[
     ),
     (".penumbra.core.ibc.v1alpha1.IBCAction", SERIALIZE),
     (".penumbra.core.ibc.v1alpha1.ICS20Withdrawal", SERIALIZE),
-    (".penumbra.core.dex.v1alpha1.MockFlowCiphertext", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.FlowCiphertext", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.SwapFlow", SERIALIZE),
     (
-        ".penumbra.core.dex.v1alpha1.MockFlowCiphertext",
-        SERDE_TRANSPARENT,
+        ".penumbra.core.dex.v1alpha1.FlowEncryptionCommittee",
+        SERIALIZE,
+    ),
+    (
+        ".penumbra.core.dex.v1alpha1.FlowEncryptionCommittee.PublicKeyShare",
+        SERIALIZE,
     ),
     (".penumbra.core.dex.v1alpha1.TradingPair", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.LimitPrices", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.LimitTiers", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.TradingFunction", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.Reserves", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.Position", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.PositionId", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.PositionId", SERDE_TRANSPARENT),
     (".penumbra.core.dex.v1alpha1.PositionState", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.PositionMetadata", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.PositionIdList", SERIALIZE),
+    (".penumbra.core.dex.v1alpha1.TradingPairList", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.PositionOpen", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.PositionClose", SERIALIZE),
     (".penumbra.core.dex.v1alpha1.PositionWithdraw", SERIALIZE),
@@ -397,6 +423,13 @@ static FIELD_ATTRIBUTES: &[(&str, &str)] = &[
     ),
     (".penumbra.core.stake.v1alpha1.Uptime.bitvec", AS_BASE64),
     (".penumbra.core.crypto.v1alpha1.Clue.inner", AS_HEX),
+    (".penumbra.core.crypto.v1alpha1.EddyCiphertext.inner", AS_HEX),
+    (".penumbra.core.crypto.v1alpha1.EddyDecryptionShare.inner", AS_HEX),
+    (".penumbra.core.crypto.v1alpha1.EddyEncryptionProof.inner", AS_HEX),
+    (
+        ".penumbra.core.crypto.v1alpha1.EddyTransparentEncryptionProof.inner",
+        AS_HEX,
+    ),
     (
         ".penumbra.core.crypto.v1alpha1.Address.inner",
         AS_BECH32_ADDRESS,
@@ -494,6 +527,22 @@ static FIELD_ATTRIBUTES: &[(&str, &str)] = &[
         ".penumbra.core.transaction.v1alpha1.SwapPlan.esk",
         AS_HEX_FOR_BYTES,
     ),
+    (
+        ".penumbra.core.transaction.v1alpha1.SwapPlan.delta_1_blinding",
+        AS_HEX_FOR_BYTES,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.SwapPlan.delta_2_blinding",
+        AS_HEX_FOR_BYTES,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.SwapPlan.flow_encryption_key",
+        AS_HEX_FOR_BYTES,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.SwapPlan.flow_encryption_seed",
+        AS_HEX_FOR_BYTES,
+    ),
     (
         ".penumbra.core.transaction.v1alpha1.SwapClaimPlan.output_1_blinding",
         AS_HEX_FOR_BYTES,
@@ -539,9 +588,33 @@ static FIELD_ATTRIBUTES: &[(&str, &str)] = &[
         ".penumbra.core.transaction.v1alpha1.SpendBody.nullifier",
         AS_HEX_FOR_BYTES,
     ),
+    (
+        ".penumbra.core.transaction.v1alpha1.DelegatorVote.proof",
+        AS_BASE64_FOR_BYTES,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.DelegatorVoteBody.rk",
+        AS_HEX_FOR_BYTES,
+    ),
+    (
+        ".penumbra.core.transaction.v1alpha1.DelegatorVoteBody.nullifier",
+        AS_HEX_FOR_BYTES,
+    ),
     (".penumbra.core.dex.v1alpha1.Swap.proof", AS_BASE64),
     (".penumbra.core.dex.v1alpha1.SwapClaim.proof", AS_BASE64),
     (".penumbra.core.dex.v1alpha1.Position.nonce", AS_HEX),
+    (
+        ".penumbra.core.dex.v1alpha1.SwapBody.flow_encryption_key",
+        AS_HEX,
+    ),
+    (
+        ".penumbra.core.dex.v1alpha1.FlowEncryptionCommittee.encryption_key",
+        AS_HEX,
+    ),
+    (
+        ".penumbra.core.dex.v1alpha1.FlowEncryptionCommittee.PublicKeyShare.inner",
+        AS_HEX,
+    ),
     (
         ".penumbra.core.dex.v1alpha1.PositionId.inner",
         AS_BECH32_LP_ID,
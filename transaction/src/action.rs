@@ -11,6 +11,7 @@ mod ibc;
 pub mod output;
 mod position;
 mod propose;
+mod redelegate;
 pub mod spend;
 pub mod swap;
 pub mod swap_claim;
@@ -26,11 +27,12 @@ pub use position::{PositionClose, PositionOpen, PositionRewardClaim, PositionWit
 pub use propose::{
     Proposal, ProposalKind, ProposalPayload, ProposalSubmit, ProposalWithdraw, ProposalWithdrawBody,
 };
+pub use redelegate::Redelegate;
 pub use spend::Spend;
 pub use swap::Swap;
 pub use swap_claim::SwapClaim;
 pub use undelegate::Undelegate;
-pub use vote::{DelegatorVote, ValidatorVote, ValidatorVoteBody, Vote};
+pub use vote::{DelegatorVote, DelegatorVoteBody, ValidatorVote, ValidatorVoteBody, Vote};
 
 /// Common behavior between Penumbra actions.
 pub trait IsAction {
@@ -46,13 +48,14 @@ pub enum Action {
     Spend(spend::Spend),
     Delegate(Delegate),
     Undelegate(Undelegate),
+    Redelegate(Redelegate),
     ValidatorDefinition(pbs::ValidatorDefinition),
     IBCAction(pb_ibc::IbcAction),
     Swap(Swap),
     SwapClaim(SwapClaim),
     ProposalSubmit(ProposalSubmit),
     ProposalWithdraw(ProposalWithdraw),
-    // DelegatorVote(DelegatorVote),
+    DelegatorVote(DelegatorVote),
     ValidatorVote(ValidatorVote),
 
     PositionOpen(PositionOpen),
@@ -70,11 +73,12 @@ impl IsAction for Action {
             Action::Spend(spend) => spend.balance_commitment(),
             Action::Delegate(delegate) => delegate.balance_commitment(),
             Action::Undelegate(undelegate) => undelegate.balance_commitment(),
+            Action::Redelegate(redelegate) => redelegate.balance_commitment(),
             Action::Swap(swap) => swap.balance_commitment(),
             Action::SwapClaim(swap_claim) => swap_claim.balance_commitment(),
             Action::ProposalSubmit(submit) => submit.balance_commitment(),
             Action::ProposalWithdraw(withdraw) => withdraw.balance_commitment(),
-            // Action::DelegatorVote(_) => ...
+            Action::DelegatorVote(v) => v.balance_commitment(),
             Action::ValidatorVote(v) => v.balance_commitment(),
             Action::PositionOpen(p) => p.balance_commitment(),
             Action::PositionClose(p) => p.balance_commitment(),
@@ -96,8 +100,10 @@ impl IsAction for Action {
             Action::Spend(x) => x.view_from_perspective(txp),
             Action::Delegate(x) => x.view_from_perspective(txp),
             Action::Undelegate(x) => x.view_from_perspective(txp),
+            Action::Redelegate(x) => x.view_from_perspective(txp),
             Action::ProposalSubmit(x) => x.view_from_perspective(txp),
             Action::ProposalWithdraw(x) => x.view_from_perspective(txp),
+            Action::DelegatorVote(x) => x.view_from_perspective(txp),
             Action::ValidatorVote(x) => x.view_from_perspective(txp),
             Action::PositionOpen(x) => x.view_from_perspective(txp),
             Action::PositionClose(x) => x.view_from_perspective(txp),
@@ -128,6 +134,9 @@ impl From<Action> for pb::Action {
             Action::Undelegate(inner) => pb::Action {
                 action: Some(pb::action::Action::Undelegate(inner.into())),
             },
+            Action::Redelegate(inner) => pb::Action {
+                action: Some(pb::action::Action::Redelegate(inner.into())),
+            },
             Action::ValidatorDefinition(inner) => pb::Action {
                 action: Some(pb::action::Action::ValidatorDefinition(inner)),
             },
@@ -146,9 +155,9 @@ impl From<Action> for pb::Action {
             Action::ProposalWithdraw(inner) => pb::Action {
                 action: Some(pb::action::Action::ProposalWithdraw(inner.into())),
             },
-            // Action::DelegatorVote(inner) => pb::Action {
-            //     action: Some(pb::action::Action::DelegatorVote(inner.into())),
-            // },
+            Action::DelegatorVote(inner) => pb::Action {
+                action: Some(pb::action::Action::DelegatorVote(inner.into())),
+            },
             Action::ValidatorVote(inner) => pb::Action {
                 action: Some(pb::action::Action::ValidatorVote(inner.into())),
             },
@@ -182,6 +191,7 @@ impl TryFrom<pb::Action> for Action {
             pb::action::Action::Spend(inner) => Ok(Action::Spend(inner.try_into()?)),
             pb::action::Action::Delegate(inner) => Ok(Action::Delegate(inner.try_into()?)),
             pb::action::Action::Undelegate(inner) => Ok(Action::Undelegate(inner.try_into()?)),
+            pb::action::Action::Redelegate(inner) => Ok(Action::Redelegate(inner.try_into()?)),
             pb::action::Action::ValidatorDefinition(inner) => {
                 Ok(Action::ValidatorDefinition(inner))
             }
@@ -194,9 +204,9 @@ impl TryFrom<pb::Action> for Action {
             pb::action::Action::ProposalWithdraw(inner) => {
                 Ok(Action::ProposalWithdraw(inner.try_into()?))
             }
-            // pb::action::Action::DelegatorVote(inner) => {
-            //     Ok(Action::DelegatorVote(inner.try_into()?))
-            // }
+            pb::action::Action::DelegatorVote(inner) => {
+                Ok(Action::DelegatorVote(inner.try_into()?))
+            }
             pb::action::Action::ValidatorVote(inner) => {
                 Ok(Action::ValidatorVote(inner.try_into()?))
             }
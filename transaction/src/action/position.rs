@@ -49,17 +49,7 @@ impl PositionOpen {
             asset_id: LpNft::new(self.position.id(), position::State::Opened).asset_id(),
         };
 
-        let r1 = Value {
-            amount: self.initial_reserves.r1,
-            asset_id: self.position.pair.asset_1(),
-        };
-
-        let r2 = Value {
-            amount: self.initial_reserves.r2,
-            asset_id: self.position.pair.asset_2(),
-        };
-
-        let reserves = Balance::from(r1) + r2;
+        let reserves = self.initial_reserves.balance(&self.position.pair);
 
         // The action consumes the reserves and produces an LP NFT
         Balance::from(opened_position_nft) - reserves
@@ -132,8 +122,15 @@ impl IsAction for PositionWithdraw {
         }
         .commit(Fr::zero());
 
-        // The action consumes a closed position and produces the position's reserves.
-        self.reserves_commitment - closed_position_nft
+        let withdrawn_position_nft = Value {
+            amount: 1u64.into(),
+            asset_id: LpNft::new(self.position_id, position::State::Withdrawn).asset_id(),
+        }
+        .commit(Fr::zero());
+
+        // The action consumes a closed position and produces a withdrawn
+        // position, as well as the position's reserves.
+        self.reserves_commitment + withdrawn_position_nft - closed_position_nft
     }
 
     fn view_from_perspective(&self, _txp: &TransactionPerspective) -> anyhow::Result<ActionView> {
@@ -164,7 +161,7 @@ impl IsAction for PositionRewardClaim {
         }
         .commit(Fr::zero());
 
-        // The action consumes a closed position and produces the position's reserves.
+        // The action consumes a withdrawn position and produces the position's rewards.
         self.rewards_commitment - withdrawn_position_nft
     }
 
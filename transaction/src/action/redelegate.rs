@@ -0,0 +1,108 @@
+use ark_ff::Zero;
+use penumbra_crypto::{asset::Amount, Balance, DelegationToken, Fr, IdentityKey, Value};
+use penumbra_proto::{core::stake::v1alpha1 as pb, Protobuf};
+use serde::{Deserialize, Serialize};
+
+use crate::{ActionView, IsAction, TransactionPerspective};
+
+/// A transaction action moving stake from one validator's delegation pool to
+/// another's, without passing through the unbonded staking token.
+///
+/// The produced delegation tokens are quarantined until the source validator's
+/// unbonding period has elapsed, so they remain slashable for the source
+/// validator's misbehavior, but they accrue the destination validator's
+/// rewards from the next epoch onwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "pb::Redelegate", into = "pb::Redelegate")]
+pub struct Redelegate {
+    /// The identity key of the validator to redelegate from.
+    pub source_validator: IdentityKey,
+    /// The identity key of the validator to redelegate to.
+    pub destination_validator: IdentityKey,
+    /// The index of the epoch in which this redelegation was performed.
+    pub epoch_index: u64,
+    /// The amount of source delegation tokens consumed by this action.
+    pub source_delegation_amount: Amount,
+    /// The amount being redelegated, in units of unbonded stake.
+    ///
+    /// This is implied by the source validator's exchange rate in the
+    /// specified epoch (and should be checked in transaction validation!).
+    pub unbonded_amount: Amount,
+    /// The amount of destination delegation tokens produced by this action.
+    ///
+    /// This is implied by the destination validator's exchange rate in the
+    /// specified epoch (and should be checked in transaction validation!).
+    pub destination_delegation_amount: Amount,
+}
+
+impl IsAction for Redelegate {
+    fn balance_commitment(&self) -> penumbra_crypto::balance::Commitment {
+        self.balance().commit(Fr::zero())
+    }
+
+    fn view_from_perspective(&self, _txp: &TransactionPerspective) -> anyhow::Result<ActionView> {
+        Ok(ActionView::Redelegate(self.to_owned()))
+    }
+}
+
+impl Redelegate {
+    /// Compute a commitment to the value contributed to a transaction by this redelegation.
+    pub fn balance(&self) -> Balance {
+        let source = Value {
+            amount: self.source_delegation_amount,
+            asset_id: DelegationToken::new(self.source_validator.clone()).id(),
+        };
+        let destination = Value {
+            amount: self.destination_delegation_amount,
+            asset_id: DelegationToken::new(self.destination_validator.clone()).id(),
+        };
+
+        // We consume the source delegation tokens and produce the destination
+        // delegation tokens; the unbonded stake never appears in the balance.
+        Balance::from(destination) - source
+    }
+}
+
+impl Protobuf<pb::Redelegate> for Redelegate {}
+
+impl From<Redelegate> for pb::Redelegate {
+    fn from(d: Redelegate) -> Self {
+        pb::Redelegate {
+            source_validator: Some(d.source_validator.into()),
+            destination_validator: Some(d.destination_validator.into()),
+            epoch_index: d.epoch_index,
+            source_delegation_amount: Some(d.source_delegation_amount.into()),
+            unbonded_amount: Some(d.unbonded_amount.into()),
+            destination_delegation_amount: Some(d.destination_delegation_amount.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::Redelegate> for Redelegate {
+    type Error = anyhow::Error;
+    fn try_from(d: pb::Redelegate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            source_validator: d
+                .source_validator
+                .ok_or_else(|| anyhow::anyhow!("missing source validator"))?
+                .try_into()?,
+            destination_validator: d
+                .destination_validator
+                .ok_or_else(|| anyhow::anyhow!("missing destination validator"))?
+                .try_into()?,
+            epoch_index: d.epoch_index,
+            source_delegation_amount: d
+                .source_delegation_amount
+                .ok_or_else(|| anyhow::anyhow!("missing source delegation amount"))?
+                .try_into()?,
+            unbonded_amount: d
+                .unbonded_amount
+                .ok_or_else(|| anyhow::anyhow!("missing unbonded amount"))?
+                .try_into()?,
+            destination_delegation_amount: d
+                .destination_delegation_amount
+                .ok_or_else(|| anyhow::anyhow!("missing destination delegation amount"))?
+                .try_into()?,
+        })
+    }
+}
@@ -1,10 +1,8 @@
-use ark_ff::Zero;
-use decaf377::Fr;
-use penumbra_crypto::asset::Amount;
-use penumbra_crypto::dex::TradingPair;
+use penumbra_crypto::dex::{LimitPrices, TradingPair};
+use penumbra_crypto::eddy::{proofs::EncryptionProof, EncryptionKey};
 use penumbra_crypto::proofs::transparent::SwapProof;
 use penumbra_crypto::{balance, dex::swap::SwapCiphertext};
-use penumbra_crypto::{Note, NotePayload, Value};
+use penumbra_crypto::{FlowCiphertext, Note, NotePayload};
 use penumbra_proto::{core::dex::v1alpha1 as pb, Protobuf};
 
 use crate::view::action_view::SwapView;
@@ -14,11 +12,6 @@ use crate::{ActionView, IsAction, TransactionPerspective};
 pub struct Swap {
     // A proof that this is a valid state change.
     pub proof: SwapProof,
-    // Amounts will be plaintext until flow encryption is available.
-    // // The encrypted amount of asset 1 to be swapped.
-    // pub enc_amount_1: MockFlowCiphertext,
-    // // The encrypted amount of asset 2 to be swapped.
-    // pub enc_amount_2: MockFlowCiphertext,
     pub body: Body,
 }
 
@@ -26,18 +19,7 @@ impl IsAction for Swap {
     /// Compute a commitment to the value contributed to a transaction by this swap.
     /// Will subtract (v1,t1), (v2,t2), and (f,fee_token)
     fn balance_commitment(&self) -> balance::Commitment {
-        let input_1 = Value {
-            amount: self.body.delta_1_i,
-            asset_id: self.body.trading_pair.asset_1(),
-        }
-        .commit(Fr::zero());
-        let input_2 = Value {
-            amount: self.body.delta_2_i,
-            asset_id: self.body.trading_pair.asset_2(),
-        }
-        .commit(Fr::zero());
-
-        -(input_1 + input_2 + self.body.fee_commitment)
+        -(self.body.delta_1_commitment + self.body.delta_2_commitment + self.body.fee_commitment)
     }
 
     fn view_from_perspective(&self, txp: &TransactionPerspective) -> anyhow::Result<ActionView> {
@@ -94,16 +76,23 @@ impl TryFrom<pb::Swap> for Swap {
 #[derive(Debug, Clone)]
 pub struct Body {
     pub trading_pair: TradingPair,
-    // No commitments for the values, as they're plaintext
-    // until flow encryption is available
-    // pub asset_1_commitment: balance::Commitment,
-    // pub asset_2_commitment: balance::Commitment,
-    pub delta_1_i: Amount,
-    pub delta_2_i: Amount,
+    pub delta_1_commitment: balance::Commitment,
+    pub delta_2_commitment: balance::Commitment,
     pub fee_commitment: balance::Commitment,
     // TODO: rename to note_payload
     pub swap_nft: NotePayload,
     pub swap_ciphertext: SwapCiphertext,
+    // The key of the flow encryption committee the amounts are encrypted to.
+    pub flow_encryption_key: EncryptionKey,
+    // The amounts being swapped, encrypted to the flow encryption committee.
+    pub enc_delta_1: FlowCiphertext,
+    pub enc_delta_2: FlowCiphertext,
+    // Proofs that the encrypted amounts are well-formed.
+    pub enc_delta_1_proof: EncryptionProof,
+    pub enc_delta_2_proof: EncryptionProof,
+    // The worst clearing prices the swap accepts, which are public so that the
+    // swap can be excluded from a batch that would violate them.
+    pub limit_prices: LimitPrices,
 }
 
 impl Protobuf<pb::SwapBody> for Body {}
@@ -112,11 +101,17 @@ impl From<Body> for pb::SwapBody {
     fn from(s: Body) -> Self {
         pb::SwapBody {
             trading_pair: Some(s.trading_pair.into()),
-            delta_1_i: Some(s.delta_1_i.into()),
-            delta_2_i: Some(s.delta_2_i.into()),
+            delta_1_commitment: s.delta_1_commitment.to_bytes().to_vec(),
+            delta_2_commitment: s.delta_2_commitment.to_bytes().to_vec(),
             fee_commitment: s.fee_commitment.to_bytes().to_vec(),
             swap_nft: Some(s.swap_nft.into()),
             swap_ciphertext: s.swap_ciphertext.0.to_vec(),
+            flow_encryption_key: s.flow_encryption_key.to_bytes().to_vec(),
+            enc_delta_1: Some(s.enc_delta_1.into()),
+            enc_delta_2: Some(s.enc_delta_2.into()),
+            enc_delta_1_proof: Some(s.enc_delta_1_proof.into()),
+            enc_delta_2_proof: Some(s.enc_delta_2_proof.into()),
+            limit_prices: Some(s.limit_prices.into()),
         }
     }
 }
@@ -129,22 +124,35 @@ impl TryFrom<pb::SwapBody> for Body {
                 .trading_pair
                 .ok_or_else(|| anyhow::anyhow!("missing trading_pair"))?
                 .try_into()?,
-
-            delta_1_i: s
-                .delta_1_i
-                .ok_or_else(|| anyhow::anyhow!("missing delta_1"))?
-                .try_into()?,
-            delta_2_i: s
-                .delta_2_i
-                .ok_or_else(|| anyhow::anyhow!("missing delta_2"))?
-                .try_into()?,
-
+            delta_1_commitment: (&s.delta_1_commitment[..]).try_into()?,
+            delta_2_commitment: (&s.delta_2_commitment[..]).try_into()?,
             fee_commitment: (&s.fee_commitment[..]).try_into()?,
             swap_nft: s
                 .swap_nft
                 .ok_or_else(|| anyhow::anyhow!("missing swap_nft"))?
                 .try_into()?,
             swap_ciphertext: (&s.swap_ciphertext[..]).try_into()?,
+            flow_encryption_key: s.flow_encryption_key[..].try_into()?,
+            enc_delta_1: s
+                .enc_delta_1
+                .ok_or_else(|| anyhow::anyhow!("missing enc_delta_1"))?
+                .try_into()?,
+            enc_delta_2: s
+                .enc_delta_2
+                .ok_or_else(|| anyhow::anyhow!("missing enc_delta_2"))?
+                .try_into()?,
+            enc_delta_1_proof: s
+                .enc_delta_1_proof
+                .ok_or_else(|| anyhow::anyhow!("missing enc_delta_1_proof"))?
+                .try_into()?,
+            enc_delta_2_proof: s
+                .enc_delta_2_proof
+                .ok_or_else(|| anyhow::anyhow!("missing enc_delta_2_proof"))?
+                .try_into()?,
+            limit_prices: s
+                .limit_prices
+                .ok_or_else(|| anyhow::anyhow!("missing limit_prices"))?
+                .try_into()?,
         })
     }
 }
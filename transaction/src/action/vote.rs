@@ -3,11 +3,15 @@ use std::{
     str::FromStr,
 };
 
-use decaf377_rdsa::{Signature, SpendAuth};
-use penumbra_crypto::{GovernanceKey, IdentityKey};
+use bytes::Bytes;
+use decaf377_rdsa::{Signature, SpendAuth, VerificationKey};
+use penumbra_crypto::{
+    proofs::transparent::DelegatorVoteProof, GovernanceKey, IdentityKey, Nullifier, Value,
+};
 use penumbra_proto::{
     core::governance::v1alpha1 as pb_g, core::transaction::v1alpha1 as pb_t, Protobuf,
 };
+use penumbra_tct as tct;
 use serde::{Deserialize, Serialize};
 
 use crate::{ActionView, IsAction, TransactionPerspective};
@@ -202,13 +206,122 @@ impl TryFrom<pb_t::ValidatorVoteBody> for ValidatorVoteBody {
 
 impl Protobuf<pb_t::ValidatorVoteBody> for ValidatorVoteBody {}
 
-#[derive(Debug, Clone)]
+/// A vote by a delegator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "pb_t::DelegatorVote", into = "pb_t::DelegatorVote")]
 pub struct DelegatorVote {
-    // TODO: fill this in
+    /// The body of the delegator vote.
     pub body: DelegatorVoteBody,
+    /// The signature authorizing the vote (signed with the randomized spend key of the staked
+    /// note over the transaction's auth hash).
+    pub auth_sig: Signature<SpendAuth>,
+    /// The proof that the staked note existed when the proposal started.
+    pub proof: DelegatorVoteProof,
+}
+
+impl IsAction for DelegatorVote {
+    fn balance_commitment(&self) -> penumbra_crypto::balance::Commitment {
+        Default::default()
+    }
+
+    fn view_from_perspective(&self, _txp: &TransactionPerspective) -> anyhow::Result<ActionView> {
+        Ok(ActionView::DelegatorVote(self.to_owned()))
+    }
+}
+
+impl From<DelegatorVote> for pb_t::DelegatorVote {
+    fn from(msg: DelegatorVote) -> Self {
+        let proof: Vec<u8> = msg.proof.into();
+        Self {
+            body: Some(msg.body.into()),
+            auth_sig: Some(msg.auth_sig.into()),
+            proof: proof.into(),
+        }
+    }
+}
+
+impl TryFrom<pb_t::DelegatorVote> for DelegatorVote {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb_t::DelegatorVote) -> Result<Self, Self::Error> {
+        Ok(Self {
+            body: msg
+                .body
+                .ok_or_else(|| anyhow::anyhow!("missing delegator vote body"))?
+                .try_into()?,
+            auth_sig: msg
+                .auth_sig
+                .ok_or_else(|| anyhow::anyhow!("missing delegator auth sig"))?
+                .try_into()?,
+            proof: (msg.proof[..])
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("malformed delegator vote proof"))?,
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
+impl Protobuf<pb_t::DelegatorVote> for DelegatorVote {}
+
+/// A private vote as a delegator, weighted by the delegation tokens in a single staked note.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "pb_t::DelegatorVoteBody", into = "pb_t::DelegatorVoteBody")]
 pub struct DelegatorVoteBody {
-    // TODO: fill this in
+    /// The proposal ID to vote on.
+    pub proposal: u64,
+    /// The position in the note commitment tree at which the proposal started.
+    pub start_position: tct::Position,
+    /// The vote to cast.
+    pub vote: Vote,
+    /// The value of the staked note being voted with, which must be a delegation token.
+    pub value: Value,
+    /// The nullifier of the staked note being voted with.
+    pub nullifier: Nullifier,
+    /// The randomized validating key for the spend authorization signature.
+    pub rk: VerificationKey<SpendAuth>,
+}
+
+impl From<DelegatorVoteBody> for pb_t::DelegatorVoteBody {
+    fn from(value: DelegatorVoteBody) -> Self {
+        let nullifier_bytes: [u8; 32] = value.nullifier.into();
+        let rk_bytes: [u8; 32] = value.rk.into();
+        pb_t::DelegatorVoteBody {
+            proposal: value.proposal,
+            start_position: value.start_position.into(),
+            vote: Some(value.vote.into()),
+            value: Some(value.value.into()),
+            nullifier: Bytes::copy_from_slice(&nullifier_bytes),
+            rk: Bytes::copy_from_slice(&rk_bytes),
+        }
+    }
+}
+
+impl TryFrom<pb_t::DelegatorVoteBody> for DelegatorVoteBody {
+    type Error = anyhow::Error;
+
+    fn try_from(msg: pb_t::DelegatorVoteBody) -> Result<Self, Self::Error> {
+        let rk_bytes: [u8; 32] = (msg.rk[..])
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("malformed rk in `DelegatorVote`"))?;
+
+        Ok(DelegatorVoteBody {
+            proposal: msg.proposal,
+            start_position: msg.start_position.into(),
+            vote: msg
+                .vote
+                .ok_or_else(|| anyhow::anyhow!("missing vote in `DelegatorVote`"))?
+                .try_into()?,
+            value: msg
+                .value
+                .ok_or_else(|| anyhow::anyhow!("missing value in `DelegatorVote`"))?
+                .try_into()?,
+            nullifier: (msg.nullifier[..])
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("malformed nullifier in `DelegatorVote`"))?,
+            rk: rk_bytes
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("malformed rk in `DelegatorVote`"))?,
+        })
+    }
 }
+
+impl Protobuf<pb_t::DelegatorVoteBody> for DelegatorVoteBody {}
@@ -15,6 +15,9 @@ pub struct AuthorizationData {
     /// The required withdraw proposal authorization signatures, returned in the same order as the
     /// ProposalWithdraw actions in the original request.
     pub withdraw_proposal_auths: Vec<Signature<SpendAuth>>,
+    /// The required delegator vote authorization signatures, returned in the same order as the
+    /// DelegatorVote actions in the original request.
+    pub delegator_vote_auths: Vec<Signature<SpendAuth>>,
 }
 
 impl Protobuf<pb::AuthorizationData> for AuthorizationData {}
@@ -29,6 +32,11 @@ impl From<AuthorizationData> for pb::AuthorizationData {
                 .into_iter()
                 .map(Into::into)
                 .collect(),
+            delegator_vote_auths: msg
+                .delegator_vote_auths
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -51,6 +59,11 @@ impl TryFrom<pb::AuthorizationData> for AuthorizationData {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
+            delegator_vote_auths: value
+                .delegator_vote_auths
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
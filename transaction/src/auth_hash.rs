@@ -6,9 +6,10 @@ use penumbra_proto::{core::transaction::v1alpha1 as pb, Message, Protobuf};
 
 use crate::{
     action::{
-        output, spend, swap, swap_claim, Delegate, ICS20Withdrawal, PositionClose, PositionOpen,
-        PositionRewardClaim, PositionWithdraw, Proposal, ProposalSubmit, ProposalWithdraw,
-        ProposalWithdrawBody, Undelegate, ValidatorVote, ValidatorVoteBody, Vote,
+        output, spend, swap, swap_claim, Delegate, DelegatorVoteBody, ICS20Withdrawal,
+        PositionClose, PositionOpen, PositionRewardClaim, PositionWithdraw, Proposal,
+        ProposalSubmit, ProposalWithdraw, ProposalWithdrawBody, Redelegate, Undelegate,
+        ValidatorVote, ValidatorVoteBody, Vote,
     },
     plan::{ProposalWithdrawPlan, TransactionPlan},
     Action, Transaction, TransactionBody,
@@ -98,6 +99,19 @@ impl TransactionBody {
 }
 
 impl TransactionPlan {
+    /// Computes an [`AuthHash`] identifying this [`TransactionPlan`] by its contents.
+    ///
+    /// Unlike [`TransactionPlan::auth_hash`], this does not require a [`FullViewingKey`]. It is
+    /// used to identify transactions scheduled by the DAO, which are executed by the chain itself
+    /// rather than built and authorized by the holder of a key.
+    pub fn plan_auth_hash(&self) -> AuthHash {
+        let hash = Params::default()
+            .personal(b"PAH:tx_plan")
+            .hash(&self.encode_to_vec());
+
+        AuthHash(*hash.as_array())
+    }
+
     /// Computes the [`AuthHash`] for the [`Transaction`] described by this
     /// [`TransactionPlan`].
     ///
@@ -156,12 +170,32 @@ impl TransactionPlan {
         for swap_claim in self.swap_claim_plans() {
             state.update(swap_claim.swap_claim_body(fvk).auth_hash().as_bytes());
         }
+        for position_open in self.position_openings() {
+            state.update(position_open.auth_hash().as_bytes());
+        }
+        for position_close in self.position_closings() {
+            state.update(position_close.auth_hash().as_bytes());
+        }
+        for position_withdraw in self.position_withdrawals() {
+            state.update(position_withdraw.position_withdraw().auth_hash().as_bytes());
+        }
+        for position_reward_claim in self.position_reward_claims() {
+            state.update(
+                position_reward_claim
+                    .position_reward_claim()
+                    .auth_hash()
+                    .as_bytes(),
+            );
+        }
         for delegation in self.delegations() {
             state.update(delegation.auth_hash().as_bytes());
         }
         for undelegation in self.undelegations() {
             state.update(undelegation.auth_hash().as_bytes());
         }
+        for redelegation in self.redelegations() {
+            state.update(redelegation.auth_hash().as_bytes());
+        }
         for proposal_submit in self.proposal_submits() {
             state.update(proposal_submit.auth_hash().as_bytes());
         }
@@ -171,8 +205,13 @@ impl TransactionPlan {
         for validator_vote in self.validator_votes() {
             state.update(validator_vote.auth_hash().as_bytes());
         }
-        for _delegator_vote in self.delegator_vote_plans() {
-            // TODO: get the authorization hash of the delegator vote body for each plan
+        for delegator_vote in self.delegator_vote_plans() {
+            state.update(
+                delegator_vote
+                    .delegator_vote_body(fvk)
+                    .auth_hash()
+                    .as_bytes(),
+            );
         }
         // These are data payloads, so just hash them directly,
         // since we consider them authorizing data.
@@ -211,8 +250,10 @@ impl AuthorizingData for Action {
             Action::Spend(spend) => spend.body.auth_hash(),
             Action::Delegate(delegate) => delegate.auth_hash(),
             Action::Undelegate(undelegate) => undelegate.auth_hash(),
+            Action::Redelegate(redelegate) => redelegate.auth_hash(),
             Action::ProposalSubmit(submit) => submit.auth_hash(),
             Action::ProposalWithdraw(withdraw) => withdraw.auth_hash(),
+            Action::DelegatorVote(vote) => vote.body.auth_hash(),
             Action::ValidatorVote(vote) => vote.auth_hash(),
             Action::SwapClaim(swap_claim) => swap_claim.body.auth_hash(),
             Action::Swap(swap) => swap.body.auth_hash(),
@@ -278,11 +319,18 @@ impl AuthorizingData for swap::Body {
         // All of these fields are fixed-length, so we can just throw them
         // in the hash one after the other.
         state.update(self.trading_pair.auth_hash().as_bytes());
-        state.update(&self.delta_1_i.to_le_bytes());
-        state.update(&self.delta_2_i.to_le_bytes());
+        state.update(&self.delta_1_commitment.to_bytes());
+        state.update(&self.delta_2_commitment.to_bytes());
         state.update(&self.fee_commitment.to_bytes());
         state.update(self.swap_nft.auth_hash().as_bytes());
         state.update(&self.swap_ciphertext.0);
+        state.update(&self.flow_encryption_key.to_bytes());
+        state.update(&self.enc_delta_1.to_bytes());
+        state.update(&self.enc_delta_2.to_bytes());
+        state.update(&self.enc_delta_1_proof.to_bytes());
+        state.update(&self.enc_delta_2_proof.to_bytes());
+        state.update(&self.limit_prices.min_price_1.to_le_bytes());
+        state.update(&self.limit_prices.min_price_2.to_le_bytes());
 
         state.finalize()
     }
@@ -340,6 +388,25 @@ impl AuthorizingData for Undelegate {
     }
 }
 
+impl AuthorizingData for Redelegate {
+    fn auth_hash(&self) -> Hash {
+        let mut state = blake2b_simd::Params::default()
+            .personal(b"PAH:redelegate")
+            .to_state();
+
+        // All of these fields are fixed-length, so we can just throw them
+        // in the hash one after the other.
+        state.update(&self.source_validator.0.to_bytes());
+        state.update(&self.destination_validator.0.to_bytes());
+        state.update(&self.epoch_index.to_le_bytes());
+        state.update(&self.source_delegation_amount.to_le_bytes());
+        state.update(&self.unbonded_amount.to_le_bytes());
+        state.update(&self.destination_delegation_amount.to_le_bytes());
+
+        state.finalize()
+    }
+}
+
 impl AuthorizingData for Proposal {
     fn auth_hash(&self) -> Hash {
         let mut state = blake2b_simd::Params::default()
@@ -431,6 +498,26 @@ impl AuthorizingData for ValidatorVoteBody {
     }
 }
 
+impl AuthorizingData for DelegatorVoteBody {
+    fn auth_hash(&self) -> Hash {
+        let mut state = blake2b_simd::Params::default()
+            .personal(b"PAH:del_vote")
+            .to_state();
+
+        // All of these fields are fixed-length, so we can just throw them in the hash one after the
+        // other.
+        state.update(&self.proposal.to_le_bytes());
+        state.update(&u64::from(self.start_position).to_le_bytes());
+        state.update(self.vote.auth_hash().as_bytes());
+        state.update(&self.value.amount.to_le_bytes());
+        state.update(&self.value.asset_id.0.to_bytes());
+        state.update(&self.nullifier.0.to_bytes());
+        state.update(&self.rk.to_bytes());
+
+        state.finalize()
+    }
+}
+
 impl AuthorizingData for PositionOpen {
     fn auth_hash(&self) -> Hash {
         let mut state = blake2b_simd::Params::default()
@@ -578,6 +665,7 @@ mod tests {
         )
         .unwrap();
 
+        let (flow_encryption_key, _) = penumbra_crypto::eddy::dkg::deal(1, 1, OsRng).unwrap();
         let swap_plaintext = SwapPlaintext {
             trading_pair,
             delta_1_i: 100000u64.into(),
@@ -587,6 +675,7 @@ mod tests {
                 asset_id: asset::REGISTRY.parse_denom("upenumbra").unwrap().id(),
             }),
             claim_address: addr,
+            limit_prices: Default::default(),
         };
 
         let plan = TransactionPlan {
@@ -607,7 +696,7 @@ mod tests {
                 .into(),
                 SpendPlan::new(&mut OsRng, note0, 0u64.into()).into(),
                 SpendPlan::new(&mut OsRng, note1, 1u64.into()).into(),
-                SwapPlan::new(&mut OsRng, swap_plaintext).into(),
+                SwapPlan::new(&mut OsRng, swap_plaintext, flow_encryption_key).into(),
             ],
             clue_plans: vec![CluePlan::new(&mut OsRng, addr, 1)],
             memo_plan: Some(MemoPlan::new(&mut OsRng, MemoPlaintext::default())),
@@ -10,7 +10,9 @@ use penumbra_proto::{
 use rand::{CryptoRng, Rng};
 use serde::{Deserialize, Serialize};
 
-use crate::action::{Delegate, ProposalSubmit, Undelegate, ValidatorVote};
+use crate::action::{
+    Delegate, PositionClose, PositionOpen, ProposalSubmit, Redelegate, Undelegate, ValidatorVote,
+};
 
 mod action;
 mod auth;
@@ -19,8 +21,8 @@ mod clue;
 mod memo;
 
 pub use action::{
-    ActionPlan, DelegatorVotePlan, OutputPlan, ProposalWithdrawPlan, SpendPlan, SwapClaimPlan,
-    SwapPlan,
+    ActionPlan, DelegatorVotePlan, OutputPlan, PositionRewardClaimPlan, PositionWithdrawPlan,
+    ProposalWithdrawPlan, SpendPlan, SwapClaimPlan, SwapPlan,
 };
 pub use clue::CluePlan;
 pub use memo::MemoPlan;
@@ -84,6 +86,16 @@ impl TransactionPlan {
         })
     }
 
+    pub fn redelegations(&self) -> impl Iterator<Item = &Redelegate> {
+        self.actions.iter().filter_map(|action| {
+            if let ActionPlan::Redelegate(d) = action {
+                Some(d)
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn ibc_actions(&self) -> impl Iterator<Item = &pb_ibc::IbcAction> {
         self.actions.iter().filter_map(|action| {
             if let ActionPlan::IBCAction(ibc_action) = action {
@@ -164,6 +176,46 @@ impl TransactionPlan {
         })
     }
 
+    pub fn position_openings(&self) -> impl Iterator<Item = &PositionOpen> {
+        self.actions.iter().filter_map(|action| {
+            if let ActionPlan::PositionOpen(v) = action {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn position_closings(&self) -> impl Iterator<Item = &PositionClose> {
+        self.actions.iter().filter_map(|action| {
+            if let ActionPlan::PositionClose(v) = action {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn position_withdrawals(&self) -> impl Iterator<Item = &PositionWithdrawPlan> {
+        self.actions.iter().filter_map(|action| {
+            if let ActionPlan::PositionWithdraw(v) = action {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn position_reward_claims(&self) -> impl Iterator<Item = &PositionRewardClaimPlan> {
+        self.actions.iter().filter_map(|action| {
+            if let ActionPlan::PositionRewardClaim(v) = action {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
+
     /// Convenience method to get all the destination addresses for each `OutputPlan`s.
     pub fn dest_addresses(&self) -> Vec<Address> {
         self.output_plans()
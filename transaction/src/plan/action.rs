@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 
 mod delegator_vote;
 mod output;
+mod position;
 mod proposal_withdraw;
 mod spend;
 mod swap;
@@ -14,14 +15,14 @@ mod swap_claim;
 
 pub use delegator_vote::DelegatorVotePlan;
 pub use output::OutputPlan;
+pub use position::{PositionRewardClaimPlan, PositionWithdrawPlan};
 pub use proposal_withdraw::ProposalWithdrawPlan;
 pub use spend::SpendPlan;
 pub use swap::SwapPlan;
 pub use swap_claim::SwapClaimPlan;
 
 use crate::action::{
-    Delegate, PositionClose, PositionOpen, PositionRewardClaim, PositionWithdraw, ProposalSubmit,
-    Undelegate, ValidatorVote,
+    Delegate, PositionClose, PositionOpen, ProposalSubmit, Redelegate, Undelegate, ValidatorVote,
 };
 
 /// A declaration of a planned [`Action`], for use in transaction creation.
@@ -43,6 +44,8 @@ pub enum ActionPlan {
     /// We don't need any extra information (yet) to understand undelegations,
     /// because we don't yet use flow encryption.
     Undelegate(Undelegate),
+    /// Redelegations are likewise public.
+    Redelegate(Redelegate),
     ValidatorDefinition(pb_stake::ValidatorDefinition),
     /// Describes a proposed swap.
     Swap(SwapPlan),
@@ -58,10 +61,14 @@ pub enum ActionPlan {
     /// Vote on a proposal as a validator.
     ValidatorVote(ValidatorVote),
 
+    /// Open a liquidity position.
     PositionOpen(PositionOpen),
+    /// Close a liquidity position.
     PositionClose(PositionClose),
-    PositionWithdraw(PositionWithdraw),
-    PositionRewardClaim(PositionRewardClaim),
+    /// Describes a withdrawal of the funds from a closed position.
+    PositionWithdraw(PositionWithdrawPlan),
+    /// Describes a claim of the rewards of a withdrawn position.
+    PositionRewardClaim(PositionRewardClaimPlan),
 }
 
 impl ActionPlan {
@@ -73,15 +80,14 @@ impl ActionPlan {
             Output(output) => output.balance(),
             Delegate(delegate) => delegate.balance(),
             Undelegate(undelegate) => undelegate.balance(),
+            Redelegate(redelegate) => redelegate.balance(),
             Swap(swap) => swap.balance(),
             SwapClaim(swap_claim) => swap_claim.balance(),
             ProposalSubmit(proposal_submit) => proposal_submit.balance(),
-            PositionOpen(_position_open) => todo!(),
-            PositionClose(_position_close) => todo!(),
-            PositionWithdraw(_position_withdraw) => todo!(),
-            PositionRewardClaim(_position_reward_claim) => {
-                todo!()
-            }
+            PositionOpen(position_open) => position_open.balance(),
+            PositionClose(position_close) => position_close.balance(),
+            PositionWithdraw(position_withdraw) => position_withdraw.balance(),
+            PositionRewardClaim(position_reward_claim) => position_reward_claim.balance(),
             // None of these contribute to transaction balance:
             IBCAction(_)
             | ValidatorDefinition(_)
@@ -130,6 +136,12 @@ impl From<Undelegate> for ActionPlan {
     }
 }
 
+impl From<Redelegate> for ActionPlan {
+    fn from(inner: Redelegate) -> ActionPlan {
+        ActionPlan::Redelegate(inner)
+    }
+}
+
 impl From<pb_stake::ValidatorDefinition> for ActionPlan {
     fn from(inner: pb_stake::ValidatorDefinition) -> ActionPlan {
         ActionPlan::ValidatorDefinition(inner)
@@ -178,14 +190,14 @@ impl From<PositionClose> for ActionPlan {
     }
 }
 
-impl From<PositionWithdraw> for ActionPlan {
-    fn from(inner: PositionWithdraw) -> ActionPlan {
+impl From<PositionWithdrawPlan> for ActionPlan {
+    fn from(inner: PositionWithdrawPlan) -> ActionPlan {
         ActionPlan::PositionWithdraw(inner)
     }
 }
 
-impl From<PositionRewardClaim> for ActionPlan {
-    fn from(inner: PositionRewardClaim) -> ActionPlan {
+impl From<PositionRewardClaimPlan> for ActionPlan {
+    fn from(inner: PositionRewardClaimPlan) -> ActionPlan {
         ActionPlan::PositionRewardClaim(inner)
     }
 }
@@ -207,6 +219,9 @@ impl From<ActionPlan> for pb_t::ActionPlan {
             ActionPlan::Undelegate(inner) => pb_t::ActionPlan {
                 action: Some(pb_t::action_plan::Action::Undelegate(inner.into())),
             },
+            ActionPlan::Redelegate(inner) => pb_t::ActionPlan {
+                action: Some(pb_t::action_plan::Action::Redelegate(inner.into())),
+            },
             ActionPlan::ValidatorDefinition(inner) => pb_t::ActionPlan {
                 action: Some(pb_t::action_plan::Action::ValidatorDefinition(inner)),
             },
@@ -264,6 +279,9 @@ impl TryFrom<pb_t::ActionPlan> for ActionPlan {
             pb_t::action_plan::Action::Undelegate(inner) => {
                 Ok(ActionPlan::Undelegate(inner.try_into()?))
             }
+            pb_t::action_plan::Action::Redelegate(inner) => {
+                Ok(ActionPlan::Redelegate(inner.try_into()?))
+            }
             pb_t::action_plan::Action::ValidatorDefinition(inner) => {
                 Ok(ActionPlan::ValidatorDefinition(inner))
             }
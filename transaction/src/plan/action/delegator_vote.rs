@@ -1,9 +1,14 @@
+use ark_ff::UniformRand;
 use decaf377::{FieldExt, Fr};
-use penumbra_crypto::Note;
+use decaf377_rdsa::{Signature, SpendAuth};
+use penumbra_crypto::{proofs::transparent::DelegatorVoteProof, FullViewingKey, Note};
 use penumbra_proto::{core::transaction::v1alpha1 as pb, Protobuf};
 use penumbra_tct as tct;
+use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 
+use crate::action::{DelegatorVote, DelegatorVoteBody, Vote};
+
 /// A plan to vote as a delegator.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(try_from = "pb::DelegatorVotePlan", into = "pb::DelegatorVotePlan")]
@@ -11,13 +16,78 @@ pub struct DelegatorVotePlan {
     /// The proposal ID to vote on.
     pub proposal: u64,
     /// The vote to cast.
-    pub vote: crate::action::Vote,
+    pub vote: Vote,
     /// A staked note that was spendable before the proposal started.
     pub staked_note: Note,
     /// The position of the staked note.
     pub position: tct::Position,
     /// The randomizer to use.
     pub randomizer: Fr,
+    /// The position in the note commitment tree at which the proposal started.
+    pub start_position: tct::Position,
+}
+
+impl DelegatorVotePlan {
+    /// Create a new [`DelegatorVotePlan`] that votes using the given `position`ed `staked_note`.
+    pub fn new<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        proposal: u64,
+        start_position: tct::Position,
+        vote: Vote,
+        staked_note: Note,
+        position: tct::Position,
+    ) -> DelegatorVotePlan {
+        DelegatorVotePlan {
+            proposal,
+            vote,
+            staked_note,
+            position,
+            randomizer: Fr::rand(rng),
+            start_position,
+        }
+    }
+
+    /// Convenience method to construct the [`DelegatorVote`] described by this [`DelegatorVotePlan`].
+    pub fn delegator_vote(
+        &self,
+        fvk: &FullViewingKey,
+        auth_sig: Signature<SpendAuth>,
+        auth_path: tct::Proof,
+    ) -> DelegatorVote {
+        DelegatorVote {
+            body: self.delegator_vote_body(fvk),
+            auth_sig,
+            proof: self.delegator_vote_proof(fvk, auth_path),
+        }
+    }
+
+    /// Construct the [`DelegatorVoteBody`] described by this [`DelegatorVotePlan`].
+    pub fn delegator_vote_body(&self, fvk: &FullViewingKey) -> DelegatorVoteBody {
+        DelegatorVoteBody {
+            proposal: self.proposal,
+            start_position: self.start_position,
+            vote: self.vote,
+            value: self.staked_note.value(),
+            nullifier: fvk.derive_nullifier(self.position, &self.staked_note.commit()),
+            rk: fvk.spend_verification_key().randomize(&self.randomizer),
+        }
+    }
+
+    /// Construct the [`DelegatorVoteProof`] required by the [`DelegatorVoteBody`] described by
+    /// this [`DelegatorVotePlan`].
+    pub fn delegator_vote_proof(
+        &self,
+        fvk: &FullViewingKey,
+        note_commitment_proof: tct::Proof,
+    ) -> DelegatorVoteProof {
+        DelegatorVoteProof {
+            note_commitment_proof,
+            note: self.staked_note.clone(),
+            spend_auth_randomizer: self.randomizer,
+            ak: *fvk.spend_verification_key(),
+            nk: *fvk.nullifier_key(),
+        }
+    }
 }
 
 impl From<DelegatorVotePlan> for pb::DelegatorVotePlan {
@@ -28,6 +98,7 @@ impl From<DelegatorVotePlan> for pb::DelegatorVotePlan {
             staked_note: Some(inner.staked_note.into()),
             position: inner.position.into(),
             randomizer: inner.randomizer.to_bytes().to_vec().into(),
+            start_position: inner.start_position.into(),
         }
     }
 }
@@ -48,6 +119,7 @@ impl TryFrom<pb::DelegatorVotePlan> for DelegatorVotePlan {
                 .try_into()?,
             position: value.position.into(),
             randomizer: Fr::from_bytes(value.randomizer.as_ref().try_into()?)?,
+            start_position: value.start_position.into(),
         })
     }
 }
@@ -0,0 +1,153 @@
+use penumbra_crypto::{
+    dex::{
+        lp::{position, LpNft, Reserves},
+        TradingPair,
+    },
+    Balance, Fr, Value, Zero,
+};
+use penumbra_proto::{core::transaction::v1alpha1 as pb, Protobuf};
+use serde::{Deserialize, Serialize};
+
+use crate::action::{PositionRewardClaim, PositionWithdraw};
+
+/// A plan to withdraw the funds from a closed position.
+///
+/// The plan records the final reserves of the position, which the planner
+/// needs to balance the transaction, but which only appear in the action as a
+/// transparent commitment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    try_from = "pb::PositionWithdrawPlan",
+    into = "pb::PositionWithdrawPlan"
+)]
+pub struct PositionWithdrawPlan {
+    pub position_id: position::Id,
+    pub pair: TradingPair,
+    pub reserves: Reserves,
+}
+
+impl PositionWithdrawPlan {
+    /// Create the [`PositionWithdraw`] action described by this plan.
+    pub fn position_withdraw(&self) -> PositionWithdraw {
+        PositionWithdraw {
+            position_id: self.position_id,
+            reserves_commitment: self.reserves.balance(&self.pair).commit(Fr::zero()),
+        }
+    }
+
+    /// Compute the value this action will contribute to its transaction.
+    pub fn balance(&self) -> Balance {
+        let closed_position_nft = Value {
+            amount: 1u64.into(),
+            asset_id: LpNft::new(self.position_id, position::State::Closed).asset_id(),
+        };
+
+        let withdrawn_position_nft = Value {
+            amount: 1u64.into(),
+            asset_id: LpNft::new(self.position_id, position::State::Withdrawn).asset_id(),
+        };
+
+        // The action consumes a closed position and produces a withdrawn
+        // position, as well as the position's reserves.
+        self.reserves.balance(&self.pair) + withdrawn_position_nft - closed_position_nft
+    }
+}
+
+/// A plan to claim the retroactive rewards of a withdrawn position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    try_from = "pb::PositionRewardClaimPlan",
+    into = "pb::PositionRewardClaimPlan"
+)]
+pub struct PositionRewardClaimPlan {
+    pub position_id: position::Id,
+    pub pair: TradingPair,
+    pub rewards: Reserves,
+}
+
+impl PositionRewardClaimPlan {
+    /// Create the [`PositionRewardClaim`] action described by this plan.
+    pub fn position_reward_claim(&self) -> PositionRewardClaim {
+        PositionRewardClaim {
+            position_id: self.position_id,
+            rewards_commitment: self.rewards.balance(&self.pair).commit(Fr::zero()),
+        }
+    }
+
+    /// Compute the value this action will contribute to its transaction.
+    pub fn balance(&self) -> Balance {
+        let withdrawn_position_nft = Value {
+            amount: 1u64.into(),
+            asset_id: LpNft::new(self.position_id, position::State::Withdrawn).asset_id(),
+        };
+
+        // The action consumes a withdrawn position and produces the position's rewards.
+        self.rewards.balance(&self.pair) - withdrawn_position_nft
+    }
+}
+
+impl Protobuf<pb::PositionWithdrawPlan> for PositionWithdrawPlan {}
+
+impl From<PositionWithdrawPlan> for pb::PositionWithdrawPlan {
+    fn from(inner: PositionWithdrawPlan) -> Self {
+        pb::PositionWithdrawPlan {
+            position_id: Some(inner.position_id.into()),
+            pair: Some(inner.pair.into()),
+            reserves: Some(inner.reserves.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::PositionWithdrawPlan> for PositionWithdrawPlan {
+    type Error = anyhow::Error;
+
+    fn try_from(value: pb::PositionWithdrawPlan) -> Result<Self, Self::Error> {
+        Ok(PositionWithdrawPlan {
+            position_id: value
+                .position_id
+                .ok_or_else(|| anyhow::anyhow!("missing position_id"))?
+                .try_into()?,
+            pair: value
+                .pair
+                .ok_or_else(|| anyhow::anyhow!("missing pair"))?
+                .try_into()?,
+            reserves: value
+                .reserves
+                .ok_or_else(|| anyhow::anyhow!("missing reserves"))?
+                .try_into()?,
+        })
+    }
+}
+
+impl Protobuf<pb::PositionRewardClaimPlan> for PositionRewardClaimPlan {}
+
+impl From<PositionRewardClaimPlan> for pb::PositionRewardClaimPlan {
+    fn from(inner: PositionRewardClaimPlan) -> Self {
+        pb::PositionRewardClaimPlan {
+            position_id: Some(inner.position_id.into()),
+            pair: Some(inner.pair.into()),
+            rewards: Some(inner.rewards.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::PositionRewardClaimPlan> for PositionRewardClaimPlan {
+    type Error = anyhow::Error;
+
+    fn try_from(value: pb::PositionRewardClaimPlan) -> Result<Self, Self::Error> {
+        Ok(PositionRewardClaimPlan {
+            position_id: value
+                .position_id
+                .ok_or_else(|| anyhow::anyhow!("missing position_id"))?
+                .try_into()?,
+            pair: value
+                .pair
+                .ok_or_else(|| anyhow::anyhow!("missing pair"))?
+                .try_into()?,
+            rewards: value
+                .rewards
+                .ok_or_else(|| anyhow::anyhow!("missing rewards"))?
+                .try_into()?,
+        })
+    }
+}
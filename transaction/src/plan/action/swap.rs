@@ -2,11 +2,17 @@ use anyhow::{anyhow, Result};
 use ark_ff::UniformRand;
 use decaf377::Fq;
 use penumbra_crypto::dex::swap::SwapPlaintext;
+use penumbra_crypto::eddy::{
+    proofs::{EncryptionProof, TransparentEncryptionProof},
+    EncryptionKey,
+};
 use penumbra_crypto::Balance;
 use penumbra_crypto::{
-    proofs::transparent::SwapProof, FieldExt, Fr, FullViewingKey, Note, NotePayload, Value,
+    proofs::transparent::SwapProof, FieldExt, FlowCiphertext, Fr, FullViewingKey, Note,
+    NotePayload, Value,
 };
 use penumbra_proto::{core::transaction::v1alpha1 as pb, Protobuf};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 
@@ -16,27 +22,40 @@ use crate::action::{swap, Swap};
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(try_from = "pb::SwapPlan", into = "pb::SwapPlan")]
 pub struct SwapPlan {
-    // No commitments for the values, as they're plaintext
-    // until flow encryption is available
-    // pub asset_1_commitment: balance::Commitment,
-    // pub asset_2_commitment: balance::Commitment,
     pub swap_plaintext: SwapPlaintext,
     pub fee_blinding: Fr,
     pub note_blinding: Fq,
     pub esk: decaf377_ka::Secret,
+    pub delta_1_blinding: Fr,
+    pub delta_2_blinding: Fr,
+    pub flow_encryption_key: EncryptionKey,
+    pub flow_encryption_seed: [u8; 32],
 }
 
 impl SwapPlan {
-    /// Create a new [`SwapPlan`] that requests a swap between the given assets and input amounts.
-    pub fn new<R: CryptoRng + RngCore>(rng: &mut R, swap_plaintext: SwapPlaintext) -> SwapPlan {
+    /// Create a new [`SwapPlan`] that requests a swap between the given assets and input amounts,
+    /// with the input amounts encrypted to the flow encryption committee's `flow_encryption_key`.
+    pub fn new<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        swap_plaintext: SwapPlaintext,
+        flow_encryption_key: EncryptionKey,
+    ) -> SwapPlan {
         let note_blinding = Fq::rand(rng);
         let fee_blinding = Fr::rand(rng);
         let esk = decaf377_ka::Secret::new(rng);
+        let delta_1_blinding = Fr::rand(rng);
+        let delta_2_blinding = Fr::rand(rng);
+        let mut flow_encryption_seed = [0u8; 32];
+        rng.fill_bytes(&mut flow_encryption_seed);
         SwapPlan {
             fee_blinding,
             note_blinding,
             esk,
             swap_plaintext,
+            delta_1_blinding,
+            delta_2_blinding,
+            flow_encryption_key,
+            flow_encryption_seed,
         }
     }
 
@@ -81,13 +100,22 @@ impl SwapPlan {
 
         let swap_ciphertext = self.swap_plaintext.encrypt(&self.esk);
 
+        let [(enc_delta_1, enc_delta_1_proof, _), (enc_delta_2, enc_delta_2_proof, _)] =
+            self.encrypted_flows();
+
         swap::Body {
             trading_pair: self.swap_plaintext.trading_pair,
-            delta_1_i: self.swap_plaintext.delta_1_i,
-            delta_2_i: self.swap_plaintext.delta_2_i,
+            delta_1_commitment: self.value_1().commit(self.delta_1_blinding),
+            delta_2_commitment: self.value_2().commit(self.delta_2_blinding),
             fee_commitment,
             swap_nft,
             swap_ciphertext,
+            flow_encryption_key: self.flow_encryption_key,
+            enc_delta_1,
+            enc_delta_2,
+            enc_delta_1_proof,
+            enc_delta_2_proof,
+            limit_prices: self.swap_plaintext.limit_prices,
         }
     }
 
@@ -95,25 +123,51 @@ impl SwapPlan {
     pub fn swap_proof(&self) -> SwapProof {
         let swap_nft_asset_id = self.swap_plaintext.asset_id();
 
+        let [(_, _, enc_delta_1_opening), (_, _, enc_delta_2_opening)] = self.encrypted_flows();
+
         SwapProof {
             claim_address: self.swap_plaintext.claim_address,
             note_blinding: self.note_blinding,
             fee_delta: self.swap_plaintext.claim_fee.clone(),
             fee_blinding: self.fee_blinding,
-            value_t1: Value {
-                amount: self.swap_plaintext.delta_1_i,
-                asset_id: self.swap_plaintext.trading_pair.asset_1(),
-            },
-            value_t2: Value {
-                amount: self.swap_plaintext.delta_2_i,
-                asset_id: self.swap_plaintext.trading_pair.asset_2(),
-            },
+            value_t1: self.value_1(),
+            value_t2: self.value_2(),
             swap_nft_asset_id,
             esk: self.esk.clone(),
-            // TODO: no blinding factors for deltas yet, they're plaintext
-            // until flow encryption is available
-            // delta_1_blinding: self.delta_1_blinding(),
-            // delta_2_blinding: self.delta_2_blinding(),
+            delta_1_blinding: self.delta_1_blinding,
+            delta_2_blinding: self.delta_2_blinding,
+            enc_delta_1_opening,
+            enc_delta_2_opening,
+        }
+    }
+
+    /// Encrypts the input amounts to the flow encryption committee.
+    ///
+    /// The randomness is derived from the plan's seed, so that the body and
+    /// the proof are built from the same encryptions.
+    fn encrypted_flows(
+        &self,
+    ) -> [(FlowCiphertext, EncryptionProof, TransparentEncryptionProof); 2] {
+        let mut rng = ChaCha20Rng::from_seed(self.flow_encryption_seed);
+        [self.swap_plaintext.delta_1_i, self.swap_plaintext.delta_2_i].map(|amount| {
+            FlowCiphertext::encrypt(amount.into(), &self.flow_encryption_key, &mut rng)
+                .expect("amounts are 64-bit values")
+        })
+    }
+
+    /// The input value of asset 1.
+    fn value_1(&self) -> Value {
+        Value {
+            amount: self.swap_plaintext.delta_1_i,
+            asset_id: self.swap_plaintext.trading_pair.asset_1(),
+        }
+    }
+
+    /// The input value of asset 2.
+    fn value_2(&self) -> Value {
+        Value {
+            amount: self.swap_plaintext.delta_2_i,
+            asset_id: self.swap_plaintext.trading_pair.asset_2(),
         }
     }
 
@@ -122,14 +176,8 @@ impl SwapPlan {
         // - the input amount of asset 1
         // - the input amount of asset 2
         // - the pre-paid swap claim fee
-        let value_1 = Value {
-            amount: self.swap_plaintext.delta_1_i,
-            asset_id: self.swap_plaintext.trading_pair.asset_1(),
-        };
-        let value_2 = Value {
-            amount: self.swap_plaintext.delta_2_i,
-            asset_id: self.swap_plaintext.trading_pair.asset_2(),
-        };
+        let value_1 = self.value_1();
+        let value_2 = self.value_2();
         let value_fee = Value {
             amount: self.swap_plaintext.claim_fee.amount(),
             asset_id: self.swap_plaintext.claim_fee.asset_id(),
@@ -152,6 +200,10 @@ impl From<SwapPlan> for pb::SwapPlan {
             fee_blinding: msg.fee_blinding.to_bytes().to_vec().into(),
             note_blinding: msg.note_blinding.to_bytes().to_vec().into(),
             esk: msg.esk.to_bytes().to_vec().into(),
+            delta_1_blinding: msg.delta_1_blinding.to_bytes().to_vec().into(),
+            delta_2_blinding: msg.delta_2_blinding.to_bytes().to_vec().into(),
+            flow_encryption_key: msg.flow_encryption_key.to_bytes().to_vec().into(),
+            flow_encryption_seed: msg.flow_encryption_seed.to_vec().into(),
         }
     }
 }
@@ -171,6 +223,14 @@ impl TryFrom<pb::SwapPlan> for SwapPlan {
                 .try_into()?,
             note_blinding: Fq::from_bytes(msg.note_blinding[..].try_into()?)?,
             esk: msg.esk.as_ref().try_into()?,
+            delta_1_blinding: Fr::from_bytes(msg.delta_1_blinding[..].try_into()?)
+                .map_err(|_| anyhow!("proto malformed"))?,
+            delta_2_blinding: Fr::from_bytes(msg.delta_2_blinding[..].try_into()?)
+                .map_err(|_| anyhow!("proto malformed"))?,
+            flow_encryption_key: msg.flow_encryption_key[..].try_into()?,
+            flow_encryption_seed: msg.flow_encryption_seed[..]
+                .try_into()
+                .map_err(|_| anyhow!("proto malformed"))?,
         })
     }
 }
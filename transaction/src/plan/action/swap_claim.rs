@@ -80,6 +80,8 @@ impl SwapClaimPlan {
         note_commitment_proof: &tct::Proof,
         nk: &NullifierKey,
     ) -> SwapClaimProof {
+        let (lambda_1, lambda_2) = self.pro_rata_outputs();
+
         SwapClaimProof {
             swap_nft_asset_id: self.swap_plaintext.asset_id(),
             claim_address: self.swap_nft_note.address(),
@@ -88,8 +90,9 @@ impl SwapClaimPlan {
             note_blinding: self.swap_nft_note.note_blinding(),
             delta_1_i: self.swap_plaintext.delta_1_i.into(),
             delta_2_i: self.swap_plaintext.delta_2_i.into(),
-            lambda_1: self.output_data.lambda_1,
-            lambda_2: self.output_data.lambda_2,
+            limit_prices: self.swap_plaintext.limit_prices,
+            lambda_1,
+            lambda_2,
             note_blinding_1: self.output_1_blinding,
             note_blinding_2: self.output_2_blinding,
             esk_1: self.esk_1.clone(),
@@ -98,12 +101,21 @@ impl SwapClaimPlan {
         }
     }
 
+    /// Compute the outputs of the swap being claimed: its pro rata share of
+    /// the batch output, or a refund of its inputs.
+    fn pro_rata_outputs(&self) -> (u64, u64) {
+        self.output_data.pro_rata_outputs(
+            (
+                self.swap_plaintext.delta_1_i.into(),
+                self.swap_plaintext.delta_2_i.into(),
+            ),
+            self.swap_plaintext.limit_prices,
+        )
+    }
+
     /// Construct the [`swap_claim::Body`] described by this plan.
     pub fn swap_claim_body(&self, fvk: &FullViewingKey) -> swap_claim::Body {
-        let (lambda_1, lambda_2) = self.output_data.pro_rata_outputs((
-            self.swap_plaintext.delta_1_i.into(),
-            self.swap_plaintext.delta_2_i.into(),
-        ));
+        let (lambda_1, lambda_2) = self.pro_rata_outputs();
 
         let output_1_note = Note::from_parts(
             self.swap_nft_note.address(),
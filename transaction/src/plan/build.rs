@@ -33,6 +33,15 @@ impl TransactionPlan {
             ));
         }
 
+        let delegator_vote_count = self.delegator_vote_plans().count();
+        if auth_data.delegator_vote_auths.len() != delegator_vote_count {
+            return Err(anyhow::anyhow!(
+                "expected {} delegator vote auths but got {}",
+                delegator_vote_count,
+                auth_data.delegator_vote_auths.len()
+            ));
+        }
+
         let mut actions = Vec::new();
         let mut fmd_clues = Vec::new();
         let mut synthetic_blinding_factor = Fr::zero();
@@ -83,6 +92,8 @@ impl TransactionPlan {
         // Build the transaction's swaps.
         for swap_plan in self.swap_plans() {
             synthetic_blinding_factor -= swap_plan.fee_blinding;
+            synthetic_blinding_factor -= swap_plan.delta_1_blinding;
+            synthetic_blinding_factor -= swap_plan.delta_2_blinding;
             actions.push(Action::Swap(swap_plan.swap(fvk)));
         }
 
@@ -112,12 +123,31 @@ impl TransactionPlan {
         // blinding factor, so they don't contribute to the
         // synthetic_blinding_factor used for the binding signature.
 
+        for position_open in self.position_openings().cloned() {
+            actions.push(Action::PositionOpen(position_open))
+        }
+        for position_close in self.position_closings().cloned() {
+            actions.push(Action::PositionClose(position_close))
+        }
+        for position_withdraw in self.position_withdrawals() {
+            actions.push(Action::PositionWithdraw(
+                position_withdraw.position_withdraw(),
+            ))
+        }
+        for position_reward_claim in self.position_reward_claims() {
+            actions.push(Action::PositionRewardClaim(
+                position_reward_claim.position_reward_claim(),
+            ))
+        }
         for delegation in self.delegations().cloned() {
             actions.push(Action::Delegate(delegation))
         }
         for undelegation in self.undelegations().cloned() {
             actions.push(Action::Undelegate(undelegation))
         }
+        for redelegation in self.redelegations().cloned() {
+            actions.push(Action::Redelegate(redelegation))
+        }
         for proposal_submit in self.proposal_submits().cloned() {
             actions.push(Action::ProposalSubmit(proposal_submit))
         }
@@ -133,7 +163,22 @@ impl TransactionPlan {
         for validator_vote in self.validator_votes().cloned() {
             actions.push(Action::ValidatorVote(validator_vote))
         }
-        // TODO: delegator vote
+        for (delegator_vote_plan, auth_sig) in self
+            .delegator_vote_plans()
+            .zip(auth_data.delegator_vote_auths.into_iter())
+        {
+            let note_commitment = delegator_vote_plan.staked_note.commit();
+            let auth_path = witness_data
+                .note_commitment_proofs
+                .get(&note_commitment)
+                .context(format!("could not get proof for {:?}", note_commitment))?;
+
+            actions.push(Action::DelegatorVote(delegator_vote_plan.delegator_vote(
+                fvk,
+                auth_sig,
+                auth_path.clone(),
+            )));
+        }
         for vd in self.validator_definitions().cloned() {
             actions.push(Action::ValidatorDefinition(vd))
         }
@@ -18,7 +18,10 @@ use penumbra_tct as tct;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    action::{Delegate, Output, ProposalSubmit, ProposalWithdraw, Swap, Undelegate, ValidatorVote},
+    action::{
+        Delegate, DelegatorVote, Output, ProposalSubmit, ProposalWithdraw, Redelegate, Swap,
+        Undelegate, ValidatorVote,
+    },
     Action, ActionView, IsAction, TransactionPerspective, TransactionView,
 };
 
@@ -103,6 +106,16 @@ impl Transaction {
         })
     }
 
+    pub fn redelegations(&self) -> impl Iterator<Item = &Redelegate> {
+        self.actions().filter_map(|action| {
+            if let Action::Redelegate(d) = action {
+                Some(d)
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn proposal_submits(&self) -> impl Iterator<Item = &ProposalSubmit> {
         self.actions().filter_map(|action| {
             if let Action::ProposalSubmit(s) = action {
@@ -133,15 +146,15 @@ impl Transaction {
         })
     }
 
-    // pub fn delegator_votes(&self) -> impl Iterator<Item = &DelegatorVote> {
-    //     self.actions().filter_map(|action| {
-    //         if let Action::DelegatorVote(v) = action {
-    //             Some(v)
-    //         } else {
-    //             None
-    //         }
-    //     })
-    // }
+    pub fn delegator_votes(&self) -> impl Iterator<Item = &DelegatorVote> {
+        self.actions().filter_map(|action| {
+            if let Action::DelegatorVote(v) = action {
+                Some(v)
+            } else {
+                None
+            }
+        })
+    }
 
     pub fn ibc_actions(&self) -> impl Iterator<Item = &pb_ibc::IbcAction> {
         self.actions().filter_map(|action| {
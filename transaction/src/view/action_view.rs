@@ -10,8 +10,8 @@ pub use swap_claim_view::SwapClaimView;
 pub use swap_view::SwapView;
 
 use crate::action::{
-    Delegate, ICS20Withdrawal, PositionClose, PositionOpen, PositionRewardClaim, PositionWithdraw,
-    ProposalSubmit, ProposalWithdraw, Undelegate, ValidatorVote,
+    Delegate, DelegatorVote, ICS20Withdrawal, PositionClose, PositionOpen, PositionRewardClaim,
+    PositionWithdraw, ProposalSubmit, ProposalWithdraw, Redelegate, Undelegate, ValidatorVote,
 };
 
 pub enum ActionView {
@@ -23,10 +23,12 @@ pub enum ActionView {
     // Action types with transparent contents
     Delegate(Delegate),
     Undelegate(Undelegate),
+    Redelegate(Redelegate),
     ValidatorDefinition(ValidatorDefinition),
     IBCAction(IbcAction),
     ProposalSubmit(ProposalSubmit),
     ProposalWithdraw(ProposalWithdraw),
+    DelegatorVote(DelegatorVote),
     ValidatorVote(ValidatorVote),
     PositionOpen(PositionOpen),
     PositionClose(PositionClose),
@@ -6,6 +6,7 @@ use clap::{Parser, Subcommand};
 use penumbra_crypto::FullViewingKey;
 use penumbra_proto::client::v1alpha1::oblivious_query_client::ObliviousQueryClient;
 use penumbra_proto::client::v1alpha1::ChainParamsRequest;
+use penumbra_proto::custody::v1alpha1::custody_protocol_client::CustodyProtocolClient;
 use penumbra_proto::view::v1alpha1::view_protocol_server::ViewProtocolServer;
 use penumbra_view::ViewService;
 use std::env;
@@ -51,6 +52,11 @@ enum Command {
         /// Bind the view gRPC server to this port.
         #[clap(long, default_value = "8081")]
         view_port: u16,
+        /// If set, automatically claim swaps once their batch has been
+        /// executed, using the custody service at this address (e.g.,
+        /// `127.0.0.1:8082`) to authorize the claims.
+        #[clap(long)]
+        auto_claim_custody: Option<String>,
     },
 }
 #[tokio::main]
@@ -81,13 +87,27 @@ async fn main() -> Result<()> {
             .await?;
             Ok(())
         }
-        Command::Start { host, view_port } => {
-            tracing::info!(?opt.sqlite_path, ?host, ?view_port, ?opt.node, ?opt.tendermint_port, ?opt.pd_port, "starting pviewd");
+        Command::Start {
+            host,
+            view_port,
+            auto_claim_custody,
+        } => {
+            tracing::info!(?opt.sqlite_path, ?host, ?view_port, ?opt.node, ?opt.tendermint_port, ?opt.pd_port, ?auto_claim_custody, "starting pviewd");
 
             let storage = penumbra_view::Storage::load(opt.sqlite_path).await?;
 
+            let custody = match auto_claim_custody {
+                Some(address) => Some(
+                    CustodyProtocolClient::connect(format!("http://{}", address))
+                        .await
+                        .context("could not connect to the custody service")?,
+                ),
+                None => None,
+            };
+
             let service =
-                ViewService::new(storage, opt.node, opt.pd_port, opt.tendermint_port).await?;
+                ViewService::new(storage, opt.node, opt.pd_port, opt.tendermint_port, custody)
+                    .await?;
 
             tokio::spawn(
                 Server::builder()
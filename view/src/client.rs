@@ -11,7 +11,9 @@ use tonic::async_trait;
 use tonic::codegen::Bytes;
 use tracing::instrument;
 
-use crate::{QuarantinedNoteRecord, SpendableNoteRecord, StatusStreamResponse};
+use crate::{
+    ClaimableSwapRecord, QuarantinedNoteRecord, SpendableNoteRecord, StatusStreamResponse,
+};
 
 /// The view protocol is used by a view client, who wants to do some
 /// transaction-related actions, to request data from a view service, which is
@@ -103,6 +105,9 @@ pub trait ViewClient {
         end_height: Option<u64>,
     ) -> Result<Vec<(u64, Transaction)>>;
 
+    /// Queries for unclaimed swaps whose batch has been executed.
+    async fn claimable_swaps(&mut self, account_id: AccountID) -> Result<Vec<ClaimableSwapRecord>>;
+
     /// Return unspent notes, grouped by address index and then by asset id.
     #[instrument(skip(self, account_id))]
     async fn unspent_notes_by_address_and_asset(
@@ -460,4 +465,17 @@ where
 
         Ok(txs)
     }
+
+    async fn claimable_swaps(&mut self, account_id: AccountID) -> Result<Vec<ClaimableSwapRecord>> {
+        let pb_swaps: Vec<_> = self
+            .claimable_swaps(tonic::Request::new(pb::ClaimableSwapsRequest {
+                account_id: Some(account_id.into()),
+            }))
+            .await?
+            .into_inner()
+            .try_collect()
+            .await?;
+
+        pb_swaps.into_iter().map(TryInto::try_into).collect()
+    }
 }
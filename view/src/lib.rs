@@ -4,14 +4,16 @@
 mod client;
 mod metrics;
 mod note_record;
+mod pending_swaps;
 mod quarantined_note_record;
 mod service;
 mod status;
 mod storage;
+mod swap_record;
 mod sync;
 mod worker;
 
-use worker::Worker;
+use worker::{ClaimableSwaps, Worker};
 
 pub use crate::metrics::register_metrics;
 pub use client::ViewClient;
@@ -20,3 +22,4 @@ pub use quarantined_note_record::QuarantinedNoteRecord;
 pub use service::ViewService;
 pub use status::StatusStreamResponse;
 pub use storage::Storage;
+pub use swap_record::ClaimableSwapRecord;
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use penumbra_crypto::{
+    dex::{swap::SwapPlaintext, BatchSwapOutputData, TradingPair},
+    note, Nullifier,
+};
+
+use crate::{ClaimableSwapRecord, SpendableNoteRecord};
+
+/// A batch swap, identified by the height at which it was made and its trading pair.
+pub(crate) type BatchId = (u64, TradingPair);
+
+/// Unclaimed swaps whose batch hasn't been executed yet, grouped by batch.
+///
+/// All the swaps in a batch share the same output data, so it only needs to be
+/// fetched once per batch, rather than once per swap.
+#[derive(Debug, Default)]
+pub(crate) struct PendingSwaps {
+    batches: BTreeMap<BatchId, PendingBatch>,
+}
+
+#[derive(Debug)]
+struct PendingBatch {
+    /// The swaps in the batch, keyed by the note commitment of their swap NFT.
+    swaps: BTreeMap<note::Commitment, (SpendableNoteRecord, SwapPlaintext)>,
+    /// The sync height at which to next fetch the batch's output data.
+    next_fetch: u64,
+}
+
+impl PendingSwaps {
+    /// The number of pending swaps.
+    pub fn len(&self) -> usize {
+        self.batches.values().map(|batch| batch.swaps.len()).sum()
+    }
+
+    /// Starts tracking a swap. Its batch's output data is first fetched once
+    /// we've synced the height at which the swap was made.
+    pub fn insert(&mut self, record: SpendableNoteRecord, swap_plaintext: SwapPlaintext) {
+        let height = record.height_created;
+        self.batches
+            .entry((height, swap_plaintext.trading_pair))
+            .or_insert_with(|| PendingBatch {
+                swaps: BTreeMap::new(),
+                next_fetch: height,
+            })
+            .swaps
+            .insert(record.note_commitment, (record, swap_plaintext));
+    }
+
+    /// Stops tracking the swaps whose swap NFTs were spent, i.e., which were claimed.
+    pub fn forget_spent(&mut self, spent_nullifiers: &[Nullifier]) {
+        for batch in self.batches.values_mut() {
+            batch
+                .swaps
+                .retain(|_, (record, _)| !spent_nullifiers.contains(&record.nullifier));
+        }
+        self.batches.retain(|_, batch| !batch.swaps.is_empty());
+    }
+
+    /// The batches whose output data should be fetched at the sync height `height`.
+    pub fn due(&self, height: u64) -> Vec<BatchId> {
+        self.batches
+            .iter()
+            .filter(|(_, batch)| batch.next_fetch <= height)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Defers fetching the output data of a batch until the sync height
+    /// `height`, because the batch hasn't been executed yet, or fetching its
+    /// output data failed.
+    pub fn defer(&mut self, id: BatchId, height: u64) {
+        if let Some(batch) = self.batches.get_mut(&id) {
+            batch.next_fetch = height;
+        }
+    }
+
+    /// Stops tracking the swaps in an executed batch, returning them as claimable swaps.
+    pub fn executed(
+        &mut self,
+        id: BatchId,
+        output_data: BatchSwapOutputData,
+    ) -> Vec<ClaimableSwapRecord> {
+        self.batches
+            .remove(&id)
+            .map(|batch| {
+                batch
+                    .swaps
+                    .into_values()
+                    .map(|(swap_nft, swap_plaintext)| ClaimableSwapRecord {
+                        swap_nft,
+                        swap_plaintext,
+                        output_data,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use penumbra_chain::NoteSource;
+    use penumbra_crypto::{
+        asset,
+        dex::{LimitPrices, LimitTiers},
+        keys::{AddressIndex, SeedPhrase, SpendKey},
+        transaction::Fee,
+        Address, Fq, Note, Value, STAKING_TOKEN_ASSET_ID,
+    };
+    use rand_core::OsRng;
+
+    use super::*;
+
+    fn address() -> Address {
+        let sk = SpendKey::from_seed_phrase(SeedPhrase::generate(&mut OsRng), 0);
+        sk.full_viewing_key()
+            .incoming()
+            .payment_address(0u64.into())
+            .0
+    }
+
+    fn trading_pair(unit: &str) -> TradingPair {
+        let id = asset::REGISTRY.parse_unit(unit).id();
+        TradingPair::canonical_order_for((*STAKING_TOKEN_ASSET_ID, id)).unwrap()
+    }
+
+    fn swap(
+        height: u64,
+        trading_pair: TradingPair,
+        n: u64,
+    ) -> (SpendableNoteRecord, SwapPlaintext) {
+        let claim_address = address();
+        let note = Note::generate(
+            &mut OsRng,
+            &claim_address,
+            Value {
+                amount: 1u64.into(),
+                asset_id: *STAKING_TOKEN_ASSET_ID,
+            },
+        );
+        let record = SpendableNoteRecord {
+            note_commitment: note.commit(),
+            note,
+            address_index: AddressIndex::Numeric(0),
+            nullifier: Nullifier(Fq::from(n)),
+            height_created: height,
+            height_spent: None,
+            position: n.into(),
+            source: NoteSource::Genesis,
+        };
+        let swap_plaintext = SwapPlaintext {
+            trading_pair,
+            delta_1_i: n.into(),
+            delta_2_i: 0u64.into(),
+            claim_fee: Fee::default(),
+            claim_address,
+            limit_prices: Default::default(),
+        };
+        (record, swap_plaintext)
+    }
+
+    fn output_data((height, trading_pair): BatchId) -> BatchSwapOutputData {
+        BatchSwapOutputData {
+            delta_1: 3,
+            delta_2: 0,
+            lambda_1: 0,
+            lambda_2: 3,
+            height,
+            trading_pair,
+            success: true,
+            limit_thresholds: LimitPrices::MAX,
+            refunded_tiers: LimitTiers::default(),
+        }
+    }
+
+    #[test]
+    fn output_data_is_fetched_once_per_batch() {
+        let (gm, gn) = (trading_pair("gm"), trading_pair("gn"));
+        let mut pending = PendingSwaps::default();
+        for (height, trading_pair, n) in [(10, gm, 1), (10, gm, 2), (10, gn, 3), (12, gm, 4)] {
+            let (record, swap_plaintext) = swap(height, trading_pair, n);
+            pending.insert(record, swap_plaintext);
+        }
+        assert_eq!(pending.len(), 4);
+
+        // Batches aren't fetched before the height at which their swaps were
+        // made, and swaps in the same batch share a single fetch.
+        assert!(pending.due(9).is_empty());
+        assert_eq!(pending.due(10), vec![(10, gm), (10, gn)]);
+
+        let claimable = pending.executed((10, gm), output_data((10, gm)));
+        assert_eq!(claimable.len(), 2);
+        assert!(claimable
+            .iter()
+            .all(|swap| swap.output_data == output_data((10, gm))));
+
+        // A batch whose output data couldn't be fetched is fetched again later.
+        pending.defer((10, gn), 11);
+        assert!(pending.due(10).is_empty());
+        assert_eq!(pending.due(11), vec![(10, gn)]);
+        assert_eq!(pending.due(12), vec![(10, gn), (12, gm)]);
+    }
+
+    #[test]
+    fn claimed_swaps_are_forgotten() {
+        let gm = trading_pair("gm");
+        let mut pending = PendingSwaps::default();
+        let (record, swap_plaintext) = swap(10, gm, 1);
+        let nullifier = record.nullifier;
+        pending.insert(record, swap_plaintext);
+
+        pending.forget_spent(&[nullifier]);
+        assert_eq!(pending.len(), 0);
+        assert!(pending.due(10).is_empty());
+    }
+}
@@ -15,6 +15,7 @@ use penumbra_proto::{
     core::chain::v1alpha1 as pbp,
     core::crypto::v1alpha1 as pbc,
     core::transaction::v1alpha1 as pbt,
+    custody::v1alpha1::custody_protocol_client::CustodyProtocolClient,
     view::v1alpha1::{
         self as pb, view_protocol_server::ViewProtocol, StatusResponse,
         TransactionHashStreamResponse, TransactionStreamResponse,
@@ -24,10 +25,10 @@ use penumbra_tct::{Commitment, Proof};
 use penumbra_transaction::WitnessData;
 use tokio::sync::{watch, RwLock};
 use tokio_stream::wrappers::WatchStream;
-use tonic::async_trait;
+use tonic::{async_trait, transport::Channel};
 use tracing::instrument;
 
-use crate::{Storage, Worker};
+use crate::{ClaimableSwaps, Storage, Worker};
 
 /// A service that synchronizes private chain state and responds to queries
 /// about it.
@@ -52,6 +53,8 @@ pub struct ViewService {
     tendermint_port: u16,
     /// Used to watch for changes to the sync height.
     sync_height_rx: watch::Receiver<u64>,
+    // The claimable swaps detected by the worker task.
+    claimable_swaps: ClaimableSwaps,
 }
 
 impl ViewService {
@@ -65,7 +68,7 @@ impl ViewService {
     ) -> anyhow::Result<Self> {
         let storage = Storage::load_or_initialize(storage_path, fvk, node.clone(), pd_port).await?;
 
-        Self::new(storage, node, pd_port, tendermint_port).await
+        Self::new(storage, node, pd_port, tendermint_port, None).await
     }
 
     /// Constructs a new [`ViewService`], spawning a sync task internally.
@@ -75,14 +78,25 @@ impl ViewService {
     /// To create multiple [`ViewService`]s, clone the [`ViewService`] returned
     /// by this method, rather than calling it multiple times.  That way, each clone
     /// will be backed by the same scanning task, rather than each spawning its own.
+    ///
+    /// If `custody` is set, the sync task also claims swaps automatically as
+    /// soon as their batch has been executed, with the claims authorized by
+    /// that custody service.
     pub async fn new(
         storage: Storage,
         node: String,
         pd_port: u16,
         tendermint_port: u16,
+        custody: Option<CustodyProtocolClient<Channel>>,
     ) -> Result<Self, anyhow::Error> {
-        let (worker, nct, error_slot, sync_height_rx) =
-            Worker::new(storage.clone(), node.clone(), pd_port, tendermint_port).await?;
+        let (worker, nct, error_slot, sync_height_rx, claimable_swaps) = Worker::new(
+            storage.clone(),
+            node.clone(),
+            pd_port,
+            tendermint_port,
+            custody,
+        )
+        .await?;
 
         tokio::spawn(worker.run());
 
@@ -97,6 +111,7 @@ impl ViewService {
             note_commitment_tree: nct,
             node,
             tendermint_port,
+            claimable_swaps,
         })
     }
 
@@ -238,6 +253,8 @@ impl ViewProtocol for ViewService {
     type TransactionsStream = Pin<
         Box<dyn futures::Stream<Item = Result<TransactionStreamResponse, tonic::Status>> + Send>,
     >;
+    type ClaimableSwapsStream =
+        Pin<Box<dyn futures::Stream<Item = Result<pb::ClaimableSwapRecord, tonic::Status>> + Send>>;
 
     async fn note_by_commitment(
         &self,
@@ -509,6 +526,37 @@ impl ViewProtocol for ViewService {
         ))
     }
 
+    async fn claimable_swaps(
+        &self,
+        request: tonic::Request<pb::ClaimableSwapsRequest>,
+    ) -> Result<tonic::Response<Self::ClaimableSwapsStream>, tonic::Status> {
+        self.check_worker().await?;
+        self.check_fvk(request.get_ref().account_id.as_ref())
+            .await?;
+
+        let swaps = self
+            .claimable_swaps
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let stream = try_stream! {
+            for swap in swaps {
+                yield swap.into()
+            }
+        };
+
+        Ok(tonic::Response::new(
+            stream
+                .map_err(|e: anyhow::Error| {
+                    tonic::Status::unavailable(format!("error getting claimable swaps: {}", e))
+                })
+                .boxed(),
+        ))
+    }
+
     async fn witness(
         &self,
         request: tonic::Request<pb::WitnessRequest>,
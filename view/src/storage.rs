@@ -947,6 +947,15 @@ impl Storage {
             .await?;
         }
 
+        // Update chain parameters if they've changed.
+        if let Some(chain_parameters) = &filtered_block.chain_parameters {
+            let chain_params_bytes = &ChainParameters::encode_to_vec(chain_parameters)[..];
+
+            sqlx::query!("UPDATE chain_params SET bytes = ?", chain_params_bytes)
+                .execute(&mut dbtx)
+                .await?;
+        }
+
         // Record block height as latest synced height
 
         let latest_sync_height = filtered_block.height as i64;
@@ -0,0 +1,43 @@
+use penumbra_crypto::dex::{swap::SwapPlaintext, BatchSwapOutputData};
+use penumbra_proto::{view::v1alpha1 as pb, Protobuf};
+
+use crate::SpendableNoteRecord;
+
+/// Corresponds to the ClaimableSwapRecord proto
+#[derive(Debug, Clone)]
+pub struct ClaimableSwapRecord {
+    pub swap_nft: SpendableNoteRecord,
+    pub swap_plaintext: SwapPlaintext,
+    pub output_data: BatchSwapOutputData,
+}
+
+impl Protobuf<pb::ClaimableSwapRecord> for ClaimableSwapRecord {}
+impl From<ClaimableSwapRecord> for pb::ClaimableSwapRecord {
+    fn from(v: ClaimableSwapRecord) -> Self {
+        pb::ClaimableSwapRecord {
+            swap_nft: Some(v.swap_nft.into()),
+            swap_plaintext: Some(v.swap_plaintext.into()),
+            output_data: Some(v.output_data.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::ClaimableSwapRecord> for ClaimableSwapRecord {
+    type Error = anyhow::Error;
+    fn try_from(v: pb::ClaimableSwapRecord) -> Result<Self, Self::Error> {
+        Ok(ClaimableSwapRecord {
+            swap_nft: v
+                .swap_nft
+                .ok_or_else(|| anyhow::anyhow!("missing swap NFT"))?
+                .try_into()?,
+            swap_plaintext: v
+                .swap_plaintext
+                .ok_or_else(|| anyhow::anyhow!("missing swap plaintext"))?
+                .try_into()?,
+            output_data: v
+                .output_data
+                .ok_or_else(|| anyhow::anyhow!("missing output data"))?
+                .try_into()?,
+        })
+    }
+}
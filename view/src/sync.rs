@@ -1,7 +1,8 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use penumbra_chain::{
-    params::FmdParameters, AnnotatedNotePayload, CompactBlock, Epoch, NoteSource,
+    params::{ChainParameters, FmdParameters},
+    AnnotatedNotePayload, CompactBlock, Epoch, NoteSource,
 };
 use penumbra_crypto::{FullViewingKey, IdentityKey, Note, NotePayload, Nullifier};
 use penumbra_tct as tct;
@@ -18,6 +19,7 @@ pub struct FilteredBlock {
     pub slashed_validators: Vec<IdentityKey>,
     pub height: u64,
     pub fmd_parameters: Option<FmdParameters>,
+    pub chain_parameters: Option<ChainParameters>,
 }
 
 impl FilteredBlock {
@@ -55,6 +57,7 @@ pub async fn scan_block(
         slashed,
         fmd_parameters,
         proposal_started,
+        chain_parameters,
     }: CompactBlock,
     epoch_duration: u64,
     storage: &Storage,
@@ -213,6 +216,7 @@ pub async fn scan_block(
         slashed_validators: slashed,
         height,
         fmd_parameters,
+        chain_parameters,
     };
 
     if !result.spent_quarantined_nullifiers.is_empty() || !result.new_quarantined_notes.is_empty() {
@@ -1,30 +1,46 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     sync::{Arc, Mutex},
 };
 
 use penumbra_chain::{sync::CompactBlock, Epoch};
-use penumbra_crypto::{Asset, FullViewingKey, Nullifier};
+use penumbra_crypto::{dex::BatchSwapOutputData, note, Asset, FullViewingKey, Nullifier};
 use penumbra_proto::{
     client::v1alpha1::{
-        oblivious_query_client::ObliviousQueryClient, AssetListRequest, CompactBlockRangeRequest,
+        oblivious_query_client::ObliviousQueryClient, specific_query_client::SpecificQueryClient,
+        AssetListRequest, BatchSwapOutputDataRequest, CompactBlockRangeRequest,
     },
+    custody::v1alpha1::{custody_protocol_client::CustodyProtocolClient, AuthorizeRequest},
     Protobuf,
 };
-use penumbra_transaction::Transaction;
+use penumbra_transaction::{
+    plan::{SwapClaimPlan, TransactionPlan},
+    AuthorizationData, Transaction, WitnessData,
+};
+use rand_core::OsRng;
 use sha2::Digest;
 use tendermint_rpc::Client;
 use tokio::sync::{watch, RwLock};
 use tonic::transport::Channel;
 
-#[cfg(feature = "nct-divergence-check")]
-use penumbra_proto::client::v1alpha1::specific_query_client::SpecificQueryClient;
-
 use crate::{
+    pending_swaps::PendingSwaps,
     sync::{scan_block, FilteredBlock},
-    Storage,
+    ClaimableSwapRecord, SpendableNoteRecord, Storage,
 };
 
+/// The number of blocks after which a swap claim that was submitted
+/// automatically, but hasn't landed, is submitted again.
+const CLAIM_RETRY_BLOCKS: u64 = 10;
+
+/// The number of blocks after which fetching the output data of a batch is
+/// retried, if it failed.
+const FETCH_RETRY_BLOCKS: u64 = 10;
+
+/// Unclaimed swaps whose batch has been executed, keyed by the note commitment
+/// of their swap NFT.
+pub(crate) type ClaimableSwaps = Arc<RwLock<BTreeMap<note::Commitment, ClaimableSwapRecord>>>;
+
 pub struct Worker {
     storage: Storage,
     client: ObliviousQueryClient<Channel>,
@@ -33,8 +49,15 @@ pub struct Worker {
     error_slot: Arc<Mutex<Option<anyhow::Error>>>,
     sync_height_tx: watch::Sender<u64>,
     tm_client: tendermint_rpc::HttpClient,
-    #[cfg(feature = "nct-divergence-check")]
     specific_client: SpecificQueryClient<Channel>,
+    // If set, claimable swaps are claimed automatically, with claims
+    // authorized by this custody service.
+    custody: Option<CustodyProtocolClient<Channel>>,
+    // Unclaimed swaps whose batch hasn't been executed yet.
+    pending_swaps: PendingSwaps,
+    claimable_swaps: ClaimableSwaps,
+    // The heights at which claims were automatically submitted for claimable swaps.
+    claims_submitted: BTreeMap<note::Commitment, u64>,
 }
 
 impl Worker {
@@ -43,18 +66,24 @@ impl Worker {
     /// - the worker itself;
     /// - a shared, in-memory NCT instance;
     /// - a shared error slot;
-    /// - a channel for notifying the client of sync progress.
+    /// - a channel for notifying the client of sync progress;
+    /// - a shared set of claimable swaps.
+    ///
+    /// If `custody` is set, the worker claims swaps automatically as soon as
+    /// they become claimable, with claims authorized by that custody service.
     pub async fn new(
         storage: Storage,
         node: String,
         pd_port: u16,
         tendermint_port: u16,
+        custody: Option<CustodyProtocolClient<Channel>>,
     ) -> Result<
         (
             Self,
             Arc<RwLock<penumbra_tct::Tree>>,
             Arc<Mutex<Option<anyhow::Error>>>,
             watch::Receiver<u64>,
+            ClaimableSwaps,
         ),
         anyhow::Error,
     > {
@@ -70,8 +99,10 @@ impl Worker {
         // Mark the current height as seen, since it's not new.
         sync_height_rx.borrow_and_update();
 
+        // Create a shared set of claimable swaps.
+        let claimable_swaps = ClaimableSwaps::default();
+
         let client = ObliviousQueryClient::connect(format!("http://{}:{}", node, pd_port)).await?;
-        #[cfg(feature = "nct-divergence-check")]
         let specific_client =
             SpecificQueryClient::connect(format!("http://{}:{}", node, pd_port)).await?;
 
@@ -88,12 +119,16 @@ impl Worker {
                 error_slot: error_slot.clone(),
                 sync_height_tx,
                 tm_client,
-                #[cfg(feature = "nct-divergence-check")]
                 specific_client,
+                custody,
+                pending_swaps: Default::default(),
+                claimable_swaps: claimable_swaps.clone(),
+                claims_submitted: Default::default(),
             },
             nct,
             error_slot,
             sync_height_rx,
+            claimable_swaps,
         ))
     }
 
@@ -265,6 +300,18 @@ impl Worker {
                 // Download any transactions we detected.
                 let transactions = self.fetch_transactions(&filtered_block).await?;
 
+                // Track any swaps we made, and forget any we claimed.
+                let new_notes = filtered_block
+                    .new_notes
+                    .iter()
+                    .map(|record| (record.note_commitment, record.clone()))
+                    .collect();
+                for transaction in transactions.iter() {
+                    self.track_swaps(transaction, &new_notes)?;
+                }
+                self.forget_claimed_swaps(&filtered_block.spent_nullifiers)
+                    .await;
+
                 self.storage
                     .record_block(filtered_block.clone(), transactions, &mut nct_guard)
                     .await?;
@@ -277,6 +324,8 @@ impl Worker {
             // Release the NCT RwLock
             drop(nct_guard);
 
+            self.update_claimable_swaps(height).await;
+
             // Check if we should stop waiting for blocks to arrive, because the view
             // services are dropped and we're supposed to shut down.
             if self.sync_height_tx.is_closed() {
@@ -300,9 +349,222 @@ impl Worker {
         // created at genesis. In the future, we'll want to have a way for
         // clients to learn about assets as they're created.
         self.fetch_assets().await?;
+        self.load_unclaimed_swaps().await?;
         self.sync().await?;
         Ok(())
     }
+
+    /// Loads the unclaimed swaps we've already synced, so that they're
+    /// tracked until they're claimed.
+    async fn load_unclaimed_swaps(&mut self) -> anyhow::Result<()> {
+        let unspent_notes = self
+            .storage
+            .notes(false, None, None, 0)
+            .await?
+            .into_iter()
+            .map(|record| (record.note_commitment, record))
+            .collect();
+
+        for (_, _, transaction) in self.storage.transactions(None, None).await? {
+            self.track_swaps(&transaction, &unspent_notes)?;
+        }
+
+        tracing::info!(pending = self.pending_swaps.len(), "loaded unclaimed swaps");
+
+        Ok(())
+    }
+
+    /// Starts tracking the swaps in `transaction` whose swap NFTs are in `notes`.
+    fn track_swaps(
+        &mut self,
+        transaction: &Transaction,
+        notes: &BTreeMap<note::Commitment, SpendableNoteRecord>,
+    ) -> anyhow::Result<()> {
+        for swap in transaction.swaps() {
+            if let Some(record) = notes.get(&swap.body.swap_nft.note_commitment) {
+                let swap_plaintext = swap
+                    .body
+                    .swap_ciphertext
+                    .decrypt2(self.fvk.incoming(), &swap.body.swap_nft.ephemeral_key)?;
+                self.pending_swaps.insert(record.clone(), swap_plaintext);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops tracking the swaps whose swap NFTs were spent, i.e., which were claimed.
+    async fn forget_claimed_swaps(&mut self, spent_nullifiers: &[Nullifier]) {
+        let unclaimed =
+            |record: &SpendableNoteRecord| !spent_nullifiers.contains(&record.nullifier);
+
+        self.pending_swaps.forget_spent(spent_nullifiers);
+
+        let mut claimable_swaps = self.claimable_swaps.write().await;
+        claimable_swaps.retain(|_, swap| unclaimed(&swap.swap_nft));
+        self.claims_submitted
+            .retain(|commitment, _| claimable_swaps.contains_key(commitment));
+    }
+
+    /// Checks whether the batches of any pending swaps have been executed,
+    /// making those swaps claimable, and claims them if enabled.
+    ///
+    /// The output data of each batch is fetched once we've synced the height at
+    /// which it was made, and again at each later height until it's executed.
+    /// Failures are logged and retried later, rather than stopping the sync.
+    async fn update_claimable_swaps(&mut self, height: u64) {
+        let mut executed = Vec::new();
+        for id @ (batch_height, trading_pair) in self.pending_swaps.due(height) {
+            let output_data = self
+                .specific_client
+                .batch_swap_output_data(BatchSwapOutputDataRequest {
+                    height: batch_height,
+                    trading_pair: Some(trading_pair.into()),
+                })
+                .await
+                .map(|rsp| BatchSwapOutputData::try_from(rsp.into_inner()));
+            match output_data {
+                Ok(Ok(output_data)) => {
+                    executed.extend(self.pending_swaps.executed(id, output_data))
+                }
+                // The batch hasn't been decrypted and executed yet.
+                Err(status) if status.code() == tonic::Code::NotFound => {
+                    self.pending_swaps.defer(id, height + 1)
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!(?e, batch_height, "malformed batch swap output data");
+                    self.pending_swaps.defer(id, height + FETCH_RETRY_BLOCKS);
+                }
+                Err(status) => {
+                    tracing::warn!(
+                        ?status,
+                        batch_height,
+                        "failed to fetch batch swap output data"
+                    );
+                    self.pending_swaps.defer(id, height + FETCH_RETRY_BLOCKS);
+                }
+            }
+        }
+
+        if !executed.is_empty() {
+            let mut claimable_swaps = self.claimable_swaps.write().await;
+            for swap in executed {
+                let commitment = swap.swap_nft.note_commitment;
+                tracing::debug!(?commitment, "detected claimable swap");
+                claimable_swaps.insert(commitment, swap);
+            }
+        }
+
+        if self.custody.is_some() {
+            if let Err(e) = self.claim_swaps(height).await {
+                tracing::warn!(?e, "failed to claim swaps");
+            }
+        }
+    }
+
+    /// Submits claims for the claimable swaps.
+    ///
+    /// Claims are only submitted once we've caught up with the chain, since a
+    /// swap that seems claimable while syncing may already have been claimed
+    /// in a block we haven't scanned yet.  Claims that don't land are submitted
+    /// again after [`CLAIM_RETRY_BLOCKS`] blocks.
+    async fn claim_swaps(&mut self, height: u64) -> anyhow::Result<()> {
+        let swaps = self
+            .claimable_swaps
+            .read()
+            .await
+            .values()
+            .filter(|swap| {
+                self.claims_submitted
+                    .get(&swap.swap_nft.note_commitment)
+                    .map_or(true, |submitted| height >= submitted + CLAIM_RETRY_BLOCKS)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if swaps.is_empty() {
+            return Ok(());
+        }
+
+        let latest_height = self
+            .tm_client
+            .status()
+            .await?
+            .sync_info
+            .latest_block_height
+            .value();
+        if height < latest_height {
+            return Ok(());
+        }
+
+        for swap in swaps {
+            let commitment = swap.swap_nft.note_commitment;
+            match self.claim_swap(swap).await {
+                Ok(()) => tracing::info!(?commitment, "submitted swap claim"),
+                Err(e) => tracing::warn!(?e, ?commitment, "failed to submit swap claim"),
+            }
+            self.claims_submitted.insert(commitment, height);
+        }
+
+        Ok(())
+    }
+
+    /// Builds a `SwapClaim` transaction for a claimable swap, has it authorized
+    /// by the custody service, and submits it.
+    async fn claim_swap(&mut self, swap: ClaimableSwapRecord) -> anyhow::Result<()> {
+        let chain_params = self.storage.chain_params().await?;
+        let commitment = swap.swap_nft.note_commitment;
+
+        let plan = TransactionPlan {
+            chain_id: chain_params.chain_id,
+            fee: swap.swap_plaintext.claim_fee.clone(),
+            // The transaction doesn't need a memo, because it's to ourselves.
+            memo_plan: None,
+            actions: vec![SwapClaimPlan::new(
+                &mut OsRng,
+                swap.swap_plaintext,
+                swap.swap_nft.note,
+                swap.swap_nft.position,
+                chain_params.epoch_duration,
+                swap.output_data,
+            )
+            .into()],
+            ..Default::default()
+        };
+
+        let auth_data: AuthorizationData = self
+            .custody
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("no custody service is configured"))?
+            .authorize(AuthorizeRequest {
+                plan: Some(plan.clone().into()),
+                account_id: Some(self.fvk.hash().into()),
+            })
+            .await?
+            .into_inner()
+            .try_into()?;
+
+        let witness_data = {
+            let nct = self.nct.read().await;
+            let proof = nct
+                .witness(commitment)
+                .ok_or_else(|| anyhow::anyhow!("swap NFT commitment is not in the NCT"))?;
+            WitnessData {
+                anchor: nct.root(),
+                note_commitment_proofs: [(commitment, proof)].into_iter().collect(),
+            }
+        };
+
+        let transaction = plan.build(&mut OsRng, &self.fvk, auth_data, witness_data)?;
+
+        let rsp = self
+            .tm_client
+            .broadcast_tx_sync(transaction.encode_to_vec().into())
+            .await?;
+        if rsp.code.is_err() {
+            return Err(anyhow::anyhow!("error submitting swap claim: {}", rsp.log));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "nct-divergence-check")]
@@ -37,6 +37,10 @@ where
             plan.swap_claim_plans()
                 .map(|swap_claim| swap_claim.swap_nft_note.commit().into()),
         )
+        .chain(
+            plan.delegator_vote_plans()
+                .map(|vote| vote.staked_note.commit().into()),
+        )
         .collect();
     let mut witness_data = view
         .witness(WitnessRequest {
@@ -7,18 +7,23 @@ use penumbra_component::stake::rate::RateData;
 use penumbra_component::stake::validator;
 use penumbra_crypto::{
     asset::Denom,
-    dex::{swap::SwapPlaintext, BatchSwapOutputData},
+    dex::{
+        lp::{position, Reserves},
+        swap::SwapPlaintext,
+        BatchSwapOutputData, TradingPair,
+    },
+    eddy::EncryptionKey,
     keys::AddressIndex,
     memo::MemoPlaintext,
     transaction::Fee,
-    Address, FullViewingKey, Note, Value,
+    Address, Amount, DelegationToken, FullViewingKey, Note, Value,
 };
 use penumbra_proto::{
     client::v1alpha1::{specific_query_client::SpecificQueryClient, BatchSwapOutputDataRequest},
     view::v1alpha1::NotesRequest,
 };
 use penumbra_transaction::{
-    action::{Proposal, ValidatorVote},
+    action::{Proposal, ValidatorVote, Vote},
     plan::{SwapClaimPlan, TransactionPlan},
 };
 use penumbra_view::{SpendableNoteRecord, ViewClient};
@@ -122,6 +127,41 @@ where
         .context("can't build undelegate plan")
 }
 
+/// Generate a new transaction plan redelegating stake from one validator to another
+#[allow(clippy::too_many_arguments)]
+pub async fn redelegate<V, R>(
+    fvk: &FullViewingKey,
+    view: &mut V,
+    rng: R,
+    source_rate_data: RateData,
+    destination_rate_data: RateData,
+    delegation_notes: Vec<SpendableNoteRecord>,
+    fee: Fee,
+    source_address: Option<u64>,
+) -> Result<TransactionPlan>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    let delegation_amount = delegation_notes
+        .iter()
+        .map(|record| u64::from(record.note.amount()))
+        .sum();
+
+    let mut planner = Planner::new(rng);
+    planner
+        .fee(fee)
+        .redelegate(delegation_amount, source_rate_data, destination_rate_data);
+    for record in delegation_notes {
+        planner.spend(record.note, record.position);
+    }
+
+    planner
+        .plan(view, fvk, source_address.map(Into::into))
+        .await
+        .context("can't build redelegate plan")
+}
+
 #[allow(clippy::too_many_arguments)]
 #[allow(dead_code)]
 #[instrument(skip(
@@ -173,9 +213,11 @@ pub async fn swap<V, R>(
     rng: R,
     input_value: Value,
     into_denom: Denom,
+    min_output: Amount,
     swap_fee: Fee,
     swap_claim_fee: Fee,
     source_address: Option<u64>,
+    flow_encryption_key: EncryptionKey,
 ) -> Result<TransactionPlan, anyhow::Error>
 where
     V: ViewClient,
@@ -191,13 +233,87 @@ where
 
     let mut planner = Planner::new(rng);
     planner.fee(swap_fee);
-    planner.swap(input_value, into_denom, swap_claim_fee, claim_address)?;
+    planner.swap(
+        input_value,
+        into_denom,
+        min_output,
+        swap_claim_fee,
+        claim_address,
+        flow_encryption_key,
+    )?;
     planner
         .plan(view, fvk, source_address.map(Into::into))
         .await
         .context("can't build send transaction")
 }
 
+#[instrument(skip(fvk, view, rng, fee, source_address))]
+pub async fn position_open<V, R>(
+    fvk: &FullViewingKey,
+    view: &mut V,
+    rng: R,
+    position: position::Position,
+    initial_reserves: Reserves,
+    fee: Fee,
+    source_address: Option<u64>,
+) -> Result<TransactionPlan>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    Planner::new(rng)
+        .fee(fee)
+        .position_open(position, initial_reserves)
+        .plan(view, fvk, source_address.map(Into::into))
+        .await
+        .context("can't build position open transaction")
+}
+
+#[instrument(skip(fvk, view, rng, fee, source_address))]
+pub async fn position_close<V, R>(
+    fvk: &FullViewingKey,
+    view: &mut V,
+    rng: R,
+    position_id: position::Id,
+    fee: Fee,
+    source_address: Option<u64>,
+) -> Result<TransactionPlan>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    Planner::new(rng)
+        .fee(fee)
+        .position_close(position_id)
+        .plan(view, fvk, source_address.map(Into::into))
+        .await
+        .context("can't build position close transaction")
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(fvk, view, rng, fee, source_address))]
+pub async fn position_withdraw<V, R>(
+    fvk: &FullViewingKey,
+    view: &mut V,
+    rng: R,
+    position_id: position::Id,
+    pair: TradingPair,
+    reserves: Reserves,
+    fee: Fee,
+    source_address: Option<u64>,
+) -> Result<TransactionPlan>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    Planner::new(rng)
+        .fee(fee)
+        .position_withdraw(position_id, pair, reserves)
+        .plan(view, fvk, source_address.map(Into::into))
+        .await
+        .context("can't build position withdraw transaction")
+}
+
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip(fvk, view, rng, values, fee, dest_address, source_address, tx_memo))]
 pub async fn send<V, R>(
@@ -307,15 +423,22 @@ where
                 let ivk = fvk.incoming();
                 let swap_plaintext = swap_ciphertext.decrypt2(ivk, &epk)?;
 
-                let output_data = specific_client
+                let output_data = match specific_client
                     .batch_swap_output_data(BatchSwapOutputDataRequest {
                         height: swap_nft_record.height_created,
                         trading_pair: Some(swap_plaintext.trading_pair.into()),
                     })
-                    .await?
-                    .into_inner()
-                    .try_into()
-                    .context("cannot parse batch swap output data")?;
+                    .await
+                {
+                    Ok(rsp) => rsp
+                        .into_inner()
+                        .try_into()
+                        .context("cannot parse batch swap output data")?,
+                    // The batch hasn't been decrypted and executed yet, so the
+                    // swap can't be claimed until later.
+                    Err(status) if status.code() == tonic::Code::NotFound => continue,
+                    Err(status) => return Err(status.into()),
+                };
 
                 let mut plan = TransactionPlan {
                     chain_id: chain_params.clone().chain_id,
@@ -453,3 +576,63 @@ where
         .await
         .context("can't build proposal withdraw transaction")
 }
+
+/// Generate a new transaction plan voting on a proposal as a delegator, with every staked note
+/// which was created before the proposal started.
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(fvk, view, rng))]
+pub async fn delegator_vote<V, R>(
+    fvk: &FullViewingKey,
+    view: &mut V,
+    rng: R,
+    proposal_id: u64,
+    start_position: Position,
+    vote: Vote,
+    fee: Fee,
+    source_address: Option<u64>,
+) -> Result<TransactionPlan>
+where
+    V: ViewClient,
+    R: RngCore + CryptoRng,
+{
+    let asset_cache = view.assets().await?;
+    let staked_notes = view
+        .notes(NotesRequest {
+            account_id: Some(fvk.hash().into()),
+            ..Default::default()
+        })
+        .await?
+        .into_iter()
+        .filter(|record| record.position < start_position)
+        .filter(|record| {
+            asset_cache
+                .get(&record.note.asset_id())
+                .map(|denom| DelegationToken::try_from(denom.clone()).is_ok())
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+
+    if staked_notes.is_empty() {
+        anyhow::bail!(
+            "no delegation tokens were held before proposal {} started",
+            proposal_id
+        );
+    }
+
+    let mut planner = Planner::new(rng);
+    planner.fee(fee);
+    for record in staked_notes {
+        planner.delegator_vote(
+            proposal_id,
+            start_position,
+            vote,
+            record.note,
+            record.position,
+        );
+    }
+
+    planner
+        .plan(view, fvk, source_address.map(Into::into))
+        .await
+        .context("can't build delegator vote transaction")
+}
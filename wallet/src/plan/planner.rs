@@ -9,7 +9,15 @@ use penumbra_component::stake::{rate::RateData, validator};
 use penumbra_crypto::{
     asset::Amount,
     asset::Denom,
-    dex::{swap::SwapPlaintext, BatchSwapOutputData, TradingPair},
+    dex::{
+        lp::{
+            position::{self, Position},
+            Reserves,
+        },
+        swap::SwapPlaintext,
+        BatchSwapOutputData, LimitPrices, TradingPair,
+    },
+    eddy::EncryptionKey,
     keys::AddressIndex,
     memo::MemoPlaintext,
     rdsa::{SpendAuth, VerificationKey},
@@ -19,10 +27,13 @@ use penumbra_crypto::{
 use penumbra_proto::view::v1alpha1::NotesRequest;
 use penumbra_tct as tct;
 use penumbra_transaction::{
-    action::{Proposal, ProposalSubmit, ProposalWithdrawBody, ValidatorVote},
+    action::{
+        PositionClose, PositionOpen, Proposal, ProposalSubmit, ProposalWithdrawBody, ValidatorVote,
+        Vote,
+    },
     plan::{
-        ActionPlan, MemoPlan, OutputPlan, ProposalWithdrawPlan, SpendPlan, SwapClaimPlan, SwapPlan,
-        TransactionPlan,
+        ActionPlan, DelegatorVotePlan, MemoPlan, OutputPlan, PositionWithdrawPlan,
+        ProposalWithdrawPlan, SpendPlan, SwapClaimPlan, SwapPlan, TransactionPlan,
     },
 };
 use penumbra_view::ViewClient;
@@ -132,13 +143,23 @@ impl<R: RngCore + CryptoRng> Planner<R> {
     }
 
     /// Perform a swap based on input notes in the transaction.
-    #[instrument(skip(self))]
+    ///
+    /// If the swap would produce less than `min_output` of the output asset,
+    /// it is not filled, and the input is refunded when the swap is claimed.
+    /// The minimum output is enforced through the swap's limit price, which is
+    /// public, unlike the input amount.
+    ///
+    /// The input amounts are encrypted to the flow encryption committee's
+    /// `flow_encryption_key`.
+    #[instrument(skip(self, flow_encryption_key))]
     pub fn swap(
         &mut self,
         input_value: Value,
         into_denom: Denom,
+        min_output: Amount,
         swap_claim_fee: Fee,
         claim_address: Address,
+        flow_encryption_key: EncryptionKey,
     ) -> Result<&mut Self> {
         // Determine the canonical order for the assets being swapped.
         // This will determine whether the input amount is assigned to delta_1 or delta_2.
@@ -149,10 +170,27 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         // and `delta_2` is 0.
         //
         // Otherwise, `delta_1` is 0, and `delta_2` is the input amount.
-        let (delta_1, delta_2) = if trading_pair.asset_1() == input_value.asset_id {
-            (input_value.amount, 0u64.into())
+        //
+        // The limit price, which guarantees the minimum output, applies to
+        // the other asset.  It is rounded up to one of the coarse limit tiers,
+        // so the swap may be refunded at a price slightly above the minimum.
+        let min_price = LimitPrices::min_price(input_value.amount.into(), min_output.into());
+        let ((delta_1, delta_2), limit_prices) = if trading_pair.asset_1() == input_value.asset_id {
+            (
+                (input_value.amount, 0u64.into()),
+                LimitPrices {
+                    min_price_1: 0,
+                    min_price_2: min_price,
+                },
+            )
         } else {
-            (0u64.into(), input_value.amount)
+            (
+                (0u64.into(), input_value.amount),
+                LimitPrices {
+                    min_price_1: min_price,
+                    min_price_2: 0,
+                },
+            )
         };
 
         // If there is no input, then there is no swap.
@@ -167,15 +205,62 @@ impl<R: RngCore + CryptoRng> Planner<R> {
             delta_2,
             swap_claim_fee,
             claim_address,
+            limit_prices,
         )
         .map_err(|_| anyhow!("error generating swap plaintext"))?;
 
-        let swap = SwapPlan::new(&mut self.rng, swap_plaintext).into();
+        let swap = SwapPlan::new(&mut self.rng, swap_plaintext, flow_encryption_key).into();
         self.action(swap);
 
         Ok(self)
     }
 
+    /// Open a liquidity position with the given initial reserves.
+    ///
+    /// The reserves are spent from the transaction, and an opened position NFT
+    /// is produced in their place.
+    #[instrument(skip(self))]
+    pub fn position_open(&mut self, position: Position, initial_reserves: Reserves) -> &mut Self {
+        self.action(
+            PositionOpen {
+                position,
+                initial_reserves,
+            }
+            .into(),
+        );
+        self
+    }
+
+    /// Close a liquidity position, spending its opened position NFT.
+    #[instrument(skip(self))]
+    pub fn position_close(&mut self, position_id: position::Id) -> &mut Self {
+        self.action(PositionClose { position_id }.into());
+        self
+    }
+
+    /// Withdraw the final reserves of a closed liquidity position, spending its
+    /// closed position NFT.
+    ///
+    /// The `reserves` must be exactly the reserves of the position recorded on
+    /// chain, or the transaction will be rejected.
+    #[instrument(skip(self))]
+    pub fn position_withdraw(
+        &mut self,
+        position_id: position::Id,
+        pair: TradingPair,
+        reserves: Reserves,
+    ) -> &mut Self {
+        self.action(
+            PositionWithdrawPlan {
+                position_id,
+                pair,
+                reserves,
+            }
+            .into(),
+        );
+        self
+    }
+
     /// Add an output note from this transaction.
     ///
     /// Any unused output value will be redirected back to the originating address as change notes
@@ -219,6 +304,27 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         self
     }
 
+    /// Add a redelegation to this transaction.
+    ///
+    /// Redelegations are quarantined like undelegations, so the planner imposes the same kind of
+    /// "exact change" rules on them: a transaction containing a redelegation must contain exactly
+    /// one redelegation, must spend only the source validator's delegation tokens (or staking
+    /// tokens, to pay the fee), and must output only the destination validator's delegation tokens
+    /// (or staking tokens, as fee change).
+    #[instrument(skip(self))]
+    pub fn redelegate(
+        &mut self,
+        delegation_amount: u64,
+        source_rate_data: RateData,
+        destination_rate_data: RateData,
+    ) -> &mut Self {
+        let redelegation = source_rate_data
+            .build_redelegate(&destination_rate_data, delegation_amount)
+            .into();
+        self.action(redelegation);
+        self
+    }
+
     /// Upload a validator definition in this transaction.
     #[instrument(skip(self))]
     pub fn validator_definition(&mut self, new_validator: validator::Definition) -> &mut Self {
@@ -258,6 +364,28 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         self
     }
 
+    /// Cast a delegator vote in this transaction, using the given staked note.
+    #[instrument(skip(self, start_position, staked_note, position))]
+    pub fn delegator_vote(
+        &mut self,
+        proposal_id: u64,
+        start_position: tct::Position,
+        vote: Vote,
+        staked_note: Note,
+        position: tct::Position,
+    ) -> &mut Self {
+        let plan = DelegatorVotePlan::new(
+            &mut self.rng,
+            proposal_id,
+            start_position,
+            vote,
+            staked_note,
+            position,
+        );
+        self.action(plan.into());
+        self
+    }
+
     fn action(&mut self, action: ActionPlan) -> &mut Self {
         // Track the contribution of the action to the transaction's balance
         self.balance += action.balance();
@@ -351,6 +479,7 @@ impl<R: RngCore + CryptoRng> Planner<R> {
 
         // Ensure that the transaction won't cause excessive quarantining
         self.check_undelegate_rules()?;
+        self.check_redelegate_rules()?;
 
         // Add clue plans for `Output`s.
         let fmd_params = view.fmd_parameters().await?;
@@ -436,6 +565,72 @@ impl<R: RngCore + CryptoRng> Planner<R> {
         Ok(())
     }
 
+    /// Redelegations are subject to the same quarantine as undelegations, so they should likewise
+    /// contain only the spends and outputs needed to move the stake and pay the fee.
+    fn check_redelegate_rules(&self) -> anyhow::Result<()> {
+        match self
+            .plan
+            .actions
+            .iter()
+            .filter_map(|action| {
+                if let ActionPlan::Redelegate(redelegate) = action {
+                    Some(redelegate)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [] => {
+                // No redelegations
+            }
+            [redelegate] => {
+                let source_asset_id = DelegationToken::new(redelegate.source_validator).id();
+                let destination_asset_id =
+                    DelegationToken::new(redelegate.destination_validator).id();
+                for action in self.plan.actions.iter() {
+                    match action {
+                        ActionPlan::Spend(spend) => {
+                            let asset_id = spend.note.value().asset_id;
+                            if asset_id != source_asset_id && asset_id != *STAKING_TOKEN_ASSET_ID {
+                                return Err(anyhow::anyhow!(
+                                    "redelegation transaction must spend only source delegation tokens or staking tokens"
+                                ));
+                            }
+                        }
+                        ActionPlan::Output(output) => {
+                            let asset_id = output.value.asset_id;
+                            if asset_id != destination_asset_id
+                                && asset_id != *STAKING_TOKEN_ASSET_ID
+                            {
+                                return Err(anyhow::anyhow!(
+                                    "redelegation transaction must output only destination delegation tokens or staking tokens"
+                                ));
+                            }
+                        }
+                        ActionPlan::Redelegate(_) => {
+                            // There's only one redelegate action, so this is the one we already
+                            // know about, so we don't have to do anything with it
+                        }
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "redelegation transaction must not contain extraneous actions"
+                            ))
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "redelegation transaction must not contain multiple redelegations"
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get a random address/withdraw key pair for proposals.
     fn proposal_address_and_withdraw_key(
         &mut self,